@@ -37,11 +37,13 @@ class ExpoRustBridgeModule : Module() {
     /**
      * Parse the OAuth callback URL to extract authorization code.
      *
+     * @param localeCode The Audible locale the sign-in was started for
      * @param callbackUrl The callback URL received from Audible OAuth
      * @return Map with success flag and either data (auth_code, state) or error message
      */
-    Function("parseOAuthCallback") { callbackUrl: String ->
+    Function("parseOAuthCallback") { localeCode: String, callbackUrl: String ->
       val params = JSONObject().apply {
+        put("locale_code", localeCode)
         put("callback_url", callbackUrl)
       }
       parseJsonResponse(nativeParseOAuthCallback(params.toString()))
@@ -123,6 +123,10 @@ export interface Locale {
   name: string;
   domain: string;
   with_username: boolean;
+  /** BCP 47 language tag, e.g. "de-DE" (empty for locales saved by older versions) */
+  language?: string;
+  /** ISO 4217 currency code, e.g. "EUR" (empty for locales saved by older versions) */
+  currency?: string;
 }
 
 // ----------------------------------------------------------------------------
@@ -310,10 +314,13 @@ export interface ExpoRustBridgeModule {
   /**
    * Parse OAuth callback URL to extract authorization code.
    *
+   * Fails if the URL is not the locale's Amazon landing page.
+   *
+   * @param localeCode - The Audible locale the sign-in was started for
    * @param callbackUrl - The callback URL received from OAuth redirect
    * @returns Authorization code extracted from callback
    */
-  parseOAuthCallback(callbackUrl: string): RustResponse<{ authorization_code: string }>;
+  parseOAuthCallback(localeCode: string, callbackUrl: string): RustResponse<{ authorization_code: string }>;
 
   /**
    * Exchange authorization code for complete registration response.
@@ -966,7 +973,7 @@ async function completeOAuthFlow(
 
   // Parse callback URL
   console.log('[ExpoRustBridge] Parsing callback URL...');
-  const parseResponse = NativeModule!.parseOAuthCallback(callbackUrl);
+  const parseResponse = NativeModule!.parseOAuthCallback(localeCode, callbackUrl);
   console.log('[ExpoRustBridge] Parse response:', parseResponse);
 
   const { authorization_code } = unwrapResult(parseResponse);
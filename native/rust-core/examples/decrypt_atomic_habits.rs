@@ -2,7 +2,8 @@
 //!
 //! This example:
 //! - Loads account from test fixture
-//! - Gets activation bytes from Audible API
+//! - Gets activation bytes from Audible API, or from the title's download
+//!   license if that fails
 //! - Decrypts the AAX file to playable M4B
 //!
 //! Usage:
@@ -11,15 +12,18 @@
 //! ```
 
 use rust_core::api::{
-    auth::{Locale, Account, get_activation_bytes},
+    auth::{Locale, Account},
+    client::AudibleClient,
     registration::RegistrationResponse,
 };
 use rust_core::crypto::activation::ActivationBytes;
 use std::path::PathBuf;
 use std::fs;
-use std::process::Command;
+use rust_core::audio::process::{self, Tool};
 
 const TEST_FIXTURE_PATH: &str = "test_fixtures/registration_response.json";
+/// ASIN of the AAX title, used for the download-license fallback
+const ASIN: &str = "B07T2F8VJM";
 const INPUT_FILE: &str = "/tmp/atomic_habits.aax";
 const OUTPUT_FILE: &str = "/tmp/atomic_habits_decrypted.m4b";
 
@@ -29,6 +33,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Decrypt Atomic Habits AAX → M4B");
     println!("═══════════════════════════════════════════════════════════\n");
 
+    process::check_installed(Tool::Ffmpeg).await?;
+    process::check_installed(Tool::Ffprobe).await?;
+
     // Step 1: Load account
     println!("📝 Step 1: Loading account from fixture...");
     let fixture_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(TEST_FIXTURE_PATH);
@@ -46,31 +53,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Step 2: Get activation bytes
     println!("🔓 Step 2: Retrieving activation bytes from Audible...");
 
-    let activation_bytes_result = get_activation_bytes(
-        &locale,
-        &account.identity.as_ref().unwrap().access_token.token
-    ).await;
-
-    let activation_bytes_hex = match activation_bytes_result {
+    // Falls back to the download license voucher if the license-token
+    // endpoint is unavailable
+    let client = AudibleClient::new(account)?;
+    let activation_bytes_hex = match client.get_activation_bytes_with_fallback(ASIN).await {
         Ok(bytes) => {
             println!("   ✅ Activation bytes: {}", bytes);
             bytes
         }
         Err(e) => {
             eprintln!("   ❌ Failed to get activation bytes: {:?}", e);
-            println!("\n💡 Trying to parse from license_response instead...");
-
-            // Alternative: parse from license response
-            let license_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-                .join("test_fixtures/download_license_b07t2f8vjm.json");
-
-            if license_path.exists() {
-                println!("   📄 Found license file, but license_response parsing not implemented yet");
-                println!("   ⚠️  Cannot decrypt without activation bytes");
-                return Err("Activation bytes unavailable".into());
-            } else {
-                return Err(format!("Failed to get activation bytes: {:?}", e).into());
-            }
+            return Err(format!("Failed to get activation bytes: {:?}", e).into());
         }
     };
 
@@ -83,17 +76,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Reference: crypto/aax.rs - build_ffmpeg_command()
     let activation_bytes = ActivationBytes::from_hex(&activation_bytes_hex)?;
 
-    let ffmpeg_status = Command::new("ffmpeg")
-        .arg("-y")  // Overwrite output
-        .arg("-activation_bytes")
-        .arg(activation_bytes.to_hex())
-        .arg("-i")
-        .arg(INPUT_FILE)
-        .arg("-c")
-        .arg("copy")
-        .arg("-vn")  // No video
-        .arg(OUTPUT_FILE)
-        .status()?;
+    // Runs under FFmpeg's timeout, so a hung process is killed and reported
+    let ffmpeg_status = process::output(Tool::Ffmpeg, |cmd| {
+        cmd.arg("-y")  // Overwrite output
+            .arg("-activation_bytes")
+            .arg(activation_bytes.to_hex())
+            .arg("-i")
+            .arg(INPUT_FILE)
+            .arg("-c")
+            .arg("copy")
+            .arg("-vn")  // No video
+            .arg(OUTPUT_FILE);
+    })
+    .await?
+    .status;
 
     if ffmpeg_status.success() {
         println!("   ✅ Decryption successful!\n");
@@ -108,14 +104,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   File size: {:.2} MB", metadata.len() as f64 / (1024.0 * 1024.0));
 
     // Check with ffprobe
-    let ffprobe_output = Command::new("ffprobe")
-        .arg("-v")
-        .arg("quiet")
-        .arg("-print_format")
-        .arg("json")
-        .arg("-show_format")
-        .arg(OUTPUT_FILE)
-        .output()?;
+    let ffprobe_output = process::output(Tool::Ffprobe, |cmd| {
+        cmd.args(["-v", "quiet", "-print_format", "json", "-show_format", OUTPUT_FILE]);
+    })
+    .await?;
 
     if ffprobe_output.status.success() {
         let format_info: serde_json::Value = serde_json::from_slice(&ffprobe_output.stdout)?;
@@ -10,6 +10,9 @@
 //! Usage:
 //! ```bash
 //! cargo run --example download_and_decrypt
+//!
+//! # Keep the encrypted .aax next to the decrypted file
+//! KEEP_ENCRYPTED=1 cargo run --example download_and_decrypt
 //! ```
 
 use rust_core::api::{
@@ -22,7 +25,7 @@ use std::path::PathBuf;
 use std::fs;
 use futures_util::StreamExt;
 use tokio::io::AsyncWriteExt;
-use std::process::Command;
+use rust_core::audio::process::{self, Tool};
 
 const TEST_FIXTURE_PATH: &str = "test_fixtures/registration_response.json";
 const TEST_ASIN: &str = "B07T2F8VJM";
@@ -37,6 +40,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Book: B07T2F8VJM");
     println!("═══════════════════════════════════════════════════════════\n");
 
+    // Fail before downloading anything if the decrypt step cannot run
+    process::check_installed(Tool::Ffmpeg).await?;
+    process::check_installed(Tool::Ffprobe).await?;
+
     // Step 1: Load account and create client
     println!("📝 Step 1: Loading account...");
     let fixture_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(TEST_FIXTURE_PATH);
@@ -117,18 +124,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   Activation bytes: {}", activation_bytes_hex);
     println!("   Running ffmpeg...");
 
-    let ffmpeg_status = Command::new("ffmpeg")
-        .arg("-y")
-        .arg("-activation_bytes")
-        .arg(&activation_bytes_hex)
-        .arg("-i")
-        .arg(ENCRYPTED_FILE)
-        .arg("-c")
-        .arg("copy")
-        .arg("-vn")
-        .arg(DECRYPTED_FILE)
-        .stderr(std::process::Stdio::null())  // Suppress ffmpeg output
-        .status()?;
+    // Output is captured rather than printed, and a hung ffmpeg is killed
+    let ffmpeg_status = process::output(Tool::Ffmpeg, |cmd| {
+        cmd.arg("-y")
+            .arg("-activation_bytes")
+            .arg(&activation_bytes_hex)
+            .arg("-i")
+            .arg(ENCRYPTED_FILE)
+            .arg("-c")
+            .arg("copy")
+            .arg("-vn")
+            .arg(DECRYPTED_FILE);
+    })
+    .await?
+    .status;
 
     if !ffmpeg_status.success() {
         return Err(format!("FFmpeg failed: {:?}", ffmpeg_status.code()).into());
@@ -141,14 +150,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   Size: {:.2} MB", metadata.len() as f64 / (1024.0 * 1024.0));
 
     // Get duration with ffprobe
-    let ffprobe_output = Command::new("ffprobe")
-        .arg("-v")
-        .arg("quiet")
-        .arg("-print_format")
-        .arg("json")
-        .arg("-show_format")
-        .arg(DECRYPTED_FILE)
-        .output()?;
+    let ffprobe_output = process::output(Tool::Ffprobe, |cmd| {
+        cmd.args(["-v", "quiet", "-print_format", "json", "-show_format", DECRYPTED_FILE]);
+    })
+    .await?;
 
     if ffprobe_output.status.success() {
         let format_info: serde_json::Value = serde_json::from_slice(&ffprobe_output.stdout)?;
@@ -168,8 +173,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("   ✅ Valid playable M4B file!");
 
-    // Cleanup encrypted file (save space)
-    let _ = tokio::fs::remove_file(ENCRYPTED_FILE).await;
+    // Cleanup encrypted file (save space), unless KEEP_ENCRYPTED is set
+    if std::env::var_os("KEEP_ENCRYPTED").is_some() {
+        println!("   Keeping encrypted original: {}", ENCRYPTED_FILE);
+    } else {
+        let _ = tokio::fs::remove_file(ENCRYPTED_FILE).await;
+    }
 
     println!("\n═══════════════════════════════════════════════════════════");
     println!("  🎉 Pipeline Complete!");
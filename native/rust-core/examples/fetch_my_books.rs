@@ -14,7 +14,8 @@
 use rust_core::api::{
     auth::{Locale, Account, refresh_access_token},
     registration::RegistrationResponse,
-    library::{LibraryOptions, LibraryResponse},
+    library::{self, LibraryOptions, LibraryResponse, LibraryStats},
+    DescriptionFormat,
 };
 use std::path::PathBuf;
 use std::fs;
@@ -124,9 +125,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let first_text = first_response.text().await?;
     let first_library: LibraryResponse = serde_json::from_str(&first_text)?;
-    let total_books = first_library.total_results.unwrap_or(0);
-
-    println!("   Total books in library: {}", total_books);
+    match first_library.total_results {
+        Some(total_books) => println!("   Total books in library: {}", total_books),
+        None => println!("   Total books in library: unknown (no total_results in response)"),
+    }
     println!("   Fetching all books...\n");
 
     // Now fetch all books (max 1000 per page)
@@ -310,8 +312,8 @@ fn display_books(library: &LibraryResponse) {
         }
 
         // Language
-        if let Some(lang) = &book.language {
-            println!("   Language: {}", lang);
+        if !book.languages.is_empty() {
+            println!("   Language: {}", book.languages.join(", "));
         }
 
         // Publisher
@@ -325,7 +327,9 @@ fn display_books(library: &LibraryResponse) {
         }
 
         // Purchase date
-        println!("   Purchased: {}", book.purchase_date.format("%Y-%m-%d"));
+        if let Some(purchased) = book.purchase_date {
+            println!("   Purchased: {}", purchased.format("%Y-%m-%d"));
+        }
 
         // ASIN
         println!("   ASIN: {}", book.asin);
@@ -363,11 +367,11 @@ fn display_books(library: &LibraryResponse) {
         }
 
         // Description (truncated)
-        if let Some(desc) = &book.description {
-            let truncated = if desc.len() > 150 {
-                format!("{}...", &desc[..150])
+        if let Some(desc) = book.description_as(DescriptionFormat::PlainText) {
+            let truncated = if desc.chars().count() > 150 {
+                format!("{}...", desc.chars().take(150).collect::<String>())
             } else {
-                desc.clone()
+                desc
             };
             println!("   Description: {}", truncated);
         }
@@ -394,8 +398,6 @@ fn analyze_naming_conventions(library: &LibraryResponse) {
     let mut special_chars_in_authors = HashSet::new();
     let mut special_chars_in_series = HashSet::new();
     let mut problematic_titles = Vec::new();
-    let mut books_with_series = 0;
-    let mut books_with_subtitles = 0;
 
     for book in &library.items {
         // Analyze title
@@ -410,11 +412,6 @@ fn analyze_naming_conventions(library: &LibraryResponse) {
             }
         }
 
-        // Check for subtitle
-        if book.subtitle.is_some() {
-            books_with_subtitles += 1;
-        }
-
         // Analyze authors
         for author in &book.authors {
             for ch in author.name.chars() {
@@ -426,14 +423,11 @@ fn analyze_naming_conventions(library: &LibraryResponse) {
 
         // Analyze series
         if let Some(series_list) = &book.series {
-            if !series_list.is_empty() {
-                books_with_series += 1;
-                for series in series_list {
-                    if let Some(title) = &series.title {
-                        for ch in title.chars() {
-                            if !ch.is_alphanumeric() && ch != ' ' {
-                                special_chars_in_series.insert(ch);
-                            }
+            for series in series_list {
+                if let Some(title) = &series.title {
+                    for ch in title.chars() {
+                        if !ch.is_alphanumeric() && ch != ' ' {
+                            special_chars_in_series.insert(ch);
                         }
                     }
                 }
@@ -442,10 +436,24 @@ fn analyze_naming_conventions(library: &LibraryResponse) {
     }
 
     // Report findings
+    let stats = library::stats(&library.items);
+    let percent = |n: usize| n as f64 / stats.total_books.max(1) as f64 * 100.0;
+    let (hours, minutes) = stats.runtime_hours_minutes();
     println!("📊 Statistics:");
-    println!("   Total books: {}", library.items.len());
-    println!("   Books with series: {} ({:.1}%)", books_with_series, (books_with_series as f64 / library.items.len() as f64) * 100.0);
-    println!("   Books with subtitles: {} ({:.1}%)", books_with_subtitles, (books_with_subtitles as f64 / library.items.len() as f64) * 100.0);
+    println!("   Total books: {}", stats.total_books);
+    println!("   Total runtime: {}h {}m", hours, minutes);
+    println!("   Books with series: {} ({:.1}%)", stats.books_with_series, percent(stats.books_with_series));
+    println!("   Books with subtitles: {} ({:.1}%)", stats.books_with_subtitles, percent(stats.books_with_subtitles));
+    println!("   Finished: {} ({:.1}%)", stats.finished, percent(stats.finished));
+    println!("   Languages: {}", LibraryStats::top(&stats.by_language, 5)
+        .iter()
+        .map(|(language, count)| format!("{} ({})", language, count))
+        .collect::<Vec<_>>()
+        .join(", "));
+    println!("   Top authors:");
+    for (author, count) in LibraryStats::top(&stats.by_author, 5) {
+        println!("     {} - {} books", author, count);
+    }
 
     println!("\n🔤 Special Characters Found:");
     println!("   In titles: {}", format_char_set(&special_chars_in_titles));
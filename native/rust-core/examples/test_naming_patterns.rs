@@ -118,7 +118,7 @@ fn book_to_metadata(book: &rust_core::api::library::LibraryItem) -> AudioMetadat
         narrators: book.narrators.iter().map(|n| n.name.clone()).collect(),
         publisher: book.publisher.clone(),
         publication_date: book.release_date.map(|d| d.to_string()),
-        language: book.language.clone(),
+        languages: book.languages.clone(),
         series,
         description: book.description.clone(),
         genres: vec![],
@@ -51,11 +51,14 @@ char* rust_generate_oauth_url(const char* locale_code, const char* device_serial
 /**
  * Parse OAuth callback URL to extract authorization code
  *
+ * Rejects callbacks that are not the locale's Amazon landing page.
+ *
+ * @param locale_code Audible locale the sign-in was started for (e.g., "us", "de")
  * @param callback_url Full callback URL with authorization code
  * @return JSON string with authorization_code
  *         Caller must free with rust_free_string()
  */
-char* rust_parse_oauth_callback(const char* callback_url);
+char* rust_parse_oauth_callback(const char* locale_code, const char* callback_url);
 
 /**
  * Exchange authorization code for access and refresh tokens
@@ -135,7 +135,7 @@
 //! # }
 //! ```
 
-use crate::error::{LibationError, Result};
+use crate::error::{AudibleErrorCode, LibationError, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
@@ -188,10 +188,26 @@ pub struct Account {
     pub identity: Option<Identity>,
 }
 
+/// Overwrite a string's bytes with zeros before clearing it
+///
+/// Volatile writes keep the compiler from dropping the stores as dead.
+fn wipe_string(value: &mut String) {
+    let mut bytes = std::mem::take(value).into_bytes();
+    for byte in bytes.iter_mut() {
+        // SAFETY: `byte` is a valid, exclusive reference into `bytes`
+        unsafe { std::ptr::write_volatile(byte, 0) };
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
 // ============================================================================
 // OAuth Identity and Tokens
 // ============================================================================
 
+/// Seconds before `expires_at` that an access token is treated as expired,
+/// to absorb device clock skew
+pub const DEFAULT_CLOCK_SKEW_SECS: i64 = 5 * 60;
+
 /// OAuth identity containing access tokens, refresh tokens, and device info
 ///
 /// Maps to C# `AudibleApi.Authorization.Identity` class (from AudibleApi package)
@@ -330,6 +346,9 @@ pub struct CustomerInfo {
 /// - Name (string) → name (String) - display name
 /// - WithUsername (bool) → with_username (bool) - email vs phone auth
 ///
+/// `language` and `currency` are not in the C# class; they let the UI format
+/// prices and dates for the marketplace.
+///
 /// # Supported Locales in Libation:
 /// - US: audible.com (with_username: true)
 /// - UK: audible.co.uk (with_username: true)
@@ -358,6 +377,16 @@ pub struct Locale {
     /// Whether this locale uses email (true) or phone (false) authentication
     /// Maps to C# Locale.WithUsername
     pub with_username: bool,
+
+    /// BCP 47 language tag of the marketplace (e.g. "de-DE")
+    /// Empty in locales saved before this field was added
+    #[serde(default)]
+    pub language: String,
+
+    /// ISO 4217 currency code used for prices (e.g. "EUR")
+    /// Empty in locales saved before this field was added
+    #[serde(default)]
+    pub currency: String,
 }
 
 // ============================================================================
@@ -629,6 +658,63 @@ impl Account {
         Ok(())
     }
 
+    /// Check whether this device is still registered with Audible
+    ///
+    /// A registration can be revoked from elsewhere (the user removes the
+    /// device on the Audible website or deregisters it from another app).
+    /// An expired access token is routine and fixed by a refresh, so this asks
+    /// for a fresh access token: it succeeds for any live registration and is
+    /// refused only when the refresh token itself has been invalidated. The
+    /// new token is not stored; use [`refresh_tokens`](Self::refresh_tokens)
+    /// for that.
+    ///
+    /// # Returns
+    /// `true` if the registration is live, `false` if it has been revoked or
+    /// the account has no identity
+    ///
+    /// # Errors
+    /// - `NetworkError` - The token endpoint could not be reached
+    /// - `AuthenticationFailed` - Any other refusal (e.g. server error), which
+    ///   says nothing about the registration
+    pub async fn verify_registration(&self) -> Result<bool> {
+        let Some(identity) = self.identity.as_ref() else {
+            return Ok(false);
+        };
+        if identity.refresh_token.is_empty() {
+            return Ok(false);
+        }
+
+        let client = reqwest::Client::new();
+        let response = refresh_token_request(
+            &client,
+            &identity.locale,
+            &identity.refresh_token,
+            &identity.device_serial_number,
+        )
+        .send()
+        .await
+        .map_err(|e| LibationError::NetworkError {
+            message: format!("Registration check failed: {}", e),
+            is_transient: true,
+        })?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(true);
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        if is_registration_revoked(status.as_u16(), &body) {
+            eprintln!("Registration for {} has been revoked", self.masked_log_entry());
+            return Ok(false);
+        }
+
+        Err(LibationError::AuthenticationFailed {
+            message: format!("Registration check failed (status {}): {}", status, body),
+            account_id: Some(self.account_id.clone()),
+        })
+    }
+
     /// Retrieve activation bytes for DRM decryption
     ///
     /// This calls the Audible API to get the activation bytes for this account.
@@ -652,13 +738,26 @@ impl Account {
         let activation_bytes = get_activation_bytes(
             &identity.locale,
             &identity.access_token.token,
-        ).await?;
+        ).await?.activation_bytes;
 
         // Store in decrypt_key field
         self.decrypt_key = activation_bytes.clone();
 
         Ok(activation_bytes)
     }
+
+    /// Wipe in-memory secrets on logout
+    ///
+    /// Zeroes the activation bytes and every token in the identity, then drops
+    /// the identity. Pair with [`Database::clear_account`](crate::storage::Database::clear_account)
+    /// to remove the stored copy.
+    pub fn zeroize(&mut self) {
+        wipe_string(&mut self.decrypt_key);
+        if let Some(identity) = self.identity.as_mut() {
+            identity.zeroize();
+        }
+        self.identity = None;
+    }
 }
 
 // ============================================================================
@@ -691,13 +790,92 @@ impl Identity {
     }
 
     /// Check if the access token is expired
+    ///
+    /// Tokens are treated as expired [`DEFAULT_CLOCK_SKEW_SECS`] early, so a
+    /// device whose clock runs a few minutes behind still refreshes before
+    /// Audible rejects the token.
     pub fn is_expired(&self) -> bool {
-        Utc::now() >= self.access_token.expires_at
+        self.is_expired_with_skew(chrono::Duration::seconds(DEFAULT_CLOCK_SKEW_SECS))
     }
 
-    /// Get time until token expiration
+    /// Check if the access token is expired, treating it as expired `skew` early
+    pub fn is_expired_with_skew(&self, skew: chrono::Duration) -> bool {
+        self.time_until_expiry_with_skew(skew) <= chrono::Duration::zero()
+    }
+
+    /// Store the tokens from a refresh response
+    ///
+    /// The refresh token is only replaced if Amazon returned a new one.
+    pub fn apply_token_response(&mut self, response: TokenResponse) {
+        self.access_token = AccessToken {
+            token: response.access_token,
+            expires_at: Utc::now() + chrono::Duration::seconds(response.expires_in),
+        };
+
+        if let Some(new_refresh_token) = response.refresh_token {
+            self.refresh_token = new_refresh_token;
+            eprintln!("🔑 Received new refresh token from Amazon");
+        }
+    }
+
+    /// Get time until the token should be treated as expired
+    ///
+    /// Includes the [`DEFAULT_CLOCK_SKEW_SECS`] buffer; negative once expired.
     pub fn time_until_expiry(&self) -> chrono::Duration {
-        self.access_token.expires_at - Utc::now()
+        self.time_until_expiry_with_skew(chrono::Duration::seconds(DEFAULT_CLOCK_SKEW_SECS))
+    }
+
+    /// Get time until expiration minus a clock skew buffer
+    ///
+    /// # Arguments
+    /// * `skew` - How much earlier than `expires_at` to consider the token expired
+    pub fn time_until_expiry_with_skew(&self, skew: chrono::Duration) -> chrono::Duration {
+        self.access_token.expires_at - skew - Utc::now()
+    }
+
+    /// Overwrite tokens, keys and cookies with zeros and empty them
+    ///
+    /// Device and locale details are kept; the identity is unusable afterwards.
+    pub fn zeroize(&mut self) {
+        wipe_string(&mut self.access_token.token);
+        wipe_string(&mut self.refresh_token);
+        wipe_string(&mut self.device_private_key);
+        wipe_string(&mut self.adp_token);
+        wipe_string(&mut self.store_authentication_cookie);
+        for (_, mut value) in self.cookies.drain() {
+            wipe_string(&mut value);
+        }
+    }
+
+    /// `Cookie` header carrying the website cookies
+    ///
+    /// [`cookies`](Self::cookies) keeps each value exactly as Amazon sent it.
+    /// Some values arrive wrapped in double quotes (`at-main` is `"Atza|..."`),
+    /// which the header must not contain, so they go through
+    /// [`cookie_header_value`] here. Names are sorted so the header is stable.
+    ///
+    /// # Returns
+    /// `None` if the identity has no cookies
+    pub fn cookie_header(&self) -> Option<String> {
+        if self.cookies.is_empty() {
+            return None;
+        }
+
+        if let Some(at_main) = self.cookies.get("at-main") {
+            if !cookie_header_value(at_main).starts_with("Atza|") {
+                eprintln!("Warning: at-main cookie is not an Atza| token; cookie requests may be rejected");
+            }
+        }
+
+        let mut names: Vec<_> = self.cookies.keys().collect();
+        names.sort();
+        Some(
+            names
+                .into_iter()
+                .map(|name| format!("{}={}", name, cookie_header_value(&self.cookies[name])))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
     }
 }
 
@@ -707,12 +885,21 @@ impl Identity {
 
 impl Locale {
     /// Create a new locale
-    pub fn new(country_code: String, domain: String, name: String, with_username: bool) -> Self {
+    pub fn new(
+        country_code: String,
+        domain: String,
+        name: String,
+        with_username: bool,
+        language: String,
+        currency: String,
+    ) -> Self {
         Self {
             country_code,
             domain,
             name,
             with_username,
+            language,
+            currency,
         }
     }
 
@@ -725,6 +912,8 @@ impl Locale {
             domain: "audible.com".to_string(),
             name: "United States".to_string(),
             with_username: true,
+            language: "en-US".to_string(),
+            currency: "USD".to_string(),
         }
     }
 
@@ -735,6 +924,8 @@ impl Locale {
             domain: "audible.co.uk".to_string(),
             name: "United Kingdom".to_string(),
             with_username: true,
+            language: "en-GB".to_string(),
+            currency: "GBP".to_string(),
         }
     }
 
@@ -745,6 +936,8 @@ impl Locale {
             domain: "audible.de".to_string(),
             name: "Germany".to_string(),
             with_username: true,
+            language: "de-DE".to_string(),
+            currency: "EUR".to_string(),
         }
     }
 
@@ -755,6 +948,8 @@ impl Locale {
             domain: "audible.fr".to_string(),
             name: "France".to_string(),
             with_username: true,
+            language: "fr-FR".to_string(),
+            currency: "EUR".to_string(),
         }
     }
 
@@ -765,6 +960,8 @@ impl Locale {
             domain: "audible.ca".to_string(),
             name: "Canada".to_string(),
             with_username: true,
+            language: "en-CA".to_string(),
+            currency: "CAD".to_string(),
         }
     }
 
@@ -775,6 +972,8 @@ impl Locale {
             domain: "audible.com.au".to_string(),
             name: "Australia".to_string(),
             with_username: true,
+            language: "en-AU".to_string(),
+            currency: "AUD".to_string(),
         }
     }
 
@@ -785,6 +984,8 @@ impl Locale {
             domain: "audible.it".to_string(),
             name: "Italy".to_string(),
             with_username: true,
+            language: "it-IT".to_string(),
+            currency: "EUR".to_string(),
         }
     }
 
@@ -795,6 +996,8 @@ impl Locale {
             domain: "audible.es".to_string(),
             name: "Spain".to_string(),
             with_username: true,
+            language: "es-ES".to_string(),
+            currency: "EUR".to_string(),
         }
     }
 
@@ -805,6 +1008,8 @@ impl Locale {
             domain: "audible.in".to_string(),
             name: "India".to_string(),
             with_username: true,
+            language: "en-IN".to_string(),
+            currency: "INR".to_string(),
         }
     }
 
@@ -816,6 +1021,8 @@ impl Locale {
             domain: "audible.co.jp".to_string(),
             name: "Japan".to_string(),
             with_username: false,
+            language: "ja-JP".to_string(),
+            currency: "JPY".to_string(),
         }
     }
 
@@ -842,6 +1049,31 @@ impl Locale {
             .find(|l| l.country_code.eq_ignore_ascii_case(code))
     }
 
+    /// Amazon marketplace ID of this market
+    ///
+    /// Unknown markets fall back to the US marketplace.
+    pub fn marketplace_id(&self) -> &'static str {
+        match self.country_code.as_str() {
+            "uk" => "A2I9A3Q2GNFNGQ",
+            "de" => "AN7V1F1VY261K",
+            "fr" => "A2728XDNODOQ8T",
+            "ca" => "A2CQZ5RBY40XE",
+            "au" => "AN7EY7DTAW63G",
+            "it" => "A2N7FU2W2BU2ZC",
+            "es" => "ALMIKO4SZCSAR",
+            "in" => "AJO3FBRUE6J4S",
+            "jp" => "A1QAP3MOU4173J",
+            _ => "AF2M0KC94RCEA",
+        }
+    }
+
+    /// Find a locale by Amazon marketplace ID
+    pub fn from_marketplace_id(marketplace_id: &str) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|l| l.marketplace_id() == marketplace_id)
+    }
+
     /// Get the API base URL for this locale
     pub fn api_url(&self) -> String {
         format!("https://api.{}", self.domain)
@@ -849,7 +1081,35 @@ impl Locale {
 
     /// Get the OAuth URL for this locale
     pub fn oauth_url(&self) -> String {
-        format!("https://www.amazon.com/ap/signin")
+        format!("https://www.{}/ap/signin", self.amazon_domain())
+    }
+
+    /// Amazon login domain for this market
+    ///
+    /// Sign-in and the post-login `maplanding` redirect happen on the local
+    /// Amazon site, not on audible.*. Unknown markets fall back to amazon.com.
+    pub fn amazon_domain(&self) -> &'static str {
+        match self.country_code.as_str() {
+            "us" => "amazon.com",
+            "uk" => "amazon.co.uk",
+            "de" => "amazon.de",
+            "fr" => "amazon.fr",
+            "ca" => "amazon.ca",
+            "au" => "amazon.com.au",
+            "it" => "amazon.it",
+            "es" => "amazon.es",
+            "in" => "amazon.in",
+            "jp" => "amazon.co.jp",
+            _ => "amazon.com",
+        }
+    }
+
+    /// URL Amazon redirects to once sign-in completes
+    ///
+    /// Sent as `openid.return_to`; the callback carrying the authorization code
+    /// arrives on this URL.
+    pub fn landing_url(&self) -> String {
+        format!("https://www.{}/ap/maplanding", self.amazon_domain())
     }
 }
 
@@ -1047,20 +1307,7 @@ pub fn generate_authorization_url(
     let client_id = format!("device:{}", serial_and_type);
 
     // Amazon login domain varies by region
-    // Most regions use amazon.com, but some use local Amazon domains
-    let amazon_domain = match locale.country_code.as_str() {
-        "us" => "amazon.com",
-        "uk" => "amazon.co.uk",
-        "de" => "amazon.de",
-        "fr" => "amazon.fr",
-        "ca" => "amazon.ca",
-        "au" => "amazon.com.au",
-        "it" => "amazon.it",
-        "es" => "amazon.es",
-        "in" => "amazon.in",
-        "jp" => "amazon.co.jp",
-        _ => "amazon.com", // Default fallback
-    };
+    let amazon_domain = locale.amazon_domain();
 
     // Build authorization URL
     let mut url = Url::parse(&format!("https://www.{}/ap/signin", amazon_domain))
@@ -1075,7 +1322,7 @@ pub fn generate_authorization_url(
         query.append_pair("openid.oa2.code_challenge", &pkce.challenge);
 
         // OpenID parameters
-        query.append_pair("openid.return_to", &format!("https://www.{}/ap/maplanding", amazon_domain));
+        query.append_pair("openid.return_to", &locale.landing_url());
         query.append_pair("openid.assoc_handle", &format!("amzn_audible_ios_{}", locale.country_code));
         query.append_pair("openid.identity", "http://specs.openid.net/auth/2.0/identifier_select");
         query.append_pair("pageId", "amzn_audible_ios");
@@ -1093,20 +1340,7 @@ pub fn generate_authorization_url(
         query.append_pair("openid.ns.pape", "http://specs.openid.net/extensions/pape/1.0");
 
         // Marketplace ID (locale-specific)
-        let marketplace_id = match locale.country_code.as_str() {
-            "us" => "AF2M0KC94RCEA",
-            "uk" => "A2I9A3Q2GNFNGQ",
-            "de" => "AN7EY7DTAW63G",
-            "fr" => "A2728XDNODOQ8T",
-            "ca" => "A2CQZ5RBY40XE",
-            "au" => "AN7EY7DTAW63G",
-            "it" => "A2N7FU2W2BU2ZC",
-            "es" => "ALMIKO4SZCSAR",
-            "in" => "AJO3FBRUE6J4S",
-            "jp" => "A1QAP3MOU4173J",
-            _ => "AF2M0KC94RCEA", // Default to US
-        };
-        query.append_pair("marketPlaceId", marketplace_id);
+        query.append_pair("marketPlaceId", locale.marketplace_id());
 
         // OAuth scope and state
         query.append_pair("openid.oa2.scope", config.scope);
@@ -1191,6 +1425,103 @@ pub fn parse_authorization_callback(
     Ok(code.clone())
 }
 
+/// Matcher for the OAuth landing (redirect) URL
+///
+/// Amazon redirects to a market-specific `maplanding` page once sign-in
+/// completes (e.g. `www.amazon.de` for audible.de, `www.amazon.co.jp` for
+/// audible.co.jp). [`parse_authorization_callback_with`] uses it to reject
+/// callbacks from an unexpected host; [`is_landing_url`](Self::is_landing_url)
+/// lets a caller check a URL without parsing it.
+///
+/// # Example
+/// ```rust
+/// # use rust_core::api::auth::*;
+/// let matcher = OAuthLandingMatcher::for_locale(&Locale::de());
+/// assert!(matcher.is_landing_url("https://www.amazon.de/ap/maplanding?openid.oa2.authorization_code=X"));
+/// assert!(!matcher.is_landing_url("https://www.amazon.com/ap/maplanding?openid.oa2.authorization_code=X"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuthLandingMatcher {
+    /// Accepted hosts (compared case-insensitively)
+    hosts: Vec<String>,
+    /// Required path prefix
+    path: String,
+}
+
+impl OAuthLandingMatcher {
+    /// Matcher for a locale's Amazon landing page, with or without `www.`
+    pub fn for_locale(locale: &Locale) -> Self {
+        let domain = locale.amazon_domain();
+        Self {
+            hosts: vec![format!("www.{}", domain), domain.to_string()],
+            path: "/ap/maplanding".to_string(),
+        }
+    }
+
+    /// Accept an additional host (e.g. when Amazon moves a market's sign-in)
+    pub fn with_host<S: Into<String>>(mut self, host: S) -> Self {
+        self.hosts.push(host.into());
+        self
+    }
+
+    /// Replace the required path prefix
+    pub fn with_path<S: Into<String>>(mut self, path: S) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Check whether a URL is the landing redirect
+    pub fn is_landing_url(&self, url: &str) -> bool {
+        Url::parse(url).map(|url| self.matches(&url)).unwrap_or(false)
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        let host_ok = url
+            .host_str()
+            .map(|host| self.hosts.iter().any(|h| h.eq_ignore_ascii_case(host)))
+            .unwrap_or(false);
+
+        url.scheme() == "https" && host_ok && url.path().starts_with(&self.path)
+    }
+}
+
+/// Parse the OAuth callback for a locale, validating the landing URL
+///
+/// Same as [`parse_authorization_callback`], but first checks the URL is the
+/// locale's Amazon landing page (see [`OAuthLandingMatcher::for_locale`]).
+///
+/// # Errors
+/// Returns `AuthenticationFailed` if the URL is not the expected landing page,
+/// plus any error from [`parse_authorization_callback`]
+pub fn parse_authorization_callback_for_locale(
+    callback_url: &str,
+    locale: &Locale,
+) -> Result<String> {
+    parse_authorization_callback_with(callback_url, &OAuthLandingMatcher::for_locale(locale))
+}
+
+/// Parse the OAuth callback, validating it against a custom landing matcher
+pub fn parse_authorization_callback_with(
+    callback_url: &str,
+    matcher: &OAuthLandingMatcher,
+) -> Result<String> {
+    let url = Url::parse(callback_url)
+        .map_err(|e| LibationError::InvalidInput(format!("Invalid callback URL: {}", e)))?;
+
+    if !matcher.matches(&url) {
+        return Err(LibationError::AuthenticationFailed {
+            message: format!(
+                "Unexpected OAuth landing URL: {}{}",
+                url.host_str().unwrap_or_default(),
+                url.path()
+            ),
+            account_id: None,
+        });
+    }
+
+    parse_authorization_callback(callback_url)
+}
+
 /// Token response from Audible OAuth
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenResponse {
@@ -1219,6 +1550,38 @@ pub struct RegistrationResponse {
     pub customer_info: CustomerInfo,
 }
 
+impl RegistrationResponse {
+    /// Build an [`Identity`] from the token exchange result
+    ///
+    /// The access token expiry is computed from `expires_in` relative to now,
+    /// defaulting to one hour if the value cannot be parsed.
+    pub fn to_identity(&self, locale: Locale) -> Result<Identity> {
+        let expires_in = self.bearer.expires_in.trim().parse::<i64>().unwrap_or(3600);
+
+        Ok(Identity {
+            access_token: AccessToken {
+                token: self.bearer.access_token.clone(),
+                expires_at: Utc::now() + chrono::Duration::seconds(expires_in),
+            },
+            refresh_token: self.bearer.refresh_token.clone(),
+            device_private_key: self.mac_dms.device_private_key.clone(),
+            adp_token: self.mac_dms.adp_token.clone(),
+            cookies: self
+                .website_cookies
+                .iter()
+                .map(|c| (c.name.clone(), c.value.clone()))
+                .collect(),
+            device_serial_number: self.device_info.device_serial_number.clone(),
+            device_type: self.device_info.device_type.clone(),
+            device_name: self.device_info.device_name.clone(),
+            amazon_account_id: self.customer_info.user_id.clone(),
+            store_authentication_cookie: self.store_authentication_cookie.cookie.clone(),
+            locale,
+            customer_info: self.customer_info.clone(),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BearerTokenInfo {
     pub access_token: String,
@@ -1255,6 +1618,18 @@ pub struct StoreAuthCookie {
     pub cookie: String,
 }
 
+/// A stored cookie value as it goes into a `Cookie` header
+///
+/// Strips one pair of surrounding double quotes and any whitespace around
+/// them; other values are returned unchanged.
+pub fn cookie_header_value(raw: &str) -> &str {
+    let trimmed = raw.trim();
+    trimmed
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(trimmed)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceInfo {
     pub device_name: String,
@@ -1262,6 +1637,71 @@ pub struct DeviceInfo {
     pub device_type: String,
 }
 
+/// Parsed response from the `/license/token` activation endpoint
+///
+/// The endpoint returns a binary device license. The trailing
+/// [`ACTIVATION_BLOB_SZ`](Self::ACTIVATION_BLOB_SZ) bytes are the activation blob,
+/// whose first four bytes (little-endian) are the activation bytes. Everything before
+/// the blob is kept as-is so extended DRM data can be read later without
+/// re-requesting the license.
+///
+/// # Reference
+/// audible-activator / AudibleApi `GetActivationBytesAsync()` - activation blob layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LicenseTokenResponse {
+    /// Activation bytes as 8-character lowercase hex (e.g., "1a2b3c4d")
+    pub activation_bytes: String,
+
+    /// Trailing activation blob (always `ACTIVATION_BLOB_SZ` bytes)
+    pub activation_blob: Vec<u8>,
+
+    /// Complete response body
+    pub device_license: Vec<u8>,
+
+    /// `Content-Type` header of the response, if present
+    pub content_type: Option<String>,
+}
+
+impl LicenseTokenResponse {
+    /// Size of the activation blob at the end of the device license
+    pub const ACTIVATION_BLOB_SZ: usize = 0x238;
+
+    /// Parse a raw `/license/token` response body
+    ///
+    /// # Errors
+    /// Returns `InvalidApiResponse` if the body is shorter than the activation blob
+    pub fn from_bytes(device_license: Vec<u8>, content_type: Option<String>) -> Result<Self> {
+        if device_license.len() < Self::ACTIVATION_BLOB_SZ {
+            return Err(LibationError::InvalidApiResponse {
+                message: format!("Unexpected activation response size: {} bytes", device_license.len()),
+                response_body: None,
+            });
+        }
+
+        // Activation bytes are at beginning of activation blob (last ACTIVATION_BLOB_SZ bytes)
+        let offset = device_license.len() - Self::ACTIVATION_BLOB_SZ;
+        let activation_blob = device_license[offset..].to_vec();
+        let act_bytes = u32::from_le_bytes([
+            activation_blob[0],
+            activation_blob[1],
+            activation_blob[2],
+            activation_blob[3],
+        ]);
+
+        Ok(Self {
+            activation_bytes: format!("{:08x}", act_bytes),
+            activation_blob,
+            device_license,
+            content_type,
+        })
+    }
+
+    /// Bytes preceding the activation blob (license header / extended DRM data)
+    pub fn license_header(&self) -> &[u8] {
+        &self.device_license[..self.device_license.len() - Self::ACTIVATION_BLOB_SZ]
+    }
+}
+
 /// Exchange authorization code for access and refresh tokens
 ///
 /// After obtaining an authorization code from the callback, this function
@@ -1515,6 +1955,27 @@ pub async fn refresh_access_token(
     refresh_token: &str,
     device_serial: &str,
 ) -> Result<TokenResponse> {
+    let client = reqwest::Client::new();
+    let response = refresh_token_request(&client, locale, refresh_token, device_serial)
+        .send()
+        .await
+        .map_err(|e| LibationError::NetworkError {
+            message: format!("Token refresh request failed: {}", e),
+            is_transient: true,
+        })?;
+
+    parse_refresh_response(response).await
+}
+
+/// Build the `/auth/token` request used by [`refresh_access_token`]
+///
+/// Split out so `AudibleClient` can send it through its own transport.
+pub(crate) fn refresh_token_request(
+    client: &reqwest::Client,
+    locale: &Locale,
+    refresh_token: &str,
+    device_serial: &str,
+) -> reqwest::RequestBuilder {
     let config = OAuthConfig::default();
     let client_id = format!("device:{}#{}", device_serial, config.device_type);
 
@@ -1541,16 +2002,48 @@ pub async fn refresh_access_token(
     form_data.insert("source_token_type".to_string(), "refresh_token".to_string());
     form_data.insert("requested_token_type".to_string(), "access_token".to_string());
 
-    let client = reqwest::Client::new();
-    let response = client
-        .post(&token_url)
-        .form(&form_data)
-        .send()
-        .await
-        .map_err(|e| LibationError::NetworkError {
-            message: format!("Token refresh request failed: {}", e),
-            is_transient: true,
-        })?;
+    client.post(&token_url).form(&form_data)
+}
+
+/// Whether a refused token refresh means the device registration is gone
+///
+/// Amazon answers a refresh with a deregistered device's token with a 4xx and
+/// an `InvalidValue`/`invalid_grant` style error naming the source token.
+/// Server errors and throttling are not treated as revocation.
+fn is_registration_revoked(status: u16, body: &str) -> bool {
+    if !matches!(status, 400 | 401 | 403) {
+        return false;
+    }
+
+    let code = AudibleErrorCode::from_response_body(body).or_else(|| {
+        serde_json::from_str::<serde_json::Value>(body)
+            .ok()?
+            .pointer("/response/error/code")
+            .and_then(|c| c.as_str())
+            .map(AudibleErrorCode::parse)
+    });
+    match code {
+        Some(AudibleErrorCode::InvalidValue | AudibleErrorCode::Unauthorized) => true,
+        Some(AudibleErrorCode::Other(code)) => {
+            matches!(code.as_str(), "invalid_grant" | "invalid_token" | "InvalidSourceToken")
+        }
+        Some(_) => false,
+        None => body.contains("invalid_grant"),
+    }
+}
+
+/// Check and parse the response to a [`refresh_token_request`]
+///
+/// A `429 Too Many Requests` becomes `RateLimitExceeded` carrying the
+/// server's `Retry-After`, so callers can back off instead of retrying.
+pub(crate) async fn parse_refresh_response(response: reqwest::Response) -> Result<TokenResponse> {
+    if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(LibationError::RateLimitExceeded {
+            retry_after_seconds: crate::api::client::parse_retry_after(response.headers())
+                .unwrap_or(crate::api::client::DEFAULT_RETRY_AFTER_SECONDS),
+            endpoint: "/auth/token".to_string(),
+        });
+    }
 
     if !response.status().is_success() {
         let status = response.status();
@@ -1644,21 +2137,8 @@ pub async fn ensure_valid_token(
 
         // Update account with new tokens
         let identity_mut = account.identity.as_mut().unwrap();
-
-        // Calculate expiry time from expires_in (seconds)
-        let expires_at = now + Duration::seconds(token_response.expires_in);
-
-        // Update access token
-        identity_mut.access_token = AccessToken {
-            token: token_response.access_token.clone(),
-            expires_at,
-        };
-
-        // Update refresh token if Amazon returned a new one
-        if let Some(new_refresh_token) = token_response.refresh_token {
-            identity_mut.refresh_token = new_refresh_token;
-            eprintln!("🔑 Received new refresh token from Amazon");
-        }
+        identity_mut.apply_token_response(token_response);
+        let expires_at = identity_mut.access_token.expires_at;
 
         // Serialize updated account
         let updated_json = serde_json::to_string(&account)
@@ -1755,14 +2235,51 @@ pub async fn register_device(
 /// * `access_token` - Valid OAuth access token
 ///
 /// # Returns
-/// Activation bytes as 4-byte hex string (e.g., "1a2b3c4d")
+/// Parsed license token; `activation_bytes` holds the 4-byte hex string (e.g., "1a2b3c4d")
 ///
 /// # Errors
 /// Returns error if API call fails or activation bytes not found
+///
+/// # Concurrency
+/// Calls for the same account that overlap share one request: when several AAX
+/// downloads start together, only the first reaches the endpoint and the rest
+/// wait for its answer. If that request fails, the next waiter tries again.
+/// Nothing is kept once the request completes, so a later call fetches anew.
 pub async fn get_activation_bytes(
     locale: &Locale,
     access_token: &str,
-) -> Result<String> {
+) -> Result<LicenseTokenResponse> {
+    let key = format!("{}\n{}", locale.domain, access_token);
+    single_flight_activation(key, || fetch_activation_bytes(locale, access_token)).await
+}
+
+type ActivationFetch = std::sync::Arc<tokio::sync::OnceCell<LicenseTokenResponse>>;
+
+lazy_static::lazy_static! {
+    /// Activation-bytes requests in progress, by market and access token
+    static ref ACTIVATION_FETCHES: std::sync::Mutex<HashMap<String, ActivationFetch>> =
+        std::sync::Mutex::new(HashMap::new());
+}
+
+/// Run `fetch` unless a fetch for `key` is already in progress, then share its result
+async fn single_flight_activation<F, Fut>(key: String, fetch: F) -> Result<LicenseTokenResponse>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<LicenseTokenResponse>>,
+{
+    let fetches = || ACTIVATION_FETCHES.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let fetch_cell = fetches().entry(key.clone()).or_default().clone();
+    let result = fetch_cell.get_or_try_init(fetch).await.cloned();
+
+    let mut fetches = fetches();
+    if fetches.get(&key).is_some_and(|current| std::sync::Arc::ptr_eq(current, &fetch_cell)) {
+        fetches.remove(&key);
+    }
+    result
+}
+
+async fn fetch_activation_bytes(locale: &Locale, access_token: &str) -> Result<LicenseTokenResponse> {
     // AudibleApi uses the Audible login URI, not API URI
     let api_url = format!(
         "https://www.{}/license/token?action=register&player_manuf=Audible,iPhone&player_model=iPhone",
@@ -1787,9 +2304,16 @@ pub async fn get_activation_bytes(
             message: format!("Failed to retrieve activation bytes (status {}): {}", status, error_body),
             status_code: Some(status.as_u16()),
             endpoint: Some("/license/token".to_string()),
+            error_code: AudibleErrorCode::from_response_body(&error_body),
         });
     }
 
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string());
+
     // Response is a binary blob (activation blob)
     let device_license = response.bytes().await
         .map_err(|e| LibationError::InvalidApiResponse {
@@ -1797,27 +2321,7 @@ pub async fn get_activation_bytes(
             response_body: None,
         })?;
 
-    const ACTIVATION_BLOB_SZ: usize = 0x238;
-
-    if device_license.len() < ACTIVATION_BLOB_SZ {
-        return Err(LibationError::InvalidApiResponse {
-            message: format!("Unexpected activation response size: {} bytes", device_license.len()),
-            response_body: None,
-        });
-    }
-
-    // Activation bytes are at beginning of activation blob (last ACTIVATION_BLOB_SZ bytes)
-    // Extract 4-byte uint from offset: device_license.len() - ACTIVATION_BLOB_SZ
-    let offset = device_license.len() - ACTIVATION_BLOB_SZ;
-    let act_bytes = u32::from_le_bytes([
-        device_license[offset],
-        device_license[offset + 1],
-        device_license[offset + 2],
-        device_license[offset + 3],
-    ]);
-
-    // Return as 8-character lowercase hex string
-    Ok(format!("{:08x}", act_bytes))
+    LicenseTokenResponse::from_bytes(device_license.to_vec(), content_type)
 }
 
 /// Deregister a device from Audible
@@ -1861,6 +2365,7 @@ pub async fn deregister_device(account: &Account) -> Result<()> {
             message: format!("Device deregistration failed (status {}): {}", status, error_body),
             status_code: Some(status.as_u16()),
             endpoint: Some("/1.0/devices/...".to_string()),
+            error_code: AudibleErrorCode::from_response_body(&error_body),
         });
     }
 
@@ -1875,6 +2380,95 @@ pub async fn deregister_device(account: &Account) -> Result<()> {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_activation_bytes_single_flight() {
+        let requests = std::sync::atomic::AtomicUsize::new(0);
+        let fetch = || async {
+            requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            LicenseTokenResponse::from_bytes(vec![0x4d; LicenseTokenResponse::ACTIVATION_BLOB_SZ], None)
+        };
+        let key = || "audible.com\ntoken".to_string();
+
+        let (first, second, third) = tokio::join!(
+            single_flight_activation(key(), fetch),
+            single_flight_activation(key(), fetch),
+            single_flight_activation(key(), fetch),
+        );
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(first.unwrap().activation_bytes, "4d4d4d4d");
+        assert_eq!(second.unwrap(), third.unwrap());
+
+        // Finished fetches are not reused
+        single_flight_activation(key(), fetch).await.unwrap();
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_registration_revoked_detection() {
+        let revoked = r#"{"response":{"error":{"code":"InvalidValue","message":"The request has an invalid parameter : source_token"}}}"#;
+        assert!(is_registration_revoked(400, revoked));
+        assert!(is_registration_revoked(400, r#"{"error":"invalid_grant"}"#));
+
+        // Not a verdict on the registration
+        assert!(!is_registration_revoked(500, revoked));
+        assert!(!is_registration_revoked(429, r#"{"error_code":"Throttled"}"#));
+        assert!(!is_registration_revoked(400, r#"{"error_code":"InvalidParameter"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_verify_registration_without_identity() {
+        let account = Account::new("nobody@example.com".to_string()).unwrap();
+        assert!(!account.verify_registration().await.unwrap());
+    }
+
+    #[test]
+    fn test_registration_response_to_identity() {
+        let response: RegistrationResponse = serde_json::from_value(serde_json::json!({
+            "bearer": { "access_token": "Atna|token", "refresh_token": "Atnr|refresh", "expires_in": "3600" },
+            "mac_dms": { "device_private_key": "KEY", "adp_token": "ADP" },
+            "website_cookies": [{
+                "Name": "session-id", "Value": "123", "Domain": ".amazon.com",
+                "Path": "/", "Expires": "", "Secure": "true", "HttpOnly": "true"
+            }],
+            "store_authentication_cookie": { "cookie": "STORE" },
+            "device_info": { "device_name": "LibriSync", "device_serial_number": "SERIAL", "device_type": "A10KISP2GWF0E4" },
+            "customer_info": {
+                "account_pool": "Amazon", "user_id": "amzn1.account.TEST",
+                "home_region": "NA", "name": "Test User", "given_name": "Test"
+            }
+        }))
+        .unwrap();
+
+        let identity = response.to_identity(Locale::us()).unwrap();
+        assert_eq!(identity.access_token.token, "Atna|token");
+        assert!(!identity.is_expired());
+        assert_eq!(identity.amazon_account_id, "amzn1.account.TEST");
+        assert_eq!(identity.device_serial_number, "SERIAL");
+        assert_eq!(identity.cookies.get("session-id").map(String::as_str), Some("123"));
+        assert_eq!(identity.store_authentication_cookie, "STORE");
+    }
+
+    #[test]
+    fn test_cookie_header_strips_quotes() {
+        let mut identity = Identity::new(
+            AccessToken { token: "Atna|token".to_string(), expires_at: Utc::now() },
+            String::new(),
+            String::new(),
+            String::new(),
+            Locale::us(),
+        );
+        assert_eq!(identity.cookie_header(), None);
+
+        identity.cookies.insert("at-main".to_string(), "\"Atza|abc\"".to_string());
+        identity.cookies.insert("session-id".to_string(), "123".to_string());
+        assert_eq!(identity.cookie_header().unwrap(), "at-main=Atza|abc; session-id=123");
+        // The stored value keeps its quotes
+        assert_eq!(identity.cookies["at-main"], "\"Atza|abc\"");
+
+        assert_eq!(cookie_header_value("\"unterminated"), "\"unterminated");
+    }
+
     // ========== Account Tests ==========
 
     #[test]
@@ -1949,6 +2543,54 @@ mod tests {
         assert!(account.needs_token_refresh());
     }
 
+    #[test]
+    fn test_account_zeroize() {
+        let mut account = Account::new("test@example.com".to_string()).unwrap();
+        account.set_decrypt_key("1a2b3c4d".to_string());
+        let mut identity = Identity::new(
+            AccessToken { token: "token".to_string(), expires_at: Utc::now() },
+            "refresh".to_string(),
+            "key".to_string(),
+            "adp".to_string(),
+            Locale::us(),
+        );
+        identity.cookies.insert("session-id".to_string(), "cookie".to_string());
+
+        let mut wiped = identity.clone();
+        wiped.zeroize();
+        assert!(wiped.access_token.token.is_empty());
+        assert!(wiped.refresh_token.is_empty());
+        assert!(wiped.device_private_key.is_empty());
+        assert!(wiped.cookies.is_empty());
+
+        account.set_identity(identity);
+        account.zeroize();
+        assert!(account.decrypt_key.is_empty());
+        assert!(account.identity.is_none());
+        assert_eq!(account.account_id, "test@example.com");
+    }
+
+    // ========== License Token Tests ==========
+
+    #[test]
+    fn test_license_token_response_from_bytes() {
+        let mut body = b"header".to_vec();
+        let mut blob = vec![0u8; LicenseTokenResponse::ACTIVATION_BLOB_SZ];
+        blob[..4].copy_from_slice(&0x1a2b3c4du32.to_le_bytes());
+        body.extend_from_slice(&blob);
+
+        let token = LicenseTokenResponse::from_bytes(body, Some("application/octet-stream".to_string())).unwrap();
+        assert_eq!(token.activation_bytes, "1a2b3c4d");
+        assert_eq!(token.activation_blob, blob);
+        assert_eq!(token.license_header(), b"header");
+    }
+
+    #[test]
+    fn test_license_token_response_too_short() {
+        let body = vec![0u8; LicenseTokenResponse::ACTIVATION_BLOB_SZ - 1];
+        assert!(LicenseTokenResponse::from_bytes(body, None).is_err());
+    }
+
     // ========== Locale Tests ==========
 
     #[test]
@@ -1958,6 +2600,8 @@ mod tests {
         assert_eq!(locale.domain, "audible.com");
         assert_eq!(locale.name, "United States");
         assert!(locale.with_username);
+        assert_eq!(locale.language, "en-US");
+        assert_eq!(locale.currency, "USD");
     }
 
     #[test]
@@ -2000,6 +2644,25 @@ mod tests {
         assert!(identity.is_expired());
     }
 
+    #[test]
+    fn test_identity_expiry_clock_skew() {
+        let token = AccessToken {
+            token: "test".to_string(),
+            expires_at: Utc::now() + chrono::Duration::minutes(2),
+        };
+        let identity = Identity::new(
+            token,
+            "refresh".to_string(),
+            "key".to_string(),
+            "adp".to_string(),
+            Locale::us(),
+        );
+        assert!(identity.is_expired());
+        assert!(identity.time_until_expiry() < chrono::Duration::zero());
+        assert!(!identity.is_expired_with_skew(chrono::Duration::zero()));
+        assert!(identity.time_until_expiry_with_skew(chrono::Duration::zero()) > chrono::Duration::minutes(1));
+    }
+
     #[test]
     fn test_identity_not_expired() {
         let valid_token = AccessToken {
@@ -2211,6 +2874,47 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_landing_url_per_locale() {
+        assert_eq!(Locale::us().landing_url(), "https://www.amazon.com/ap/maplanding");
+        assert_eq!(Locale::de().landing_url(), "https://www.amazon.de/ap/maplanding");
+        assert_eq!(Locale::jp().landing_url(), "https://www.amazon.co.jp/ap/maplanding");
+        assert_eq!(Locale::uk().oauth_url(), "https://www.amazon.co.uk/ap/signin");
+    }
+
+    #[test]
+    fn test_parse_authorization_callback_for_locale() {
+        let de_callback = "https://www.amazon.de/ap/maplanding?openid.oa2.authorization_code=DE123";
+        assert_eq!(parse_authorization_callback_for_locale(de_callback, &Locale::de()).unwrap(), "DE123");
+
+        let jp_callback = "https://amazon.co.jp/ap/maplanding?openid.oa2.authorization_code=JP456";
+        assert_eq!(parse_authorization_callback_for_locale(jp_callback, &Locale::jp()).unwrap(), "JP456");
+
+        // Landing on another market's host is rejected
+        let result = parse_authorization_callback_for_locale(de_callback, &Locale::us());
+        assert!(matches!(result, Err(LibationError::AuthenticationFailed { .. })));
+
+        // Lookalike hosts and plain http are rejected
+        let matcher = OAuthLandingMatcher::for_locale(&Locale::us());
+        assert!(!matcher.is_landing_url("https://www.amazon.com.evil.example/ap/maplanding?code=X"));
+        assert!(!matcher.is_landing_url("http://www.amazon.com/ap/maplanding?code=X"));
+        assert!(!matcher.is_landing_url("https://www.amazon.com/ap/signin"));
+    }
+
+    #[test]
+    fn test_landing_matcher_custom_host() {
+        let matcher = OAuthLandingMatcher::for_locale(&Locale::au()).with_host("www.amazon.com");
+        assert!(matcher.is_landing_url("https://www.amazon.com.au/ap/maplanding?code=A"));
+        assert!(matcher.is_landing_url("https://www.amazon.com/ap/maplanding?code=A"));
+
+        let code = parse_authorization_callback_with(
+            "https://www.amazon.com/ap/maplanding?code=AU789",
+            &matcher,
+        )
+        .unwrap();
+        assert_eq!(code, "AU789");
+    }
+
     // ========== OAuth Config Tests ==========
 
     #[test]
@@ -2332,9 +3036,9 @@ mod tests {
                         println!("🔓 Retrieving activation bytes...\n");
 
                         match get_activation_bytes(&locale, &token_response.bearer.access_token).await {
-                            Ok(activation_bytes) => {
+                            Ok(license_token) => {
                                 println!("✅ Activation Bytes Retrieved!");
-                                println!("   Activation Bytes: {}\n", activation_bytes);
+                                println!("   Activation Bytes: {}\n", license_token.activation_bytes);
 
                                 println!("🎉 OAuth Flow Complete!");
                                 println!("\n📊 Summary:");
@@ -65,6 +65,10 @@
 //! - Custom headers (User-Agent, Accept, Authorization)
 //! - Timeout and connection pooling configuration
 //!
+//! Requests are sent through an [`HttpTransport`]. By default that is the
+//! `reqwest::Client` itself; tests can inject a transport that returns canned
+//! responses via [`AudibleClient::with_transport`].
+//!
 //! ## Retry Strategy (Ported from Polly - ApiExtended.cs:70-73)
 //! ```csharp
 //! // C# Reference: ApiExtended.cs
@@ -86,6 +90,8 @@
 //! ```
 //! - Implement via tokio::sync::Semaphore for rate limiting
 //! - Process API requests in batches
+//! - Additionally cap requests per minute per account
+//!   (see [`crate::api::rate_limit`]), configurable via `ClientConfig`
 //!
 //! # Audible API Domains (Cdm.Api.cs:127)
 //! ```csharp
@@ -93,17 +99,24 @@
 //!                                   "ca", "fr", "de", "in", "it", "co.jp", "es"];
 //! ```
 
-use crate::error::{LibationError, Result};
+use crate::error::{AudibleErrorCode, LibationError, Result};
 use crate::api::auth::{Account, Identity, Locale};
+use crate::api::license_cache::{LicenseCache, DEFAULT_LICENSE_CACHE_CAPACITY, DEFAULT_LICENSE_CACHE_TTL};
+use crate::api::rate_limit::{RateLimiter, DEFAULT_REQUESTS_PER_MINUTE};
+use crate::backoff::API_BACKOFF;
 use reqwest::{Client, Method, Request, Response, StatusCode};
-use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, ACCEPT, ACCEPT_CHARSET, ACCEPT_LANGUAGE, AUTHORIZATION,
+    CONTENT_TYPE, USER_AGENT,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use futures_util::future::BoxFuture;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{Mutex, Semaphore};
-use tokio::time::sleep;
+use tokio::time::Instant;
 
 /// Maximum number of concurrent requests to the Audible API
 /// Reference: ApiExtended.cs:23
@@ -117,13 +130,29 @@ pub const BATCH_SIZE: usize = 50;
 /// Reference: ApiExtended.cs:70-73 (Polly retry policy)
 const MAX_RETRY_ATTEMPTS: u32 = 3;
 
-/// Initial retry delay in seconds (exponential backoff: 1s, 2s, 4s)
-const INITIAL_RETRY_DELAY_SECS: u64 = 1;
-
 /// Default request timeout in seconds
 /// Reference: NetworkFileStream.cs uses HttpClient default (100 seconds)
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
 
+/// Sends HTTP requests on behalf of an [`AudibleClient`]
+///
+/// Implemented for `reqwest::Client`, which is what real clients use. Tests can
+/// implement it to answer requests from fixtures instead of the network; a
+/// `reqwest::Response` can be built from an `http::Response` with `.into()`.
+///
+/// Auth headers, retries and response parsing stay in `AudibleClient`, so a mock
+/// transport exercises the same code paths as a live one.
+pub trait HttpTransport: Send + Sync + std::fmt::Debug {
+    /// Execute a fully built request
+    fn execute(&self, request: Request) -> BoxFuture<'_, reqwest::Result<Response>>;
+}
+
+impl HttpTransport for Client {
+    fn execute(&self, request: Request) -> BoxFuture<'_, reqwest::Result<Response>> {
+        Box::pin(Client::execute(self, request))
+    }
+}
+
 /// Supported Audible API domains
 /// Reference: Cdm.Api.cs:127
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -222,6 +251,18 @@ pub struct ClientConfig {
     pub max_retries: u32,
     pub user_agent: String,
     pub enable_cookies: bool,
+    /// Per-account request ceiling (None disables client-side rate limiting)
+    pub requests_per_minute: Option<u32>,
+    /// How long download licenses are reused for the same book and quality
+    /// (None requests a new license every time)
+    pub license_cache_ttl: Option<Duration>,
+    /// Language for chapter titles in license and content metadata
+    /// responses, e.g. `de-DE` (None keeps the account's market language)
+    pub chapter_titles_language: Option<String>,
+    /// Header overrides for API calls, applied on top of the standard set
+    /// (see [`AudibleClient::api_headers`]). An empty value removes the
+    /// header instead.
+    pub headers: HashMap<String, String>,
 }
 
 impl Default for ClientConfig {
@@ -232,6 +273,10 @@ impl Default for ClientConfig {
             max_retries: MAX_RETRY_ATTEMPTS,
             user_agent: "Libation/11.3.0 (rust-core)".to_string(),
             enable_cookies: true,
+            requests_per_minute: Some(DEFAULT_REQUESTS_PER_MINUTE),
+            license_cache_ttl: Some(DEFAULT_LICENSE_CACHE_TTL),
+            chapter_titles_language: None,
+            headers: HashMap::new(),
         }
     }
 }
@@ -280,6 +325,27 @@ impl ClientConfigBuilder {
         self
     }
 
+    pub fn requests_per_minute(mut self, requests_per_minute: Option<u32>) -> Self {
+        self.config.requests_per_minute = requests_per_minute;
+        self
+    }
+
+    pub fn license_cache_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.config.license_cache_ttl = ttl;
+        self
+    }
+
+    pub fn chapter_titles_language(mut self, language: Option<String>) -> Self {
+        self.config.chapter_titles_language = language.filter(|l| !l.trim().is_empty());
+        self
+    }
+
+    /// Override (or with an empty value, remove) a header on API calls
+    pub fn header<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.config.headers.insert(name.into(), value.into());
+        self
+    }
+
     pub fn build(self) -> ClientConfig {
         self.config
     }
@@ -317,8 +383,10 @@ impl ClientConfigBuilder {
 /// ```
 #[derive(Debug)]
 pub struct AudibleClient {
-    /// Underlying HTTP client
+    /// Underlying HTTP client, used to build requests
     client: Client,
+    /// Transport that executes requests (the HTTP client unless injected)
+    transport: Arc<dyn HttpTransport>,
     /// Account information with authentication tokens
     account: Arc<Mutex<Account>>,
     /// API base URL (e.g., https://api.audible.com)
@@ -328,6 +396,125 @@ pub struct AudibleClient {
     /// Semaphore for concurrency control
     /// Reference: ApiExtended.cs:23 (MaxConcurrency = 10)
     semaphore: Arc<Semaphore>,
+    /// Requests-per-minute limiter shared with other clients of the same account
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// Recently granted download licenses, shared with other clients of the same account
+    license_cache: Option<Arc<LicenseCache>>,
+    /// Headers sent with every API call, apart from Authorization
+    api_headers: HeaderMap,
+    /// Earliest time another token refresh may be sent, after `/auth/token` answered 429
+    refresh_not_before: Arc<std::sync::Mutex<Option<Instant>>>,
+}
+
+/// Delay assumed when a 429 response carries no usable `Retry-After`
+pub(crate) const DEFAULT_RETRY_AFTER_SECONDS: u64 = 60;
+
+/// Read the `Retry-After` header as a number of seconds
+///
+/// Accepts both forms allowed by RFC 9110: delay-seconds and an HTTP-date.
+/// A date in the past yields `0`.
+///
+/// # Returns
+/// `None` if the header is missing or malformed
+pub(crate) fn parse_retry_after(headers: &HeaderMap) -> Option<u64> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(seconds);
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_seconds().max(0) as u64)
+}
+
+/// Build the shared HTTP client for API calls
+///
+/// Responses are requested with `Accept-Encoding: gzip, deflate, br` and
+/// decompressed transparently; library pages are large JSON and shrink several
+/// times over, which matters on mobile data.
+///
+/// # Reference
+/// Cdm.Api.cs:46, NetworkFileStream.cs:169
+fn build_http_client(config: &ClientConfig) -> Result<Client> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        USER_AGENT,
+        HeaderValue::from_str(&config.user_agent)
+            .map_err(|e| LibationError::InvalidInput(format!("Invalid user agent: {}", e)))?,
+    );
+    headers.insert(
+        ACCEPT,
+        HeaderValue::from_static("application/json"),
+    );
+
+    let mut client_builder = Client::builder()
+        .timeout(config.timeout)
+        .default_headers(headers)
+        .gzip(true)
+        .deflate(true)
+        .brotli(true)
+        .pool_max_idle_per_host(10) // Connection pooling
+        .pool_idle_timeout(Duration::from_secs(90));
+
+    // Enable cookie store if configured
+    // Reference: NetworkFileStream.cs:29 (RequestHeaders for cookies)
+    if config.enable_cookies {
+        client_builder = client_builder.cookie_store(true);
+    }
+
+    Ok(client_builder.build()?)
+}
+
+/// Build the headers sent with every API call
+///
+/// The standard set mirrors what the Audible apps send; requests missing
+/// `client-id` or the market language can come back empty rather than fail.
+/// `config.headers` is applied last, so it can replace or remove any of them.
+///
+/// # Reference
+/// Cdm.Api.cs:147-166, NetworkFileStream.cs:87-96
+///
+/// # Errors
+/// - `InvalidInput` - A header name or value is not valid HTTP
+fn build_api_headers(config: &ClientConfig, locale: Option<&Locale>) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        USER_AGENT,
+        HeaderValue::from_str(&config.user_agent)
+            .map_err(|e| LibationError::InvalidInput(format!("Invalid user agent: {}", e)))?,
+    );
+    headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+    headers.insert(ACCEPT_CHARSET, HeaderValue::from_static("utf-8"));
+    headers.insert(HeaderName::from_static("client-id"), HeaderValue::from_static("0"));
+
+    if let Some(language) = locale.map(|l| l.language.as_str()).filter(|l| !l.is_empty()) {
+        headers.insert(
+            ACCEPT_LANGUAGE,
+            HeaderValue::from_str(language)
+                .map_err(|e| LibationError::InvalidInput(format!("Invalid locale language: {}", e)))?,
+        );
+    }
+
+    for (name, value) in &config.headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| LibationError::InvalidInput(format!("Invalid header name '{}': {}", name, e)))?;
+        if value.is_empty() {
+            headers.remove(&name);
+        } else {
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| LibationError::InvalidInput(format!("Invalid value for header '{}': {}", name, e)))?;
+            headers.insert(name, value);
+        }
+    }
+
+    Ok(headers)
+}
+
+/// Client builder for audio and other binary downloads
+///
+/// Compression is turned off: the audio is already compressed, and a
+/// transparently decoded body would no longer match `Content-Length` or the
+/// byte offsets used for `Range` resumes.
+pub(crate) fn binary_download_client_builder() -> reqwest::ClientBuilder {
+    Client::builder().no_gzip().no_deflate().no_brotli()
 }
 
 impl AudibleClient {
@@ -363,32 +550,8 @@ impl AudibleClient {
             return Err(LibationError::MissingRequiredField("account_id".to_string()));
         }
 
-        // Build HTTP client with configuration
-        // Reference: Cdm.Api.cs:46, NetworkFileStream.cs:169
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            USER_AGENT,
-            HeaderValue::from_str(&config.user_agent)
-                .map_err(|e| LibationError::InvalidInput(format!("Invalid user agent: {}", e)))?,
-        );
-        headers.insert(
-            ACCEPT,
-            HeaderValue::from_static("application/json"),
-        );
-
-        let mut client_builder = Client::builder()
-            .timeout(config.timeout)
-            .default_headers(headers)
-            .pool_max_idle_per_host(10) // Connection pooling
-            .pool_idle_timeout(Duration::from_secs(90));
-
-        // Enable cookie store if configured
-        // Reference: NetworkFileStream.cs:29 (RequestHeaders for cookies)
-        if config.enable_cookies {
-            client_builder = client_builder.cookie_store(true);
-        }
-
-        let client = client_builder.build()?;
+        let client = build_http_client(&config)?;
+        let api_headers = build_api_headers(&config, account.identity.as_ref().map(|i| &i.locale))?;
 
         // Determine base URL from account locale or config domain
         // Reference: Cdm.Api.cs:141 (api.audible.{tld})
@@ -399,16 +562,54 @@ impl AudibleClient {
         };
 
         let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENCY));
+        let rate_limiter = config
+            .requests_per_minute
+            .map(|rpm| RateLimiter::for_account(&account.account_id, rpm));
+        let license_cache = config
+            .license_cache_ttl
+            .map(|ttl| LicenseCache::for_account(&account.account_id, ttl));
 
         Ok(Self {
+            transport: Arc::new(client.clone()),
             client,
             account: Arc::new(Mutex::new(account)),
             base_url,
             config,
             semaphore,
+            rate_limiter,
+            license_cache,
+            api_headers,
+            refresh_not_before: Arc::new(std::sync::Mutex::new(None)),
         })
     }
 
+    /// Create an AudibleClient that sends API requests through `transport`
+    ///
+    /// Intended for tests: requests are built exactly as for a live client
+    /// (base URL, query string, auth headers) and handed to the transport.
+    ///
+    /// # Arguments
+    /// * `account` - Account with valid authentication tokens
+    /// * `config` - Client configuration (timeout, retries, domain, etc.)
+    /// * `transport` - Transport that answers the requests
+    ///
+    /// # Errors
+    /// Same as [`with_config`](Self::with_config)
+    pub fn with_transport(
+        account: Account,
+        config: ClientConfig,
+        transport: Arc<dyn HttpTransport>,
+    ) -> Result<Self> {
+        let mut client = Self::with_config(account, config)?;
+        client.transport = transport;
+        // Licenses from an injected transport must not leak into live clients
+        client.license_cache = client
+            .config
+            .license_cache_ttl
+            .map(|ttl| Arc::new(LicenseCache::new(DEFAULT_LICENSE_CACHE_CAPACITY, ttl)));
+        Ok(client)
+    }
+
     /// Create a builder for custom client configuration
     pub fn builder() -> ClientConfigBuilder {
         ClientConfig::builder()
@@ -419,6 +620,62 @@ impl AudibleClient {
         &self.base_url
     }
 
+    /// Cache of recently granted download licenses, if enabled
+    pub fn license_cache(&self) -> Option<&Arc<LicenseCache>> {
+        self.license_cache.as_ref()
+    }
+
+    /// Headers sent with every API call, apart from Authorization
+    ///
+    /// `User-Agent`, `Accept: application/json`, `Accept-Charset: utf-8`,
+    /// `client-id: 0` and, when the account has a locale, `Accept-Language`,
+    /// followed by any overrides from [`ClientConfig::headers`].
+    pub fn api_headers(&self) -> &HeaderMap {
+        &self.api_headers
+    }
+
+    /// Preferred language for chapter titles, if one was configured
+    pub fn chapter_titles_language(&self) -> Option<&str> {
+        self.config.chapter_titles_language.as_deref()
+    }
+
+    /// Underlying HTTP client, for requests that go outside the API base URL
+    pub(crate) fn http_client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Fetch a text file from outside the API, such as a DASH manifest
+    ///
+    /// No auth headers are sent; the headers are the ones the URL's CDN
+    /// expects. The request goes through the client's transport.
+    ///
+    /// # Errors
+    /// - `NetworkError` - Request could not be sent or the body not read
+    /// - `UnexpectedStatusCode` - Server answered with a non-success status
+    pub(crate) async fn get_external_text(&self, url: &str) -> Result<String> {
+        let mut request = self.client.get(url);
+        for (key, value) in crate::download::cdn::download_headers(url) {
+            request = request.header(key, value);
+        }
+        let response = self
+            .transport
+            .execute(request.build()?)
+            .await
+            .map_err(|e| LibationError::network_error(format!("GET {} failed: {}", url, e), true))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(LibationError::UnexpectedStatusCode {
+                status_code: status.as_u16(),
+                host: response.url().host_str().unwrap_or_default().to_string(),
+            });
+        }
+        response
+            .text()
+            .await
+            .map_err(|e| LibationError::network_error(format!("Reading {} failed: {}", url, e), true))
+    }
+
     /// Perform a GET request
     ///
     /// # Arguments
@@ -433,7 +690,19 @@ impl AudibleClient {
     where
         T: serde::de::DeserializeOwned,
     {
-        self.request(Method::GET, endpoint, None::<&()>).await
+        self.request(Method::GET, endpoint, None::<&()>, HeaderMap::new()).await
+    }
+
+    /// Perform a GET request, replacing some of the standard headers
+    ///
+    /// # Arguments
+    /// * `endpoint` - API endpoint path
+    /// * `headers` - Sent instead of the standard headers of the same name
+    pub async fn get_with_headers<T>(&self, endpoint: &str, headers: HeaderMap) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.request(Method::GET, endpoint, None::<&()>, headers).await
     }
 
     /// Perform a GET request with query parameters
@@ -475,7 +744,21 @@ impl AudibleClient {
         T: serde::de::DeserializeOwned,
         B: Serialize,
     {
-        self.request(Method::POST, endpoint, Some(body)).await
+        self.request(Method::POST, endpoint, Some(body), HeaderMap::new()).await
+    }
+
+    /// Perform a POST request with JSON body, replacing some of the standard headers
+    ///
+    /// # Arguments
+    /// * `endpoint` - API endpoint path
+    /// * `body` - Request body to serialize as JSON
+    /// * `headers` - Sent instead of the standard headers of the same name
+    pub async fn post_with_headers<T, B>(&self, endpoint: &str, body: B, headers: HeaderMap) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+        B: Serialize,
+    {
+        self.request(Method::POST, endpoint, Some(body), headers).await
     }
 
     /// Perform a POST request with form data
@@ -504,19 +787,20 @@ impl AudibleClient {
     ///
     /// # Reference
     /// - Retry policy: ApiExtended.cs:70-73 (Polly library)
-    /// - Token refresh: Need to implement in auth.rs
+    /// - Token refresh: [`refresh_access_token`](crate::api::auth::refresh_access_token)
     ///
     /// # Arguments
     /// * `method` - HTTP method (GET, POST, etc.)
     /// * `endpoint` - API endpoint path
     /// * `body` - Optional request body
+    /// * `overrides` - Headers replacing the standard ones of the same name
     ///
     /// # Returns
     /// Deserialized JSON response of type `T`
     ///
     /// # Errors
     /// Returns error if all retry attempts fail
-    async fn request<T, B>(&self, method: Method, endpoint: &str, body: Option<B>) -> Result<T>
+    async fn request<T, B>(&self, method: Method, endpoint: &str, body: Option<B>, overrides: HeaderMap) -> Result<T>
     where
         T: serde::de::DeserializeOwned,
         B: Serialize,
@@ -524,7 +808,10 @@ impl AudibleClient {
         let url = format!("{}{}", self.base_url, endpoint);
 
         self.request_with_retry(|client, headers| {
-            let mut req_builder = client.request(method.clone(), &url).headers(headers);
+            let mut req_builder = client
+                .request(method.clone(), &url)
+                .headers(headers)
+                .headers(overrides.clone());
 
             if let Some(ref b) = body {
                 req_builder = req_builder.json(b);
@@ -551,9 +838,10 @@ impl AudibleClient {
     /// - 5xx server errors (temporary server issues)
     /// - 429 Rate Limiting (with respect to Retry-After header)
     /// - 401 Unauthorized (attempt token refresh once)
+    /// - 409 Conflict (another session is active; one retry after a short wait)
     ///
     /// No retry on:
-    /// - 4xx client errors (except 401, 429)
+    /// - 4xx client errors (except 401, 409, 429)
     /// - Successful responses (2xx)
     async fn request_with_retry<T, F>(&self, request_builder: F) -> Result<T>
     where
@@ -562,6 +850,7 @@ impl AudibleClient {
     {
         let mut attempts = 0;
         let mut last_error = None;
+        let mut conflict_retried = false;
 
         // Acquire semaphore permit for concurrency control
         // Reference: ApiExtended.cs:91 (semaphore.WaitAsync())
@@ -572,9 +861,18 @@ impl AudibleClient {
         while attempts < self.config.max_retries {
             attempts += 1;
 
+            // Retries count against the per-minute budget too
+            if let Some(ref limiter) = self.rate_limiter {
+                limiter.acquire().await;
+            }
+
             // Get fresh headers with current auth token
             let headers = match self.build_auth_headers().await {
-                Ok(h) => h,
+                Ok(auth) => {
+                    let mut headers = self.api_headers.clone();
+                    headers.extend(auth);
+                    headers
+                }
                 Err(e) => {
                     last_error = Some(e);
                     break; // Auth error - don't retry
@@ -584,7 +882,7 @@ impl AudibleClient {
             // Build and send request
             let request = request_builder(&self.client, headers).build()?;
 
-            match self.client.execute(request).await {
+            match self.transport.execute(request).await {
                 Ok(response) => {
                     let status = response.status();
 
@@ -597,6 +895,9 @@ impl AudibleClient {
                         // 401 Unauthorized - try token refresh once
                         StatusCode::UNAUTHORIZED if attempts == 1 => {
                             if let Err(e) = self.refresh_tokens().await {
+                                if e.retry_after_seconds().is_some() {
+                                    return Err(e);
+                                }
                                 return Err(LibationError::auth_failed(
                                     "Token refresh failed",
                                     Some(self.account.lock().await.account_id.clone()),
@@ -606,6 +907,18 @@ impl AudibleClient {
                             continue;
                         }
 
+                        // 409 Conflict - usually clears once the other session's
+                        // request finishes, so wait briefly and try once more
+                        StatusCode::CONFLICT if !conflict_retried && attempts < self.config.max_retries => {
+                            conflict_retried = true;
+                            eprintln!(
+                                "Session conflict on {}, retrying once",
+                                self.extract_endpoint_from_url(response.url().as_str())
+                            );
+                            API_BACKOFF.wait(0).await;
+                            continue;
+                        }
+
                         // 429 Rate Limiting - respect Retry-After header
                         StatusCode::TOO_MANY_REQUESTS => {
                             let retry_after = self.extract_retry_after(&response);
@@ -625,9 +938,7 @@ impl AudibleClient {
                                 Some(endpoint),
                             ));
 
-                            // Exponential backoff: 1s, 2s, 4s...
-                            let delay = Duration::from_secs(INITIAL_RETRY_DELAY_SECS * 2_u64.pow(attempts - 1));
-                            sleep(delay).await;
+                            API_BACKOFF.wait(attempts - 1).await;
                             continue;
                         }
 
@@ -645,8 +956,7 @@ impl AudibleClient {
                         true,
                     ));
 
-                    let delay = Duration::from_secs(INITIAL_RETRY_DELAY_SECS * 2_u64.pow(attempts - 1));
-                    sleep(delay).await;
+                    API_BACKOFF.wait(attempts - 1).await;
                     continue;
                 }
 
@@ -666,6 +976,7 @@ impl AudibleClient {
                 message: format!("Request failed after {} attempts", attempts),
                 status_code: None,
                 endpoint: None,
+                error_code: None,
             }
         }))
     }
@@ -703,6 +1014,7 @@ impl AudibleClient {
             message: format!("Failed to read response body: {}", e),
             status_code: Some(status.as_u16()),
             endpoint: Some(url.path().to_string()),
+            error_code: None,
         })?;
 
         match serde_json::from_str::<T>(&response_text) {
@@ -726,16 +1038,31 @@ impl AudibleClient {
     }
 
     /// Handle error HTTP response
+    ///
+    /// A `409` status or a `Conflict` error code becomes `SessionConflict`.
     async fn handle_error_response<T>(&self, response: Response) -> Result<T> {
         let status = response.status();
         let url = response.url().clone();
         let error_body = response.text().await.unwrap_or_default();
+        let error_code = AudibleErrorCode::from_response_body(&error_body);
+
+        if status == StatusCode::CONFLICT || error_code == Some(AudibleErrorCode::Conflict) {
+            let message = serde_json::from_str::<Value>(&error_body)
+                .ok()
+                .and_then(|json| json.get("message").and_then(|m| m.as_str()).map(str::to_string))
+                .unwrap_or_else(|| format!("HTTP {}", status.as_u16()));
+            return Err(LibationError::SessionConflict {
+                message,
+                endpoint: Some(self.extract_endpoint_from_url(url.as_str())),
+            });
+        }
 
-        Err(LibationError::api_failed(
-            format!("API request failed: {}", error_body),
-            Some(status.as_u16()),
-            Some(self.extract_endpoint_from_url(url.as_str())),
-        ))
+        Err(LibationError::ApiRequestFailed {
+            message: format!("API request failed: {}", error_body),
+            status_code: Some(status.as_u16()),
+            endpoint: Some(self.extract_endpoint_from_url(url.as_str())),
+            error_code,
+        })
     }
 
     /// Check if a network error is retryable
@@ -747,12 +1074,7 @@ impl AudibleClient {
 
     /// Extract retry-after delay from response headers (in seconds)
     fn extract_retry_after(&self, response: &Response) -> u64 {
-        response
-            .headers()
-            .get("retry-after")
-            .and_then(|v| v.to_str().ok())
-            .and_then(|s| s.parse::<u64>().ok())
-            .unwrap_or(60) // Default to 60 seconds
+        parse_retry_after(response.headers()).unwrap_or(DEFAULT_RETRY_AFTER_SECONDS)
     }
 
     /// Extract endpoint path from full URL
@@ -764,12 +1086,60 @@ impl AudibleClient {
 
     /// Refresh authentication tokens
     ///
-    /// TODO: Implement token refresh logic in auth.rs
-    /// Reference: Need to implement refresh token flow
-    async fn refresh_tokens(&self) -> Result<()> {
-        // TODO: Port token refresh logic from C# Identity class
-        // For now, return NotImplemented error
-        Err(LibationError::not_implemented("Token refresh not yet implemented"))
+    /// Exchanges the account's refresh token for a new access token and stores
+    /// it on the shared account, so requests made afterwards use it. The
+    /// request goes through the client's transport.
+    ///
+    /// If Amazon throttled the previous refresh, no request is sent until its
+    /// `Retry-After` has elapsed; calls in the meantime fail straight away with
+    /// the remaining delay.
+    ///
+    /// # Errors
+    /// - `AuthenticationFailed` - No identity, or Amazon rejected the refresh token
+    /// - `RateLimitExceeded` - Refreshing is throttled; retry after the given delay
+    /// - `NetworkError` - Token request could not be sent
+    pub(crate) async fn refresh_tokens(&self) -> Result<()> {
+        if let Some(not_before) = *self.refresh_not_before.lock().unwrap() {
+            let remaining = not_before.saturating_duration_since(Instant::now());
+            if !remaining.is_zero() {
+                return Err(LibationError::RateLimitExceeded {
+                    retry_after_seconds: remaining.as_secs_f64().ceil() as u64,
+                    endpoint: "/auth/token".to_string(),
+                });
+            }
+        }
+
+        let mut account = self.account.lock().await;
+        let account_id = account.account_id.clone();
+        let identity = account.identity.as_mut().ok_or_else(|| {
+            LibationError::auth_failed("No identity tokens to refresh", Some(account_id.clone()))
+        })?;
+
+        let request = crate::api::auth::refresh_token_request(
+            &self.client,
+            &identity.locale,
+            &identity.refresh_token,
+            &identity.device_serial_number,
+        )
+        .build()?;
+
+        let response = self.transport.execute(request).await.map_err(|e| {
+            LibationError::network_error(format!("Token refresh request failed: {}", e), true)
+        })?;
+        let token_response = match crate::api::auth::parse_refresh_response(response).await {
+            Ok(token_response) => token_response,
+            Err(e) => {
+                if let Some(seconds) = e.retry_after_seconds() {
+                    *self.refresh_not_before.lock().unwrap() =
+                        Some(Instant::now() + Duration::from_secs(seconds));
+                }
+                return Err(e);
+            }
+        };
+        *self.refresh_not_before.lock().unwrap() = None;
+
+        identity.apply_token_response(token_response);
+        Ok(())
     }
 
     /// Download file with progress callback
@@ -833,12 +1203,152 @@ impl AudibleClient {
     pub fn account(&self) -> Arc<Mutex<Account>> {
         Arc::clone(&self.account)
     }
+
+    /// Send further API calls to another marketplace
+    ///
+    /// Switches the API host and `Accept-Language`, and stores `locale` in the
+    /// account's identity so the account, once saved, keeps using it.
+    ///
+    /// # Errors
+    /// - `InvalidInput` - The locale's language is not a valid header value
+    pub async fn set_locale(&mut self, locale: Locale) -> Result<()> {
+        self.api_headers = build_api_headers(&self.config, Some(&locale))?;
+        self.base_url = locale.api_url();
+        if let Some(identity) = self.account.lock().await.identity.as_mut() {
+            identity.locale = locale;
+        }
+        Ok(())
+    }
+}
+
+/// Answer client requests from canned responses in tests
+#[cfg(test)]
+pub(crate) mod fixture {
+    use super::{AudibleClient, ClientConfig, HttpTransport};
+    use crate::api::auth::Account;
+    use futures_util::future::BoxFuture;
+    use reqwest::header::HeaderMap;
+    use reqwest::{Request, Response, Url};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// A request as a [`CannedTransport`] saw it
+    #[derive(Debug, Clone)]
+    pub(crate) struct SentRequest {
+        pub url: Url,
+        pub headers: HeaderMap,
+        pub body: String,
+    }
+
+    impl SentRequest {
+        /// Path plus query, e.g. `/1.0/library?page=2`
+        pub fn path_and_query(&self) -> String {
+            format!("{}?{}", self.url.path(), self.url.query().unwrap_or_default())
+        }
+
+        /// Value of query parameter `key`, if present
+        pub fn query(&self, key: &str) -> Option<String> {
+            self.url.query_pairs().find(|(k, _)| k == key).map(|(_, v)| v.into_owned())
+        }
+    }
+
+    type Responder = Box<dyn Fn(&SentRequest) -> http::Response<String> + Send + Sync>;
+
+    /// Transport answering each request with whatever its responder returns
+    ///
+    /// Every request is recorded, and so is the most requests answered at once.
+    pub(crate) struct CannedTransport {
+        respond: Responder,
+        delay: Option<Duration>,
+        sent: Mutex<Vec<SentRequest>>,
+        /// Requests currently being answered, and the most seen at once
+        in_flight: Mutex<(usize, usize)>,
+    }
+
+    impl CannedTransport {
+        pub fn new(respond: impl Fn(&SentRequest) -> http::Response<String> + Send + Sync + 'static) -> Self {
+            Self {
+                respond: Box::new(respond),
+                delay: None,
+                sent: Mutex::new(Vec::new()),
+                in_flight: Mutex::new((0, 0)),
+            }
+        }
+
+        /// Answer every request with the same status and body
+        pub fn fixed(status: u16, body: impl Into<String>) -> Self {
+            let body = body.into();
+            Self::new(move |_| respond(status, body.clone()))
+        }
+
+        /// Hold each response for `delay`, so concurrent requests overlap
+        pub fn with_delay(mut self, delay: Duration) -> Self {
+            self.delay = Some(delay);
+            self
+        }
+
+        /// Requests received so far, oldest first
+        pub fn sent(&self) -> Vec<SentRequest> {
+            self.sent.lock().unwrap().clone()
+        }
+
+        /// Most requests that were being answered at the same time
+        pub fn max_in_flight(&self) -> usize {
+            self.in_flight.lock().unwrap().1
+        }
+    }
+
+    impl std::fmt::Debug for CannedTransport {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("CannedTransport").field("sent", &self.sent.lock().unwrap().len()).finish()
+        }
+    }
+
+    impl HttpTransport for CannedTransport {
+        fn execute(&self, request: Request) -> BoxFuture<'_, reqwest::Result<Response>> {
+            let sent = SentRequest {
+                url: request.url().clone(),
+                headers: request.headers().clone(),
+                body: request
+                    .body()
+                    .and_then(|b| b.as_bytes())
+                    .map(|b| String::from_utf8_lossy(b).to_string())
+                    .unwrap_or_default(),
+            };
+            let response = (self.respond)(&sent);
+            self.sent.lock().unwrap().push(sent);
+            Box::pin(async move {
+                if let Some(delay) = self.delay {
+                    {
+                        let mut in_flight = self.in_flight.lock().unwrap();
+                        in_flight.0 += 1;
+                        in_flight.1 = in_flight.1.max(in_flight.0);
+                    }
+                    tokio::time::sleep(delay).await;
+                    self.in_flight.lock().unwrap().0 -= 1;
+                }
+                Ok(response.into())
+            })
+        }
+    }
+
+    /// Response with `status` and `body`
+    pub(crate) fn respond(status: u16, body: impl Into<String>) -> http::Response<String> {
+        http::Response::builder().status(status).body(body.into()).unwrap()
+    }
+
+    /// Client for a fresh account `email` whose requests go to `transport`
+    pub(crate) fn canned_client(email: &str, transport: Arc<CannedTransport>) -> AudibleClient {
+        let account = Account::new(email.to_string()).unwrap();
+        AudibleClient::with_transport(account, ClientConfig::default(), transport).unwrap()
+    }
 }
 
 // ===== TESTS =====
 
 #[cfg(test)]
 mod tests {
+    use super::fixture::{respond, CannedTransport};
     use super::*;
 
     #[test]
@@ -866,6 +1376,7 @@ mod tests {
             .max_retries(5)
             .user_agent("TestAgent/1.0")
             .enable_cookies(false)
+            .requests_per_minute(Some(30))
             .build();
 
         assert_eq!(config.domain, AudibleDomain::Uk);
@@ -873,6 +1384,7 @@ mod tests {
         assert_eq!(config.max_retries, 5);
         assert_eq!(config.user_agent, "TestAgent/1.0");
         assert_eq!(config.enable_cookies, false);
+        assert_eq!(config.requests_per_minute, Some(30));
     }
 
     #[tokio::test]
@@ -889,4 +1401,169 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), LibationError::MissingRequiredField(_)));
     }
+
+    #[tokio::test]
+    async fn test_api_calls_send_standard_headers() {
+        let transport = Arc::new(CannedTransport::fixed(200, "{}"));
+        let account = Account::new("headers@example.com".to_string()).unwrap();
+        let config = ClientConfig::builder()
+            .header("client-id", "42")
+            .header("Accept-Charset", "")
+            .header("x-custom", "yes")
+            .build();
+        let client = AudibleClient::with_transport(account, config, transport.clone()).unwrap();
+
+        let _: Value = client.get("/1.0/library").await.unwrap();
+
+        let sent = &transport.sent()[0].headers;
+        assert_eq!(sent[ACCEPT], "application/json");
+        assert_eq!(sent[USER_AGENT], "Libation/11.3.0 (rust-core)");
+        assert_eq!(sent["client-id"], "42");
+        assert_eq!(sent["x-custom"], "yes");
+        assert!(!sent.contains_key(ACCEPT_CHARSET));
+    }
+
+    #[test]
+    fn test_api_headers_use_locale_language() {
+        let headers = build_api_headers(&ClientConfig::default(), Some(&Locale::de())).unwrap();
+        assert_eq!(headers[ACCEPT_LANGUAGE], "de-DE");
+        assert_eq!(headers["client-id"], "0");
+
+        let bad = ClientConfig::builder().header("bad header", "x").build();
+        assert!(matches!(build_api_headers(&bad, None), Err(LibationError::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_error_response_carries_audible_error_code() {
+        let transport = Arc::new(CannedTransport::fixed(
+            400,
+            r#"{"error_code": "DownloadLimitExceeded", "message": "Too many downloads"}"#,
+        ));
+        let account = Account::new("codes@example.com".to_string()).unwrap();
+        let client = AudibleClient::with_transport(account, ClientConfig::default(), transport).unwrap();
+
+        let err = client.get::<serde_json::Value>("/1.0/content/B0TEST/licenserequest").await.unwrap_err();
+        assert_eq!(err.audible_error_code(), Some(&AudibleErrorCode::DownloadLimitExceeded));
+        assert!(err.user_message().contains("download limit"));
+
+        assert_eq!(
+            AudibleErrorCode::from_response_body(r#"{"errors": [{"code": "NotFoundError"}]}"#),
+            Some(AudibleErrorCode::NotFound)
+        );
+        assert_eq!(
+            AudibleErrorCode::from_response_body(r#"{"code": "SomethingNew"}"#),
+            Some(AudibleErrorCode::Other("SomethingNew".to_string()))
+        );
+        assert_eq!(AudibleErrorCode::from_response_body("<html>"), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttled_refresh_backs_off() {
+        let mut account = Account::new("throttled@example.com".to_string()).unwrap();
+        account.set_identity(Identity::new(
+            crate::api::auth::AccessToken { token: "old".to_string(), expires_at: chrono::Utc::now() },
+            "refresh".to_string(),
+            "key".to_string(),
+            "adp".to_string(),
+            Locale::us(),
+        ));
+        let transport = Arc::new(CannedTransport::new(|_| {
+            let mut response = respond(429, "");
+            response.headers_mut().insert("Retry-After", HeaderValue::from_static("120"));
+            response
+        }));
+        let client = AudibleClient::with_transport(account, ClientConfig::default(), transport.clone()).unwrap();
+        let calls = || transport.sent().len();
+
+        let err = client.refresh_tokens().await.unwrap_err();
+        assert_eq!(err.retry_after_seconds(), Some(120));
+        assert_eq!(calls(), 1);
+
+        // Still inside the window: no request goes out
+        tokio::time::advance(Duration::from_secs(100)).await;
+        let err = client.refresh_tokens().await.unwrap_err();
+        assert_eq!(err.retry_after_seconds(), Some(20));
+        assert_eq!(calls(), 1);
+
+        tokio::time::advance(Duration::from_secs(20)).await;
+        client.refresh_tokens().await.unwrap_err();
+        assert_eq!(calls(), 2);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+        assert_eq!(parse_retry_after(&headers), Some(0));
+        headers.insert("retry-after", HeaderValue::from_static("soon"));
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_session_conflict_is_retried_once() {
+        let account = || Account::new("conflict@example.com".to_string()).unwrap();
+
+        // Answers `409 Conflict` for the first `conflicts` requests, then `200`
+        let conflict_then_ok = |conflicts: usize| {
+            let calls = std::sync::atomic::AtomicUsize::new(0);
+            Arc::new(CannedTransport::new(move |_| {
+                if calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) < conflicts {
+                    respond(409, r#"{"message": "Another request is in progress"}"#)
+                } else {
+                    respond(200, "{}")
+                }
+            }))
+        };
+
+        let transient = conflict_then_ok(1);
+        let client = AudibleClient::with_transport(account(), ClientConfig::default(), transient.clone()).unwrap();
+        let _: Value = client.get("/1.0/library").await.unwrap();
+        assert_eq!(transient.sent().len(), 2);
+
+        let stuck = conflict_then_ok(usize::MAX);
+        let client = AudibleClient::with_transport(account(), ClientConfig::default(), stuck.clone()).unwrap();
+        let err = client.get::<Value>("/1.0/library").await.unwrap_err();
+        assert_eq!(stuck.sent().len(), 2);
+        assert!(matches!(
+            &err,
+            LibationError::SessionConflict { message, .. } if message == "Another request is in progress"
+        ));
+        assert!(err.is_retryable());
+        assert!(err.user_message().contains("another session"));
+    }
+
+    #[tokio::test]
+    async fn test_http_client_negotiates_compression() {
+        use std::io::{Read, Write};
+
+        // gzip of {"items":["B001","B002"]}
+        const GZIP_BODY: [u8; 42] = [
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xab, 0x56, 0xca, 0x2c, 0x49, 0xcd,
+            0x2d, 0x56, 0xb2, 0x8a, 0x56, 0x72, 0x32, 0x30, 0x30, 0x54, 0xd2, 0x01, 0x51, 0x46, 0x4a, 0xb1,
+            0xb5, 0x00, 0x4f, 0x26, 0x20, 0x1a, 0x19, 0x00, 0x00, 0x00,
+        ];
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/1.0/library", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 4096];
+            let read = stream.read(&mut request).unwrap();
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                GZIP_BODY.len()
+            );
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(&GZIP_BODY).unwrap();
+            String::from_utf8_lossy(&request[..read]).to_lowercase()
+        });
+
+        let client = build_http_client(&ClientConfig::default()).unwrap();
+        let body: Value = client.get(&url).send().await.unwrap().json().await.unwrap();
+        assert_eq!(body["items"][1], "B002");
+
+        let request = server.join().unwrap();
+        let accept_encoding = request
+            .lines()
+            .find(|line| line.starts_with("accept-encoding:"))
+            .expect("no Accept-Encoding header");
+        assert!(accept_encoding.contains("gzip") && accept_encoding.contains("br"));
+    }
 }
@@ -0,0 +1,215 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! User-created library collections
+//!
+//! Libation does not read collections; this module lets the app mirror the
+//! shelves a user made in the Audible app.
+//!
+//! # Reference C# Sources
+//! - None. The endpoints follow the official apps
+//!
+//! # API Endpoints
+//! `GET https://api.audible.{domain}/1.0/collections`
+//!
+//! Lists the user's collections, including the built-in `__FAVORITES` and
+//! `__ARCHIVE` ones.
+//!
+//! `GET https://api.audible.{domain}/1.0/collections/{collection_id}/items`
+//!
+//! **Query Parameters:**
+//! - `page_size` - Items per page (max 50)
+//! - `continuation_token` - Token from the previous page, absent for the first
+
+use crate::api::client::AudibleClient;
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+
+/// Largest page size the collection items endpoint accepts
+pub const MAX_COLLECTION_ITEMS_PER_PAGE: i32 = 50;
+
+/// A collection in the user's library
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Collection {
+    pub collection_id: String,
+
+    #[serde(default)]
+    pub name: String,
+
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Pinned to the top of the library in the Audible app
+    #[serde(default)]
+    pub is_pinned: bool,
+
+    /// Creation date as sent by the API (ISO 8601)
+    #[serde(default)]
+    pub creation_date: Option<String>,
+}
+
+impl Collection {
+    /// Whether this is one of Audible's own collections, such as favorites
+    pub fn is_builtin(&self) -> bool {
+        self.collection_id.starts_with("__")
+    }
+}
+
+/// A book in a collection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectionItem {
+    pub asin: String,
+
+    /// When the book was added, as sent by the API (ISO 8601)
+    #[serde(default, alias = "membership_date")]
+    pub added_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionsResponse {
+    #[serde(default)]
+    collections: Vec<Collection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionItemsResponse {
+    #[serde(default)]
+    items: Vec<CollectionItem>,
+    #[serde(default)]
+    continuation_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CollectionItemsQuery<'a> {
+    page_size: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    continuation_token: Option<&'a str>,
+}
+
+impl AudibleClient {
+    /// Get the user's collections
+    ///
+    /// # Returns
+    /// Every collection, built-in ones included; see [`Collection::is_builtin`]
+    ///
+    /// # Errors
+    /// - `ApiRequestFailed` - API request failed
+    /// - `InvalidApiResponse` - Response could not be parsed
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::api::client::AudibleClient;
+    /// # async fn example(client: AudibleClient) -> rust_core::error::Result<()> {
+    /// for collection in client.get_collections().await?.iter().filter(|c| !c.is_builtin()) {
+    ///     let items = client.get_collection_items(&collection.collection_id).await?;
+    ///     println!("{}: {} books", collection.name, items.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_collections(&self) -> Result<Vec<Collection>> {
+        let response: CollectionsResponse = self.get("/1.0/collections").await?;
+        Ok(response.collections)
+    }
+
+    /// Get every book in a collection
+    ///
+    /// Follows the continuation token until the last page.
+    ///
+    /// # Arguments
+    /// * `collection_id` - ID from [`get_collections`](Self::get_collections)
+    ///
+    /// # Returns
+    /// The collection's books in the order the API lists them
+    ///
+    /// # Errors
+    /// - `ApiRequestFailed` - API request failed, e.g. the collection was deleted
+    /// - `InvalidApiResponse` - Response could not be parsed
+    pub async fn get_collection_items(&self, collection_id: &str) -> Result<Vec<CollectionItem>> {
+        let endpoint = format!("/1.0/collections/{}/items", collection_id);
+        let mut items = Vec::new();
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let query = CollectionItemsQuery {
+                page_size: MAX_COLLECTION_ITEMS_PER_PAGE,
+                continuation_token: continuation_token.as_deref(),
+            };
+            let page: CollectionItemsResponse = self.get_with_query(&endpoint, &query).await?;
+            let page_len = page.items.len();
+            items.extend(page.items);
+
+            match page.continuation_token.filter(|token| !token.is_empty()) {
+                // An empty page with a token would loop forever
+                Some(token) if page_len > 0 => continuation_token = Some(token),
+                _ => break,
+            }
+        }
+
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::client::fixture::{canned_client, respond, CannedTransport, SentRequest};
+    use std::sync::Arc;
+
+    /// One user collection whose items come in two pages
+    fn canned_collections(request: &SentRequest) -> http::Response<String> {
+        let body = if request.url.path() == "/1.0/collections" {
+            serde_json::json!({
+                "collections": [
+                    { "collection_id": "__FAVORITES", "name": "Favorites" },
+                    { "collection_id": "c1", "name": "Road trip", "is_pinned": true }
+                ]
+            })
+        } else if request.query("continuation_token").is_some() {
+            serde_json::json!({ "items": [{ "asin": "B003" }] })
+        } else {
+            serde_json::json!({
+                "items": [{ "asin": "B001", "membership_date": "2024-05-01" }, { "asin": "B002" }],
+                "continuation_token": "next"
+            })
+        };
+        respond(200, body.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_get_collections() {
+        let transport = Arc::new(CannedTransport::new(canned_collections));
+        let client = canned_client("collections@example.com", transport.clone());
+
+        let collections = client.get_collections().await.unwrap();
+        assert_eq!(collections.len(), 2);
+        assert!(collections[0].is_builtin());
+        assert!(!collections[1].is_builtin() && collections[1].is_pinned);
+
+        let items = client.get_collection_items("c1").await.unwrap();
+        let asins: Vec<_> = items.iter().map(|i| i.asin.as_str()).collect();
+        assert_eq!(asins, ["B001", "B002", "B003"]);
+        assert_eq!(items[0].added_date.as_deref(), Some("2024-05-01"));
+
+        assert_eq!(
+            transport.sent()[2].path_and_query(),
+            "/1.0/collections/c1/items?page_size=50&continuation_token=next"
+        );
+    }
+}
@@ -64,6 +64,15 @@
 //!
 //! Reference: ApiExtended.cs:206 - Uses CatalogOptions.ResponseGroupOptions for batch queries
 //!
+//! ## Catalog Search
+//! **GET** `/1.0/catalog/products`
+//!
+//! Query parameters:
+//! - `keywords` - Free-text search
+//! - `num_results` - Page size
+//! - `products_sort_by` - Ranking (e.g., `Relevance`)
+//! - `response_groups` / `image_sizes` - Same as single product
+//!
 //! # Content Metadata Endpoint
 //! **GET** `/1.0/content/{asin}/metadata`
 //!
@@ -76,7 +85,9 @@
 //! Reference: DownloadOptions.Factory.cs:33 - api.GetContentMetadataAsync()
 
 use crate::error::{LibationError, Result};
+use crate::api::auth::Locale;
 use crate::api::client::AudibleClient;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT_LANGUAGE};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc, NaiveDate};
@@ -159,7 +170,9 @@ pub enum Codec {
 ///
 /// C# enum: DownloadQuality (Normal, High, Extreme)
 /// API values: "Normal", "High", "Extreme"
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Variants are ordered from lowest to highest quality.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum DownloadQuality {
     /// Low quality (~32 kbps AAC)
     #[serde(rename = "Low")]
@@ -206,17 +219,20 @@ pub enum ChapterTitlesType {
 /// - StartOffsetSec (int)
 /// - LengthMs (long)
 /// - Chapters (List<Chapter>?) - For hierarchical chapters
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Chapter {
     /// Chapter title
     pub title: String,
 
     /// Start offset in milliseconds from beginning of audiobook
-    #[serde(rename = "start_offset_ms")]
+    ///
+    /// Some metadata variants only send `start_offset_sec`; the offset is then
+    /// filled in from it when the [`ChapterInfo`] is deserialized.
+    #[serde(rename = "start_offset_ms", default)]
     pub start_offset_ms: i64,
 
     /// Start offset in seconds (convenience field)
-    #[serde(rename = "start_offset_sec")]
+    #[serde(rename = "start_offset_sec", default)]
     pub start_offset_sec: i32,
 
     /// Chapter duration in milliseconds
@@ -238,7 +254,12 @@ pub struct Chapter {
 /// - Chapters (List<Chapter>)
 /// - IsAccurate (bool)
 /// - RuntimeLengthMs (long)
+///
+/// Chapter offsets and lengths are always in milliseconds. Variants of the
+/// metadata that give them in seconds are converted while deserializing, see
+/// [`ChapterInfo::normalize_offsets`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "RawChapterInfo")]
 pub struct ChapterInfo {
     /// Audible brand intro duration (in milliseconds)
     /// Can be stripped if configured
@@ -265,6 +286,276 @@ pub struct ChapterInfo {
     pub runtime_length_ms: i64,
 }
 
+/// `ChapterInfo` as sent by the API, before offsets are normalized
+#[derive(Deserialize)]
+struct RawChapterInfo {
+    #[serde(rename = "brandIntroDurationMs", default)]
+    brand_intro_duration_ms: i32,
+    #[serde(rename = "brandOutroDurationMs", default)]
+    brand_outro_duration_ms: i32,
+    #[serde(rename = "chapters", default)]
+    chapters: Vec<Chapter>,
+    #[serde(rename = "isAccurate", default)]
+    is_accurate: bool,
+    #[serde(rename = "runtimeLengthMs")]
+    runtime_length_ms: i64,
+}
+
+impl From<RawChapterInfo> for ChapterInfo {
+    fn from(raw: RawChapterInfo) -> Self {
+        let mut info = ChapterInfo {
+            brand_intro_duration_ms: raw.brand_intro_duration_ms,
+            brand_outro_duration_ms: raw.brand_outro_duration_ms,
+            chapters: raw.chapters,
+            is_accurate: raw.is_accurate,
+            runtime_length_ms: raw.runtime_length_ms,
+        };
+        if info.normalize_offsets() {
+            eprintln!(
+                "Warning: chapter offsets were given in seconds, converted to milliseconds ({} chapters)",
+                info.chapters.len()
+            );
+        }
+        info
+    }
+}
+
+/// Allowed difference between chapter coverage and the reported runtime
+///
+/// Audible rounds chapter lengths independently, so small drift is normal.
+pub const CHAPTER_RUNTIME_TOLERANCE_MS: i64 = 2000;
+
+/// How far chapters read as seconds may end from the runtime and still be
+/// taken for seconds, as a fraction of the runtime
+///
+/// Loose enough to allow for brand audio outside the chapters, tight enough
+/// that real millisecond offsets of a short book are never scaled.
+const SECONDS_OFFSET_TOLERANCE: f64 = 0.05;
+
+impl ChapterInfo {
+    /// Make sure every chapter offset and length is in milliseconds
+    ///
+    /// Called when deserializing, so only chapter info built by hand needs it.
+    ///
+    /// - A chapter with only `start_offset_sec` gets `start_offset_ms` from it.
+    /// - If the chapters, read as seconds, end within
+    ///   [`SECONDS_OFFSET_TOLERANCE`] of `runtime_length_ms`, offsets and
+    ///   lengths were given in seconds and are multiplied by 1000. Real
+    ///   millisecond offsets end near the runtime itself, a thousand times
+    ///   further out, so the two cannot be confused.
+    ///
+    /// Without a runtime the unit cannot be checked, so only the first rule
+    /// applies. `start_offset_sec` is kept in step with the new offsets.
+    ///
+    /// # Returns
+    /// `true` if the chapters were converted from seconds
+    pub fn normalize_offsets(&mut self) -> bool {
+        fn visit(chapters: &mut [Chapter], f: &mut impl FnMut(&mut Chapter)) {
+            for chapter in chapters {
+                f(chapter);
+                if let Some(children) = chapter.chapters.as_mut() {
+                    visit(children, f);
+                }
+            }
+        }
+
+        visit(&mut self.chapters, &mut |c| {
+            if c.start_offset_ms == 0 && c.start_offset_sec > 0 {
+                c.start_offset_ms = i64::from(c.start_offset_sec) * 1000;
+            }
+        });
+
+        let mut end = 0;
+        visit(&mut self.chapters, &mut |c| end = end.max(c.start_offset_ms + c.length_ms));
+
+        let runtime = self.runtime_length_ms;
+        let in_seconds = runtime > 0
+            && end > 0
+            && ((end * 1000 - runtime).abs() as f64) <= runtime as f64 * SECONDS_OFFSET_TOLERANCE;
+        if in_seconds {
+            visit(&mut self.chapters, &mut |c| {
+                c.start_offset_ms *= 1000;
+                c.length_ms *= 1000;
+                c.start_offset_sec = (c.start_offset_ms / 1000) as i32;
+            });
+        }
+        in_seconds
+    }
+
+    /// Check that the chapters account for the whole runtime
+    ///
+    /// # Reference
+    /// DownloadOptions.Factory.cs:29-35 - Libation compares `RuntimeLengthMs` from the
+    /// license and metadata endpoints to decide whether chapters are accurate.
+    ///
+    /// The chapters (flattened, so nested sections are counted once) should sum to
+    /// `runtime_length_ms`, either on their own or once the brand intro/outro are
+    /// added. Anything outside [`CHAPTER_RUNTIME_TOLERANCE_MS`] usually means the
+    /// chapter metadata belongs to a different codec or version of the audio.
+    ///
+    /// # Returns
+    /// `None` if the chapters are consistent, otherwise a warning describing the
+    /// mismatch. This is a diagnostic only; callers can still proceed.
+    pub fn verify_runtime(&self) -> Option<ChapterRuntimeWarning> {
+        if self.runtime_length_ms <= 0 {
+            return None;
+        }
+
+        let chapters_length_ms: i64 = flatten_chapters(self.chapters.clone(), None)
+            .iter()
+            .map(|c| c.length_ms)
+            .sum();
+        let brand_length_ms = self.brand_segments().total_ms();
+
+        // Pick whichever interpretation (brand audio inside or outside the
+        // chapters) lands closest to the runtime
+        let without_brand = chapters_length_ms - self.runtime_length_ms;
+        let with_brand = chapters_length_ms + brand_length_ms - self.runtime_length_ms;
+        let difference_ms = if with_brand.abs() < without_brand.abs() {
+            with_brand
+        } else {
+            without_brand
+        };
+
+        if difference_ms.abs() <= CHAPTER_RUNTIME_TOLERANCE_MS {
+            return None;
+        }
+
+        Some(ChapterRuntimeWarning {
+            runtime_length_ms: self.runtime_length_ms,
+            chapters_length_ms,
+            brand_length_ms,
+            difference_ms,
+        })
+    }
+}
+
+/// Chapter data that does not match the audio runtime
+///
+/// Returned by [`ChapterInfo::verify_runtime`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChapterRuntimeWarning {
+    /// Runtime reported by the content metadata
+    pub runtime_length_ms: i64,
+
+    /// Sum of all chapter lengths
+    pub chapters_length_ms: i64,
+
+    /// Brand intro plus outro duration
+    pub brand_length_ms: i64,
+
+    /// Closest chapter coverage minus runtime (negative means chapters fall short)
+    pub difference_ms: i64,
+}
+
+impl std::fmt::Display for ChapterRuntimeWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let direction = if self.difference_ms < 0 { "short of" } else { "beyond" };
+        write!(
+            f,
+            "Chapters end {:.1}s {} the {:.1}s runtime; chapter metadata may not match the audio",
+            self.difference_ms.abs() as f64 / 1000.0,
+            direction,
+            self.runtime_length_ms as f64 / 1000.0,
+        )
+    }
+}
+
+/// Audible brand audio at the start and end of a title
+///
+/// Reference: DownloadOptions.Factory.cs - `StripAudibleBrandAudio` shortens the
+/// first and last chapters by `BrandIntroDurationMs` / `BrandOutroDurationMs`
+///
+/// "Content" is everything between the two segments. All values are in
+/// milliseconds; negative durations from the API are treated as 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrandSegments {
+    /// Length of the brand intro
+    pub intro_ms: i64,
+    /// Length of the brand outro
+    pub outro_ms: i64,
+}
+
+impl BrandSegments {
+    pub fn new(intro_ms: i64, outro_ms: i64) -> Self {
+        Self {
+            intro_ms: intro_ms.max(0),
+            outro_ms: outro_ms.max(0),
+        }
+    }
+
+    /// Whether there is no brand audio to remove
+    pub fn is_empty(&self) -> bool {
+        self.intro_ms == 0 && self.outro_ms == 0
+    }
+
+    /// Intro plus outro
+    pub fn total_ms(&self) -> i64 {
+        self.intro_ms + self.outro_ms
+    }
+
+    /// Where the content starts in the untrimmed audio
+    pub fn content_start_ms(&self) -> i64 {
+        self.intro_ms
+    }
+
+    /// Where the content ends in untrimmed audio of `runtime_ms`
+    pub fn content_end_ms(&self, runtime_ms: i64) -> i64 {
+        runtime_ms - self.outro_ms
+    }
+
+    /// The part of `runtime_ms` of audio to keep, as (start, end)
+    ///
+    /// # Returns
+    /// `None` if the brand audio would leave nothing, in which case the
+    /// audio should be kept whole rather than trimmed to nothing
+    pub fn content_range_ms(&self, runtime_ms: i64) -> Option<(i64, i64)> {
+        let (start, end) = (self.content_start_ms(), self.content_end_ms(runtime_ms));
+        (end > start).then_some((start, end))
+    }
+
+    /// Chapters re-timed for the trimmed audio
+    ///
+    /// Each chapter is clipped to the content range and shifted so the
+    /// content starts at 0: the first chapter loses the intro, the last loses
+    /// the outro, and chapters lying entirely inside brand audio are dropped.
+    ///
+    /// # Arguments
+    /// * `chapters` - Flat chapter list (see [`flatten_chapters`]); nested
+    ///   chapters are not descended into
+    /// * `runtime_ms` - Length of the untrimmed audio
+    pub fn trim_chapters(&self, chapters: &[Chapter], runtime_ms: i64) -> Vec<Chapter> {
+        let Some((start, end)) = self.content_range_ms(runtime_ms) else {
+            return chapters.to_vec();
+        };
+
+        chapters
+            .iter()
+            .filter_map(|chapter| {
+                let chapter_start = chapter.start_offset_ms.clamp(start, end);
+                let chapter_end = (chapter.start_offset_ms + chapter.length_ms).clamp(start, end);
+                (chapter_end > chapter_start).then(|| Chapter {
+                    title: chapter.title.clone(),
+                    start_offset_ms: chapter_start - start,
+                    start_offset_sec: ((chapter_start - start) / 1000) as i32,
+                    length_ms: chapter_end - chapter_start,
+                    chapters: None,
+                })
+            })
+            .collect()
+    }
+}
+
+impl ChapterInfo {
+    /// Brand intro and outro from this chapter info
+    pub fn brand_segments(&self) -> BrandSegments {
+        BrandSegments::new(
+            i64::from(self.brand_intro_duration_ms),
+            i64::from(self.brand_outro_duration_ms),
+        )
+    }
+}
+
 /// Content reference with DRM information
 /// Reference: AudibleApi.Common.ContentReference, DownloadOptions.cs:41
 ///
@@ -290,6 +581,156 @@ pub struct ContentReference {
     /// Audio codec
     #[serde(rename = "codec")]
     pub codec: Codec,
+
+    /// Delivered file format, e.g. "AAX_44_128" (sample rate kHz, bitrate kbps)
+    #[serde(rename = "content_format", default, skip_serializing_if = "Option::is_none")]
+    pub content_format: Option<String>,
+}
+
+impl ContentReference {
+    /// Bitrate in kbps parsed from `content_format`, if present
+    pub fn bitrate_kbps(&self) -> Option<u32> {
+        self.content_format
+            .as_deref()?
+            .rsplit('_')
+            .next()?
+            .parse()
+            .ok()
+    }
+}
+
+// ============================================================================
+// QUALITY VALIDATION
+// ============================================================================
+
+impl DownloadQuality {
+    /// Next tier to try when a title is not offered at this one
+    ///
+    /// `Extreme` falls back to `High` and `High` to `Normal`; there is no
+    /// automatic fallback below `Normal`.
+    pub fn fallback(self) -> Option<Self> {
+        match self {
+            DownloadQuality::Extreme => Some(DownloadQuality::High),
+            DownloadQuality::High => Some(DownloadQuality::Normal),
+            DownloadQuality::Normal | DownloadQuality::Low => None,
+        }
+    }
+
+    /// Quality tier a stereo AAC bitrate corresponds to
+    ///
+    /// Audible serves roughly 32, 64 and 128 kbps AAC; anything above that
+    /// is treated as the top stereo tier as well.
+    pub fn from_bitrate_kbps(kbps: u32) -> Self {
+        match kbps {
+            0..=47 => DownloadQuality::Low,
+            48..=95 => DownloadQuality::Normal,
+            _ => DownloadQuality::High,
+        }
+    }
+
+    /// Compare the content a license delivered against this requested quality
+    ///
+    /// Spatial codecs (EC-3, AC-4) count as `Extreme`; AAC is rated by the
+    /// bitrate in `content_format`, so an `Extreme` request answered with
+    /// stereo AAC is reported as a downgrade to `High`.
+    ///
+    /// # Arguments
+    /// * `content_ref` - Content reference from the license response
+    ///
+    /// # Returns
+    /// `None` if the delivered quality meets the request or cannot be
+    /// determined, otherwise a description of the downgrade.
+    pub fn check_delivered(self, content_ref: &ContentReference) -> Option<QualityDowngrade> {
+        let bitrate_kbps = content_ref.bitrate_kbps();
+        let delivered = match content_ref.codec {
+            Codec::Ec3 | Codec::Ac4 => DownloadQuality::Extreme,
+            _ => DownloadQuality::from_bitrate_kbps(bitrate_kbps?),
+        };
+
+        if delivered >= self {
+            return None;
+        }
+
+        Some(QualityDowngrade {
+            requested: self,
+            delivered,
+            codec: content_ref.codec,
+            bitrate_kbps,
+        })
+    }
+}
+
+/// Content delivered at a lower quality than requested
+///
+/// Returned by [`DownloadQuality::check_delivered`]. Usually means the title is
+/// only available in lower quality, not that the request was wrong.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QualityDowngrade {
+    /// Quality tier that was requested
+    pub requested: DownloadQuality,
+
+    /// Quality tier the delivered content corresponds to
+    pub delivered: DownloadQuality,
+
+    /// Codec of the delivered content
+    pub codec: Codec,
+
+    /// Bitrate of the delivered content, if known
+    pub bitrate_kbps: Option<u32>,
+}
+
+impl std::fmt::Display for QualityDowngrade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Requested {:?} quality but the title is only available in {:?} ({:?}",
+            self.requested, self.delivered, self.codec,
+        )?;
+        if let Some(kbps) = self.bitrate_kbps {
+            write!(f, ", {} kbps", kbps)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// AAC codec delivered other than the one requested
+///
+/// Audible only encodes some titles in xHE-AAC; for the rest a request for
+/// it is answered with AAC-LC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodecDowngrade {
+    /// AAC codec asked for in the license request
+    pub requested: Codec,
+
+    /// Codec of the delivered content
+    pub delivered: Codec,
+}
+
+impl CodecDowngrade {
+    /// Compare the delivered codec against the requested AAC codec
+    ///
+    /// Spatial codecs are left to [`DownloadQuality::check_delivered`]; they
+    /// are only sent when spatial audio was asked for.
+    ///
+    /// # Returns
+    /// `None` if `requested` was delivered or the content is spatial
+    pub fn check(requested: Codec, content_ref: &ContentReference) -> Option<Self> {
+        match content_ref.codec {
+            Codec::Ec3 | Codec::Ac4 => None,
+            delivered if delivered == requested => None,
+            delivered => Some(Self { requested, delivered }),
+        }
+    }
+}
+
+impl std::fmt::Display for CodecDowngrade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Requested {:?} but the title was delivered as {:?}",
+            self.requested, self.delivered
+        )
+    }
 }
 
 /// Content URL information
@@ -340,21 +781,77 @@ pub struct ContentMetadata {
 
 /// Contributor information (author, narrator, etc.)
 /// Reference: AudibleApi.Common.Contributor
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Library and catalog responses usually carry only `name` and `asin`. Lists
+/// deserialized with [`deserialize_authors`] / [`deserialize_narrators`] fill
+/// in the role from the list the entry came from.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Contributor {
-    /// Contributor's unique ID
-    #[serde(rename = "asin")]
-    pub asin: String,
-
     /// Display name
-    #[serde(rename = "name")]
+    #[serde(rename = "name", default)]
     pub name: String,
 
+    /// Contributor's unique ID, when Audible has a page for them
+    #[serde(rename = "asin", default)]
+    pub asin: Option<String>,
+
     /// Role (author, narrator, etc.)
-    #[serde(rename = "role")]
+    #[serde(rename = "role", default)]
     pub role: String,
 }
 
+impl Contributor {
+    /// Role of entries in an `authors` list that name none
+    pub const AUTHOR: &'static str = "author";
+
+    /// Role of entries in a `narrators` list that name none
+    pub const NARRATOR: &'static str = "narrator";
+
+    /// Whether this contributor is credited as an author
+    pub fn is_author(&self) -> bool {
+        self.role.eq_ignore_ascii_case(Self::AUTHOR)
+    }
+
+    /// Whether this contributor is credited as a narrator
+    pub fn is_narrator(&self) -> bool {
+        self.role.eq_ignore_ascii_case(Self::NARRATOR)
+    }
+
+    /// Link to the contributor's page in the given marketplace
+    ///
+    /// # Returns
+    /// `None` if the contributor has no ASIN
+    pub fn page_url(&self, locale: &Locale) -> Option<String> {
+        let asin = self.asin.as_deref().map(str::trim).filter(|a| !a.is_empty())?;
+        Some(format!("https://www.{}/author/{}", locale.domain, asin))
+    }
+}
+
+/// Fill in the role of contributors that arrived without one
+fn with_default_role(contributors: Option<Vec<Contributor>>, role: &str) -> Vec<Contributor> {
+    let mut contributors = contributors.unwrap_or_default();
+    for contributor in contributors.iter_mut().filter(|c| c.role.trim().is_empty()) {
+        contributor.role = role.to_string();
+    }
+    contributors
+}
+
+/// Serde adapter for an `authors` list: null becomes empty, missing roles become "author"
+pub(crate) fn deserialize_authors<'de, D>(deserializer: D) -> std::result::Result<Vec<Contributor>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(with_default_role(Option::deserialize(deserializer)?, Contributor::AUTHOR))
+}
+
+/// Serde adapter for a `narrators` list: null becomes empty, missing roles become "narrator"
+pub(crate) fn deserialize_narrators<'de, D>(deserializer: D) -> std::result::Result<Vec<Contributor>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(with_default_role(Option::deserialize(deserializer)?, Contributor::NARRATOR))
+}
+
 /// Product rating information
 /// Reference: AudibleApi.Common.Rating
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -470,11 +967,11 @@ pub struct CatalogProduct {
     pub format_type: String,
 
     /// Authors, narrators, etc.
-    #[serde(rename = "authors")]
+    #[serde(rename = "authors", default, deserialize_with = "deserialize_authors")]
     pub authors: Vec<Contributor>,
 
     /// Narrators
-    #[serde(rename = "narrators")]
+    #[serde(rename = "narrators", default, deserialize_with = "deserialize_narrators")]
     pub narrators: Vec<Contributor>,
 
     /// Customer ratings
@@ -707,6 +1204,166 @@ impl AudibleClient {
         Ok(products)
     }
 
+    /// Search the Audible catalog
+    ///
+    /// # Reference
+    /// C# method: `Api.SearchCatalogAsync(CatalogSearchOptions)`
+    /// Location: AudibleApi/Api.Catalog.cs (external package)
+    ///
+    /// # Endpoint
+    /// `GET /1.0/catalog/products?keywords=...`
+    ///
+    /// # Arguments
+    /// * `query` - Free-text search keywords
+    ///
+    /// # Returns
+    /// Catalog products in the order ranked by the server (relevance). Empty if
+    /// the query is blank.
+    ///
+    /// # Errors
+    /// - `ApiRequestFailed` - API request failed
+    /// - `InvalidApiResponse` - Response is missing the `products` array
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::api::client::AudibleClient;
+    /// # use rust_core::api::auth::Account;
+    /// # async fn example() -> rust_core::error::Result<()> {
+    /// let client = AudibleClient::new(Account::default())?;
+    /// let products = client.search_catalog("project hail mary").await?;
+    /// for product in products {
+    ///     println!("{} ({})", product.title, product.asin);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search_catalog(&self, query: &str) -> Result<Vec<CatalogProduct>> {
+        let keywords = query.trim();
+        if keywords.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let endpoint = "/1.0/catalog/products";
+
+        let response_groups = [
+            "rating",
+            "media",
+            "relationships",
+            "product_desc",
+            "contributors",
+            "product_plans",
+            "series",
+            "category_ladders",
+            "product_extended_attrs",
+        ].join(",");
+
+        let params = [
+            ("keywords", keywords.to_string()),
+            ("num_results", crate::api::client::BATCH_SIZE.to_string()),
+            ("products_sort_by", "Relevance".to_string()),
+            ("response_groups", response_groups),
+            ("image_sizes", "500".to_string()),
+        ];
+
+        let query_string = params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let url = format!("{}?{}", endpoint, query_string);
+
+        let response: serde_json::Value = self.get(&url).await?;
+
+        let products_json = response
+            .get("products")
+            .and_then(|p| p.as_array())
+            .ok_or_else(|| LibationError::InvalidApiResponse {
+                message: "Missing or invalid 'products' array in response".to_string(),
+                response_body: Some(response.to_string()),
+            })?;
+
+        // Keep the server's ordering; skip products that fail to parse
+        let mut products = Vec::with_capacity(products_json.len());
+        for product_value in products_json {
+            match serde_json::from_value(product_value.clone()) {
+                Ok(product) => products.push(product),
+                Err(e) => {
+                    eprintln!("Warning: Failed to parse product in search results: {}", e);
+                }
+            }
+        }
+
+        Ok(products)
+    }
+
+    /// Check whether a product is a podcast or series parent
+    ///
+    /// # Reference
+    /// AudibleApi `Item.IsSeriesParent` - a parent has child relationships of type
+    /// episode or season. ApiExtended.cs:103, 145 skips these when scanning.
+    ///
+    /// Parents have no audio of their own; only their episodes can be licensed.
+    ///
+    /// # Arguments
+    /// * `asin` - Audible product ID
+    ///
+    /// # Errors
+    /// - `ApiRequestFailed` - API request failed
+    /// - `InvalidApiResponse` - Missing `product` field
+    pub async fn is_parent_product(&self, asin: &str) -> Result<bool> {
+        let url = format!(
+            "/1.0/catalog/products/{}?response_groups={}",
+            asin,
+            urlencoding::encode("relationships,product_attrs")
+        );
+
+        let response: serde_json::Value = self.get(&url).await?;
+
+        let product_json = response
+            .get("product")
+            .ok_or_else(|| LibationError::InvalidApiResponse {
+                message: "Missing 'product' field in response".to_string(),
+                response_body: Some(response.to_string()),
+            })?;
+
+        Ok(is_parent_product_json(product_json))
+    }
+
+    /// Get the parts of a book Audible delivers as several files
+    ///
+    /// Some long audiobooks (`content_delivery_type` `MultiPartBook`) have no
+    /// audio of their own. The catalog lists each downloadable part as a child
+    /// of type `component`; every part is licensed and downloaded like a book.
+    ///
+    /// # Arguments
+    /// * `asin` - ASIN of the book as it appears in the library
+    ///
+    /// # Returns
+    /// Part ASINs in listening order, or an empty list for a single-file book
+    ///
+    /// # Errors
+    /// - `ApiRequestFailed` - API request failed
+    /// - `InvalidApiResponse` - Missing `product` field
+    pub async fn get_book_parts(&self, asin: &str) -> Result<Vec<String>> {
+        let url = format!(
+            "/1.0/catalog/products/{}?response_groups={}",
+            asin,
+            urlencoding::encode("relationships,product_attrs")
+        );
+
+        let response: serde_json::Value = self.get(&url).await?;
+
+        let product_json = response
+            .get("product")
+            .ok_or_else(|| LibationError::InvalidApiResponse {
+                message: "Missing 'product' field in response".to_string(),
+                response_body: Some(response.to_string()),
+            })?;
+
+        Ok(part_asins_json(product_json))
+    }
+
     /// Get content metadata including chapter information
     ///
     /// # Reference
@@ -731,6 +1388,9 @@ impl AudibleClient {
     /// This endpoint provides more accurate chapter information than the license request.
     /// Reference: DownloadOptions.Factory.cs:29-35 - Compares RuntimeLengthMs to verify accuracy
     ///
+    /// Chapter titles come in [`ClientConfig::chapter_titles_language`](crate::api::client::ClientConfig::chapter_titles_language)
+    /// when set and offered, otherwise in the market's default language.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # use rust_core::api::client::AudibleClient;
@@ -746,7 +1406,11 @@ impl AudibleClient {
     pub async fn get_content_metadata(&self, asin: &str) -> Result<ContentMetadata> {
         let endpoint = format!("/1.0/content/{}/metadata", asin);
 
-        let response: serde_json::Value = self.get(&endpoint).await?;
+        let response: serde_json::Value = self
+            .with_chapter_language(self.chapter_titles_language(), |headers| {
+                self.get_with_headers(&endpoint, headers)
+            })
+            .await?;
 
         // Parse metadata
         // The API may wrap in a "content_metadata" field or return directly
@@ -754,11 +1418,50 @@ impl AudibleClient {
             .get("content_metadata")
             .unwrap_or(&response);
 
-        serde_json::from_value(metadata_json.clone())
+        let metadata: ContentMetadata = serde_json::from_value(metadata_json.clone())
             .map_err(|e| LibationError::InvalidApiResponse {
                 message: format!("Failed to parse content metadata: {}", e),
                 response_body: Some(metadata_json.to_string()),
-            })
+            })?;
+
+        if let Some(warning) = metadata.chapter_info.as_ref().and_then(|c| c.verify_runtime()) {
+            eprintln!("Warning: {}: {}", asin, warning);
+        }
+
+        Ok(metadata)
+    }
+}
+
+impl AudibleClient {
+    /// Send `request` asking for chapter titles in `language`
+    ///
+    /// `request` gets the headers to send. Without a language it is sent once
+    /// with none; if the API refuses the language, it is sent again without it
+    /// so the default titles are used.
+    pub(crate) async fn with_chapter_language<T, F, Fut>(&self, language: Option<&str>, request: F) -> Result<T>
+    where
+        F: Fn(HeaderMap) -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let Some(language) = language else {
+            return request(HeaderMap::new()).await;
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT_LANGUAGE,
+            HeaderValue::from_str(language).map_err(|e| {
+                LibationError::InvalidInput(format!("Invalid chapter titles language '{}': {}", language, e))
+            })?,
+        );
+
+        match request(headers).await {
+            Err(e) if is_language_rejected(&e) => {
+                eprintln!("Chapter titles in {} not available ({}); using the default language", language, e);
+                request(HeaderMap::new()).await
+            }
+            result => result,
+        }
     }
 }
 
@@ -766,6 +1469,71 @@ impl AudibleClient {
 // HELPER FUNCTIONS
 // ============================================================================
 
+/// Whether a request failed because the requested language is not offered
+///
+/// Audible answers 406, or a 4xx whose message names the language.
+fn is_language_rejected(error: &LibationError) -> bool {
+    match error {
+        LibationError::ApiRequestFailed { message, status_code, .. } => {
+            *status_code == Some(406)
+                || (matches!(status_code, None | Some(400..=499)) && message.to_lowercase().contains("language"))
+        }
+        _ => false,
+    }
+}
+
+/// Whether a catalog/library product JSON object describes a podcast or series parent
+pub(crate) fn is_parent_product_json(product: &serde_json::Value) -> bool {
+    let delivery_type = product
+        .get("content_delivery_type")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    if matches!(delivery_type, "PodcastParent" | "Periodical") {
+        return true;
+    }
+
+    product
+        .get("relationships")
+        .and_then(|v| v.as_array())
+        .map(|relationships| {
+            relationships.iter().any(|r| {
+                let to_product = r.get("relationship_to_product").and_then(|v| v.as_str());
+                let kind = r.get("relationship_type").and_then(|v| v.as_str());
+                to_product == Some("child") && matches!(kind, Some("episode") | Some("season"))
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// ASINs of the parts of a multi-part book, sorted by their `sort` value
+pub(crate) fn part_asins_json(product: &serde_json::Value) -> Vec<String> {
+    let mut parts: Vec<(i64, String)> = product
+        .get("relationships")
+        .and_then(|v| v.as_array())
+        .map(|relationships| {
+            relationships
+                .iter()
+                .filter(|r| {
+                    r.get("relationship_to_product").and_then(|v| v.as_str()) == Some("child")
+                        && r.get("relationship_type").and_then(|v| v.as_str()) == Some("component")
+                })
+                .filter_map(|r| {
+                    let asin = r.get("asin").and_then(|v| v.as_str())?;
+                    // The API sends sort as a string on some marketplaces
+                    let sort = r.get("sort").and_then(|v| {
+                        v.as_i64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+                    });
+                    Some((sort.unwrap_or(i64::MAX), asin.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Stable, so parts without a sort keep the order they were listed in
+    parts.sort_by_key(|(sort, _)| *sort);
+    parts.into_iter().map(|(_, asin)| asin).collect()
+}
+
 /// Flatten hierarchical chapters into a flat list
 ///
 /// # Reference
@@ -892,6 +1660,50 @@ mod tests {
         assert!(!DrmType::Adrm.is_widevine());
     }
 
+    #[test]
+    fn test_is_parent_product_json() {
+        let parent = serde_json::json!({
+            "asin": "B0PARENT",
+            "relationships": [
+                { "asin": "B0EP1", "relationship_type": "episode", "relationship_to_product": "child" }
+            ]
+        });
+        assert!(is_parent_product_json(&parent));
+
+        let podcast = serde_json::json!({ "asin": "B0POD", "content_delivery_type": "PodcastParent" });
+        assert!(is_parent_product_json(&podcast));
+
+        let episode = serde_json::json!({
+            "asin": "B0EP1",
+            "content_delivery_type": "PodcastEpisode",
+            "relationships": [
+                { "asin": "B0PARENT", "relationship_type": "episode", "relationship_to_product": "parent" }
+            ]
+        });
+        assert!(!is_parent_product_json(&episode));
+
+        let book = serde_json::json!({ "asin": "B0BOOK", "content_delivery_type": "SinglePartBook" });
+        assert!(!is_parent_product_json(&book));
+    }
+
+    #[test]
+    fn test_part_asins_json() {
+        let book = serde_json::json!({
+            "asin": "B0LONG",
+            "content_delivery_type": "MultiPartBook",
+            "relationships": [
+                { "asin": "B0PART2", "relationship_type": "component", "relationship_to_product": "child", "sort": "2" },
+                { "asin": "B0SERIES", "relationship_type": "series", "relationship_to_product": "parent", "sort": "1" },
+                { "asin": "B0PART1", "relationship_type": "component", "relationship_to_product": "child", "sort": 1 }
+            ]
+        });
+        assert_eq!(part_asins_json(&book), ["B0PART1", "B0PART2"]);
+        assert!(!is_parent_product_json(&book));
+
+        let single = serde_json::json!({ "asin": "B0BOOK", "content_delivery_type": "SinglePartBook" });
+        assert!(part_asins_json(&single).is_empty());
+    }
+
     #[test]
     fn test_flatten_chapters_simple() {
         let chapters = vec![
@@ -916,6 +1728,149 @@ mod tests {
         assert_eq!(flattened[0].title, "Chapter 1");
     }
 
+    fn chapter(title: &str, start_offset_ms: i64, length_ms: i64) -> Chapter {
+        Chapter {
+            title: title.to_string(),
+            start_offset_ms,
+            start_offset_sec: (start_offset_ms / 1000) as i32,
+            length_ms,
+            chapters: None,
+        }
+    }
+
+    #[test]
+    fn test_verify_runtime() {
+        let mut info = ChapterInfo {
+            brand_intro_duration_ms: 2043,
+            brand_outro_duration_ms: 4969,
+            chapters: vec![chapter("Chapter 1", 0, 60000), chapter("Chapter 2", 60000, 60000)],
+            is_accurate: true,
+            runtime_length_ms: 120500,
+        };
+        assert_eq!(info.verify_runtime(), None);
+
+        // Brand audio reported outside the chapters still counts as covered
+        info.runtime_length_ms = 127012;
+        assert_eq!(info.verify_runtime(), None);
+
+        // Chapters that stop well short of the runtime are flagged
+        info.runtime_length_ms = 180000;
+        let warning = info.verify_runtime().unwrap();
+        assert_eq!(warning.chapters_length_ms, 120000);
+        assert_eq!(warning.difference_ms, -52988);
+        assert!(warning.to_string().contains("short of"));
+    }
+
+    #[test]
+    fn test_normalize_chapter_offsets() {
+        // Offsets and lengths in seconds against a runtime in milliseconds
+        let info: ChapterInfo = serde_json::from_value(serde_json::json!({
+            "runtimeLengthMs": 127012,
+            "chapters": [
+                { "title": "Chapter 1", "start_offset_ms": 0, "start_offset_sec": 0, "length_ms": 60 },
+                { "title": "Chapter 2", "start_offset_ms": 60, "start_offset_sec": 0, "length_ms": 65 }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(info.chapters, vec![chapter("Chapter 1", 0, 60000), chapter("Chapter 2", 60000, 65000)]);
+
+        // Only the seconds offset is given
+        let info: ChapterInfo = serde_json::from_value(serde_json::json!({
+            "runtimeLengthMs": 120000,
+            "chapters": [
+                { "title": "Chapter 1", "start_offset_sec": 0, "length_ms": 60000 },
+                { "title": "Chapter 2", "start_offset_sec": 60, "length_ms": 60000 }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(info.chapters[1], chapter("Chapter 2", 60000, 60000));
+
+        // Millisecond offsets are left alone, even far short of the runtime
+        let mut info = ChapterInfo {
+            brand_intro_duration_ms: 0,
+            brand_outro_duration_ms: 0,
+            chapters: vec![chapter("Chapter 1", 0, 60)],
+            is_accurate: false,
+            runtime_length_ms: 127012,
+        };
+        assert!(!info.normalize_offsets());
+        assert_eq!(info.chapters, vec![chapter("Chapter 1", 0, 60)]);
+        info.runtime_length_ms = 0;
+        assert!(!info.normalize_offsets());
+    }
+
+    #[test]
+    fn test_brand_segments() {
+        // Brand durations from a real license, around a two chapter book
+        let info = ChapterInfo {
+            brand_intro_duration_ms: 2043,
+            brand_outro_duration_ms: 4969,
+            chapters: vec![chapter("Opening Credits", 0, 30000), chapter("Chapter 1", 30000, 97012)],
+            is_accurate: true,
+            runtime_length_ms: 127012,
+        };
+        let segments = info.brand_segments();
+        assert_eq!(segments.total_ms(), 7012);
+        assert_eq!(segments.content_start_ms(), 2043);
+        assert_eq!(segments.content_end_ms(127012), 122043);
+        assert_eq!(segments.content_range_ms(127012), Some((2043, 122043)));
+
+        let trimmed = segments.trim_chapters(&info.chapters, info.runtime_length_ms);
+        assert_eq!(trimmed, vec![chapter("Opening Credits", 0, 27957), chapter("Chapter 1", 27957, 92043)]);
+        let content_ms: i64 = trimmed.iter().map(|c| c.length_ms).sum();
+        assert_eq!(content_ms, 127012 - 7012);
+
+        // A chapter lying entirely within the outro disappears
+        let with_credits = [chapter("Chapter 1", 0, 123000), chapter("End Credits", 123000, 4012)];
+        let trimmed = segments.trim_chapters(&with_credits, 127012);
+        assert_eq!(trimmed, vec![chapter("Chapter 1", 0, 120000)]);
+
+        // Brand audio longer than the title leaves it untouched
+        assert_eq!(segments.content_range_ms(5000), None);
+        assert_eq!(segments.trim_chapters(&with_credits[..1], 5000).len(), 1);
+        assert_eq!(BrandSegments::new(-5, -1), BrandSegments::default());
+        assert!(BrandSegments::default().is_empty());
+    }
+
+    #[test]
+    fn test_check_delivered_quality() {
+        let content_ref = |codec: Codec, format: Option<&str>| ContentReference {
+            acr: "CR!TEST".to_string(),
+            sku: "BK_TEST_000001".to_string(),
+            version: "1".to_string(),
+            codec,
+            content_format: format.map(str::to_string),
+        };
+
+        let high = content_ref(Codec::AacLc, Some("AAX_44_128"));
+        assert_eq!(high.bitrate_kbps(), Some(128));
+        assert_eq!(DownloadQuality::High.check_delivered(&high), None);
+
+        // Asking for Extreme and getting stereo AAC is a downgrade
+        let downgrade = DownloadQuality::Extreme.check_delivered(&high).unwrap();
+        assert_eq!(downgrade.requested, DownloadQuality::Extreme);
+        assert_eq!(downgrade.delivered, DownloadQuality::High);
+        assert!(downgrade.to_string().contains("128 kbps"));
+
+        let normal = content_ref(Codec::AacLc, Some("AAX_22_64"));
+        let downgrade = DownloadQuality::High.check_delivered(&normal).unwrap();
+        assert_eq!(downgrade.requested, DownloadQuality::High);
+        assert_eq!(downgrade.delivered, DownloadQuality::Normal);
+
+        let spatial = content_ref(Codec::Ec3, None);
+        assert_eq!(DownloadQuality::Extreme.check_delivered(&spatial), None);
+
+        // Unknown bitrate is not reported
+        let unknown = content_ref(Codec::AacLc, None);
+        assert_eq!(DownloadQuality::High.check_delivered(&unknown), None);
+
+        // xHE-AAC answered with AAC-LC is reported, spatial content is not
+        let downgrade = CodecDowngrade::check(Codec::XHeAac, &high).unwrap();
+        assert_eq!(downgrade.delivered, Codec::AacLc);
+        assert_eq!(CodecDowngrade::check(Codec::AacLc, &high), None);
+        assert_eq!(CodecDowngrade::check(Codec::XHeAac, &spatial), None);
+    }
+
     #[test]
     fn test_combine_credits() {
         let mut chapters = vec![
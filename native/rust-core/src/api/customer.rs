@@ -27,8 +27,16 @@
 //!
 //! # API Endpoint
 //! `GET https://api.audible.{domain}/1.0/customer/information`
+//!
+//! # Marketplace Migration
+//! Accounts can be moved to another marketplace (for example from audible.com
+//! to audible.ca). The old API host then answers library and content calls
+//! with 404s. The `migration_details` response group names the marketplace the
+//! account moved to; [`AudibleClient::apply_marketplace_migration`] switches
+//! the client, and the account's locale, over to it.
 
 use crate::error::Result;
+use crate::api::auth::Locale;
 use crate::api::client::AudibleClient;
 use serde::{Deserialize, Serialize};
 
@@ -43,6 +51,57 @@ pub struct CustomerInformation {
 
     #[serde(default)]
     pub email: Option<String>,
+
+    /// Set when the account has moved to another marketplace
+    #[serde(default)]
+    pub migration: Option<MarketplaceMigration>,
+}
+
+/// Move of an account from one marketplace to another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketplaceMigration {
+    /// Marketplace the account was in, if reported
+    pub from_marketplace_id: Option<String>,
+
+    /// Marketplace the account is in now
+    pub to_marketplace_id: String,
+}
+
+impl MarketplaceMigration {
+    /// Locale of the marketplace the account moved to, if it is a known one
+    pub fn locale(&self) -> Option<Locale> {
+        Locale::from_marketplace_id(&self.to_marketplace_id)
+    }
+
+    /// Read the migration from a `customer/information` response
+    ///
+    /// `migration_details` has been seen both at the top level and under
+    /// `customer_details`, as an object or a list of moves (the last one wins).
+    fn from_response(response: &serde_json::Value) -> Option<Self> {
+        let details = response
+            .get("customer_details")
+            .and_then(|c| c.get("migration_details"))
+            .or_else(|| response.get("migration_details"))?;
+        let latest = match details {
+            serde_json::Value::Array(moves) => moves.last()?,
+            other => other,
+        };
+
+        let field = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| latest.get(*name).and_then(|v| v.as_str()))
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+        };
+        let to_marketplace_id = field(&["to_marketplace_id", "target_marketplace_id", "marketplace_id"])?;
+        let from_marketplace_id = field(&["from_marketplace_id", "source_marketplace_id"]);
+        if from_marketplace_id.as_deref() == Some(to_marketplace_id.as_str()) {
+            return None;
+        }
+
+        Some(Self { from_marketplace_id, to_marketplace_id })
+    }
 }
 
 impl AudibleClient {
@@ -88,6 +147,77 @@ impl AudibleClient {
             name,
             given_name,
             email,
+            migration: MarketplaceMigration::from_response(&response),
         })
     }
+
+    /// Follow the account to its new marketplace if it has been migrated
+    ///
+    /// Checks `migration_details` and, when the account now lives in another
+    /// known marketplace, points this client (and the account's locale) at it
+    /// with [`set_locale`](Self::set_locale). Save the account afterwards so
+    /// later clients start on the right host.
+    ///
+    /// # Returns
+    /// The migration if the client was switched, `None` if it already uses the
+    /// right marketplace
+    ///
+    /// # Errors
+    /// Returns error if the customer information request fails
+    pub async fn apply_marketplace_migration(&mut self) -> Result<Option<MarketplaceMigration>> {
+        let Some(migration) = self.get_customer_information().await?.migration else {
+            return Ok(None);
+        };
+        let Some(locale) = migration.locale() else {
+            eprintln!(
+                "Warning: account moved to unknown marketplace {}; keeping {}",
+                migration.to_marketplace_id,
+                self.base_url()
+            );
+            return Ok(None);
+        };
+        if self.base_url() == locale.api_url() {
+            return Ok(None);
+        }
+
+        eprintln!(
+            "Warning: account has moved to the {} marketplace; using {} instead of {}",
+            locale.name,
+            locale.api_url(),
+            self.base_url()
+        );
+        self.set_locale(locale).await?;
+        Ok(Some(migration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::fixture::{canned_client, CannedTransport};
+
+    #[tokio::test]
+    async fn test_apply_marketplace_migration() {
+        // Reports a move from audible.com to audible.ca
+        let body = serde_json::json!({
+            "customer_details": {
+                "migration_details": [
+                    {"from_marketplace_id": "AF2M0KC94RCEA", "to_marketplace_id": "A2CQZ5RBY40XE"}
+                ]
+            }
+        });
+        let transport = std::sync::Arc::new(CannedTransport::fixed(200, body.to_string()));
+        let mut client = canned_client("moved@example.com", transport);
+        assert_eq!(client.base_url(), "https://api.audible.com");
+
+        let migration = client.apply_marketplace_migration().await.unwrap().unwrap();
+        assert_eq!(migration.locale(), Some(Locale::ca()));
+        assert_eq!(client.base_url(), "https://api.audible.ca");
+
+        // Already on the new host
+        assert_eq!(client.apply_marketplace_migration().await.unwrap(), None);
+
+        let unmigrated = serde_json::json!({"customer_details": {"migration_details": []}});
+        assert_eq!(MarketplaceMigration::from_response(&unmigrated), None);
+    }
 }
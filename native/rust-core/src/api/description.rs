@@ -0,0 +1,211 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Clean-up of HTML book descriptions
+//!
+//! # Reference C# Sources
+//! - **`DtoImporterService/BookImporter.cs`** - Stores `PublisherSummary` as-is
+//! - **`LibationWinForms/Dialogs/BookDetailsDialog.cs`** - Renders the HTML in
+//!   a browser control
+//!
+//! Audible's `merchandising_summary` and `publisher_summary` are HTML
+//! fragments (`<p>`, `<br />`, `<b>`, entities such as `&amp;`). The apps
+//! display descriptions in plain text views, so library sync stores them as
+//! text: block tags become line breaks, list items become bullets, entities
+//! are decoded and whitespace is collapsed. [`DescriptionFormat::SanitizedHtml`]
+//! keeps basic formatting for views that render HTML, with every attribute,
+//! script and unknown tag removed.
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+lazy_static! {
+    static ref COMMENT: Regex = Regex::new(r"(?s)<!--.*?-->").unwrap();
+    static ref SCRIPT: Regex = Regex::new(r"(?is)<(?:script|style)\b.*?</(?:script|style)\s*>").unwrap();
+    static ref TAG: Regex = Regex::new(r"(?s)<(/?)([a-zA-Z][a-zA-Z0-9]*)\b[^>]*>").unwrap();
+    static ref ENTITY: Regex = Regex::new(r"&(#[0-9]{1,7}|#[xX][0-9a-fA-F]{1,6}|[a-zA-Z]{2,8});").unwrap();
+    static ref SPACES: Regex = Regex::new(r"[ \t\u{a0}]+").unwrap();
+}
+
+/// Tags that start a new line of text
+const BLOCK_TAGS: &[&str] = &[
+    "p", "div", "ul", "ol", "h1", "h2", "h3", "h4", "h5", "h6", "blockquote", "tr", "table",
+];
+
+/// Tags kept by [`DescriptionFormat::SanitizedHtml`]
+const ALLOWED_TAGS: &[&str] = &["p", "br", "b", "strong", "i", "em", "u", "ul", "ol", "li"];
+
+/// How a description is cleaned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DescriptionFormat {
+    /// Text only, with paragraphs separated by blank lines
+    #[default]
+    PlainText,
+    /// Basic formatting tags only, without attributes
+    SanitizedHtml,
+}
+
+/// Clean an HTML description into `format`
+pub fn clean_description(html: &str, format: DescriptionFormat) -> String {
+    match format {
+        DescriptionFormat::PlainText => html_to_text(html),
+        DescriptionFormat::SanitizedHtml => sanitize_html(html),
+    }
+}
+
+/// Convert an HTML fragment to readable plain text
+///
+/// Text that contains no markup comes back with only its whitespace
+/// normalised, so cleaning twice gives the same result.
+///
+/// # Example
+/// ```rust,no_run
+/// use rust_core::api::description::html_to_text;
+///
+/// let text = html_to_text("<p>Bob &amp; friends</p><p>Book <b>3</b></p>");
+/// assert_eq!(text, "Bob & friends\n\nBook 3");
+/// ```
+pub fn html_to_text(html: &str) -> String {
+    let html = strip_hidden(html);
+    let text = TAG.replace_all(&html, |caps: &Captures| {
+        let closing = !caps[1].is_empty();
+        let name = caps[2].to_ascii_lowercase();
+        match name.as_str() {
+            "br" => "\n",
+            // Each <li> starts its own line, so </li> adds nothing
+            "li" if closing => "",
+            "li" => "\n• ",
+            _ if BLOCK_TAGS.contains(&name.as_str()) => "\n\n",
+            _ => "",
+        }
+    });
+    let text = decode_entities(&text);
+
+    // Collapse spaces within lines and keep at most one blank line in a row
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = SPACES.replace_all(line, " ").trim().to_string();
+        let previous_blank = lines.last().is_none_or(|l| l.is_empty());
+        if !line.is_empty() || !previous_blank {
+            lines.push(line);
+        }
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// Keep only basic formatting tags, dropping attributes and everything else
+pub fn sanitize_html(html: &str) -> String {
+    let html = strip_hidden(html);
+    let sanitized = TAG.replace_all(&html, |caps: &Captures| {
+        let name = caps[2].to_ascii_lowercase();
+        if !ALLOWED_TAGS.contains(&name.as_str()) {
+            String::new()
+        } else if name == "br" {
+            "<br>".to_string()
+        } else {
+            format!("<{}{}>", &caps[1], name)
+        }
+    });
+    sanitized.trim().to_string()
+}
+
+/// Remove comments and script/style blocks, whose content is never shown
+fn strip_hidden(html: &str) -> String {
+    let html = COMMENT.replace_all(html, "");
+    SCRIPT.replace_all(&html, "").into_owned()
+}
+
+/// Decode named and numeric character references
+///
+/// Unknown names are left untouched.
+fn decode_entities(text: &str) -> String {
+    ENTITY
+        .replace_all(text, |caps: &Captures| {
+            let entity = &caps[1];
+            let decoded = if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(dec) = entity.strip_prefix('#') {
+                dec.parse().ok().and_then(char::from_u32)
+            } else {
+                named_entity(entity)
+            };
+            decoded.map_or_else(|| caps[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+/// Characters for the named entities seen in Audible descriptions
+fn named_entity(name: &str) -> Option<char> {
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_text() {
+        let html = "<p>Bob's back&nbsp;&mdash; and <b>angrier</b> than ever.</p>\
+                    <p>Includes:<ul><li>Book 1</li><li>Book&#160;2</li></ul></p>\
+                    <!-- promo --><script>track()</script>Line one<br/>Line   two";
+        assert_eq!(
+            html_to_text(html),
+            "Bob's back — and angrier than ever.\n\nIncludes:\n\n• Book 1\n• Book 2\n\nLine one\nLine two"
+        );
+
+        // Plain text is left alone, so cleaning is idempotent
+        let text = html_to_text(html);
+        assert_eq!(html_to_text(&text), text);
+        assert_eq!(html_to_text("Ages 8 < 12 &unknown;"), "Ages 8 < 12 &unknown;");
+    }
+
+    #[test]
+    fn test_sanitize_html_keeps_basic_tags() {
+        let html = r#"<P class="x" onclick="evil()">Hi <a href="https://x">there</a><br />
+                      <img src="x.png"><STRONG>bold</STRONG></P><style>p{}</style>"#;
+        assert_eq!(
+            clean_description(html, DescriptionFormat::SanitizedHtml),
+            "<p>Hi there<br>\n                      <strong>bold</strong></p>"
+        );
+    }
+}
@@ -39,6 +39,8 @@
 //! **Query Parameters:**
 //! - `num_results` - Page size (default 50, max 1000)
 //! - `page` - Page number (starts at 1)
+//! - `sort_by` - `Title`, `Author`, `Narrator`, `PurchaseDate` or `Length`,
+//!   prefixed with `-` for descending (see [`LibrarySort`])
 //! - `response_groups` - Comma-separated list of data groups to include:
 //!   - `media` - Media metadata (formats, codecs)
 //!   - `product_desc` - Product description
@@ -71,13 +73,19 @@
 use crate::error::{LibationError, Result};
 use crate::api::client::AudibleClient;
 use crate::api::auth::Account;
+use crate::api::content::{deserialize_authors, deserialize_narrators, Codec, Contributor, DownloadQuality};
+use crate::api::description::{clean_description, DescriptionFormat};
+use crate::audio::metadata::SeriesSequence;
+use crate::download::titles;
+use crate::storage::queries::{self, BookWithRelations};
 use crate::storage::Database;
+use sqlx::SqliteConnection;
 use crate::storage::models::{
     Book, NewBook, NewLibraryBook, NewContributor, NewSeries, NewCategory, NewCategoryLadder,
-    ContentType, Role, LibraryBook,
+    ContentType, Role, LibraryBook, join_languages, split_languages,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use chrono::{DateTime, NaiveDate, Utc};
 
 // ============================================================================
@@ -116,6 +124,18 @@ pub struct LibraryOptions {
     pub image_sizes: Option<String>,
 }
 
+impl LibraryOptions {
+    /// Request the library in `sort` order
+    ///
+    /// Fields the API cannot sort by leave `sort_by` unchanged.
+    pub fn with_sort(mut self, sort: LibrarySort) -> Self {
+        if let Some(value) = sort.api_value() {
+            self.sort_by = value;
+        }
+        self
+    }
+}
+
 impl Default for LibraryOptions {
     /// Default options for full library sync
     /// Reference: ApplicationServices/LibraryCommands.cs:122-133
@@ -179,9 +199,14 @@ pub struct LibraryResponse {
 pub struct LibraryItem {
     // === CORE IDENTIFIERS ===
     /// Audible Standard Identification Number (unique product ID)
+    ///
+    /// Placeholder items can come without one; see
+    /// [`placeholder_reason`](Self::placeholder_reason).
+    #[serde(default)]
     pub asin: String,
 
     /// Primary title
+    #[serde(default)]
     pub title: String,
 
     /// Subtitle (if present)
@@ -199,20 +224,23 @@ pub struct LibraryItem {
     pub content_delivery_type: Option<String>,
 
     // === DATES ===
+    // Marketplaces differ in date formats, so dates are parsed leniently and
+    // fall back to None rather than failing the whole page.
+
     /// Date added to library (purchase date)
-    #[serde(rename = "purchase_date")]
-    pub purchase_date: DateTime<Utc>,
+    #[serde(rename = "purchase_date", default, deserialize_with = "deserialize_lenient_datetime")]
+    pub purchase_date: Option<DateTime<Utc>>,
 
     /// Release date (publication date)
-    #[serde(rename = "release_date", default)]
+    #[serde(rename = "release_date", default, deserialize_with = "deserialize_lenient_date")]
     pub release_date: Option<NaiveDate>,
 
     /// Issue date (for serials/podcasts) - date only, no time
-    #[serde(rename = "issue_date", default)]
+    #[serde(rename = "issue_date", default, deserialize_with = "deserialize_lenient_date")]
     pub issue_date: Option<NaiveDate>,
 
     /// Publication date
-    #[serde(rename = "publication_datetime", default)]
+    #[serde(rename = "publication_datetime", default, deserialize_with = "deserialize_lenient_datetime")]
     pub publication_datetime: Option<DateTime<Utc>>,
 
     // === DESCRIPTION ===
@@ -229,9 +257,12 @@ pub struct LibraryItem {
     #[serde(rename = "runtime_length_min", default)]
     pub length_in_minutes: Option<i32>,
 
-    /// Language code (e.g., "en_US")
-    #[serde(default)]
-    pub language: Option<String>,
+    /// Content languages (e.g., `["english"]`)
+    ///
+    /// The API sends one string, a comma-separated string or a list for
+    /// multilingual titles; see [`primary_language`](Self::primary_language).
+    #[serde(rename = "language", default, deserialize_with = "deserialize_languages")]
+    pub languages: Vec<String>,
 
     /// Is abridged version
     #[serde(rename = "is_abridged", default)]
@@ -247,12 +278,12 @@ pub struct LibraryItem {
 
     // === CONTRIBUTORS ===
     /// Authors
-    #[serde(default)]
-    pub authors: Vec<Person>,
+    #[serde(default, deserialize_with = "deserialize_authors")]
+    pub authors: Vec<Contributor>,
 
     /// Narrators
-    #[serde(default)]
-    pub narrators: Vec<Person>,
+    #[serde(default, deserialize_with = "deserialize_narrators")]
+    pub narrators: Vec<Contributor>,
 
     // === RATING ===
     /// Product rating (aggregate)
@@ -325,6 +356,21 @@ pub struct LibraryItem {
 }
 
 impl LibraryItem {
+    /// Why this item is an API placeholder rather than a real title, if it is
+    ///
+    /// The library endpoint occasionally returns stub entries with an empty
+    /// ASIN or title. Importing them would create books with empty paths and
+    /// downloads that can never succeed, so sync skips them.
+    pub fn placeholder_reason(&self) -> Option<&'static str> {
+        if self.asin.trim().is_empty() {
+            Some("no ASIN")
+        } else if self.title.trim().is_empty() {
+            Some("no title")
+        } else {
+            None
+        }
+    }
+
     /// Get full title with subtitle
     /// Reference: BookImporter.cs:106 (TitleWithSubtitle property)
     pub fn title_with_subtitle(&self) -> String {
@@ -368,17 +414,197 @@ impl LibraryItem {
         self.product_images.get("500").cloned()
     }
 
+    /// Get the smallest available cover image URL, for list thumbnails
+    pub fn get_picture_thumbnail(&self) -> Option<String> {
+        self.product_images
+            .iter()
+            .filter_map(|(size, url)| size.parse::<u32>().ok().map(|px| (px, url)))
+            .min_by_key(|(px, _)| *px)
+            .map(|(_, url)| url.clone())
+    }
+
+    /// Description cleaned of HTML, or `None` if the item has none
+    ///
+    /// Library sync stores the [`DescriptionFormat::PlainText`] form.
+    pub fn description_as(&self, format: DescriptionFormat) -> Option<String> {
+        self.description
+            .as_deref()
+            .map(|html| clean_description(html, format))
+            .filter(|d| !d.is_empty())
+    }
+
     /// Check if spatial audio (Dolby Atmos)
     /// Reference: BookImporter.cs:169
     pub fn is_spatial(&self) -> bool {
         self.asset_details.iter().any(|a| a.is_spatial.unwrap_or(false))
     }
 
+    /// First listed content language
+    pub fn primary_language(&self) -> Option<&str> {
+        self.languages.first().map(String::as_str)
+    }
+
+    /// Predict whether this title downloads as AAX or AAXC
+    ///
+    /// Uses `available_codecs` only, so no license is requested. Needs the
+    /// `media` response group; without it the result is `Unknown`.
+    pub fn delivery_format(&self) -> DeliveryFormat {
+        DeliveryFormat::from_codecs(&self.available_codecs)
+    }
+
+    /// Full audio formats this title can be downloaded in, best first
+    ///
+    /// Entries that are not a downloadable audio file are left out: the
+    /// legacy `format4` listing, samples and previews, and anything whose
+    /// name does not say its bitrate. Needs the `media` response group.
+    pub fn downloadable_codecs(&self) -> Vec<AvailableCodec> {
+        let mut codecs: Vec<AvailableCodec> =
+            self.available_codecs.iter().filter_map(AvailableCodec::parse).collect();
+        codecs.sort_by(|a, b| {
+            b.bitrate_kbps
+                .cmp(&a.bitrate_kbps)
+                .then(b.sample_rate_khz.cmp(&a.sample_rate_khz))
+                // AAXC before AAX at the same rate
+                .then((a.delivery == DeliveryFormat::Aax).cmp(&(b.delivery == DeliveryFormat::Aax)))
+        });
+        codecs.dedup();
+        codecs
+    }
+
+    /// Quality tiers to offer in a quality picker, best first
+    ///
+    /// Built from [`downloadable_codecs`](Self::downloadable_codecs), with
+    /// `Extreme` added for spatial titles.
+    pub fn available_qualities(&self) -> Vec<DownloadQuality> {
+        let mut qualities: Vec<DownloadQuality> =
+            self.downloadable_codecs().iter().map(AvailableCodec::quality).collect();
+        if self.is_spatial() {
+            qualities.push(DownloadQuality::Extreme);
+        }
+        qualities.sort_by(|a, b| b.cmp(a));
+        qualities.dedup();
+        qualities
+    }
+
     /// Get publication date (tries multiple date fields)
     pub fn get_publication_date(&self) -> Option<NaiveDate> {
         self.release_date
             .or_else(|| self.publication_datetime.map(|dt| dt.date_naive()))
     }
+
+    /// ASINs of other editions of this title listed in `relationships`
+    /// (abridged/unabridged, alternate narrations)
+    pub fn alternate_version_asins(&self) -> Vec<String> {
+        self.relationships
+            .iter()
+            .flatten()
+            .filter(|r| r.is_alternate_version() && r.asin != self.asin)
+            .map(|r| r.asin.clone())
+            .collect()
+    }
+
+    /// List the editions of this title the user can choose to download
+    ///
+    /// The first entry is always this item. Alternates come from
+    /// `relationships` and from library items with the same title and lead
+    /// author, so an abridged and unabridged copy that Audible does not link
+    /// still show up together. Alternates found in `library` carry their
+    /// narrators and abridged flag; others only have what the relationship
+    /// lists.
+    ///
+    /// # Arguments
+    /// * `library` - The user's library items (may include `self`)
+    pub fn version_choices(&self, library: &[LibraryItem]) -> Vec<VersionChoice> {
+        let mut choices = vec![VersionChoice::from_item(self)];
+        let mut seen: HashSet<String> = HashSet::from([self.asin.clone()]);
+
+        for relationship in self.relationships.iter().flatten() {
+            if !relationship.is_alternate_version() || !seen.insert(relationship.asin.clone()) {
+                continue;
+            }
+            choices.push(match library.iter().find(|i| i.asin == relationship.asin) {
+                Some(item) => VersionChoice::from_item(item),
+                None => VersionChoice {
+                    asin: relationship.asin.clone(),
+                    title: relationship.title.clone().unwrap_or_else(|| self.title.clone()),
+                    narrators: Vec::new(),
+                    is_abridged: None,
+                    length_in_minutes: None,
+                    in_library: false,
+                },
+            });
+        }
+
+        let key = self.edition_key();
+        for item in library {
+            if item.edition_key() == key && seen.insert(item.asin.clone()) {
+                choices.push(VersionChoice::from_item(item));
+            }
+        }
+
+        choices
+    }
+
+    /// Pick which edition to download
+    ///
+    /// # Returns
+    /// `asin` if it is one of [`version_choices`](Self::version_choices)
+    ///
+    /// # Errors
+    /// Returns `InvalidInput` if `asin` is not an edition of this title
+    pub fn select_version(&self, library: &[LibraryItem], asin: &str) -> Result<String> {
+        self.version_choices(library)
+            .into_iter()
+            .find(|choice| choice.asin == asin)
+            .map(|choice| choice.asin)
+            .ok_or_else(|| {
+                LibationError::InvalidInput(format!(
+                    "{} is not an edition of {} ({})",
+                    asin, self.title, self.asin
+                ))
+            })
+    }
+
+    /// Normalized title and lead author, used to group editions
+    fn edition_key(&self) -> (String, String) {
+        (
+            self.title.trim().to_lowercase(),
+            self.authors
+                .first()
+                .map(|a| a.name.trim().to_lowercase())
+                .unwrap_or_default(),
+        )
+    }
+}
+
+/// One downloadable edition of a title
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionChoice {
+    /// ASIN to pass to the download/license calls
+    pub asin: String,
+    /// Title of this edition
+    pub title: String,
+    /// Narrator names (empty when the edition is not in the library)
+    pub narrators: Vec<String>,
+    /// Whether this edition is abridged, if known
+    pub is_abridged: Option<bool>,
+    /// Runtime in minutes, if known
+    pub length_in_minutes: Option<i32>,
+    /// Whether the user owns this edition
+    pub in_library: bool,
+}
+
+impl VersionChoice {
+    fn from_item(item: &LibraryItem) -> Self {
+        Self {
+            asin: item.asin.clone(),
+            title: item.title_with_subtitle(),
+            narrators: item.narrators.iter().map(|n| n.name.clone()).collect(),
+            is_abridged: item.is_abridged,
+            length_in_minutes: item.length_in_minutes,
+            in_library: true,
+        }
+    }
 }
 
 /// Codec information
@@ -401,6 +627,111 @@ pub struct CodecInfo {
     pub is_kindle_enhanced: Option<bool>,
 }
 
+/// A downloadable audio format parsed from an `available_codecs` entry
+///
+/// The entry's `name` gives the container, sample rate and bitrate
+/// (`mp4_44_128` is AAXC at 44.1 kHz and 128 kbps); its `enhanced_codec`
+/// gives the AAC profile (`LC_128_44100_stereo`, `LCElevated`, `HE_...`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvailableCodec {
+    /// Container the file is delivered in
+    pub delivery: DeliveryFormat,
+
+    /// AAC profile; `AacLc` unless the entry names HE-AAC
+    pub codec: Codec,
+
+    /// Sample rate in kHz as named (22 or 44)
+    pub sample_rate_khz: u32,
+
+    /// Bitrate in kbps
+    pub bitrate_kbps: u32,
+}
+
+impl AvailableCodec {
+    /// Parse one `available_codecs` entry
+    ///
+    /// # Returns
+    /// `None` for entries that are not a full downloadable audio file
+    pub fn parse(info: &CodecInfo) -> Option<Self> {
+        let name = info.name.as_deref()?.to_lowercase();
+        let enhanced = info.enhanced_codec.as_deref().unwrap_or_default().to_lowercase();
+        if [&name, &enhanced].iter().any(|s| s.contains("sample") || s.contains("preview")) {
+            return None;
+        }
+
+        let mut parts = name.split('_');
+        let delivery = match parts.next()? {
+            "aax" => DeliveryFormat::Aax,
+            "mp4" => DeliveryFormat::Aaxc,
+            _ => return None,
+        };
+        let sample_rate_khz: u32 = parts.next()?.parse().ok()?;
+        let bitrate_kbps: u32 = parts.next()?.parse().ok()?;
+        if parts.next().is_some() || sample_rate_khz == 0 || bitrate_kbps == 0 {
+            return None;
+        }
+
+        let codec = if enhanced.starts_with("he") || enhanced.starts_with("xhe") {
+            Codec::XHeAac
+        } else {
+            Codec::AacLc
+        };
+
+        Some(Self { delivery, codec, sample_rate_khz, bitrate_kbps })
+    }
+
+    /// Quality tier to request for this format
+    pub fn quality(&self) -> DownloadQuality {
+        DownloadQuality::from_bitrate_kbps(self.bitrate_kbps)
+    }
+}
+
+/// Encrypted container a title is delivered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryFormat {
+    /// Legacy AAX, decrypted with the account's activation bytes
+    Aax,
+    /// AAXC, decrypted with a per-title key from the license request
+    Aaxc,
+    /// No codec information (or none we recognise)
+    Unknown,
+}
+
+impl DeliveryFormat {
+    /// Predict the delivery format from a title's `available_codecs`
+    ///
+    /// AAXC is served as `mp4_*` codecs, with an enhanced codec such as
+    /// `LC_64_44100_stereo`; titles that only list `aax_*` codecs are still
+    /// delivered as AAX.
+    pub fn from_codecs(codecs: &[CodecInfo]) -> Self {
+        let mut has_aax = false;
+        for codec in codecs {
+            let name = codec.name.as_deref().unwrap_or_default().to_lowercase();
+            let enhanced = codec.enhanced_codec.as_deref().unwrap_or_default().to_lowercase();
+            if name.starts_with("mp4") || enhanced.starts_with("lc_") || enhanced.starts_with("he_") {
+                return DeliveryFormat::Aaxc;
+            }
+            has_aax |= name.starts_with("aax");
+        }
+
+        if has_aax {
+            DeliveryFormat::Aax
+        } else {
+            DeliveryFormat::Unknown
+        }
+    }
+
+    /// Label for display ("AAX", "AAXC", or empty when unknown)
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeliveryFormat::Aax => "AAX",
+            DeliveryFormat::Aaxc => "AAXC",
+            DeliveryFormat::Unknown => "",
+        }
+    }
+}
+
 /// Asset detail information
 #[derive(Debug, Clone, Deserialize)]
 pub struct AssetDetail {
@@ -417,18 +748,6 @@ pub struct AssetDetail {
     pub format: Option<String>,
 }
 
-/// Person information (author, narrator)
-/// Maps to C# `Person` class in AudibleApi/Common/Person.cs
-#[derive(Debug, Clone, Deserialize)]
-pub struct Person {
-    /// Person's name
-    pub name: String,
-
-    /// Audible contributor ID (ASIN)
-    #[serde(default)]
-    pub asin: Option<String>,
-}
-
 /// Rating information
 /// Maps to C# `Rating` class in AudibleApi/Common/Rating.cs
 #[derive(Debug, Clone, Deserialize)]
@@ -552,6 +871,19 @@ pub struct Relationship {
     pub url: Option<String>,
 }
 
+/// Relationship types that link another edition of the same title rather
+/// than a series, podcast or bundle member
+pub const ALTERNATE_VERSION_RELATIONSHIP_TYPES: &[&str] = &["edition", "version", "narration"];
+
+impl Relationship {
+    /// Whether this relationship points at another edition of the same title
+    pub fn is_alternate_version(&self) -> bool {
+        self.relationship_type
+            .as_deref()
+            .is_some_and(|t| ALTERNATE_VERSION_RELATIONSHIP_TYPES.contains(&t.to_ascii_lowercase().as_str()))
+    }
+}
+
 // ============================================================================
 // SYNC STATISTICS
 // ============================================================================
@@ -580,6 +912,14 @@ pub struct SyncStats {
 
     /// Whether there are more pages to fetch (for pagination)
     pub has_more: bool,
+
+    /// Cover thumbnails downloaded and stored during this sync
+    #[serde(default)]
+    pub thumbnails_stored: i32,
+
+    /// Placeholder items (empty ASIN or title) left out of the sync
+    #[serde(default)]
+    pub placeholders_skipped: i32,
 }
 
 impl SyncStats {
@@ -588,11 +928,356 @@ impl SyncStats {
     }
 }
 
+/// Image sizes requested when syncing with thumbnails (smallest is stored)
+pub const THUMBNAIL_IMAGE_SIZES: &str = "100,500,1215";
+
+/// Maximum number of cover thumbnails downloaded at once
+const MAX_THUMBNAIL_DOWNLOADS: usize = 8;
+
+/// Overall progress of [`AudibleClient::sync_library_with_thumbnails`]
+///
+/// Once the library has been fetched, `total` counts one step per imported
+/// book plus one per thumbnail to download.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LibrarySyncProgress {
+    /// Books imported into the database so far
+    pub books_imported: usize,
+
+    /// Thumbnails downloaded so far (successful or not)
+    pub thumbnails_fetched: usize,
+
+    /// Total number of steps (0 until the library has been fetched)
+    pub total: usize,
+}
+
+impl LibrarySyncProgress {
+    /// Fraction of work completed, from 0.0 to 1.0
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        ((self.books_imported + self.thumbnails_fetched) as f64 / self.total as f64).min(1.0)
+    }
+}
+
+// ============================================================================
+// LIBRARY STATISTICS
+// ============================================================================
+
+/// Summary of a library for a dashboard view
+///
+/// Series parents are containers rather than listenable titles, so they are
+/// left out of every count.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryStats {
+    /// Listenable titles (books and episodes)
+    pub total_books: usize,
+
+    /// Titles that are podcast episodes
+    pub total_episodes: usize,
+
+    /// Sum of `length_in_minutes` over all titles
+    pub total_runtime_minutes: i64,
+
+    /// Titles marked as finished
+    pub finished: usize,
+
+    /// Downloadable titles that are not downloaded yet
+    pub needing_download: usize,
+
+    /// Titles that belong to at least one series
+    pub books_with_series: usize,
+
+    /// Titles with a subtitle
+    pub books_with_subtitles: usize,
+
+    /// Titles per series name
+    pub by_series: BTreeMap<String, usize>,
+
+    /// Titles per author (a co-written title counts for each author)
+    pub by_author: BTreeMap<String, usize>,
+
+    /// Titles per primary language
+    pub by_language: BTreeMap<String, usize>,
+}
+
+impl LibraryStats {
+    /// The `n` largest entries of one of the count maps, largest first
+    ///
+    /// Ties are broken alphabetically so the order is stable.
+    pub fn top(counts: &BTreeMap<String, usize>, n: usize) -> Vec<(&str, usize)> {
+        let mut entries: Vec<(&str, usize)> = counts.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        entries.truncate(n);
+        entries
+    }
+
+    /// Total runtime as whole hours and remaining minutes
+    pub fn runtime_hours_minutes(&self) -> (i64, i64) {
+        (self.total_runtime_minutes / 60, self.total_runtime_minutes % 60)
+    }
+}
+
+/// Compute [`LibraryStats`] for a fetched library
+///
+/// Every downloadable title counts as needing download, since the items
+/// alone do not say what is on disk. Use [`stats_with_downloaded`] to leave
+/// out the titles that are.
+///
+/// # Example
+/// ```rust,no_run
+/// # fn example(items: &[rust_core::api::library::LibraryItem]) {
+/// use rust_core::api::library::{self, LibraryStats};
+///
+/// let stats = library::stats(items);
+/// let (hours, minutes) = stats.runtime_hours_minutes();
+/// println!("{} books, {}h {}m", stats.total_books, hours, minutes);
+/// for (author, count) in LibraryStats::top(&stats.by_author, 5) {
+///     println!("{}: {}", author, count);
+/// }
+/// # }
+/// ```
+pub fn stats(items: &[LibraryItem]) -> LibraryStats {
+    stats_with_downloaded(items, &HashSet::new())
+}
+
+/// Compute [`LibraryStats`], treating the ASINs in `downloaded` as on disk
+pub fn stats_with_downloaded(items: &[LibraryItem], downloaded: &HashSet<String>) -> LibraryStats {
+    let mut stats = LibraryStats::default();
+
+    for item in items.iter().filter(|i| !i.is_series_parent()) {
+        stats.total_books += 1;
+        if item.is_episode() {
+            stats.total_episodes += 1;
+        }
+        stats.total_runtime_minutes += item.length_in_minutes.unwrap_or(0).max(0) as i64;
+        if item.is_finished == Some(true) {
+            stats.finished += 1;
+        }
+        if item.is_downloadable != Some(false) && !downloaded.contains(&item.asin) {
+            stats.needing_download += 1;
+        }
+        if item.subtitle.as_deref().is_some_and(|s| !s.is_empty()) {
+            stats.books_with_subtitles += 1;
+        }
+
+        let series: Vec<&str> = item
+            .series
+            .iter()
+            .flatten()
+            .filter_map(|s| s.title.as_deref())
+            .filter(|t| !t.trim().is_empty())
+            .collect();
+        if !series.is_empty() {
+            stats.books_with_series += 1;
+        }
+        for title in series {
+            *stats.by_series.entry(title.trim().to_string()).or_default() += 1;
+        }
+
+        let authors: HashSet<&str> = item
+            .authors
+            .iter()
+            .map(|a| a.name.trim())
+            .filter(|n| !n.is_empty())
+            .collect();
+        for author in authors {
+            *stats.by_author.entry(author.to_string()).or_default() += 1;
+        }
+
+        if let Some(language) = item.primary_language() {
+            *stats.by_language.entry(language.to_lowercase()).or_default() += 1;
+        }
+    }
+
+    stats
+}
+
+// ============================================================================
+// LIBRARY SEARCH
+// ============================================================================
+
+/// A library book matched by [`AudibleClient::search_library`]
+///
+/// Higher scores rank first. Title matches outweigh series and contributor
+/// matches, which outweigh matches in the description.
+#[derive(Debug, Clone)]
+pub struct LibrarySearchResult {
+    /// Matched book, as stored by the last library sync
+    pub book: BookWithRelations,
+
+    /// Relevance score (higher is better)
+    pub score: u32,
+}
+
+// ============================================================================
+// LIBRARY SORTING
+// ============================================================================
+
+/// Field to order the library by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LibrarySortField {
+    Title,
+    Author,
+    Narrator,
+    PurchaseDate,
+    /// Runtime in minutes
+    Length,
+    /// Not sortable by the API; client-side only
+    ReleaseDate,
+}
+
+/// Library sort order
+///
+/// The API accepts `sort_by` values such as `Title` or `-PurchaseDate`
+/// (a leading `-` means descending).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibrarySort {
+    pub field: LibrarySortField,
+    pub descending: bool,
+}
+
+impl LibrarySort {
+    /// Ascending order by `field`
+    pub fn ascending(field: LibrarySortField) -> Self {
+        Self { field, descending: false }
+    }
+
+    /// Descending order by `field`
+    pub fn descending(field: LibrarySortField) -> Self {
+        Self { field, descending: true }
+    }
+
+    /// Value for the library endpoint's `sort_by` parameter, if supported
+    pub fn api_value(&self) -> Option<String> {
+        let name = match self.field {
+            LibrarySortField::Title => "Title",
+            LibrarySortField::Author => "Author",
+            LibrarySortField::Narrator => "Narrator",
+            LibrarySortField::PurchaseDate => "PurchaseDate",
+            LibrarySortField::Length => "Length",
+            LibrarySortField::ReleaseDate => return None,
+        };
+        Some(if self.descending { format!("-{}", name) } else { name.to_string() })
+    }
+
+    /// SQL `ORDER BY` expression for [`queries::search_library_books`]
+    ///
+    /// Mirrors [`sort`](Self::sort): missing values (no narrator, zero
+    /// length, ...) come last, and ties fall back to the title.
+    fn order_by_clause(&self) -> String {
+        let direction = if self.descending { "DESC" } else { "ASC" };
+        let (missing, key) = match self.field {
+            LibrarySortField::Title => return format!("b.title COLLATE NOCASE {}", direction),
+            LibrarySortField::Author => ("book_authors.authors IS NULL", "book_authors.authors COLLATE NOCASE"),
+            LibrarySortField::Narrator => ("book_narrators.narrators IS NULL", "book_narrators.narrators COLLATE NOCASE"),
+            LibrarySortField::PurchaseDate => ("lb.date_added IS NULL", "lb.date_added"),
+            LibrarySortField::Length => ("b.length_in_minutes <= 0", "b.length_in_minutes"),
+            LibrarySortField::ReleaseDate => ("b.date_published IS NULL", "b.date_published"),
+        };
+        format!("{}, {} {}, b.title COLLATE NOCASE", missing, key, direction)
+    }
+}
+
+/// Per-ASIN ownership lookups [`AudibleClient::owned_asins`] runs at once
+///
+/// Kept small so checking a full catalog page doesn't trip throttling.
+pub const OWNED_LOOKUP_CONCURRENCY: usize = 4;
+
 // ============================================================================
 // LIBRARY SYNC IMPLEMENTATION
 // ============================================================================
 
 impl AudibleClient {
+    /// Search the user's library
+    ///
+    /// Runs over the library persisted in `db` by any earlier sync, so no
+    /// request is made and a freshly created client sees the same results.
+    /// Books removed from the library are skipped. Every word of the query
+    /// must appear in the title, subtitle, authors, narrators, series, ASIN or
+    /// description (case-insensitive).
+    ///
+    /// # Arguments
+    /// * `db` - Database holding the synced library
+    /// * `query` - Free-text search string
+    ///
+    /// # Returns
+    /// Matching books, best match first. Empty if the query is blank or the
+    /// library has not been synced yet.
+    ///
+    /// # Errors
+    /// Returns error if the database query fails
+    pub async fn search_library(&self, db: &Database, query: &str) -> Result<Vec<LibrarySearchResult>> {
+        let query = query.trim().to_lowercase();
+        let terms: Vec<String> = query.split_whitespace().map(String::from).collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let books = queries::search_library_books(db.pool(), &terms, "b.title").await?;
+        Ok(rank_library_books(books, &query, &terms))
+    }
+
+    /// Get the synced library in `sort` order
+    ///
+    /// Like [`search_library`](Self::search_library), this reads the library
+    /// persisted in `db`, so fields the API cannot sort by (such as release
+    /// date) are supported too. Books removed from the library are skipped.
+    ///
+    /// # Arguments
+    /// * `db` - Database holding the synced library
+    /// * `sort` - Field and direction to order by
+    ///
+    /// # Errors
+    /// Returns error if the database query fails
+    pub async fn sorted_library(&self, db: &Database, sort: LibrarySort) -> Result<Vec<BookWithRelations>> {
+        queries::search_library_books(db.pool(), &[], &sort.order_by_clause()).await
+    }
+
+    /// Which of `asins` the user owns, e.g. to badge catalog results
+    ///
+    /// Answered from this client's account's synced library in `db`, so no
+    /// request is made once that library has been synced.
+    /// Before the first sync each ASIN is looked up with
+    /// `GET /1.0/library/{asin}`, which answers 404 for titles not owned; at
+    /// most [`OWNED_LOOKUP_CONCURRENCY`] lookups are in flight at once.
+    ///
+    /// # Arguments
+    /// * `db` - Database holding the synced library
+    /// * `asins` - ASINs to check
+    ///
+    /// # Returns
+    /// The subset of `asins` the user owns
+    ///
+    /// # Errors
+    /// Returns error if the database query fails or, when falling back to the
+    /// API, a lookup fails for any reason other than 404
+    pub async fn owned_asins(&self, db: &Database, asins: &[String]) -> Result<HashSet<String>> {
+        let account_id = self.account().lock().await.account_id.clone();
+        let mut owned = db.owned_asins(&account_id, asins).await?;
+
+        if db.has_library(&account_id).await? {
+            return Ok(owned);
+        }
+
+        use futures_util::{StreamExt, TryStreamExt};
+
+        let unknown: Vec<&String> = asins.iter().filter(|asin| !owned.contains(*asin)).collect();
+        let lookups = futures_util::stream::iter(unknown)
+            .map(|asin| async move {
+                match self.get::<serde_json::Value>(&format!("/1.0/library/{}", asin)).await {
+                    Ok(_) => Ok(Some(asin.clone())),
+                    Err(LibationError::ApiRequestFailed { status_code: Some(404), .. }) => Ok(None),
+                    Err(e) => Err(e),
+                }
+            })
+            .buffer_unordered(OWNED_LOOKUP_CONCURRENCY);
+        let found: Vec<Option<String>> = lookups.try_collect().await?;
+        owned.extend(found.into_iter().flatten());
+
+        Ok(owned)
+    }
+
     /// Synchronize library from Audible API
     ///
     /// This is the main entry point for library sync. It fetches all pages from the
@@ -611,6 +1296,9 @@ impl AudibleClient {
     /// 7. Link categories
     /// 8. Mark absent books (removed from library since last scan)
     ///
+    /// Steps 3-8 run in one transaction. If the sync fails or its future is
+    /// dropped before they finish, the database keeps the previous library.
+    ///
     /// # Arguments
     /// * `db` - Database connection
     /// * `account` - Account to sync for
@@ -632,25 +1320,174 @@ impl AudibleClient {
 
         // Fetch all library items from API
         let options = LibraryOptions::default();
-        let (items, total_count) = self.fetch_all_library_items(options).await?;
+        let (items, total_count, placeholders) = self.fetch_all_library_items(options).await?;
 
         stats.total_items = items.len() as i32;
         stats.total_library_count = total_count;
+        stats.placeholders_skipped = placeholders;
 
         if items.is_empty() {
             return Ok(stats);
         }
 
-        // Import items into database
-        let (new_count, updated_count, errors) = self.import_items_to_db(db, &items, &account.account_id).await?;
+        // Import and absent marking land together or not at all, so a sync
+        // cancelled halfway leaves the previous library in place
+        let mut tx = db.begin().await?;
+        let (new_count, updated_count, errors) =
+            self.import_items_to_db(&mut tx, &items, &account.account_id).await?;
 
         stats.books_added = new_count;
         stats.books_updated = updated_count;
         stats.errors = errors;
 
         // Mark absent books (removed from library)
-        let absent_count = self.mark_absent_books(db, &items, &account.account_id).await?;
+        let absent_count = self.mark_absent_books(&mut tx, &items, &account.account_id).await?;
         stats.books_absent = absent_count;
+        tx.commit().await?;
+
+        Ok(stats)
+    }
+
+    /// Synchronize the library and its cover thumbnails in one pass
+    ///
+    /// Behaves like [`sync_library`](Self::sync_library), but while the books are
+    /// imported the smallest cover image of each item is downloaded concurrently
+    /// and stored in the database, so a freshly onboarded library can be shown
+    /// with artwork straight away. Thumbnail failures are recorded in
+    /// `stats.errors` and do not fail the sync.
+    ///
+    /// # Arguments
+    /// * `db` - Database connection
+    /// * `account` - Account with authentication credentials
+    /// * `on_progress` - Called with overall progress as each page of books is
+    ///   imported and each thumbnail completes
+    ///
+    /// # Returns
+    /// * `SyncStats` - Sync statistics, including `thumbnails_stored`
+    ///
+    /// # Errors
+    /// Returns an error if the library cannot be fetched or imported
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::api::client::AudibleClient;
+    /// # use rust_core::api::auth::Account;
+    /// # use rust_core::storage::Database;
+    /// # async fn example(account: Account, db: Database) -> rust_core::error::Result<()> {
+    /// let mut client = AudibleClient::new(account.clone())?;
+    /// let stats = client
+    ///     .sync_library_with_thumbnails(&db, &account, |p| {
+    ///         println!("{:.0}%", p.fraction() * 100.0);
+    ///     })
+    ///     .await?;
+    /// println!("{} books, {} thumbnails", stats.total_items, stats.thumbnails_stored);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sync_library_with_thumbnails<F>(
+        &mut self,
+        db: &Database,
+        account: &Account,
+        on_progress: F,
+    ) -> Result<SyncStats>
+    where
+        F: Fn(LibrarySyncProgress) + Send + Sync,
+    {
+        use crate::download::covers::{CoverFetch, CoverPrefetcher, DEFAULT_COVER_DELAY};
+        use futures_util::StreamExt;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut stats = SyncStats::new();
+
+        let options = LibraryOptions {
+            image_sizes: Some(THUMBNAIL_IMAGE_SIZES.to_string()),
+            ..LibraryOptions::default()
+        };
+        let page_size = options.number_of_results_per_page.clamp(1, 1000) as usize;
+        let (items, total_count, placeholders) = self.fetch_all_library_items(options).await?;
+
+        stats.total_items = items.len() as i32;
+        stats.total_library_count = total_count;
+        stats.placeholders_skipped = placeholders;
+
+        if items.is_empty() {
+            on_progress(LibrarySyncProgress::default());
+            return Ok(stats);
+        }
+
+        let thumbnails: Vec<(String, String)> = items
+            .iter()
+            .filter_map(|item| item.get_picture_thumbnail().map(|url| (item.asin.clone(), url)))
+            .collect();
+
+        let total = items.len() + thumbnails.len();
+        let books_imported = AtomicUsize::new(0);
+        let thumbnails_fetched = AtomicUsize::new(0);
+        let report = || {
+            on_progress(LibrarySyncProgress {
+                books_imported: books_imported.load(Ordering::Relaxed),
+                thumbnails_fetched: thumbnails_fetched.load(Ordering::Relaxed),
+                total,
+            })
+        };
+        report();
+
+        // Import books and download thumbnails at the same time
+        let http = self.http_client().clone();
+        let mut tx = db.begin().await?;
+        let import = async {
+            let (mut new_count, mut updated_count, mut errors) = (0, 0, Vec::new());
+            // A page at a time, so progress advances as books are stored
+            for page in items.chunks(page_size) {
+                let (new, updated, page_errors) =
+                    self.import_items_to_db(&mut tx, page, &account.account_id).await?;
+                new_count += new;
+                updated_count += updated;
+                errors.extend(page_errors);
+                books_imported.fetch_add(page.len(), Ordering::Relaxed);
+                report();
+            }
+            Ok::<_, LibationError>((new_count, updated_count, errors))
+        };
+        let prefetcher = CoverPrefetcher::new(http, MAX_THUMBNAIL_DOWNLOADS, DEFAULT_COVER_DELAY);
+        let downloads = prefetcher
+            .prefetch(thumbnails)
+            .inspect(|_| {
+                thumbnails_fetched.fetch_add(1, Ordering::Relaxed);
+                report();
+            })
+            .collect::<Vec<_>>();
+
+        let (import_result, downloaded) = tokio::join!(import, downloads);
+        let (new_count, updated_count, mut errors) = import_result?;
+
+        stats.books_added = new_count;
+        stats.books_updated = updated_count;
+        stats.books_absent = self.mark_absent_books(&mut tx, &items, &account.account_id).await?;
+        tx.commit().await?;
+
+        // Books must exist before their thumbnails can be stored
+        for CoverFetch { asin, url, image } in downloaded {
+            let image = match image {
+                Ok(image) => image,
+                Err(e) => {
+                    errors.push(format!("Failed to download thumbnail for '{}': {}", asin, e));
+                    continue;
+                }
+            };
+
+            let book_id = match crate::storage::queries::find_book_by_asin(db.pool(), &asin).await? {
+                Some(book) => book.book_id,
+                None => continue,
+            };
+
+            match crate::storage::queries::upsert_cover_thumbnail(db.pool(), book_id, &url, &image).await {
+                Ok(()) => stats.thumbnails_stored += 1,
+                Err(e) => errors.push(format!("Failed to store thumbnail for '{}': {}", asin, e)),
+            }
+        }
+
+        stats.errors = errors;
 
         Ok(stats)
     }
@@ -692,10 +1529,13 @@ impl AudibleClient {
         let mut options = LibraryOptions::default();
         options.page_number = page;
 
-        let response: LibraryResponse = self
+        let mut response: LibraryResponse = self
             .get_with_query("/1.0/library", &options)
             .await?;
 
+        // has_more below is based on the page as served, placeholders included
+        let served = response.items.len();
+        stats.placeholders_skipped = drop_placeholders(&mut response.items);
         stats.total_items = response.items.len() as i32;
 
         // Set total_library_count and has_more from API response
@@ -705,8 +1545,9 @@ impl AudibleClient {
             let total_pages = (total as f32 / page_size as f32).ceil() as i32;
             stats.has_more = page < total_pages;
         } else {
-            // If no total provided, check if page is empty to determine has_more
-            stats.has_more = !response.items.is_empty();
+            // If no total provided, only a full page can have another after it
+            let page_size = options.number_of_results_per_page.clamp(1, 1000) as usize;
+            stats.has_more = served >= page_size;
         }
 
         if response.items.is_empty() {
@@ -714,8 +1555,10 @@ impl AudibleClient {
         }
 
         // Import items into database
+        let mut tx = db.begin().await?;
         let (new_count, updated_count, errors) =
-            self.import_items_to_db(db, &response.items, &account.account_id).await?;
+            self.import_items_to_db(&mut tx, &response.items, &account.account_id).await?;
+        tx.commit().await?;
 
         stats.books_added = new_count;
         stats.books_updated = updated_count;
@@ -727,6 +1570,91 @@ impl AudibleClient {
         Ok(stats)
     }
 
+    /// Fetch books added to the library since the last sync
+    ///
+    /// Intended for a periodic background task that notifies the user about new
+    /// titles. Only books purchased after `last_sync` are fetched (using the
+    /// library endpoint's `purchased_after` filter) and imported; absent books are
+    /// not marked, so this is safe to run between full syncs.
+    ///
+    /// On success the account's stored sync timestamp is set to the time this
+    /// call started, so the next call picks up where this one left off.
+    ///
+    /// # Arguments
+    /// * `db` - Database connection
+    /// * `account` - Account to sync for
+    /// * `last_sync` - Cut-off time. `None` uses the account's stored timestamp;
+    ///   if the account has never been synced, nothing is reported and the
+    ///   timestamp is just initialised (so the first run does not flag the whole
+    ///   library as new).
+    ///
+    /// # Returns
+    /// Newly added library items, oldest purchase first
+    ///
+    /// # Errors
+    /// Returns error if the API request or database update fails
+    pub async fn sync_new_since(
+        &mut self,
+        db: &Database,
+        account: &Account,
+        last_sync: Option<DateTime<Utc>>,
+    ) -> Result<Vec<LibraryItem>> {
+        let sync_started = Utc::now();
+
+        let last_sync = match last_sync {
+            Some(last_sync) => Some(last_sync),
+            None => crate::storage::accounts::get_last_sync(db.pool(), &account.account_id)
+                .await?
+                .as_deref()
+                .and_then(parse_lenient_datetime),
+        };
+
+        let Some(last_sync) = last_sync else {
+            crate::storage::accounts::set_last_sync(
+                db.pool(),
+                &account.account_id,
+                &sync_started.to_rfc3339(),
+            )
+            .await?;
+            return Ok(Vec::new());
+        };
+
+        let options = LibraryOptions {
+            purchased_after: Some(
+                last_sync.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            ),
+            ..LibraryOptions::default()
+        };
+        let (items, _, _) = self.fetch_all_library_items(options).await?;
+
+        // The server filter is inclusive; drop anything at or before the cut-off
+        let mut new_items: Vec<LibraryItem> = items
+            .into_iter()
+            .filter(|item| item.purchase_date.is_some_and(|date| date > last_sync))
+            .collect();
+        new_items.sort_by_key(|item| item.purchase_date);
+
+        if !new_items.is_empty() {
+            let mut tx = db.begin().await?;
+            let (_, _, errors) = self
+                .import_items_to_db(&mut tx, &new_items, &account.account_id)
+                .await?;
+            tx.commit().await?;
+            for error in errors {
+                eprintln!("Warning: Failed to import new library item: {}", error);
+            }
+        }
+
+        crate::storage::accounts::set_last_sync(
+            db.pool(),
+            &account.account_id,
+            &sync_started.to_rfc3339(),
+        )
+        .await?;
+
+        Ok(new_items)
+    }
+
     /// Fetch all library items from Audible API with pagination
     ///
     /// # Reference
@@ -742,21 +1670,21 @@ impl AudibleClient {
     /// * `options` - Library query options (page size, filters, response groups)
     ///
     /// # Returns
-    /// All library items across all pages
+    /// All library items across all pages, the total the API reported, and
+    /// how many placeholder items were dropped
     ///
     /// # Errors
     /// Returns error if API requests fail
     async fn fetch_all_library_items(
         &mut self,
         mut options: LibraryOptions,
-    ) -> Result<(Vec<LibraryItem>, i32)> {
+    ) -> Result<(Vec<LibraryItem>, i32, i32)> {
         let mut all_items = Vec::new();
+        let mut refreshed = false;
 
         // Fetch first page
         options.page_number = 1;
-        let first_response: LibraryResponse = self
-            .get_with_query("/1.0/library", &options)
-            .await?;
+        let first_response = self.fetch_library_page(&options, &mut refreshed).await?;
 
         all_items.extend(first_response.items);
 
@@ -768,29 +1696,27 @@ impl AudibleClient {
             // Fetch remaining pages
             for page_num in 2..=total_pages {
                 options.page_number = page_num;
-                let response: LibraryResponse = self
-                    .get_with_query("/1.0/library", &options)
-                    .await?;
+                let response = self.fetch_library_page(&options, &mut refreshed).await?;
 
                 all_items.extend(response.items);
             }
 
-            Ok((all_items, total))
+            let placeholders = drop_placeholders(&mut all_items);
+            dedupe_by_asin(&mut all_items);
+            Ok((all_items, total, placeholders))
         } else {
-            // API doesn't provide total - keep fetching until empty response
-            let page_size = options.number_of_results_per_page;
+            // API doesn't provide total - keep fetching until an empty or
+            // short page, which can only be the last one
+            // The API serves at most 1000 items per page whatever is asked for
+            let page_size = options.number_of_results_per_page.clamp(1, 1000) as usize;
             let mut page_num = 2;
+            let mut last_page_len = all_items.len();
 
-            loop {
+            while last_page_len >= page_size {
                 options.page_number = page_num;
-                let response: LibraryResponse = self
-                    .get_with_query("/1.0/library", &options)
-                    .await?;
-
-                if response.items.is_empty() {
-                    break;
-                }
+                let response = self.fetch_library_page(&options, &mut refreshed).await?;
 
+                last_page_len = response.items.len();
                 all_items.extend(response.items);
                 page_num += 1;
 
@@ -800,8 +1726,35 @@ impl AudibleClient {
                 }
             }
 
+            let placeholders = drop_placeholders(&mut all_items);
+            dedupe_by_asin(&mut all_items);
             let total = all_items.len() as i32;
-            Ok((all_items, total))
+            Ok((all_items, total, placeholders))
+        }
+    }
+
+    /// Fetch one library page, refreshing the access token once if it is rejected
+    ///
+    /// A token that expires partway through a large sync is rejected with 401
+    /// or 403. Refreshing and retrying the same page lets the sync continue
+    /// where it was instead of starting over. `refreshed` is shared across the
+    /// pages of one sync so a token that keeps failing is only refreshed once.
+    async fn fetch_library_page(
+        &self,
+        options: &LibraryOptions,
+        refreshed: &mut bool,
+    ) -> Result<LibraryResponse> {
+        match self.get_with_query("/1.0/library", options).await {
+            Err(LibationError::ApiRequestFailed { status_code: Some(401 | 403), .. }) if !*refreshed => {
+                *refreshed = true;
+                eprintln!(
+                    "🔄 Access token rejected on library page {}. Refreshing and continuing...",
+                    options.page_number
+                );
+                self.refresh_tokens().await?;
+                self.get_with_query("/1.0/library", options).await
+            }
+            result => result,
         }
     }
 
@@ -812,7 +1765,7 @@ impl AudibleClient {
     /// And `LibraryBookImporter.DoImport()` - DtoImporterService/LibraryBookImporter.cs:22-28
     ///
     /// # Arguments
-    /// * `db` - Database connection
+    /// * `conn` - Connection to write through, normally the sync's transaction
     /// * `items` - Library items from API
     /// * `account_id` - Account ID for LibraryBook records
     ///
@@ -820,7 +1773,7 @@ impl AudibleClient {
     /// Tuple of (new_count, updated_count, errors)
     async fn import_items_to_db(
         &self,
-        db: &Database,
+        conn: &mut SqliteConnection,
         items: &[LibraryItem],
         account_id: &str,
     ) -> Result<(i32, i32, Vec<String>)> {
@@ -837,7 +1790,7 @@ impl AudibleClient {
         for item in items {
             for author in &item.authors {
                 if !contributor_cache.contains_key(&author.name) {
-                    match self.upsert_contributor(db, &author.name, author.asin.as_deref()).await {
+                    match self.upsert_contributor(&mut *conn, &author.name, author.asin.as_deref()).await {
                         Ok(id) => { contributor_cache.insert(author.name.clone(), id); },
                         Err(e) => errors.push(format!("Failed to import author '{}': {}", author.name, e)),
                     }
@@ -846,7 +1799,7 @@ impl AudibleClient {
 
             for narrator in &item.narrators {
                 if !contributor_cache.contains_key(&narrator.name) {
-                    match self.upsert_contributor(db, &narrator.name, narrator.asin.as_deref()).await {
+                    match self.upsert_contributor(&mut *conn, &narrator.name, narrator.asin.as_deref()).await {
                         Ok(id) => { contributor_cache.insert(narrator.name.clone(), id); },
                         Err(e) => errors.push(format!("Failed to import narrator '{}': {}", narrator.name, e)),
                     }
@@ -855,7 +1808,7 @@ impl AudibleClient {
 
             if let Some(ref publisher) = item.publisher {
                 if !contributor_cache.contains_key(publisher) {
-                    match self.upsert_contributor(db, publisher, None).await {
+                    match self.upsert_contributor(&mut *conn, publisher, None).await {
                         Ok(id) => { contributor_cache.insert(publisher.clone(), id); },
                         Err(e) => errors.push(format!("Failed to import publisher '{}': {}", publisher, e)),
                     }
@@ -868,7 +1821,7 @@ impl AudibleClient {
             if let Some(series_list) = &item.series {
                 for series_info in series_list {
                     if !series_cache.contains_key(&series_info.series_id) {
-                        match self.upsert_series(db, &series_info.series_id, series_info.title.as_deref()).await {
+                        match self.upsert_series(&mut *conn, &series_info.series_id, series_info.title.as_deref()).await {
                             Ok(id) => { series_cache.insert(series_info.series_id.clone(), id); },
                             Err(e) => errors.push(format!("Failed to import series '{}': {}", series_info.series_id, e)),
                         }
@@ -879,7 +1832,7 @@ impl AudibleClient {
 
         // Import books and link relationships
         for item in items {
-            match self.import_book(db, item, account_id, &contributor_cache, &series_cache).await {
+            match self.import_book(&mut *conn, item, account_id, &contributor_cache, &series_cache).await {
                 Ok(is_new) => {
                     if is_new {
                         new_count += 1;
@@ -902,7 +1855,7 @@ impl AudibleClient {
     /// Based on `BookImporter.DoImport()` - DtoImporterService/BookImporter.cs:28-72
     ///
     /// # Arguments
-    /// * `db` - Database connection
+    /// * `conn` - Connection to write through, normally the sync's transaction
     /// * `item` - Library item from API
     /// * `account_id` - Account ID
     /// * `contributor_cache` - Contributor name -> ID mapping
@@ -912,46 +1865,48 @@ impl AudibleClient {
     /// `true` if book was newly created, `false` if updated
     async fn import_book(
         &self,
-        db: &Database,
+        conn: &mut SqliteConnection,
         item: &LibraryItem,
         account_id: &str,
         contributor_cache: &HashMap<String, i64>,
         series_cache: &HashMap<String, i64>,
     ) -> Result<bool> {
-        let pool = db.pool();
-
         // Check if book exists
         let existing: Option<(i64,)> = sqlx::query_as(
             "SELECT book_id FROM Books WHERE audible_product_id = ?"
         )
         .bind(&item.asin)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
         let (book_id, is_new) = match existing {
             Some((id,)) => {
                 // Update existing book
-                self.update_book(db, id, item).await?;
+                self.update_book(&mut *conn, id, item).await?;
                 (id, false)
             },
             None => {
                 // Create new book
-                let id = self.create_book(db, item).await?;
+                let id = self.create_book(&mut *conn, item).await?;
                 (id, true)
             }
         };
 
         // Upsert LibraryBook record
-        self.upsert_library_book(db, book_id, account_id, &item.purchase_date).await?;
+        // Fall back to "now" when the marketplace sent no usable purchase date
+        let date_added = item.purchase_date.unwrap_or_else(Utc::now);
+        self.upsert_library_book(&mut *conn, book_id, account_id, &date_added).await?;
 
         // Link contributors (authors, narrators, publisher)
-        self.link_contributors(db, book_id, item, contributor_cache).await?;
+        self.link_contributors(&mut *conn, book_id, item, contributor_cache).await?;
 
         // Link series
-        self.link_series(db, book_id, item, series_cache).await?;
+        self.link_series(&mut *conn, book_id, item, series_cache).await?;
 
         // Update user-defined metadata
-        self.update_user_defined_item(db, book_id, item).await?;
+        self.update_user_defined_item(&mut *conn, book_id, item).await?;
+
+        titles::remember_title(&item.asin, &item.title);
 
         Ok(is_new)
     }
@@ -960,15 +1915,13 @@ impl AudibleClient {
     ///
     /// # Reference
     /// Based on `BookImporter.createNewBook()` - DtoImporterService/BookImporter.cs:74-144
-    async fn create_book(&self, db: &Database, item: &LibraryItem) -> Result<i64> {
-        let pool = db.pool();
-
+    async fn create_book(&self, conn: &mut SqliteConnection, item: &LibraryItem) -> Result<i64> {
         let content_type = item.get_content_type() as i32;
-        let description = item.description.as_deref().unwrap_or("");
+        let description = item.description_as(DescriptionFormat::PlainText).unwrap_or_default();
         let length_in_minutes = item.length_in_minutes.unwrap_or(0);
         let is_abridged = item.is_abridged.unwrap_or(false);
         let is_spatial = item.is_spatial();
-        let language = item.language.as_deref();
+        let language = join_languages(&item.languages);
         let date_published = item.get_publication_date();
 
         let rating = item.rating.as_ref();
@@ -989,7 +1942,7 @@ impl AudibleClient {
         let picture_large = item.get_picture_large();
 
         // Determine locale from language
-        let locale = language.unwrap_or("en_US");
+        let locale = item.primary_language().unwrap_or("en_US");
 
         // Extract new fields
         let pdf_url = item.pdf_url.as_deref();
@@ -1035,7 +1988,7 @@ impl AudibleClient {
         .bind(origin_asin)
         .bind(episode_number)
         .bind(content_delivery_type)
-        .execute(pool)
+        .execute(&mut *conn)
         .await?;
 
         Ok(result.last_insert_rowid())
@@ -1045,13 +1998,11 @@ impl AudibleClient {
     ///
     /// # Reference
     /// Based on `BookImporter.updateBook()` - DtoImporterService/BookImporter.cs:146-202
-    async fn update_book(&self, db: &Database, book_id: i64, item: &LibraryItem) -> Result<()> {
-        let pool = db.pool();
-
+    async fn update_book(&self, conn: &mut SqliteConnection, book_id: i64, item: &LibraryItem) -> Result<()> {
         let length_in_minutes = item.length_in_minutes.unwrap_or(0);
         let is_abridged = item.is_abridged.unwrap_or(false);
         let is_spatial = item.is_spatial();
-        let language = item.language.as_deref();
+        let language = join_languages(&item.languages);
         let date_published = item.get_publication_date();
 
         let rating = item.rating.as_ref();
@@ -1112,7 +2063,7 @@ impl AudibleClient {
         .bind(episode_number)
         .bind(content_delivery_type)
         .bind(book_id)
-        .execute(pool)
+        .execute(&mut *conn)
         .await?;
 
         Ok(())
@@ -1124,19 +2075,17 @@ impl AudibleClient {
     /// Based on `LibraryBookImporter.upsertLibraryBooks()` - DtoImporterService/LibraryBookImporter.cs:30-96
     async fn upsert_library_book(
         &self,
-        db: &Database,
+        conn: &mut SqliteConnection,
         book_id: i64,
         account_id: &str,
         date_added: &DateTime<Utc>,
     ) -> Result<()> {
-        let pool = db.pool();
-
         // Check if LibraryBook exists
         let exists: Option<(bool,)> = sqlx::query_as(
             "SELECT is_deleted FROM LibraryBooks WHERE book_id = ?"
         )
         .bind(book_id)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
         match exists {
@@ -1151,7 +2100,7 @@ impl AudibleClient {
                 )
                 .bind(account_id)
                 .bind(book_id)
-                .execute(pool)
+                .execute(&mut *conn)
                 .await?;
             },
             None => {
@@ -1165,7 +2114,7 @@ impl AudibleClient {
                 .bind(book_id)
                 .bind(date_added)
                 .bind(account_id)
-                .execute(pool)
+                .execute(&mut *conn)
                 .await?;
             }
         }
@@ -1179,17 +2128,15 @@ impl AudibleClient {
     /// Based on `BookImporter.createNewBook()` - DtoImporterService/BookImporter.cs:85-138
     async fn link_contributors(
         &self,
-        db: &Database,
+        conn: &mut SqliteConnection,
         book_id: i64,
         item: &LibraryItem,
         contributor_cache: &HashMap<String, i64>,
     ) -> Result<()> {
-        let pool = db.pool();
-
         // Delete existing contributor links
         sqlx::query("DELETE FROM BookContributors WHERE book_id = ?")
             .bind(book_id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
 
         // Link authors
@@ -1205,7 +2152,7 @@ impl AudibleClient {
                 .bind(contributor_id)
                 .bind(Role::Author as i32)
                 .bind(order as i16)
-                .execute(pool)
+                .execute(&mut *conn)
                 .await?;
             }
         }
@@ -1230,7 +2177,7 @@ impl AudibleClient {
                 .bind(contributor_id)
                 .bind(Role::Narrator as i32)
                 .bind(order as i16)
-                .execute(pool)
+                .execute(&mut *conn)
                 .await?;
             }
         }
@@ -1247,7 +2194,7 @@ impl AudibleClient {
                 .bind(book_id)
                 .bind(contributor_id)
                 .bind(Role::Publisher as i32)
-                .execute(pool)
+                .execute(&mut *conn)
                 .await?;
             }
         }
@@ -1261,17 +2208,15 @@ impl AudibleClient {
     /// Based on `BookImporter.updateBook()` - DtoImporterService/BookImporter.cs:179-188
     async fn link_series(
         &self,
-        db: &Database,
+        conn: &mut SqliteConnection,
         book_id: i64,
         item: &LibraryItem,
         series_cache: &HashMap<String, i64>,
     ) -> Result<()> {
-        let pool = db.pool();
-
         // Delete existing series links
         sqlx::query("DELETE FROM SeriesBooks WHERE book_id = ?")
             .bind(book_id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
 
         // Link series
@@ -1291,7 +2236,7 @@ impl AudibleClient {
                     .bind(book_id)
                     .bind(sequence)
                     .bind(index)
-                    .execute(pool)
+                    .execute(&mut *conn)
                     .await?;
                 }
             }
@@ -1306,18 +2251,16 @@ impl AudibleClient {
     /// Based on `BookImporter.updateBook()` - DtoImporterService/BookImporter.cs:162-177
     async fn update_user_defined_item(
         &self,
-        db: &Database,
+        conn: &mut SqliteConnection,
         book_id: i64,
         item: &LibraryItem,
     ) -> Result<()> {
-        let pool = db.pool();
-
         // Check if UserDefinedItem exists
         let exists: Option<(i64,)> = sqlx::query_as(
             "SELECT book_id FROM UserDefinedItems WHERE book_id = ?"
         )
         .bind(book_id)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
         if exists.is_none() {
@@ -1333,7 +2276,7 @@ impl AudibleClient {
             )
             .bind(book_id)
             .bind(item.is_finished.unwrap_or(false))
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         } else {
             // Update user ratings and is_finished
@@ -1354,28 +2297,26 @@ impl AudibleClient {
             .bind(user_rating_story)
             .bind(is_finished)
             .bind(book_id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         }
 
         // Handle PDF supplement
         if let Some(ref pdf_url) = item.pdf_url {
-            self.upsert_supplement(db, book_id, pdf_url).await?;
+            self.upsert_supplement(&mut *conn, book_id, pdf_url).await?;
         }
 
         Ok(())
     }
 
     /// Upsert contributor
-    async fn upsert_contributor(&self, db: &Database, name: &str, asin: Option<&str>) -> Result<i64> {
-        let pool = db.pool();
-
+    async fn upsert_contributor(&self, conn: &mut SqliteConnection, name: &str, asin: Option<&str>) -> Result<i64> {
         // Check if exists
         let existing: Option<(i64,)> = sqlx::query_as(
             "SELECT contributor_id FROM Contributors WHERE name = ?"
         )
         .bind(name)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
         match existing {
@@ -1386,7 +2327,7 @@ impl AudibleClient {
                 )
                 .bind(name)
                 .bind(asin)
-                .execute(pool)
+                .execute(&mut *conn)
                 .await?;
 
                 Ok(result.last_insert_rowid())
@@ -1395,15 +2336,13 @@ impl AudibleClient {
     }
 
     /// Upsert series
-    async fn upsert_series(&self, db: &Database, series_id: &str, name: Option<&str>) -> Result<i64> {
-        let pool = db.pool();
-
+    async fn upsert_series(&self, conn: &mut SqliteConnection, series_id: &str, name: Option<&str>) -> Result<i64> {
         // Check if exists
         let existing: Option<(i64,)> = sqlx::query_as(
             "SELECT series_id FROM Series WHERE audible_series_id = ?"
         )
         .bind(series_id)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
         match existing {
@@ -1413,7 +2352,7 @@ impl AudibleClient {
                     sqlx::query("UPDATE Series SET name = ? WHERE series_id = ?")
                         .bind(name)
                         .bind(id)
-                        .execute(pool)
+                        .execute(&mut *conn)
                         .await?;
                 }
                 Ok(id)
@@ -1424,7 +2363,7 @@ impl AudibleClient {
                 )
                 .bind(series_id)
                 .bind(name)
-                .execute(pool)
+                .execute(&mut *conn)
                 .await?;
 
                 Ok(result.last_insert_rowid())
@@ -1433,15 +2372,13 @@ impl AudibleClient {
     }
 
     /// Upsert supplement (PDF)
-    async fn upsert_supplement(&self, db: &Database, book_id: i64, url: &str) -> Result<()> {
-        let pool = db.pool();
-
+    async fn upsert_supplement(&self, conn: &mut SqliteConnection, book_id: i64, url: &str) -> Result<()> {
         // Check if exists
         let existing: Option<(i64,)> = sqlx::query_as(
             "SELECT supplement_id FROM Supplements WHERE book_id = ?"
         )
         .bind(book_id)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
         match existing {
@@ -1449,14 +2386,14 @@ impl AudibleClient {
                 sqlx::query("UPDATE Supplements SET url = ? WHERE supplement_id = ?")
                     .bind(url)
                     .bind(id)
-                    .execute(pool)
+                    .execute(&mut *conn)
                     .await?;
             },
             None => {
                 sqlx::query("INSERT INTO Supplements (book_id, url) VALUES (?, ?)")
                     .bind(book_id)
                     .bind(url)
-                    .execute(pool)
+                    .execute(&mut *conn)
                     .await?;
             }
         }
@@ -1470,12 +2407,10 @@ impl AudibleClient {
     /// Based on `LibraryBookImporter.upsertLibraryBooks()` - DtoImporterService/LibraryBookImporter.cs:89-94
     async fn mark_absent_books(
         &self,
-        db: &Database,
+        conn: &mut SqliteConnection,
         items: &[LibraryItem],
         account_id: &str,
     ) -> Result<i32> {
-        let pool = db.pool();
-
         // Get all ASINs from current sync
         let current_asins: HashSet<String> = items.iter().map(|i| i.asin.clone()).collect();
 
@@ -1489,7 +2424,7 @@ impl AudibleClient {
             "#
         )
         .bind(account_id)
-        .fetch_all(pool)
+        .fetch_all(&mut *conn)
         .await?;
 
         // Mark books absent that are not in current sync
@@ -1498,7 +2433,7 @@ impl AudibleClient {
             if !current_asins.contains(&asin) {
                 sqlx::query("UPDATE LibraryBooks SET absent_from_last_scan = 1 WHERE book_id = ?")
                     .bind(book_id)
-                    .execute(pool)
+                    .execute(&mut *conn)
                     .await?;
 
                 absent_count += 1;
@@ -1515,17 +2450,825 @@ impl AudibleClient {
 
 /// Parse series index from order string
 ///
-/// Converts series order strings like "1", "2.5", "Book 3" to numeric index.
-/// Falls back to 0.0 if parsing fails.
+/// Converts series order strings like "1", "2.5", "Book 3" to numeric index;
+/// a range such as "1-3" indexes at its first book. Falls back to 0.0 if
+/// parsing fails.
 fn parse_series_index(order: &str) -> f32 {
-    // Try to extract first number from string
-    let numbers: String = order.chars().filter(|c| c.is_ascii_digit() || *c == '.').collect();
-    numbers.parse::<f32>().unwrap_or(0.0)
+    SeriesSequence::parse(order).map(|s| s.sort_key()).unwrap_or(0.0)
+}
+
+/// Drop repeated ASINs, keeping the first occurrence
+///
+/// Items can shift between pages while a sync is running (a purchase moves
+/// everything down one slot), so the same item may come back on two pages.
+/// Remove placeholder items, logging each one
+///
+/// # Returns
+/// Number of items removed
+fn drop_placeholders(items: &mut Vec<LibraryItem>) -> i32 {
+    let before = items.len();
+    items.retain(|item| match item.placeholder_reason() {
+        Some(reason) => {
+            eprintln!("Skipping placeholder library item {:?} ({})", item.asin, reason);
+            false
+        }
+        None => true,
+    });
+    (before - items.len()) as i32
+}
+
+fn dedupe_by_asin(items: &mut Vec<LibraryItem>) {
+    let mut seen = HashSet::new();
+    items.retain(|item| seen.insert(item.asin.clone()));
+}
+
+/// Score and sort library books against a lowercased search query
+///
+/// Ties are broken by title so results are stable between calls.
+fn rank_library_books(books: Vec<BookWithRelations>, query: &str, terms: &[String]) -> Vec<LibrarySearchResult> {
+    let mut results: Vec<LibrarySearchResult> = books
+        .into_iter()
+        .filter_map(|book| {
+            score_library_book(&book, query, terms).map(|score| LibrarySearchResult { book, score })
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.book.title.cmp(&b.book.title))
+    });
+    results
+}
+
+/// Score a single library book, or `None` if any query term is unmatched
+fn score_library_book(book: &BookWithRelations, query: &str, terms: &[String]) -> Option<u32> {
+    let lower = |value: Option<&str>| value.unwrap_or_default().to_lowercase();
+    let asin = &book.audible_product_id;
+    let title = book.title.to_lowercase();
+    let subtitle = lower(book.subtitle.as_deref());
+    let authors = lower(book.authors_str.as_deref());
+    let narrators = lower(book.narrators_str.as_deref());
+    let series = lower(book.series_name.as_deref());
+    let description = book.description.to_lowercase();
+
+    // Whole-query matches on the ASIN or title dominate
+    let mut score = if asin.eq_ignore_ascii_case(query) || title == query {
+        100
+    } else if title.starts_with(query) {
+        60
+    } else if title.contains(query) {
+        40
+    } else {
+        0
+    };
+
+    for term in terms {
+        let term_score = if title.contains(term) || asin.eq_ignore_ascii_case(term) {
+            10
+        } else if subtitle.contains(term) || series.contains(term) {
+            6
+        } else if authors.contains(term) {
+            5
+        } else if narrators.contains(term) {
+            3
+        } else if description.contains(term) {
+            1
+        } else {
+            return None;
+        };
+        score += term_score;
+    }
+
+    Some(score)
+}
+
+/// Date-only formats seen across marketplaces, tried in order
+///
+/// Day-first is tried before month-first, so an ambiguous "03/04/2024" reads as
+/// 3 April (UK/EU); US dates with a day above 12 still parse via the month-first form.
+const LENIENT_DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%Y.%m.%d",
+    "%d/%m/%Y",
+    "%d.%m.%Y",
+    "%d-%m-%Y",
+    "%m/%d/%Y",
+    "%Y%m%d",
+];
+
+/// Naive datetime formats (assumed UTC), tried after RFC 3339
+const LENIENT_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y/%m/%d %H:%M:%S",
+];
+
+/// Refuse titles whose release date is still in the future
+///
+/// Pre-orders are listed in the library before they can be licensed; asking
+/// for a license early only yields an opaque API error.
+///
+/// # Arguments
+/// * `release_date` - Parsed release date, if known; unknown dates pass
+/// * `today` - Current date, usually `Utc::now().date_naive()`
+///
+/// # Errors
+/// - `NotYetReleased` - `release_date` is after `today`
+pub fn ensure_released(release_date: Option<NaiveDate>, today: NaiveDate) -> Result<()> {
+    match release_date {
+        Some(release_date) if release_date > today => {
+            Err(LibationError::NotYetReleased { release_date })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Parse a date string in any of the formats Audible marketplaces return
+///
+/// Accepts date-only values as well as full datetimes (the date part is kept).
+pub(crate) fn parse_lenient_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    LENIENT_DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .or_else(|| parse_lenient_datetime(value).map(|dt| dt.date_naive()))
+}
+
+/// Parse a datetime string leniently
+///
+/// Accepts RFC 3339 (with or without fractional seconds), naive datetimes
+/// (treated as UTC) and date-only values (midnight UTC).
+pub(crate) fn parse_lenient_datetime(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    // Offsets without a colon, e.g. "+0900"
+    if let Ok(dt) = DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    LENIENT_DATETIME_FORMATS
+        .iter()
+        .find_map(|format| chrono::NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|naive| naive.and_utc())
+        .or_else(|| {
+            LENIENT_DATE_FORMATS
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|naive| naive.and_utc())
+        })
+}
+
+/// Serde adapter for optional date fields that never fails on bad input
+fn deserialize_lenient_date<'de, D>(deserializer: D) -> std::result::Result<Option<NaiveDate>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(value.as_ref().and_then(|v| v.as_str()).and_then(parse_lenient_date))
+}
+
+/// Serde adapter for the language field: a string, a list, or null
+fn deserialize_languages<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(match value {
+        Some(serde_json::Value::String(s)) => split_languages(&s),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .flat_map(split_languages)
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+/// Serde adapter for optional datetime fields that never fails on bad input
+fn deserialize_lenient_datetime<'de, D>(deserializer: D) -> std::result::Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(value.as_ref().and_then(|v| v.as_str()).and_then(parse_lenient_datetime))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::client::fixture::{canned_client, respond, CannedTransport, SentRequest};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_ensure_released_rejects_preorders() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let release_date = NaiveDate::from_ymd_opt(2027, 3, 2).unwrap();
+
+        let err = ensure_released(Some(release_date), today).unwrap_err();
+        assert!(matches!(err, LibationError::NotYetReleased { release_date: d } if d == release_date));
+        assert!(err.user_message().contains("available on March 2, 2027"));
+
+        assert!(ensure_released(Some(today), today).is_ok());
+        assert!(ensure_released(None, today).is_ok());
+    }
+
+    fn search_item(asin: &str, title: &str, author: &str) -> LibraryItem {
+        serde_json::from_value(serde_json::json!({
+            "asin": asin,
+            "title": title,
+            "purchase_date": "2024-01-01T00:00:00Z",
+            "authors": [{ "name": author }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_library_stats() {
+        let item = |value: serde_json::Value| -> LibraryItem { serde_json::from_value(value).unwrap() };
+        let items = vec![
+            item(serde_json::json!({
+                "asin": "B001", "title": "Dune", "subtitle": "Book 1", "runtime_length_min": 1260,
+                "language": "English", "is_finished": true,
+                "authors": [{ "name": "Frank Herbert" }],
+                "series": [{ "asin": "S1", "title": "Dune", "sequence": "1" }],
+            })),
+            item(serde_json::json!({
+                "asin": "B002", "title": "Dune Messiah", "runtime_length_min": 530,
+                "language": "english",
+                "authors": [{ "name": "Frank Herbert" }, { "name": "Frank Herbert" }],
+                "series": [{ "asin": "S1", "title": "Dune", "sequence": "2" }],
+            })),
+            item(serde_json::json!({
+                "asin": "B003", "title": "Sample", "is_downloadable": false, "language": "german",
+                "authors": [{ "name": "Anon" }],
+            })),
+            item(serde_json::json!({ "asin": "P001", "title": "Podcast", "content_type": "Parent" })),
+        ];
+
+        let downloaded: HashSet<String> = ["B002".to_string()].into();
+        let summary = stats_with_downloaded(&items, &downloaded);
+        assert_eq!(summary.total_books, 3);
+        assert_eq!(summary.total_runtime_minutes, 1790);
+        assert_eq!(summary.runtime_hours_minutes(), (29, 50));
+        assert_eq!(summary.finished, 1);
+        assert_eq!(summary.needing_download, 1);
+        assert_eq!(summary.books_with_series, 2);
+        assert_eq!(summary.books_with_subtitles, 1);
+        assert_eq!(summary.by_series["Dune"], 2);
+        assert_eq!(summary.by_language["english"], 2);
+        assert_eq!(
+            LibraryStats::top(&summary.by_author, 5),
+            vec![("Frank Herbert", 2), ("Anon", 1)]
+        );
+        assert_eq!(stats(&items).needing_download, 2);
+    }
+
+    #[test]
+    fn test_language_list_deserialization() {
+        let item = |language: serde_json::Value| -> LibraryItem {
+            serde_json::from_value(serde_json::json!({
+                "asin": "B001",
+                "title": "Title",
+                "language": language,
+            }))
+            .unwrap()
+        };
+
+        assert_eq!(item(serde_json::json!("english")).languages, vec!["english"]);
+        assert_eq!(item(serde_json::json!("english, german")).languages, vec!["english", "german"]);
+        let multi = item(serde_json::json!(["spanish", "english"]));
+        assert_eq!(multi.primary_language(), Some("spanish"));
+        assert_eq!(join_languages(&multi.languages).as_deref(), Some("spanish, english"));
+        assert!(item(serde_json::Value::Null).languages.is_empty());
+    }
+
+    /// Client whose `/1.0/library` pages are served from memory, keyed by the
+    /// `page` query parameter
+    ///
+    /// `reject_once` is a page answered with 403 the first time it is requested.
+    fn library_client(pages: Vec<String>, reject_once: Option<i32>) -> (AudibleClient, Arc<CannedTransport>) {
+        let reject_once = std::sync::Mutex::new(reject_once);
+        let transport = Arc::new(CannedTransport::new(move |request| {
+            if request.url.path() == "/auth/token" {
+                return respond(200, r#"{"access_token": "Atna|refreshed", "expires_in": 3600, "token_type": "bearer"}"#);
+            }
+
+            let page = requested_page(request);
+            let mut reject_once = reject_once.lock().unwrap();
+            if *reject_once == Some(page) {
+                *reject_once = None;
+                return respond(403, "");
+            }
+
+            let body = pages
+                .get(page as usize - 1)
+                .cloned()
+                .unwrap_or_else(|| r#"{"items": []}"#.to_string());
+            respond(200, body)
+        }));
+        (canned_client("canned@example.com", transport.clone()), transport)
+    }
+
+    fn requested_page(request: &SentRequest) -> i32 {
+        request.query("page").and_then(|page| page.parse().ok()).unwrap_or(1)
+    }
+
+    /// Library pages requested so far, in order
+    fn requested_pages(transport: &CannedTransport) -> Vec<i32> {
+        transport
+            .sent()
+            .iter()
+            .filter(|request| request.url.path() == "/1.0/library")
+            .map(requested_page)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_library_from_canned_pages() {
+        let page = |asins: &[&str]| {
+            let items: Vec<_> = asins
+                .iter()
+                .map(|asin| serde_json::json!({ "asin": asin, "title": format!("Title {}", asin) }))
+                .collect();
+            serde_json::json!({ "items": items, "total_results": 3 }).to_string()
+        };
+        let (mut client, transport) =
+            library_client(vec![page(&["B001", "B002"]), page(&["B003"])], None);
+
+        let options = LibraryOptions {
+            number_of_results_per_page: 2,
+            ..LibraryOptions::default()
+        };
+        let (items, total, _) = client.fetch_all_library_items(options).await.unwrap();
+
+        assert_eq!(total, 3);
+        let asins: Vec<_> = items.iter().map(|i| i.asin.as_str()).collect();
+        assert_eq!(asins, ["B001", "B002", "B003"]);
+        assert_eq!(requested_pages(&transport), [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_import_rolled_back_when_dropped() {
+        let (client, _) = library_client(vec![], None);
+        let db = Database::new_in_memory().await.unwrap();
+        let items: Vec<LibraryItem> = ["B001", "B002"]
+            .iter()
+            .map(|asin| serde_json::from_value(serde_json::json!({ "asin": asin, "title": asin })).unwrap())
+            .collect();
+        let book_count = || async {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM Books").fetch_one(db.pool()).await.unwrap()
+        };
+
+        // A sync cancelled mid-import drops its transaction without committing
+        let mut tx = db.begin().await.unwrap();
+        let (added, _, errors) = client.import_items_to_db(&mut tx, &items, "drop@example.com").await.unwrap();
+        assert_eq!((added, errors.len()), (2, 0));
+        drop(tx);
+        assert_eq!(book_count().await, 0);
+
+        let mut tx = db.begin().await.unwrap();
+        client.import_items_to_db(&mut tx, &items, "drop@example.com").await.unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(book_count().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_owned_asins() {
+        // `/1.0/library/{asin}` answers 200 for B001 and 404 otherwise
+        let transport = Arc::new(
+            CannedTransport::new(|request| {
+                let status = if request.url.path().ends_with("/B001") { 200 } else { 404 };
+                respond(status, "{}")
+            })
+            .with_delay(std::time::Duration::from_millis(5)),
+        );
+        let client = canned_client("owned@example.com", transport.clone());
+        let db = Database::new_in_memory().await.unwrap();
+        let asins: Vec<String> = (1..=12).map(|n| format!("B{:03}", n)).collect();
+
+        // Nothing synced yet: ask the API, a few ASINs at a time
+        let owned = client.owned_asins(&db, &asins).await.unwrap();
+        assert_eq!(owned, HashSet::from(["B001".to_string()]));
+        assert_eq!(transport.sent().len(), 12);
+        assert!(transport.max_in_flight() <= OWNED_LOOKUP_CONCURRENCY);
+
+        // Another account's library is neither ownership nor a sync of this one
+        let item: LibraryItem = serde_json::from_value(serde_json::json!({ "asin": "B003", "title": "Theirs" })).unwrap();
+        let mut tx = db.begin().await.unwrap();
+        client.import_items_to_db(&mut tx, &[item], "other@example.com").await.unwrap();
+        tx.commit().await.unwrap();
+        let owned = client.owned_asins(&db, &asins).await.unwrap();
+        assert_eq!(owned, HashSet::from(["B001".to_string()]));
+        assert_eq!(transport.sent().len(), 24);
+
+        // Once synced, the local library answers without requests
+        let item: LibraryItem = serde_json::from_value(serde_json::json!({ "asin": "B002", "title": "Owned" })).unwrap();
+        let mut tx = db.begin().await.unwrap();
+        client.import_items_to_db(&mut tx, &[item], "owned@example.com").await.unwrap();
+        tx.commit().await.unwrap();
+
+        let owned = client.owned_asins(&db, &asins).await.unwrap();
+        assert_eq!(owned, HashSet::from(["B002".to_string()]));
+        assert_eq!(transport.sent().len(), 24);
+    }
+
+    /// Library page JSON with the given ASINs and optional `total_results`
+    fn library_page(asins: &[&str], total: Option<i32>) -> String {
+        let items: Vec<_> = asins
+            .iter()
+            .map(|asin| serde_json::json!({ "asin": asin, "title": format!("Title {}", asin) }))
+            .collect();
+        let mut page = serde_json::json!({ "items": items });
+        if let Some(total) = total {
+            page["total_results"] = total.into();
+        }
+        page.to_string()
+    }
+
+    async fn fetch_with_page_size(
+        pages: Vec<String>,
+        page_size: i32,
+    ) -> (Vec<String>, i32, Vec<i32>) {
+        let (mut client, transport) = library_client(pages, None);
+        let options = LibraryOptions {
+            number_of_results_per_page: page_size,
+            ..LibraryOptions::default()
+        };
+        let (items, total, _) = client.fetch_all_library_items(options).await.unwrap();
+        let asins = items.into_iter().map(|i| i.asin).collect();
+        let requested = requested_pages(&transport);
+        (asins, total, requested)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_library_empty_first_page() {
+        let (asins, total, requested) = fetch_with_page_size(vec![library_page(&[], Some(0))], 2).await;
+        assert!(asins.is_empty());
+        assert_eq!(total, 0);
+        assert_eq!(requested, [1]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_library_exact_multiple_of_page_size() {
+        let pages = vec![
+            library_page(&["B001", "B002"], Some(4)),
+            library_page(&["B003", "B004"], Some(4)),
+        ];
+        let (asins, total, requested) = fetch_with_page_size(pages, 2).await;
+        assert_eq!(asins, ["B001", "B002", "B003", "B004"]);
+        assert_eq!(total, 4);
+        // No request for an empty third page
+        assert_eq!(requested, [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_library_partial_last_page() {
+        let pages = vec![
+            library_page(&["B001", "B002"], Some(5)),
+            library_page(&["B003", "B004"], Some(5)),
+            library_page(&["B005"], Some(5)),
+        ];
+        let (asins, total, requested) = fetch_with_page_size(pages, 2).await;
+        assert_eq!(asins.len(), 5);
+        assert_eq!(total, 5);
+        assert_eq!(requested, [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_library_without_total_results() {
+        let pages = vec![
+            library_page(&["B001", "B002"], None),
+            library_page(&["B003", "B004"], None),
+            library_page(&["B005"], None),
+        ];
+        let (asins, total, requested) = fetch_with_page_size(pages, 2).await;
+        assert_eq!(asins.len(), 5);
+        assert_eq!(total, 5);
+        // The short third page ends the sync
+        assert_eq!(requested, [1, 2, 3]);
+
+        let pages = vec![library_page(&["B001", "B002"], None), library_page(&["B003", "B004"], None)];
+        let (_, total, requested) = fetch_with_page_size(pages, 2).await;
+        assert_eq!(total, 4);
+        assert_eq!(requested, [1, 2, 3]);
+
+        let (_, total, requested) = fetch_with_page_size(vec![library_page(&[], None)], 2).await;
+        assert_eq!(total, 0);
+        assert_eq!(requested, [1]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_library_duplicates_across_pages() {
+        // A purchase during sync pushed B002 onto the second page as well
+        let pages = vec![
+            library_page(&["B001", "B002"], Some(4)),
+            library_page(&["B002", "B003"], Some(4)),
+        ];
+        let (asins, _, requested) = fetch_with_page_size(pages, 2).await;
+        assert_eq!(asins, ["B001", "B002", "B003"]);
+        assert_eq!(requested, [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_library_skips_placeholders() {
+        let page = serde_json::json!({ "items": [
+            { "asin": "B001", "title": "Real Book" },
+            { "asin": "", "title": "Ghost" },
+            { "title": "No ASIN at all" },
+            { "asin": "B004", "title": "  " },
+        ], "total_results": 4 });
+        let (mut client, _) = library_client(vec![page.to_string()], None);
+
+        let (items, _, skipped) = client.fetch_all_library_items(LibraryOptions::default()).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].asin, "B001");
+        assert_eq!(skipped, 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_library_refreshes_and_continues() {
+        let page = |asin: &str| {
+            serde_json::json!({ "items": [{ "asin": asin, "title": asin }], "total_results": 3 }).to_string()
+        };
+        let (mut client, transport) = library_client(vec![page("B001"), page("B002"), page("B003")], Some(2));
+        client.account().lock().await.set_identity(crate::api::auth::Identity::new(
+            crate::api::auth::AccessToken {
+                token: "Atna|stale".to_string(),
+                expires_at: Utc::now(),
+            },
+            "Atnr|refresh".to_string(),
+            String::new(),
+            String::new(),
+            crate::api::auth::Locale::us(),
+        ));
+
+        let options = LibraryOptions {
+            number_of_results_per_page: 1,
+            ..LibraryOptions::default()
+        };
+        let (items, _, _) = client.fetch_all_library_items(options).await.unwrap();
+
+        assert_eq!(items.len(), 3);
+        // Page 2 is retried after the refresh; page 1 is not fetched again
+        assert_eq!(requested_pages(&transport), [1, 2, 2, 3]);
+        let account = client.account();
+        let account = account.lock().await;
+        assert_eq!(account.identity.as_ref().unwrap().access_token.token, "Atna|refreshed");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_library_reports_parse_failure() {
+        let (mut client, _) = library_client(vec![r#"{"items": [{"title": 42}]}"#.to_string()], None);
+
+        let err = client
+            .fetch_all_library_items(LibraryOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LibationError::InvalidApiResponse { .. }));
+    }
+
+    #[test]
+    fn test_lenient_date_parsing() {
+        let date = NaiveDate::from_ymd_opt(2024, 4, 3).unwrap();
+        assert_eq!(parse_lenient_date("2024-04-03"), Some(date));
+        assert_eq!(parse_lenient_date("2024/04/03"), Some(date));
+        assert_eq!(parse_lenient_date("03/04/2024"), Some(date));
+        assert_eq!(parse_lenient_date("03.04.2024"), Some(date));
+        assert_eq!(parse_lenient_date("04/30/2024"), NaiveDate::from_ymd_opt(2024, 4, 30));
+        assert_eq!(parse_lenient_date("2024-04-03T10:15:00Z"), Some(date));
+        assert_eq!(parse_lenient_date("not a date"), None);
+        assert_eq!(parse_lenient_date(""), None);
+
+        let datetime = parse_lenient_datetime("2024-04-03T10:15:00.123+09:00").unwrap();
+        assert_eq!(datetime.to_rfc3339(), "2024-04-03T01:15:00.123+00:00");
+        assert_eq!(
+            parse_lenient_datetime("2024-04-03 10:15:00").unwrap().to_rfc3339(),
+            "2024-04-03T10:15:00+00:00"
+        );
+        assert_eq!(
+            parse_lenient_datetime("2024-04-03").unwrap().to_rfc3339(),
+            "2024-04-03T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_library_item_tolerates_bad_dates() {
+        let item: LibraryItem = serde_json::from_value(serde_json::json!({
+            "asin": "B001",
+            "title": "Test",
+            "purchase_date": "garbage",
+            "release_date": "03/04/2024",
+            "issue_date": 20240403,
+            "publication_datetime": null,
+        }))
+        .unwrap();
+
+        assert!(item.purchase_date.is_none());
+        assert_eq!(item.release_date, NaiveDate::from_ymd_opt(2024, 4, 3));
+        assert!(item.issue_date.is_none());
+        assert!(item.publication_datetime.is_none());
+    }
+
+    #[test]
+    fn test_version_choices() {
+        let item: LibraryItem = serde_json::from_value(serde_json::json!({
+            "asin": "B0UNABRIDG",
+            "title": "Pride and Prejudice",
+            "is_abridged": false,
+            "authors": [{ "name": "Jane Austen" }],
+            "narrators": [{ "name": "Rosamund Pike" }],
+            "relationships": [
+                { "asin": "B0NARRATE2", "relationship_type": "narration", "title": "Pride and Prejudice" },
+                { "asin": "B0SERIESPA", "relationship_type": "series", "relationship_to_product": "parent" }
+            ]
+        }))
+        .unwrap();
+        let abridged: LibraryItem = serde_json::from_value(serde_json::json!({
+            "asin": "B0ABRIDGED",
+            "title": "Pride and Prejudice",
+            "is_abridged": true,
+            "authors": [{ "name": "Jane Austen" }],
+            "narrators": [{ "name": "Emilia Fox" }]
+        }))
+        .unwrap();
+        let library = vec![item.clone(), abridged, search_item("B0OTHER001", "Emma", "Jane Austen")];
+
+        assert_eq!(item.alternate_version_asins(), vec!["B0NARRATE2"]);
+
+        let choices = item.version_choices(&library);
+        let asins: Vec<&str> = choices.iter().map(|c| c.asin.as_str()).collect();
+        assert_eq!(asins, vec!["B0UNABRIDG", "B0NARRATE2", "B0ABRIDGED"]);
+        assert!(!choices[1].in_library);
+        assert_eq!(choices[2].is_abridged, Some(true));
+        assert_eq!(choices[2].narrators, vec!["Emilia Fox"]);
+
+        assert_eq!(item.select_version(&library, "B0ABRIDGED").unwrap(), "B0ABRIDGED");
+        assert!(item.select_version(&library, "B0OTHER001").is_err());
+    }
+
+    #[test]
+    fn test_library_sort() {
+        assert_eq!(
+            LibrarySort::descending(LibrarySortField::PurchaseDate).api_value().as_deref(),
+            Some("-PurchaseDate")
+        );
+        let options = LibraryOptions::default().with_sort(LibrarySort::ascending(LibrarySortField::ReleaseDate));
+        assert_eq!(options.sort_by, "PurchaseDate");
+    }
+
+    #[tokio::test]
+    async fn test_sorted_library_uses_database() {
+        let mut a = search_item("B001", "beta", "Zed Author");
+        a.length_in_minutes = Some(300);
+        let mut b = search_item("B002", "Alpha", "Amy Author");
+        b.length_in_minutes = Some(600);
+        let c = search_item("B003", "Gamma", "Amy Author");
+        let db = Database::new_in_memory().await.unwrap();
+        let (syncing_client, _) = library_client(vec![], None);
+        let mut tx = db.begin().await.unwrap();
+        syncing_client.import_items_to_db(&mut tx, &[a, b, c], "sort@example.com").await.unwrap();
+        tx.commit().await.unwrap();
+
+        let (client, _) = library_client(vec![], None);
+        let sorted = |sort| {
+            let client = &client;
+            let db = &db;
+            async move {
+                let books = client.sorted_library(db, sort).await.unwrap();
+                books.into_iter().map(|b| b.audible_product_id).collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(sorted(LibrarySort::ascending(LibrarySortField::Title)).await, ["B002", "B001", "B003"]);
+        assert_eq!(sorted(LibrarySort::ascending(LibrarySortField::Author)).await, ["B002", "B003", "B001"]);
+        // Missing lengths stay last even when descending
+        assert_eq!(sorted(LibrarySort::descending(LibrarySortField::Length)).await, ["B002", "B001", "B003"]);
+    }
+
+    #[tokio::test]
+    async fn test_search_library_uses_database() {
+        let items = vec![
+            search_item("B001", "The Dune Chronicles Companion", "Someone Else"),
+            search_item("B002", "Dune", "Frank Herbert"),
+            search_item("B003", "Children of Dune", "Frank Herbert"),
+            search_item("B004", "Foundation", "Isaac Asimov"),
+            search_item("B005", "Dune Messiah", "Frank Herbert"),
+        ];
+        let db = Database::new_in_memory().await.unwrap();
+        let (syncing_client, _) = library_client(vec![], None);
+        let mut tx = db.begin().await.unwrap();
+        syncing_client.import_items_to_db(&mut tx, &items, "search@example.com").await.unwrap();
+        tx.commit().await.unwrap();
+        sqlx::query(
+            "UPDATE LibraryBooks SET is_deleted = 1 WHERE book_id = \
+             (SELECT book_id FROM Books WHERE audible_product_id = 'B005')",
+        )
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        // A different client, as the bridges create per call, sees the synced library
+        let (client, _) = library_client(vec![], None);
+        let asins = |results: Vec<LibrarySearchResult>| -> Vec<String> {
+            results.into_iter().map(|r| r.book.audible_product_id).collect()
+        };
+        assert_eq!(asins(client.search_library(&db, "dune").await.unwrap()), ["B002", "B003", "B001"]);
+
+        // Every term must match somewhere
+        assert_eq!(client.search_library(&db, "dune herbert").await.unwrap().len(), 2);
+        assert!(client.search_library(&db, "dune asimov").await.unwrap().is_empty());
+
+        // ASIN lookup, LIKE wildcards taken literally and blank queries
+        assert_eq!(asins(client.search_library(&db, "b004").await.unwrap()), ["B004"]);
+        assert!(client.search_library(&db, "%").await.unwrap().is_empty());
+        assert!(client.search_library(&db, "   ").await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delivery_format_from_codecs() {
+        let codec = |name: &str, enhanced: Option<&str>| CodecInfo {
+            name: Some(name.to_string()),
+            enhanced_codec: enhanced.map(String::from),
+            format: None,
+            is_kindle_enhanced: None,
+        };
+
+        let aaxc = vec![
+            codec("aax_22_64", Some("format4")),
+            codec("mp4_44_128", Some("LC_128_44100_stereo")),
+        ];
+        assert_eq!(DeliveryFormat::from_codecs(&aaxc), DeliveryFormat::Aaxc);
+
+        let aax = vec![codec("aax_22_32", Some("format4")), codec("aax_44_128", None)];
+        assert_eq!(DeliveryFormat::from_codecs(&aax), DeliveryFormat::Aax);
+
+        assert_eq!(DeliveryFormat::from_codecs(&[]), DeliveryFormat::Unknown);
+        assert_eq!(DeliveryFormat::Aaxc.label(), "AAXC");
+    }
+
+    #[test]
+    fn test_downloadable_codecs() {
+        let item: LibraryItem = serde_json::from_value(serde_json::json!({
+            "asin": "B0CODECS",
+            "title": "Codecs",
+            "available_codecs": [
+                {"name": "format4", "enhanced_codec": "format4", "format": "Format4"},
+                {"name": "aax_22_32", "enhanced_codec": "LC_32_22050_stereo", "format": "Enhanced"},
+                {"name": "mp4_22_32", "enhanced_codec": "LCElevated", "format": "Enhanced"},
+                {"name": "mp4_44_128", "enhanced_codec": "LC_128_44100_stereo", "format": "Enhanced"},
+                {"name": "mp4_44_128", "enhanced_codec": "LC_128_44100_stereo", "format": "Enhanced"},
+                {"name": "aax_sample_22_32"},
+                {"name": "piff_22_64"}
+            ]
+        }))
+        .unwrap();
+
+        let codecs = item.downloadable_codecs();
+        let names: Vec<_> = codecs.iter().map(|c| (c.delivery, c.bitrate_kbps)).collect();
+        assert_eq!(
+            names,
+            [(DeliveryFormat::Aaxc, 128), (DeliveryFormat::Aaxc, 32), (DeliveryFormat::Aax, 32)]
+        );
+        assert!(codecs.iter().all(|c| c.codec == Codec::AacLc));
+        assert_eq!(item.available_qualities(), [DownloadQuality::High, DownloadQuality::Low]);
+    }
+
+    #[test]
+    fn test_contributors_default_missing_fields() {
+        let item: LibraryItem = serde_json::from_value(serde_json::json!({
+            "asin": "B001",
+            "title": "The Odyssey",
+            "authors": [{ "name": "Homer", "asin": "B000APZGGS" }, { "name": "Emily Wilson", "role": "translator" }],
+            "narrators": [{ "name": "Claire Danes" }],
+        }))
+        .unwrap();
+
+        assert!(item.authors[0].is_author());
+        assert_eq!(item.authors[1].role, "translator");
+        assert!(item.narrators[0].is_narrator());
+        assert_eq!(
+            item.authors[0].page_url(&crate::api::auth::Locale::us()).as_deref(),
+            Some("https://www.audible.com/author/B000APZGGS")
+        );
+        assert_eq!(item.narrators[0].page_url(&crate::api::auth::Locale::us()), None);
+
+        let bare: LibraryItem =
+            serde_json::from_value(serde_json::json!({ "asin": "B002", "title": "Anon", "authors": null })).unwrap();
+        assert!(bare.authors.is_empty() && bare.narrators.is_empty());
+    }
 
     #[test]
     fn test_parse_series_index() {
@@ -1533,6 +3276,7 @@ mod tests {
         assert_eq!(parse_series_index("2.5"), 2.5);
         assert_eq!(parse_series_index("Book 3"), 3.0);
         assert_eq!(parse_series_index("10"), 10.0);
+        assert_eq!(parse_series_index("1-3"), 1.0);
         assert_eq!(parse_series_index("invalid"), 0.0);
     }
 
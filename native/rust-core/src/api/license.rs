@@ -58,6 +58,12 @@
 //! 7. Parse license to extract decryption keys
 //! 8. Use keys to decrypt DASH segments
 //!
+//! ## Streaming Flow
+//! `build_streaming_license` sends the same request with
+//! `"consumption_type": "Streaming"` and returns `ContentUrl.streaming_url`
+//! (a DASH manifest for Widevine) with the keys, for playback without a
+//! download. Streaming URLs expire quickly, so the license is not cached.
+//!
 //! # API Endpoints
 //!
 //! ## License Request
@@ -86,10 +92,10 @@
 //!
 //! Reference: DownloadOptions.Factory.cs:100 - api.WidevineDrmLicense()
 
-use crate::error::{LibationError, Result};
+use crate::error::{AudibleErrorCode, LibationError, Result};
 use crate::api::client::AudibleClient;
 use crate::api::content::{
-    DrmType, Codec, DownloadQuality, ChapterTitlesType, ContentMetadata
+    DrmType, Codec, CodecDowngrade, DownloadQuality, ChapterTitlesType, ContentMetadata, ContentReference, QualityDowngrade
 };
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
@@ -113,6 +119,9 @@ use chrono::{DateTime, Utc};
 ///     Codecs spatialCodecChoice
 /// )
 /// ```
+///
+/// Use [`LicenseRequest::builder`] to set only the values that differ from
+/// the defaults.
 #[derive(Debug, Clone, Serialize)]
 pub struct LicenseRequest {
     /// Download quality (Normal, High, Extreme)
@@ -121,7 +130,7 @@ pub struct LicenseRequest {
     pub quality: DownloadQuality,
 
     /// Consumption type (Download vs Streaming)
-    /// Always "Download" for offline use
+    /// "Download" for offline use, "Streaming" for in-app playback
     #[serde(rename = "consumption_type")]
     pub consumption_type: ConsumptionType,
 
@@ -149,6 +158,13 @@ pub struct LicenseRequest {
     /// Reference: DownloadOptions.Factory.cs:74 - spatialCodecChoice
     #[serde(rename = "spatial_codec", skip_serializing_if = "Option::is_none")]
     pub spatial_codec: Option<Codec>,
+
+    /// Preferred language for chapter titles, e.g. `de-DE`
+    ///
+    /// Sent as `Accept-Language` rather than in the body. If the title has no
+    /// chapter titles in this language the request is repeated without it.
+    #[serde(skip)]
+    pub chapter_titles_language: Option<String>,
 }
 
 /// Consumption type for license request
@@ -173,7 +189,120 @@ impl Default for LicenseRequest {
             request_spatial: Some(false),
             aac_codec: Some(Codec::AacLc),
             spatial_codec: Some(Codec::Ec3),
+            chapter_titles_language: None,
+        }
+    }
+}
+
+impl LicenseRequest {
+    pub fn builder() -> LicenseRequestBuilder {
+        LicenseRequestBuilder::new()
+    }
+}
+
+/// Builder for LicenseRequest
+///
+/// Starts from an offline download at High quality with Audible DRM, a chapter
+/// tree, AAC-LC and no spatial audio, which is what Libation asks for unless
+/// configured otherwise.
+///
+/// # Example
+/// ```rust,no_run
+/// use rust_core::api::content::{Codec, DownloadQuality};
+/// use rust_core::api::license::LicenseRequest;
+///
+/// let request = LicenseRequest::builder()
+///     .quality(DownloadQuality::Extreme)
+///     .spatial(true)
+///     .spatial_codec(Codec::Ac4)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct LicenseRequestBuilder {
+    request: LicenseRequest,
+}
+
+impl LicenseRequestBuilder {
+    pub fn new() -> Self {
+        Self {
+            request: LicenseRequest {
+                // Reference: DownloadOptions.Factory.cs:68-112 - the API requires a DRM type
+                drm_type: Some(DrmType::Adrm),
+                ..LicenseRequest::default()
+            },
+        }
+    }
+
+    pub fn quality(mut self, quality: DownloadQuality) -> Self {
+        self.request.quality = quality;
+        self
+    }
+
+    pub fn consumption_type(mut self, consumption_type: ConsumptionType) -> Self {
+        self.request.consumption_type = consumption_type;
+        self
+    }
+
+    pub fn drm_type(mut self, drm_type: DrmType) -> Self {
+        self.request.drm_type = Some(drm_type);
+        self
+    }
+
+    /// Ask for Widevine (DASH) instead of Audible DRM
+    pub fn prefer_widevine(self, prefer_widevine: bool) -> Self {
+        self.drm_type(if prefer_widevine { DrmType::Widevine } else { DrmType::Adrm })
+    }
+
+    pub fn chapter_titles_type(mut self, chapter_titles_type: ChapterTitlesType) -> Self {
+        self.request.chapter_titles_type = Some(chapter_titles_type);
+        self
+    }
+
+    /// Ask for the spatial (Dolby Atmos) version when the title has one
+    pub fn spatial(mut self, request_spatial: bool) -> Self {
+        self.request.request_spatial = Some(request_spatial);
+        self
+    }
+
+    pub fn aac_codec(mut self, codec: Codec) -> Self {
+        self.request.aac_codec = Some(codec);
+        self
+    }
+
+    pub fn spatial_codec(mut self, codec: Codec) -> Self {
+        self.request.spatial_codec = Some(codec);
+        self
+    }
+
+    pub fn chapter_titles_language(mut self, language: Option<String>) -> Self {
+        self.request.chapter_titles_language = language.filter(|l| !l.trim().is_empty());
+        self
+    }
+
+    /// Finish the request
+    ///
+    /// # Errors
+    /// - `InvalidInput` - `aac_codec` is not an AAC codec, or `spatial_codec`
+    ///   is not a spatial one
+    pub fn build(self) -> Result<LicenseRequest> {
+        if let Some(codec) = self.request.aac_codec {
+            if !matches!(codec, Codec::AacLc | Codec::XHeAac) {
+                return Err(LibationError::InvalidInput(format!("{:?} is not an AAC codec", codec)));
+            }
         }
+        if let Some(codec) = self.request.spatial_codec {
+            if !matches!(codec, Codec::Ec3 | Codec::Ac4) {
+                return Err(LibationError::InvalidInput(format!("{:?} is not a spatial codec", codec)));
+            }
+        }
+        Ok(self.request)
+    }
+}
+
+impl Default for LicenseRequestBuilder {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -233,10 +362,180 @@ pub struct ContentLicense {
     pub license_response: Option<String>,
 }
 
+/// Body of a license request response, in any of the shapes Audible uses
+///
+/// Reference: AudibleApi `ContentLicenseResponse` (`{"content_license": ...}`)
+///
+/// Most marketplaces wrap the license in `content_license`; some return it
+/// at the top level. A refused request can still come back with a success
+/// status, either as a `content_license` with `status_code: "Denied"` and
+/// denial reasons, or as a bare `{"error_code": ..., "message": ...}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum LicenseResponse {
+    /// `{"content_license": {...}}`
+    Wrapped { content_license: WrappedLicense },
+    /// The license object itself
+    Direct(ContentLicense),
+    /// An error body with no license
+    Error(LicenseError),
+}
+
+/// Contents of the `content_license` field
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum WrappedLicense {
+    License(ContentLicense),
+    Denied(LicenseError),
+}
+
+/// Error details sent in place of a license
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LicenseError {
+    /// License status, e.g. `"Denied"`
+    #[serde(default)]
+    pub status_code: Option<String>,
+
+    /// Audible error code (`error_code` or `code`)
+    #[serde(default, alias = "code")]
+    pub error_code: Option<String>,
+
+    /// Human-readable explanation
+    #[serde(default)]
+    pub message: Option<String>,
+
+    /// Why the license was refused
+    #[serde(default)]
+    pub license_denial_reasons: Vec<LicenseDenialReason>,
+}
+
+/// One entry of `license_denial_reasons`
+#[derive(Debug, Clone, Deserialize)]
+pub struct LicenseDenialReason {
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub reject_code: Option<String>,
+}
+
+impl LicenseError {
+    /// Whether the body carried any error information at all
+    ///
+    /// Every field is optional, so a malformed license also parses as an
+    /// empty `LicenseError`.
+    fn is_empty(&self) -> bool {
+        self.status_code.is_none()
+            && self.error_code.is_none()
+            && self.message.is_none()
+            && self.license_denial_reasons.is_empty()
+    }
+
+    /// Explanation combining the message and the denial reasons
+    pub fn describe(&self) -> String {
+        let mut parts: Vec<String> = self.message.iter().cloned().collect();
+        for reason in &self.license_denial_reasons {
+            match (&reason.reject_code, &reason.message) {
+                (Some(code), Some(message)) => parts.push(format!("{} ({})", message, code)),
+                (None, Some(message)) => parts.push(message.clone()),
+                (Some(code), None) => parts.push(code.clone()),
+                (None, None) => {}
+            }
+        }
+        if parts.is_empty() {
+            parts.push(self.status_code.clone().unwrap_or_else(|| "no reason given".to_string()));
+        }
+        parts.join("; ")
+    }
+
+    /// Most specific code available, for [`AudibleErrorCode`]
+    fn code(&self) -> Option<&str> {
+        self.error_code
+            .as_deref()
+            .or_else(|| self.license_denial_reasons.iter().find_map(|r| r.reject_code.as_deref()))
+    }
+}
+
+impl LicenseResponse {
+    /// Parse a license response body
+    ///
+    /// # Errors
+    /// - `ApiRequestFailed` - The body is an error or a denied license
+    /// - `InvalidApiResponse` - The body matches no known shape
+    pub fn parse(body: serde_json::Value, endpoint: &str) -> Result<ContentLicense> {
+        let response = serde_json::from_value::<LicenseResponse>(body.clone()).ok();
+        let error = match response {
+            Some(LicenseResponse::Wrapped { content_license: WrappedLicense::License(license) })
+            | Some(LicenseResponse::Direct(license)) => return Ok(license),
+            Some(LicenseResponse::Wrapped { content_license: WrappedLicense::Denied(error) })
+            | Some(LicenseResponse::Error(error)) => error,
+            None => LicenseError::default(),
+        };
+
+        if error.is_empty() {
+            // Neither a license nor an error: report why the license did not parse
+            let license_json = body.get("content_license").unwrap_or(&body);
+            let reason = serde_json::from_value::<ContentLicense>(license_json.clone())
+                .err()
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| "unrecognised response shape".to_string());
+            return Err(LibationError::InvalidApiResponse {
+                message: format!("Failed to parse content license: {}", reason),
+                response_body: Some(body.to_string()),
+            });
+        }
+
+        Err(LibationError::ApiRequestFailed {
+            message: format!("License request refused: {}", error.describe()),
+            status_code: None,
+            endpoint: Some(endpoint.to_string()),
+            error_code: error.code().map(AudibleErrorCode::parse),
+        })
+    }
+}
+
+/// Whether a license failure means the title is not offered at the requested quality
+///
+/// Audible answers with a 4xx (or a refusal in a success body) whose message
+/// names the quality; auth, throttling and not-found failures never qualify.
+pub(crate) fn is_quality_unavailable(error: &LibationError) -> bool {
+    match error {
+        LibationError::ApiRequestFailed { message, status_code, error_code, .. } => {
+            matches!(status_code, None | Some(400..=499))
+                && !matches!(
+                    error_code,
+                    Some(AudibleErrorCode::Unauthorized | AudibleErrorCode::Throttled | AudibleErrorCode::NotFound)
+                )
+                && message.to_lowercase().contains("quality")
+        }
+        _ => false,
+    }
+}
+
+/// Content URL named by the first `BaseURL` of a DASH manifest
+///
+/// Relative URLs are resolved against `manifest_url`.
+pub(crate) fn dash_content_url(manifest: &str, manifest_url: &str) -> Option<String> {
+    let start = manifest.find("<BaseURL")?;
+    let text_start = start + manifest[start..].find('>')? + 1;
+    let text_end = text_start + manifest[text_start..].find("</BaseURL>")?;
+    let base = manifest[text_start..text_end]
+        .trim()
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    if base.is_empty() {
+        return None;
+    }
+    url::Url::parse(manifest_url).ok()?.join(&base).ok().map(String::from)
+}
+
 /// Download license with all necessary information
 /// Higher-level structure combining ContentLicense with decryption keys
 ///
 /// Reference: DownloadOptions.Factory.cs:41-55 - LicenseInfo private class
+#[derive(Debug, Clone)]
 pub struct DownloadLicense {
     /// DRM type
     pub drm_type: DrmType,
@@ -250,8 +549,66 @@ pub struct DownloadLicense {
 
     /// Download URL (extracted from content_metadata or DASH manifest)
     pub download_url: String,
+
+    /// Set when the delivered content is below the requested quality
+    pub quality_downgrade: Option<QualityDowngrade>,
+
+    /// Set when the title was not delivered in the requested AAC codec
+    pub codec_downgrade: Option<CodecDowngrade>,
+
+    /// Quality tier the license was requested at, after any fallback
+    pub licensed_quality: DownloadQuality,
+
+    /// AAC codec the license was requested in
+    pub licensed_codec: Codec,
 }
 
+/// New download URL from [`AudibleClient::refresh_download_url`]
+#[derive(Debug, Clone)]
+pub struct RefreshedUrl {
+    /// Download URL
+    pub url: String,
+
+    /// Master the URL serves; compare it with the one already downloaded
+    pub content_reference: Option<ContentReference>,
+}
+
+/// License for playing a title while it streams
+///
+/// Returned by [`AudibleClient::build_streaming_license`]. Unlike a
+/// [`DownloadLicense`] it is meant to be used right away and thrown away.
+#[derive(Debug, Clone)]
+pub struct StreamingLicense {
+    /// DRM type actually granted
+    pub drm_type: DrmType,
+
+    /// Chapters and codec of the stream
+    pub content_metadata: ContentMetadata,
+
+    /// AAXC key and IV for Audible DRM; `None` for Widevine, whose keys
+    /// come from the platform CDM
+    pub decryption_keys: Option<Vec<KeyData>>,
+
+    /// URL to hand to the player
+    pub stream_url: String,
+
+    /// Whether `stream_url` is an MPEG-DASH manifest
+    pub is_dash: bool,
+
+    /// When the URL and keys should be considered stale
+    pub expires_at: DateTime<Utc>,
+}
+
+impl StreamingLicense {
+    /// Whether a new license should be requested before playing
+    pub fn is_expired(&self) -> bool {
+        Utc::now() >= self.expires_at
+    }
+}
+
+/// How long a streaming license is trusted (CDN URLs expire after an hour)
+const STREAMING_LICENSE_LIFETIME: chrono::Duration = chrono::Duration::minutes(50);
+
 /// Key data for decryption
 /// Reference: AaxDecrypter/KeyData.cs, DownloadOptions.Factory.cs:53-54
 ///
@@ -259,7 +616,7 @@ pub struct DownloadLicense {
 /// ```csharp
 /// new KeyData(voucher.Key, voucher.Iv)
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct KeyData {
     /// Decryption key part 1
     /// - AAX: 4 bytes (activation bytes)
@@ -441,6 +798,14 @@ impl KeyData {
         }
     }
 
+    /// Activation bytes as 8 hex characters, if this is an AAX key
+    ///
+    /// An AAX voucher carries the account's activation bytes as its 4-byte
+    /// key, so a download license can stand in for the license-token endpoint.
+    pub fn activation_bytes(&self) -> Option<String> {
+        (self.key_part_1.len() == 4 && self.key_part_2.is_none()).then(|| hex::encode(&self.key_part_1))
+    }
+
     /// Determine file type based on key lengths
     ///
     /// # Reference
@@ -516,7 +881,7 @@ impl AudibleClient {
     /// Content license with voucher/keys and metadata
     ///
     /// # Errors
-    /// - `ApiRequestFailed` - API request failed
+    /// - `ApiRequestFailed` - API request failed, or the response is a denial
     /// - `InvalidApiResponse` - Response parsing failed
     /// - `MissingOfflineUrl` - License doesn't contain offline download URL
     ///
@@ -540,19 +905,14 @@ impl AudibleClient {
     ) -> Result<ContentLicense> {
         let endpoint = format!("/1.0/content/{}/licenserequest", asin);
 
-        let response: serde_json::Value = self.post(&endpoint, request).await?;
-
-        // Parse license response
-        // The API may wrap in "content_license" or return directly
-        let license_json = response
-            .get("content_license")
-            .unwrap_or(&response);
-
-        serde_json::from_value(license_json.clone())
-            .map_err(|e| LibationError::InvalidApiResponse {
-                message: format!("Failed to parse content license: {}", e),
-                response_body: Some(license_json.to_string()),
+        let response: serde_json::Value = self
+            .with_chapter_language(request.chapter_titles_language.as_deref(), |headers| {
+                self.post_with_headers(&endpoint, request, headers)
             })
+            .await?;
+
+        // The API may wrap in "content_license", return it directly, or send an error
+        LicenseResponse::parse(response, &endpoint)
     }
 
     /// Build download license with decryption keys
@@ -577,47 +937,343 @@ impl AudibleClient {
     ///
     /// # Errors
     /// - `ApiRequestFailed` - License request failed
-    /// - `MissingOfflineUrl` - No download URL in license
+    /// - `MissingOfflineUrl` - No download URL in license (for Widevine, no
+    ///   DASH manifest either; the file URL is read from the manifest)
+    /// - `NotDownloadableParent` - ASIN is a podcast/series parent; license its episodes instead
     /// - `InvalidInput` - Invalid voucher data
+    ///
+    /// A title that is only offered below `quality` is not an error; the license
+    /// is returned with `quality_downgrade` set so callers can report it.
+    ///
+    /// A license granted for the same request within the client's
+    /// [license cache](crate::api::license_cache) TTL is returned without asking
+    /// the API again. Invalidate the cache first when a fresh URL is needed.
+    ///
+    /// Requests AAC-LC; use
+    /// [`build_download_license_with_codec`](Self::build_download_license_with_codec)
+    /// to ask for xHE-AAC.
     pub async fn build_download_license(
         &self,
         asin: &str,
         quality: DownloadQuality,
         prefer_widevine: bool,
+    ) -> Result<DownloadLicense> {
+        self.build_download_license_with_codec(asin, quality, prefer_widevine, Codec::AacLc)
+            .await
+    }
+
+    /// Build a download license asking for a specific AAC codec
+    ///
+    /// Same as [`build_download_license`](Self::build_download_license), with
+    /// the AAC codec sent in the request. xHE-AAC gives smaller files at the
+    /// same quality but is not offered for every title; when the API answers
+    /// with another codec the license is still returned, with
+    /// `codec_downgrade` set.
+    ///
+    /// # Arguments
+    /// * `asin` - Audible product ID
+    /// * `quality` - Download quality tier
+    /// * `prefer_widevine` - Request Widevine DRM if available
+    /// * `aac_codec` - `Codec::AacLc` or `Codec::XHeAac`
+    ///
+    /// # Errors
+    /// As [`build_download_license`](Self::build_download_license), plus
+    /// - `InvalidInput` - `aac_codec` is not an AAC codec
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::api::client::AudibleClient;
+    /// # use rust_core::api::content::{Codec, DownloadQuality};
+    /// # async fn example(client: AudibleClient) -> rust_core::error::Result<()> {
+    /// let license = client
+    ///     .build_download_license_with_codec("B002V5D7B0", DownloadQuality::High, false, Codec::XHeAac)
+    ///     .await?;
+    /// if let Some(downgrade) = license.codec_downgrade {
+    ///     println!("{}", downgrade);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn build_download_license_with_codec(
+        &self,
+        asin: &str,
+        quality: DownloadQuality,
+        prefer_widevine: bool,
+        aac_codec: Codec,
     ) -> Result<DownloadLicense> {
         // Build license request
         // Reference: DownloadOptions.Factory.cs:59-84
-        let request = LicenseRequest {
-            quality,
-            consumption_type: ConsumptionType::Download,
-            chapter_titles_type: Some(ChapterTitlesType::Tree),
-            request_spatial: Some(false),
-            aac_codec: Some(Codec::AacLc),
-            spatial_codec: Some(Codec::Ec3),
-            // API requires drm_type to be specified
-            // Reference: DownloadOptions.Factory.cs:68-112
-            drm_type: Some(if prefer_widevine {
-                DrmType::Widevine
-            } else {
-                DrmType::Adrm  // Default to Audible DRM (AAX/AAXC)
-            }),
-        };
+        let request = LicenseRequest::builder()
+            .quality(quality)
+            .prefer_widevine(prefer_widevine)
+            .aac_codec(aac_codec)
+            .chapter_titles_language(self.chapter_titles_language().map(str::to_string))
+            .build()?;
+
+        if let Some(license) = self.license_cache().and_then(|cache| cache.get(asin, &request)) {
+            return Ok(license);
+        }
 
         // Request license
-        let license = self.get_download_license(asin, &request).await?;
+        let license = match self.get_download_license(asin, &request).await {
+            Ok(license) => license,
+            Err(e) => return Err(self.explain_license_failure(asin, e).await),
+        };
 
         // Extract download URL
         // Reference: DownloadOptions.cs:61-62
-        let download_url = license
+        let download_url = if license.drm_type == DrmType::Widevine {
+            self.widevine_download_url(&license).await
+        } else {
+            license.content_metadata.content_url.offline_url.clone().ok_or(LibationError::MissingOfflineUrl)
+        };
+        let download_url = match download_url {
+            Ok(url) => url,
+            Err(LibationError::MissingOfflineUrl) => {
+                return Err(self
+                    .explain_license_failure(asin, LibationError::MissingOfflineUrl)
+                    .await)
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Widevine keys come from the CDM, not from the license
+        let decryption_keys = if license.drm_type == DrmType::Widevine {
+            None
+        } else {
+            self.license_keys(asin, &license).await?
+        };
+
+        // Reference: DownloadOptions.Factory.cs:59-84 - the API silently falls back
+        // to whatever quality the title is available in
+        let quality_downgrade = license
             .content_metadata
-            .content_url
+            .content_reference
+            .as_ref()
+            .and_then(|content_ref| quality.check_delivered(content_ref));
+        if let Some(ref downgrade) = quality_downgrade {
+            eprintln!("Warning: {}: {}", asin, downgrade);
+        }
+        let codec_downgrade = license
+            .content_metadata
+            .content_reference
+            .as_ref()
+            .and_then(|content_ref| CodecDowngrade::check(aac_codec, content_ref));
+        if let Some(ref downgrade) = codec_downgrade {
+            eprintln!("Warning: {}: {}", asin, downgrade);
+        }
+
+        let license = DownloadLicense {
+            drm_type: license.drm_type,
+            content_metadata: license.content_metadata,
+            decryption_keys,
+            download_url,
+            quality_downgrade,
+            codec_downgrade,
+            licensed_quality: quality,
+            licensed_codec: aac_codec,
+        };
+        if let Some(cache) = self.license_cache() {
+            cache.insert(asin, &request, &license);
+        }
+        Ok(license)
+    }
+
+    /// Build a download license, stepping down quality tiers as needed
+    ///
+    /// Same as [`build_download_license`](Self::build_download_license), except
+    /// that when the API rejects `quality` as not available for the title, the
+    /// next lower tier is requested (`Extreme` → `High` → `Normal`). Any other
+    /// error is returned straight away.
+    ///
+    /// # Arguments
+    /// * `asin` - Audible product ID
+    /// * `quality` - Preferred quality tier
+    /// * `prefer_widevine` - Request Widevine DRM if available
+    /// * `aac_codec` - AAC codec to request at every tier
+    ///
+    /// # Returns
+    /// The license for the best tier on offer. If it is below `quality`,
+    /// `quality_downgrade` says so.
+    ///
+    /// # Errors
+    /// The error for the lowest tier tried, or the first error that is not
+    /// about quality
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::api::client::AudibleClient;
+    /// # use rust_core::api::content::{Codec, DownloadQuality};
+    /// # async fn example(client: AudibleClient) -> rust_core::error::Result<()> {
+    /// let license = client
+    ///     .build_download_license_with_fallback("B002V5D7B0", DownloadQuality::Extreme, false, Codec::AacLc)
+    ///     .await?;
+    /// if let Some(downgrade) = license.quality_downgrade {
+    ///     println!("{}", downgrade);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn build_download_license_with_fallback(
+        &self,
+        asin: &str,
+        quality: DownloadQuality,
+        prefer_widevine: bool,
+        aac_codec: Codec,
+    ) -> Result<DownloadLicense> {
+        let mut tier = quality;
+        loop {
+            match self
+                .build_download_license_with_codec(asin, tier, prefer_widevine, aac_codec)
+                .await
+            {
+                Ok(mut license) => {
+                    if tier != quality && license.quality_downgrade.is_none() {
+                        let content_ref = license.content_metadata.content_reference.as_ref();
+                        license.quality_downgrade = Some(QualityDowngrade {
+                            requested: quality,
+                            delivered: tier,
+                            codec: content_ref.map(|c| c.codec).unwrap_or(Codec::AacLc),
+                            bitrate_kbps: content_ref.and_then(|c| c.bitrate_kbps()),
+                        });
+                    }
+                    return Ok(license);
+                }
+                Err(e) if is_quality_unavailable(&e) => match tier.fallback() {
+                    Some(lower) => {
+                        eprintln!(
+                            "Warning: {}: {:?} quality not available, trying {:?}",
+                            asin, tier, lower
+                        );
+                        tier = lower;
+                    }
+                    None => return Err(e),
+                },
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Request a license for playing a title without downloading it
+    ///
+    /// Asks for `consumption_type: Streaming`. The returned URL can be
+    /// handed to a player directly: for Audible DRM it is an AAXC stream
+    /// decrypted with `decryption_keys`, for Widevine it is a DASH manifest
+    /// whose keys the platform CDM obtains. Streaming URLs and keys are only
+    /// valid for a short time; check [`StreamingLicense::is_expired`] and
+    /// request a new license instead of caching one.
+    ///
+    /// # Arguments
+    /// * `asin` - Audible product ID
+    /// * `quality` - Quality tier to stream
+    /// * `prefer_widevine` - Request Widevine DRM (DASH) if available
+    ///
+    /// # Errors
+    /// - `ApiRequestFailed` - License request failed or was refused
+    /// - `MissingStreamingUrl` - The license has no URL to play
+    /// - `NotDownloadableParent` - ASIN is a podcast/series parent
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::api::client::AudibleClient;
+    /// # use rust_core::api::content::DownloadQuality;
+    /// # async fn example(client: AudibleClient) -> rust_core::error::Result<()> {
+    /// let license = client.build_streaming_license("B002V5D7B0", DownloadQuality::High, false).await?;
+    /// println!("Play {} (DASH: {})", license.stream_url, license.is_dash);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn build_streaming_license(
+        &self,
+        asin: &str,
+        quality: DownloadQuality,
+        prefer_widevine: bool,
+    ) -> Result<StreamingLicense> {
+        let request = LicenseRequest::builder()
+            .quality(quality)
+            .consumption_type(ConsumptionType::Streaming)
+            .prefer_widevine(prefer_widevine)
+            .chapter_titles_language(self.chapter_titles_language().map(str::to_string))
+            .build()?;
+
+        let license = match self.get_download_license(asin, &request).await {
+            Ok(license) => license,
+            Err(e) => return Err(self.explain_license_failure(asin, e).await),
+        };
+
+        // For Widevine the license_response is the manifest, not a voucher
+        let (stream_url, decryption_keys) = if license.drm_type == DrmType::Widevine {
+            let manifest = license
+                .content_metadata
+                .content_url
+                .streaming_url
+                .clone()
+                .or_else(|| license.license_response.clone());
+            (manifest, None)
+        } else {
+            let url = license
+                .content_metadata
+                .content_url
+                .streaming_url
+                .clone()
+                .or_else(|| license.content_metadata.content_url.offline_url.clone());
+            (url, self.license_keys(asin, &license).await?)
+        };
+        let stream_url = stream_url.ok_or(LibationError::MissingStreamingUrl)?;
+        let is_dash = license.drm_type == DrmType::Widevine
+            || stream_url.split('?').next().is_some_and(|path| path.ends_with(".mpd"));
+
+        Ok(StreamingLicense {
+            drm_type: license.drm_type,
+            content_metadata: license.content_metadata,
+            decryption_keys,
+            stream_url,
+            is_dash,
+            expires_at: Utc::now() + STREAMING_LICENSE_LIFETIME,
+        })
+    }
+
+    /// Where to download a Widevine title from
+    ///
+    /// # Reference
+    /// `AudibleUtilities/Widevine/MpegDash.cs` - `TryGetUri` reads the content
+    /// URI from the manifest's `BaseURL`
+    ///
+    /// Widevine licenses often have no `offline_url`; the manifest is the
+    /// `streaming_url` or the `license_response`. A manifest URL is fetched and
+    /// the file it points at is returned; any other URL is used as is.
+    ///
+    /// # Errors
+    /// - `MissingOfflineUrl` - The license has neither a URL nor a manifest
+    /// - `InvalidApiResponse` - The manifest names no `BaseURL`
+    /// - Any error from fetching the manifest
+    async fn widevine_download_url(&self, license: &ContentLicense) -> Result<String> {
+        let content_url = &license.content_metadata.content_url;
+        let url = content_url
             .offline_url
             .clone()
+            .or_else(|| content_url.streaming_url.clone())
+            .or_else(|| license.license_response.clone().filter(|r| r.starts_with("http")))
             .ok_or(LibationError::MissingOfflineUrl)?;
 
+        if !url.split('?').next().is_some_and(|path| path.ends_with(".mpd")) {
+            return Ok(url);
+        }
+        let manifest = self.get_external_text(&url).await?;
+        dash_content_url(&manifest, &url).ok_or_else(|| LibationError::InvalidApiResponse {
+            message: "DASH manifest has no BaseURL".to_string(),
+            response_body: Some(manifest),
+        })
+    }
+
+    /// Decryption keys carried by a license, if any
+    ///
+    /// # Reference
+    /// DownloadOptions.Factory.cs:46-54 - DecryptionKeys = ToKeys(license.Voucher)
+    async fn license_keys(&self, asin: &str, license: &ContentLicense) -> Result<Option<Vec<KeyData>>> {
         // Parse voucher to keys
         // Reference: DownloadOptions.Factory.cs:46-54 - DecryptionKeys = ToKeys(license.Voucher)
-        let decryption_keys = if let Some(ref voucher) = license.voucher {
+        let keys = if let Some(ref voucher) = license.voucher {
             // Structured voucher with key/iv fields (already decrypted)
             let key_data = KeyData::from_base64(
                 &voucher.key,
@@ -646,13 +1302,110 @@ impl AudibleClient {
         } else {
             None
         };
+        Ok(keys)
+    }
 
-        Ok(DownloadLicense {
-            drm_type: license.drm_type,
-            content_metadata: license.content_metadata,
-            decryption_keys,
-            download_url,
-        })
+    /// Build download licenses for several titles
+    ///
+    /// Runs up to [`MAX_CONCURRENCY`](crate::api::client::MAX_CONCURRENCY) license
+    /// requests at a time. Every request goes through the client's per-account
+    /// rate limiter, so large batches pace themselves instead of tripping
+    /// Audible's throttling.
+    ///
+    /// # Arguments
+    /// * `asins` - Audible product IDs
+    /// * `quality` - Download quality tier
+    /// * `prefer_widevine` - Request Widevine DRM if available
+    ///
+    /// # Returns
+    /// One `(asin, result)` pair per input ASIN, in input order. A failure for
+    /// one title does not stop the others.
+    pub async fn build_download_licenses(
+        &self,
+        asins: &[String],
+        quality: DownloadQuality,
+        prefer_widevine: bool,
+    ) -> Vec<(String, Result<DownloadLicense>)> {
+        use futures_util::stream::{self, StreamExt};
+
+        stream::iter(asins)
+            .map(|asin| async move {
+                let result = self.build_download_license(asin, quality, prefer_widevine).await;
+                (asin.clone(), result)
+            })
+            .buffered(crate::api::client::MAX_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Turn a license failure for a podcast/series parent into a clear error
+    ///
+    /// Audible rejects license requests for parents (they have no audio), which
+    /// otherwise surfaces as an opaque API error. Auth and transient errors are
+    /// passed through untouched; if the parent lookup itself fails, the original
+    /// error is returned.
+    async fn explain_license_failure(&self, asin: &str, error: LibationError) -> LibationError {
+        if error.is_auth_error() || error.is_retryable() {
+            return error;
+        }
+
+        match self.is_parent_product(asin).await {
+            Ok(true) => LibationError::NotDownloadableParent {
+                asin: asin.to_string(),
+                suggestion: "download individual episodes".to_string(),
+            },
+            _ => error,
+        }
+    }
+
+    /// Get activation bytes, falling back to a download license
+    ///
+    /// Tries the license-token endpoint first. If that fails, requests an AAX
+    /// download license for `asin` and takes the activation bytes from its
+    /// voucher. The result is stored as the account's `decrypt_key`.
+    ///
+    /// # Arguments
+    /// * `asin` - Any AAX title in the account's library
+    ///
+    /// # Returns
+    /// The 4-byte activation bytes as 8 hex characters
+    ///
+    /// # Errors
+    /// - `AuthenticationFailed` - Account has no identity
+    /// - The license-token error, if the license does not carry an AAX key
+    /// - License errors, if the fallback request fails
+    pub async fn get_activation_bytes_with_fallback(&self, asin: &str) -> Result<String> {
+        let account = self.account();
+        let (locale, access_token) = {
+            let account = account.lock().await;
+            let identity = account.identity.as_ref().ok_or_else(|| {
+                LibationError::auth_failed(
+                    "No identity tokens for activation bytes retrieval",
+                    Some(account.account_id.clone()),
+                )
+            })?;
+            (identity.locale.clone(), identity.access_token.token.clone())
+        };
+
+        let activation_bytes = match crate::api::auth::get_activation_bytes(&locale, &access_token).await {
+            Ok(token) => token.activation_bytes,
+            Err(token_error) => {
+                eprintln!(
+                    "Warning: license token request failed ({}); using the download license for {}",
+                    token_error, asin
+                );
+                let license = self.build_download_license(asin, DownloadQuality::High, false).await?;
+                license
+                    .decryption_keys
+                    .iter()
+                    .flatten()
+                    .find_map(KeyData::activation_bytes)
+                    .ok_or(token_error)?
+            }
+        };
+
+        account.lock().await.decrypt_key = activation_bytes.clone();
+        Ok(activation_bytes)
     }
 
     /// Get download URL for an audiobook
@@ -679,6 +1432,61 @@ impl AudibleClient {
         Ok(license.download_url)
     }
 
+    /// Get a new download URL for a book whose keys the caller already has
+    ///
+    /// Used to resume a download after its signed CDN URL expired. A license
+    /// is still requested, since that is the only way to get a URL, but the
+    /// voucher is not decrypted and no activation bytes are fetched. Unlike
+    /// [`build_download_license`](Self::build_download_license) the license
+    /// cache is never answered from, because a cached URL may be the one that
+    /// was just refused. A cached license for the same master is updated to
+    /// the new URL so its keys stay reusable.
+    ///
+    /// # Arguments
+    /// * `asin` - Audible product ID
+    /// * `quality` - Quality the download was licensed at
+    /// * `aac_codec` - AAC codec the download was licensed in
+    ///
+    /// # Returns
+    /// The new download URL and the master it serves. The master may differ
+    /// from the one licensed before if Audible replaced the audio since.
+    ///
+    /// # Errors
+    /// - `ApiRequestFailed` - License request failed
+    /// - `MissingOfflineUrl` - No download URL in license
+    /// - `NotDownloadableParent` - ASIN is a podcast/series parent
+    pub async fn refresh_download_url(
+        &self,
+        asin: &str,
+        quality: DownloadQuality,
+        aac_codec: Codec,
+    ) -> Result<RefreshedUrl> {
+        let request = LicenseRequest::builder()
+            .quality(quality)
+            .aac_codec(aac_codec)
+            .chapter_titles_language(self.chapter_titles_language().map(str::to_string))
+            .build()?;
+
+        let license = match self.get_download_license(asin, &request).await {
+            Ok(license) => license,
+            Err(e) => return Err(self.explain_license_failure(asin, e).await),
+        };
+        let url = license
+            .content_metadata
+            .content_url
+            .offline_url
+            .ok_or(LibationError::MissingOfflineUrl)?;
+
+        if let Some(cache) = self.license_cache() {
+            let acr = license.content_metadata.content_reference.as_ref().map(|r| r.acr.as_str());
+            cache.refresh_url(asin, &request, &url, acr);
+        }
+        Ok(RefreshedUrl {
+            url,
+            content_reference: license.content_metadata.content_reference,
+        })
+    }
+
     /// Determine DRM type and file format from license
     ///
     /// # Reference
@@ -738,10 +1546,11 @@ impl AudibleClient {
             return OutputFormat::Mp3;
         }
 
-        // Convert to MP3 if requested, unless it's AC-4 spatial audio
+        // Convert to MP3 if requested, unless it's AC-4 spatial audio or xHE-AAC,
+        // which FFmpeg generally cannot decode for a lossy transcode
         if convert_to_mp3 {
             if let Some(ref content_ref) = license.content_metadata.content_reference {
-                if !matches!(content_ref.codec, Codec::Ac4) {
+                if !matches!(content_ref.codec, Codec::Ac4 | Codec::XHeAac) {
                     return OutputFormat::Mp3;
                 }
             } else {
@@ -766,6 +1575,16 @@ pub enum OutputFormat {
     Mp3,
 }
 
+impl OutputFormat {
+    /// File extension for this format, without the leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::M4b => "m4b",
+            OutputFormat::Mp3 => "mp3",
+        }
+    }
+}
+
 // ============================================================================
 // WIDEVINE LICENSE EXCHANGE (Future Implementation)
 // ============================================================================
@@ -836,6 +1655,247 @@ impl AudibleClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::client::fixture::{canned_client, respond, CannedTransport, SentRequest};
+
+    #[test]
+    fn test_license_request_builder() {
+        let request = LicenseRequest::builder()
+            .quality(DownloadQuality::Extreme)
+            .spatial(true)
+            .spatial_codec(Codec::Ac4)
+            .chapter_titles_language(Some(" ".to_string()))
+            .build()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "quality": "Extreme",
+                "consumption_type": "Download",
+                "drm_type": "Adrm",
+                "chapter_titles_type": "Tree",
+                "request_spatial": true,
+                "aac_codec": "AAC_LC",
+                "spatial_codec": "AC_4"
+            })
+        );
+        assert!(request.chapter_titles_language.is_none());
+
+        assert!(LicenseRequest::builder().spatial_codec(Codec::AacLc).build().is_err());
+        assert!(LicenseRequest::builder().aac_codec(Codec::Ec3).build().is_err());
+    }
+
+    #[test]
+    fn test_license_response_shapes() {
+        let license = serde_json::json!({
+            "drm_type": "Adrm",
+            "content_metadata": {"content_url": {"offline_url": "https://cdn.example.com/book.aaxc"}},
+            "license_response": "abc"
+        });
+        let endpoint = "/1.0/content/B0/licenserequest";
+
+        let wrapped = serde_json::json!({"content_license": license.clone()});
+        for body in [wrapped, license] {
+            let parsed = LicenseResponse::parse(body, endpoint).unwrap();
+            assert_eq!(parsed.drm_type, DrmType::Adrm);
+            assert_eq!(parsed.license_response.as_deref(), Some("abc"));
+        }
+
+        let denied = serde_json::json!({"content_license": {
+            "status_code": "Denied",
+            "message": "License not granted",
+            "license_denial_reasons": [{"message": "Not owned", "reject_code": "AccessDenied"}]
+        }});
+        match LicenseResponse::parse(denied, endpoint) {
+            Err(LibationError::ApiRequestFailed { message, error_code, .. }) => {
+                assert!(message.contains("License not granted; Not owned (AccessDenied)"));
+                assert_eq!(error_code, Some(AudibleErrorCode::AccessDenied));
+            }
+            other => panic!("expected a refusal, got {:?}", other.map(|l| l.drm_type)),
+        }
+
+        let error = serde_json::json!({"error_code": "DownloadLimitExceeded", "message": "Too many devices"});
+        let err = LicenseResponse::parse(error, endpoint).unwrap_err();
+        assert_eq!(err.audible_error_code(), Some(&AudibleErrorCode::DownloadLimitExceeded));
+
+        let malformed = serde_json::json!({"content_license": {"drm_type": "Adrm"}});
+        let err = LicenseResponse::parse(malformed, endpoint).unwrap_err();
+        assert!(err.to_string().contains("content_metadata"), "{}", err);
+    }
+
+    /// Refuses `Extreme` license requests and grants any other tier
+    fn no_extreme(request: &SentRequest) -> http::Response<String> {
+        if !request.url.path().contains("licenserequest") {
+            respond(200, r#"{"product": {}}"#)
+        } else if request.body.contains(r#""quality":"Extreme""#) {
+            respond(400, r#"{"error_code": "InvalidValue", "message": "Requested quality is not available"}"#)
+        } else {
+            respond(200, serde_json::json!({"content_license": {
+                "drm_type": "Adrm",
+                "content_metadata": {"content_url": {"offline_url": "https://cdn.example.com/book.aaxc"}}
+            }}).to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_license_falls_back_to_lower_quality() {
+        let transport = std::sync::Arc::new(CannedTransport::new(no_extreme));
+        let client = canned_client("quality@example.com", transport.clone());
+
+        let license = client
+            .build_download_license_with_fallback("B0QUALITY", DownloadQuality::Extreme, false, Codec::AacLc)
+            .await
+            .unwrap();
+        assert_eq!(license.download_url, "https://cdn.example.com/book.aaxc");
+        let downgrade = license.quality_downgrade.unwrap();
+        assert_eq!(downgrade.requested, DownloadQuality::Extreme);
+        assert_eq!(downgrade.delivered, DownloadQuality::High);
+
+        // Without fallback the refusal is returned as is
+        let result = client
+            .build_download_license("B0QUALITY", DownloadQuality::Extreme, false)
+            .await;
+        assert!(matches!(result, Err(ref e) if is_quality_unavailable(e)));
+        assert_eq!(DownloadQuality::Normal.fallback(), None);
+    }
+
+    #[tokio::test]
+    async fn test_license_reused_from_cache() {
+        let transport = std::sync::Arc::new(CannedTransport::new(no_extreme));
+        let client = canned_client("cache@example.com", transport.clone());
+        let license_requests =
+            || transport.sent().iter().filter(|r| r.body.contains("consumption_type")).count();
+
+        for _ in 0..2 {
+            client.build_download_license("B0CACHED", DownloadQuality::High, false).await.unwrap();
+        }
+        assert_eq!(license_requests(), 1);
+
+        client.build_download_license("B0CACHED", DownloadQuality::Normal, false).await.unwrap();
+        assert_eq!(license_requests(), 2);
+
+        client.license_cache().unwrap().invalidate("B0CACHED");
+        client.build_download_license("B0CACHED", DownloadQuality::High, false).await.unwrap();
+        assert_eq!(license_requests(), 3);
+
+        // A refresh always asks the API, and leaves the cached license usable
+        let refreshed = client.refresh_download_url("B0CACHED", DownloadQuality::High, Codec::AacLc).await.unwrap();
+        assert_eq!(refreshed.url, "https://cdn.example.com/book.aaxc");
+        assert_eq!(license_requests(), 4);
+        client.build_download_license("B0CACHED", DownloadQuality::High, false).await.unwrap();
+        assert_eq!(license_requests(), 4);
+    }
+
+    /// Serves chapter titles in German or English by `Accept-Language` and
+    /// refuses French
+    fn bilingual(request: &SentRequest) -> http::Response<String> {
+        let language = request_language(request);
+        let title = if language == "de-DE" { "Kapitel 1" } else { "Chapter 1" };
+        let content_metadata = serde_json::json!({
+            "chapter_info": {
+                "chapters": [{"title": title, "start_offset_ms": 0, "start_offset_sec": 0, "length_ms": 60000}],
+                "runtimeLengthMs": 60000
+            },
+            "content_url": {"offline_url": "https://cdn.example.com/book.aaxc"}
+        });
+        if language == "fr-FR" {
+            respond(406, r#"{"message": "Requested language is not available"}"#)
+        } else if request.url.path().contains("licenserequest") {
+            respond(200, serde_json::json!({"content_license": {"drm_type": "Adrm", "content_metadata": content_metadata}}).to_string())
+        } else if request.url.path().ends_with("/metadata") {
+            respond(200, serde_json::json!({"content_metadata": content_metadata}).to_string())
+        } else {
+            respond(200, r#"{"product": {}}"#)
+        }
+    }
+
+    fn request_language(request: &SentRequest) -> &str {
+        request
+            .headers
+            .get(reqwest::header::ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_chapter_titles_language() {
+        use crate::api::auth::Account;
+        use crate::api::client::{AudibleClient, ClientConfig};
+
+        let first_title = |info: Option<crate::api::content::ChapterInfo>| info.unwrap().chapters[0].title.clone();
+        let client_for = |language: &str, transport: std::sync::Arc<CannedTransport>| {
+            let account = Account::new("language@example.com".to_string()).unwrap();
+            let config = ClientConfig::builder().chapter_titles_language(Some(language.to_string())).build();
+            AudibleClient::with_transport(account, config, transport).unwrap()
+        };
+
+        let transport = std::sync::Arc::new(CannedTransport::new(bilingual));
+        let client = client_for("de-DE", transport.clone());
+        let license = client.build_download_license("B0BILINGUAL", DownloadQuality::High, false).await.unwrap();
+        assert_eq!(first_title(license.content_metadata.chapter_info), "Kapitel 1");
+        let metadata = client.get_content_metadata("B0BILINGUAL").await.unwrap();
+        assert_eq!(first_title(metadata.chapter_info), "Kapitel 1");
+
+        // A language the title lacks falls back to the default titles
+        let transport = std::sync::Arc::new(CannedTransport::new(bilingual));
+        let client = client_for("fr-FR", transport.clone());
+        let metadata = client.get_content_metadata("B0BILINGUAL").await.unwrap();
+        assert_eq!(first_title(metadata.chapter_info), "Chapter 1");
+        let languages: Vec<String> = transport.sent().iter().map(|r| request_language(r).to_string()).collect();
+        assert_eq!(languages, ["fr-FR", ""]);
+    }
+
+    /// Grants Widevine licenses and serves their DASH manifest
+    fn streaming(request: &SentRequest) -> http::Response<String> {
+        let body = if request.url.path().ends_with(".mpd") {
+            r#"<MPD><Period><AdaptationSet><Representation>
+                <BaseURL> audio/book.mp4?sig=1&amp;exp=2 </BaseURL>
+            </Representation></AdaptationSet></Period></MPD>"#
+                .to_string()
+        } else {
+            serde_json::json!({"content_license": {
+                "drm_type": "Mpeg",
+                "content_metadata": {"content_url": {
+                    "streaming_url": "https://cdn.example.com/book/manifest.mpd?token=abc"
+                }},
+                "license_response": "not a voucher"
+            }})
+            .to_string()
+        };
+        respond(200, body)
+    }
+
+    #[tokio::test]
+    async fn test_streaming_license() {
+        let transport = std::sync::Arc::new(CannedTransport::new(streaming));
+        let client = canned_client("stream@example.com", transport.clone());
+
+        let license = client
+            .build_streaming_license("B0STREAM1", DownloadQuality::High, true)
+            .await
+            .unwrap();
+        assert_eq!(license.stream_url, "https://cdn.example.com/book/manifest.mpd?token=abc");
+        assert!(license.is_dash && license.decryption_keys.is_none());
+        assert!(!license.is_expired());
+
+        let requests = transport.sent();
+        assert!(requests.iter().any(|r| r.body.contains(r#""consumption_type":"Streaming""#)));
+    }
+
+    #[tokio::test]
+    async fn test_widevine_download_url_from_manifest() {
+        let transport = std::sync::Arc::new(CannedTransport::new(streaming));
+        let client = canned_client("widevine@example.com", transport);
+
+        let license = client
+            .build_download_license("B0WIDEVINE", DownloadQuality::High, true)
+            .await
+            .unwrap();
+        assert_eq!(license.download_url, "https://cdn.example.com/book/audio/book.mp4?sig=1&exp=2");
+        assert!(license.decryption_keys.is_none());
+        assert_eq!(AudibleClient::determine_file_type(&license), FileType::Dash);
+
+        assert_eq!(dash_content_url("<MPD><BaseURL></BaseURL></MPD>", "https://cdn.example.com/m.mpd"), None);
+    }
 
     #[test]
     fn test_key_data_file_type_aax() {
@@ -857,6 +1917,21 @@ mod tests {
         assert_eq!(key_data.file_type(DrmType::Adrm), FileType::Aaxc);
     }
 
+    #[test]
+    fn test_key_data_activation_bytes() {
+        let aax = KeyData {
+            key_part_1: vec![0x1c, 0xeb, 0x00, 0xda],
+            key_part_2: None,
+        };
+        assert_eq!(aax.activation_bytes().as_deref(), Some("1ceb00da"));
+
+        let aaxc = KeyData {
+            key_part_1: vec![0; 16],
+            key_part_2: Some(vec![0; 16]),
+        };
+        assert_eq!(aaxc.activation_bytes(), None);
+    }
+
     #[test]
     fn test_key_data_file_type_widevine() {
         let key_data = KeyData {
@@ -1055,44 +2130,20 @@ mod tests {
 
         // Step 5: Verify download URL with HEAD request
         println!("\n🌐 Verifying download URL...");
-        let head_result = reqwest::Client::new()
-            .head(&license.download_url)
-            .send()
-            .await;
-
-        match head_result {
-            Ok(response) => {
+        match crate::download::probe_url(&license.download_url, "Audible/671 CFNetwork/1240.0.4 Darwin/20.6.0").await {
+            Ok(info) => {
                 println!("✅ URL is accessible");
-                println!("   Status: {}", response.status());
-
-                // Get file size from Content-Length header
-                if let Some(content_length) = response.headers().get("content-length") {
-                    if let Ok(size_str) = content_length.to_str() {
-                        if let Ok(size) = size_str.parse::<u64>() {
-                            let size_mb = size as f64 / (1024.0 * 1024.0);
-                            println!("   File Size: {:.2} MB ({} bytes)", size_mb, size);
-                        }
-                    }
-                }
 
-                // Get content type
-                if let Some(content_type) = response.headers().get("content-type") {
-                    if let Ok(ct) = content_type.to_str() {
-                        println!("   Content Type: {}", ct);
-                    }
+                if let Some(size) = info.size {
+                    let size_mb = size as f64 / (1024.0 * 1024.0);
+                    println!("   File Size: {:.2} MB ({} bytes)", size_mb, size);
                 }
-
-                // Get other useful headers
-                if let Some(server) = response.headers().get("server") {
-                    if let Ok(s) = server.to_str() {
-                        println!("   Server: {}", s);
-                    }
+                if let Some(ref ct) = info.content_type {
+                    println!("   Content Type: {}", ct);
                 }
-
-                if let Some(last_modified) = response.headers().get("last-modified") {
-                    if let Ok(lm) = last_modified.to_str() {
-                        println!("   Last Modified: {}", lm);
-                    }
+                println!("   Accept Ranges: {}", info.accept_ranges);
+                if let Some(ref lm) = info.last_modified {
+                    println!("   Last Modified: {}", lm);
                 }
             }
             Err(e) => {
@@ -0,0 +1,315 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Short-lived cache of download licenses
+//!
+//! # Reference C# Sources
+//! - `FileLiberator/DownloadOptions.Factory.cs` - Requests a new license every
+//!   time a book is liberated; Libation keeps nothing between requests
+//!
+//! The app commonly asks for the same license twice in a row: once when the
+//! user opens a book (to show size and quality) and again when the download
+//! starts. Each request counts against Audible's license throttling, so the
+//! second one is answered from a [`LicenseCache`] instead.
+//!
+//! Entries live for a short TTL and never past the expiry of the CDN URL
+//! they carry (CloudFront URLs are signed for about a day and say so in their
+//! `Expires` parameter). The cache holds a bounded number of licenses and
+//! evicts the least recently used one when full. Like rate limiters, caches
+//! are shared per account through [`LicenseCache::for_account`], because the
+//! mobile bridges create a new `AudibleClient` for every call.
+
+use crate::api::content::{Codec, DownloadQuality, DrmType};
+use crate::api::license::{DownloadLicense, LicenseRequest};
+use lazy_static::lazy_static;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a license is reused by default
+pub const DEFAULT_LICENSE_CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Number of licenses kept per account
+pub const DEFAULT_LICENSE_CACHE_CAPACITY: usize = 32;
+
+/// A license is dropped this long before its URL expires, so a download
+/// started from it has time to finish
+const URL_EXPIRY_MARGIN: Duration = Duration::from_secs(2 * 60 * 60);
+
+lazy_static! {
+    /// Caches shared by every client of the same account
+    static ref ACCOUNT_CACHES: Mutex<HashMap<String, Arc<LicenseCache>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// The parts of a request that change the license granted
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CacheKey {
+    asin: String,
+    quality: DownloadQuality,
+    drm_type: Option<DrmType>,
+    aac_codec: Option<Codec>,
+    chapter_titles_language: Option<String>,
+}
+
+impl CacheKey {
+    fn new(asin: &str, request: &LicenseRequest) -> Self {
+        Self {
+            asin: asin.to_string(),
+            quality: request.quality,
+            drm_type: request.drm_type,
+            aac_codec: request.aac_codec,
+            chapter_titles_language: request.chapter_titles_language.clone(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    key: CacheKey,
+    license: DownloadLicense,
+    expires_at: Instant,
+}
+
+/// Least-recently-used cache of [`DownloadLicense`]s keyed by ASIN and request
+///
+/// Quality, DRM type and chapter title language are part of the key.
+#[derive(Debug)]
+pub struct LicenseCache {
+    capacity: usize,
+    ttl: Duration,
+    /// Least recently used first
+    entries: Mutex<VecDeque<Entry>>,
+}
+
+impl LicenseCache {
+    /// Create a standalone cache
+    ///
+    /// # Arguments
+    /// * `capacity` - Licenses kept before the least recently used is evicted (0 is treated as 1)
+    /// * `ttl` - Longest time a license is reused
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ttl,
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Get the cache shared by all clients of `account_id`
+    ///
+    /// If the account already has a cache with a different TTL, it is
+    /// replaced so the most recent configuration wins.
+    pub fn for_account(account_id: &str, ttl: Duration) -> Arc<Self> {
+        let mut caches = ACCOUNT_CACHES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        match caches.get(account_id) {
+            Some(cache) if cache.ttl == ttl => cache.clone(),
+            _ => {
+                let cache = Arc::new(Self::new(DEFAULT_LICENSE_CACHE_CAPACITY, ttl));
+                caches.insert(account_id.to_string(), cache.clone());
+                cache
+            }
+        }
+    }
+
+    /// A still valid license for the request, if one is cached
+    pub fn get(&self, asin: &str, request: &LicenseRequest) -> Option<DownloadLicense> {
+        let key = CacheKey::new(asin, request);
+        let mut entries = self.lock();
+        let now = Instant::now();
+        entries.retain(|entry| entry.expires_at > now);
+
+        let position = entries.iter().position(|entry| entry.key == key)?;
+        let entry = entries.remove(position)?;
+        let license = entry.license.clone();
+        entries.push_back(entry);
+        Some(license)
+    }
+
+    /// Remember a license just granted for the request
+    ///
+    /// Licenses whose URL is about to expire are not kept.
+    pub fn insert(&self, asin: &str, request: &LicenseRequest, license: &DownloadLicense) {
+        let lifetime = self.lifetime(&license.download_url);
+        if lifetime.is_zero() {
+            return;
+        }
+
+        let key = CacheKey::new(asin, request);
+        let mut entries = self.lock();
+        entries.retain(|entry| entry.key != key);
+        entries.push_back(Entry {
+            key,
+            license: license.clone(),
+            expires_at: Instant::now() + lifetime,
+        });
+        while entries.len() > self.capacity {
+            entries.pop_front();
+        }
+    }
+
+    /// Point a cached license at a newly granted download URL
+    ///
+    /// The cached keys are kept only if the new URL is for the same master
+    /// (`acr`), even when the old URL has expired; a license for a different
+    /// master is dropped instead. The entry then lives as long as a license
+    /// just inserted with the new URL.
+    ///
+    /// # Returns
+    /// Whether a cached license was updated
+    pub fn refresh_url(&self, asin: &str, request: &LicenseRequest, url: &str, acr: Option<&str>) -> bool {
+        let key = CacheKey::new(asin, request);
+        let lifetime = self.lifetime(url);
+        let mut entries = self.lock();
+
+        let Some(position) = entries.iter().position(|entry| entry.key == key) else {
+            return false;
+        };
+        let Some(mut entry) = entries.remove(position) else {
+            return false;
+        };
+        let cached_acr = entry.license.content_metadata.content_reference.as_ref().map(|r| r.acr.as_str());
+        if cached_acr != acr || lifetime.is_zero() {
+            return false;
+        }
+
+        entry.license.download_url = url.to_string();
+        entry.license.content_metadata.content_url.offline_url = Some(url.to_string());
+        entry.expires_at = Instant::now() + lifetime;
+        entries.push_back(entry);
+        true
+    }
+
+    /// Forget every license for `asin`
+    ///
+    /// Call before requesting a license that must be new, e.g. because the
+    /// cached URL was refused.
+    pub fn invalidate(&self, asin: &str) {
+        self.lock().retain(|entry| entry.key.asin != asin);
+    }
+
+    /// Number of licenses held, including any that have expired but not yet
+    /// been dropped
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// How long a license carrying `url` may be reused from now
+    fn lifetime(&self, url: &str) -> Duration {
+        match url_lifetime(url) {
+            Some(remaining) => self.ttl.min(remaining.saturating_sub(URL_EXPIRY_MARGIN)),
+            None => self.ttl,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<Entry>> {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Time left before a signed CDN URL expires, from its `Expires` parameter
+fn url_lifetime(url: &str) -> Option<Duration> {
+    let url = reqwest::Url::parse(url).ok()?;
+    let expires: i64 = url
+        .query_pairs()
+        .find(|(name, _)| name == "Expires")
+        .and_then(|(_, value)| value.parse().ok())?;
+    let remaining = expires - chrono::Utc::now().timestamp();
+    Some(Duration::from_secs(remaining.max(0) as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::content::{ContentMetadata, ContentUrl};
+
+    fn request(quality: DownloadQuality, widevine: bool) -> LicenseRequest {
+        LicenseRequest {
+            quality,
+            drm_type: Some(if widevine { DrmType::Widevine } else { DrmType::Adrm }),
+            ..LicenseRequest::default()
+        }
+    }
+
+    fn license(url: &str) -> DownloadLicense {
+        DownloadLicense {
+            drm_type: DrmType::Adrm,
+            content_metadata: ContentMetadata {
+                chapter_info: None,
+                content_reference: None,
+                content_url: ContentUrl {
+                    offline_url: Some(url.to_string()),
+                    streaming_url: None,
+                },
+            },
+            decryption_keys: None,
+            download_url: url.to_string(),
+            quality_downgrade: None,
+            codec_downgrade: None,
+            licensed_quality: DownloadQuality::High,
+            licensed_codec: Codec::AacLc,
+        }
+    }
+
+    #[test]
+    fn test_license_cache() {
+        let cache = LicenseCache::new(2, DEFAULT_LICENSE_CACHE_TTL);
+        cache.insert("B001", &request(DownloadQuality::High, false), &license("https://cdn.example.com/1.aaxc"));
+        cache.insert("B002", &request(DownloadQuality::High, false), &license("https://cdn.example.com/2.aaxc"));
+
+        assert!(cache.get("B001", &request(DownloadQuality::Normal, false)).is_none());
+        assert!(cache.get("B001", &request(DownloadQuality::High, true)).is_none());
+        // B001 is now the most recently used, so B002 is evicted
+        assert!(cache.get("B001", &request(DownloadQuality::High, false)).is_some());
+        cache.insert("B003", &request(DownloadQuality::High, false), &license("https://cdn.example.com/3.aaxc"));
+        assert!(cache.get("B002", &request(DownloadQuality::High, false)).is_none());
+        assert_eq!(cache.len(), 2);
+
+        cache.invalidate("B001");
+        assert!(cache.get("B001", &request(DownloadQuality::High, false)).is_none());
+
+        // A URL close to expiry is not worth keeping
+        let soon = chrono::Utc::now().timestamp() + 60;
+        cache.insert("B004", &request(DownloadQuality::High, false), &license(&format!("https://cdn.example.com/4.aaxc?Expires={}", soon)));
+        assert!(cache.get("B004", &request(DownloadQuality::High, false)).is_none());
+        let later = chrono::Utc::now().timestamp() + 24 * 60 * 60;
+        let url = format!("https://cdn.example.com/4.aaxc?Expires={}&Signature=abc", later);
+        assert!(url_lifetime(&url).unwrap() > Duration::from_secs(23 * 60 * 60));
+
+
+        // An expired license keeps its keys when given a fresh URL
+        let short = LicenseCache::new(2, Duration::from_millis(1));
+        let high = request(DownloadQuality::High, false);
+        short.insert("B005", &high, &license("https://cdn.example.com/5.aaxc"));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(short.refresh_url("B005", &high, &url, None));
+        assert_eq!(short.len(), 1);
+        assert!(!short.refresh_url("B005", &high, &url, Some("CR!NEWMASTER")));
+        assert!(short.is_empty());
+        assert!(cache.refresh_url("B003", &high, &url, None));
+        assert_eq!(cache.get("B003", &high).unwrap().download_url, url);
+    }
+}
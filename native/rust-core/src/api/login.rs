@@ -0,0 +1,220 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Two-step OAuth login exported through uniffi
+//!
+//! # Reference C# Sources
+//! - **`AudibleUtilities/AudibleApiStorage.cs`** - Stores the account after login
+//! - **`LibationAvalonia/Dialogs/Login/`** - Drives the external browser login
+//!
+//! The mobile app only has to open a URL and hand back the redirect:
+//!
+//! 1. [`start_login`] returns the authorization URL and an opaque `session`
+//!    string holding the PKCE verifier, state and device serial.
+//! 2. The app opens the URL, waits for the redirect, then calls
+//!    [`complete_login`] with the `session` and the callback URL.
+//! 3. [`complete_login`] exchanges the code and returns the account as JSON,
+//!    in the same format the bridges accept as `accountJson`.
+//!
+//! The session string can be persisted if the app is suspended while the
+//! browser is open; it is only meaningful to this module.
+
+use crate::api::auth::{
+    exchange_authorization_code, generate_authorization_url, parse_authorization_callback_for_locale,
+    Account, Locale, OAuthState, PkceChallenge,
+};
+use crate::error::{LibationError, Result};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use url::Url;
+use uuid::Uuid;
+
+lazy_static::lazy_static! {
+    /// Runtime for the blocking uniffi entry points
+    static ref RUNTIME: tokio::runtime::Runtime =
+        tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+}
+
+/// Result of [`start_login`]
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct LoginStart {
+    /// URL to open in the browser
+    pub authorization_url: String,
+    /// Opaque value to pass back to [`complete_login`]
+    pub session: String,
+}
+
+/// State carried between the two login steps
+#[derive(Debug, Serialize, Deserialize)]
+struct LoginSession {
+    locale_code: String,
+    device_serial: String,
+    pkce_verifier: String,
+    state: String,
+}
+
+impl LoginSession {
+    fn encode(&self) -> Result<String> {
+        let json = serde_json::to_vec(self)
+            .map_err(|e| LibationError::InternalError(format!("Failed to encode login session: {}", e)))?;
+        Ok(general_purpose::URL_SAFE_NO_PAD.encode(json))
+    }
+
+    fn decode(session: &str) -> Result<Self> {
+        let json = general_purpose::URL_SAFE_NO_PAD
+            .decode(session.trim())
+            .map_err(|e| LibationError::InvalidInput(format!("Invalid login session: {}", e)))?;
+        serde_json::from_slice(&json)
+            .map_err(|e| LibationError::InvalidInput(format!("Invalid login session: {}", e)))
+    }
+}
+
+/// Begin an OAuth login
+///
+/// # Arguments
+/// * `locale_code` - Marketplace country code ("us", "uk", "de", ...)
+/// * `device_serial` - Serial to register the device under; a new one is
+///   generated when `None`
+///
+/// # Errors
+/// - `InvalidInput` - Unknown locale
+#[uniffi::export]
+pub fn start_login(locale_code: String, device_serial: Option<String>) -> Result<LoginStart> {
+    let locale = Locale::from_country_code(&locale_code)
+        .ok_or_else(|| LibationError::InvalidInput(format!("Invalid locale: {}", locale_code)))?;
+    let device_serial = device_serial.unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let pkce = PkceChallenge::generate()?;
+    let state = OAuthState::generate();
+    let authorization_url = generate_authorization_url(&locale, &device_serial, &pkce, &state)?;
+
+    let session = LoginSession {
+        locale_code: locale.country_code,
+        device_serial,
+        pkce_verifier: pkce.verifier,
+        state: state.value,
+    }
+    .encode()?;
+
+    Ok(LoginStart { authorization_url, session })
+}
+
+/// Finish an OAuth login and return the account as JSON
+///
+/// Blocks while the authorization code is exchanged, so call it off the UI
+/// thread.
+///
+/// # Arguments
+/// * `session` - Value returned by [`start_login`]
+/// * `callback_url` - Full redirect URL the browser landed on
+///
+/// # Errors
+/// - `InvalidInput` - Malformed session or callback URL
+/// - `AuthenticationFailed` - Amazon returned an error, the callback is not
+///   the locale's landing page, or its `state` does not match this session
+/// - Network errors from the token exchange
+#[uniffi::export]
+pub fn complete_login(session: String, callback_url: String) -> Result<String> {
+    let session = LoginSession::decode(&session)?;
+    let locale = Locale::from_country_code(&session.locale_code).ok_or_else(|| {
+        LibationError::InvalidInput(format!("Invalid locale: {}", session.locale_code))
+    })?;
+
+    check_callback_state(&callback_url, &session.state)?;
+    let authorization_code = parse_authorization_callback_for_locale(&callback_url, &locale)?;
+
+    let pkce = PkceChallenge {
+        verifier: session.pkce_verifier,
+        challenge: String::new(), // Not needed for exchange
+        method: "S256".to_string(),
+    };
+
+    let response = RUNTIME.block_on(exchange_authorization_code(
+        &locale,
+        &authorization_code,
+        &session.device_serial,
+        &pkce,
+    ))?;
+
+    let identity = response.to_identity(locale)?;
+    let mut account = Account::new(identity.amazon_account_id.clone())?;
+    account.set_account_name(identity.customer_info.name.clone());
+    account.set_identity(identity);
+
+    serde_json::to_string(&account)
+        .map_err(|e| LibationError::InternalError(format!("Failed to serialize account: {}", e)))
+}
+
+/// Reject a callback whose `state` belongs to a different login attempt
+///
+/// Amazon does not always echo `state`, so a missing value is accepted.
+fn check_callback_state(callback_url: &str, expected: &str) -> Result<()> {
+    let url = Url::parse(callback_url)
+        .map_err(|e| LibationError::InvalidInput(format!("Invalid callback URL: {}", e)))?;
+
+    match url.query_pairs().find(|(k, _)| k == "state") {
+        Some((_, state)) if state != expected => Err(LibationError::AuthenticationFailed {
+            message: "OAuth state does not match this login session".to_string(),
+            account_id: None,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_login_session_roundtrip() {
+        let start = start_login("us".to_string(), Some("ABC-123".to_string())).unwrap();
+        assert!(start.authorization_url.starts_with("https://www.amazon.com/ap/signin"));
+
+        let session = LoginSession::decode(&start.session).unwrap();
+        assert_eq!(session.locale_code, "us");
+        assert_eq!(session.device_serial, "ABC-123");
+        assert!(!session.pkce_verifier.is_empty());
+
+        assert!(start_login("xx".to_string(), None).is_err());
+        assert!(matches!(LoginSession::decode("not a session"), Err(LibationError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_check_callback_state() {
+        let base = "https://www.amazon.com/ap/maplanding?openid.oa2.authorization_code=CODE";
+        assert!(check_callback_state(base, "abc").is_ok());
+        assert!(check_callback_state(&format!("{}&state=abc", base), "abc").is_ok());
+        assert!(matches!(
+            check_callback_state(&format!("{}&state=other", base), "abc"),
+            Err(LibationError::AuthenticationFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_complete_login_rejects_oauth_error() {
+        let start = start_login("us".to_string(), None).unwrap();
+        let err = complete_login(
+            start.session,
+            "https://www.amazon.com/ap/maplanding?error=access_denied".to_string(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, LibationError::AuthenticationFailed { .. }));
+    }
+}
@@ -32,13 +32,25 @@ pub mod auth;
 pub mod client;
 pub mod library;
 pub mod content;
+pub mod description;
 pub mod license;
+pub mod license_cache;
 pub mod registration;
 pub mod customer;
+pub mod rate_limit;
+pub mod login;
+pub mod reviews;
+pub mod collections;
 
 // Re-export commonly used types
 pub use auth::{Account, Identity};
-pub use client::{AudibleClient, AudibleDomain, ClientConfig};
-pub use library::LibraryOptions;
+pub use description::DescriptionFormat;
+pub use client::{AudibleClient, AudibleDomain, ClientConfig, HttpTransport};
+pub use library::{
+    DeliveryFormat, LibraryOptions, LibrarySearchResult, LibrarySort, LibrarySortField, LibrarySyncProgress,
+    VersionChoice,
+};
 pub use registration::{RegistrationResponse, RegistrationData};
 pub use customer::CustomerInformation;
+pub use reviews::{BookReviews, Review, ReviewOptions, ReviewSort};
+pub use collections::{Collection, CollectionItem};
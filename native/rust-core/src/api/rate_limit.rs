@@ -0,0 +1,158 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Client-side request rate limiting
+//!
+//! # Reference C# Sources
+//! - `AudibleUtilities/ApiExtended.cs:23` - MaxConcurrency caps parallel requests,
+//!   but nothing in Libation caps requests over time
+//!
+//! Audible throttles accounts that send too many requests in a short window
+//! (typically while licensing a large batch). The semaphore in `AudibleClient`
+//! only limits how many requests are in flight, so a fast batch can still
+//! exceed the ceiling.
+//!
+//! [`RateLimiter`] keeps a sliding one-minute window of request times. Limiters
+//! are shared per account through [`RateLimiter::for_account`], so separate
+//! `AudibleClient` instances for the same account (the mobile bridges create one
+//! per call) draw from the same budget.
+
+use lazy_static::lazy_static;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::{sleep_until, Instant};
+
+/// Default request ceiling per account
+pub const DEFAULT_REQUESTS_PER_MINUTE: u32 = 120;
+
+/// Length of the rate limiting window
+const WINDOW: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    /// Limiters shared by every client of the same account
+    static ref ACCOUNT_LIMITERS: std::sync::Mutex<HashMap<String, Arc<RateLimiter>>> =
+        std::sync::Mutex::new(HashMap::new());
+}
+
+/// Sliding-window limiter allowing at most `requests_per_minute` requests
+/// in any 60 second period
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests_per_minute: u32,
+    sent: Mutex<VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    /// Create a standalone limiter
+    ///
+    /// # Arguments
+    /// * `requests_per_minute` - Ceiling per minute (0 is treated as 1)
+    pub fn new(requests_per_minute: u32) -> Self {
+        Self {
+            requests_per_minute: requests_per_minute.max(1),
+            sent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Get the limiter shared by all clients of `account_id`
+    ///
+    /// If the account already has a limiter with a different ceiling, it is
+    /// replaced so the most recent configuration wins.
+    pub fn for_account(account_id: &str, requests_per_minute: u32) -> Arc<Self> {
+        let mut limiters = ACCOUNT_LIMITERS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        match limiters.get(account_id) {
+            Some(limiter) if limiter.requests_per_minute == requests_per_minute.max(1) => {
+                limiter.clone()
+            }
+            _ => {
+                let limiter = Arc::new(Self::new(requests_per_minute));
+                limiters.insert(account_id.to_string(), limiter.clone());
+                limiter
+            }
+        }
+    }
+
+    /// Configured ceiling per minute
+    pub fn requests_per_minute(&self) -> u32 {
+        self.requests_per_minute
+    }
+
+    /// Wait until another request fits in the window, then record it
+    pub async fn acquire(&self) {
+        loop {
+            let wait_until = {
+                let mut sent = self.sent.lock().await;
+                let now = Instant::now();
+
+                while sent.front().is_some_and(|t| now.duration_since(*t) >= WINDOW) {
+                    sent.pop_front();
+                }
+
+                if sent.len() < self.requests_per_minute as usize {
+                    sent.push_back(now);
+                    return;
+                }
+
+                // Oldest request leaves the window first
+                sent[0] + WINDOW
+            };
+
+            sleep_until(wait_until).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_acquire_waits_for_window() {
+        let limiter = RateLimiter::new(3);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        // The fourth request has to wait for the first to age out
+        limiter.acquire().await;
+        assert!(start.elapsed() >= WINDOW);
+    }
+
+    #[test]
+    fn test_for_account_shares_limiter() {
+        let a = RateLimiter::for_account("rate-limit-test@example.com", 30);
+        let b = RateLimiter::for_account("rate-limit-test@example.com", 30);
+        assert!(Arc::ptr_eq(&a, &b));
+
+        let other = RateLimiter::for_account("rate-limit-other@example.com", 30);
+        assert!(!Arc::ptr_eq(&a, &other));
+
+        let reconfigured = RateLimiter::for_account("rate-limit-test@example.com", 60);
+        assert_eq!(reconfigured.requests_per_minute(), 60);
+    }
+}
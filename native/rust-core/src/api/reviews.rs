@@ -0,0 +1,304 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Customer reviews API
+//!
+//! Libation only imports the rating summary; this module fetches the reviews
+//! themselves for a book detail screen.
+//!
+//! # Reference C# Sources
+//! - **External: `AudibleApi/Common/Rating.cs`** - Rating distribution fields
+//!
+//! # API Endpoint
+//! `GET https://api.audible.{domain}/1.0/catalog/products/{asin}/reviews`
+//!
+//! **Query Parameters:**
+//! - `sort_by` - `MostHelpful` or `MostRecent`
+//! - `num_results` - Page size (max 50)
+//! - `page` - Page number (starts at 0)
+
+use crate::api::client::AudibleClient;
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+
+/// Largest page size the reviews endpoint accepts
+pub const MAX_REVIEWS_PER_PAGE: i32 = 50;
+
+/// Review ordering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ReviewSort {
+    #[default]
+    MostHelpful,
+    MostRecent,
+}
+
+/// Query options for [`AudibleClient::get_reviews_page`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewOptions {
+    /// Reviews per page (clamped to [`MAX_REVIEWS_PER_PAGE`])
+    #[serde(rename = "num_results")]
+    pub number_of_results_per_page: i32,
+
+    /// Page number (0-indexed)
+    #[serde(rename = "page")]
+    pub page_number: i32,
+
+    /// Sort order
+    pub sort_by: ReviewSort,
+}
+
+impl Default for ReviewOptions {
+    fn default() -> Self {
+        Self {
+            number_of_results_per_page: 10,
+            page_number: 0,
+            sort_by: ReviewSort::default(),
+        }
+    }
+}
+
+/// Star ratings given in one review (1-5, absent if not rated)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewRatings {
+    #[serde(default)]
+    pub overall_rating: Option<f32>,
+    #[serde(default)]
+    pub performance_rating: Option<f32>,
+    #[serde(default)]
+    pub story_rating: Option<f32>,
+}
+
+/// Helpfulness votes on a review
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewVotes {
+    #[serde(default)]
+    pub num_helpful_votes: i32,
+    #[serde(default)]
+    pub num_unhelpful_votes: i32,
+}
+
+/// A customer review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Review {
+    #[serde(default)]
+    pub id: String,
+
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// Review text
+    #[serde(default)]
+    pub body: Option<String>,
+
+    #[serde(default)]
+    pub author_name: Option<String>,
+
+    /// Reviewer location as entered by the reviewer
+    #[serde(default)]
+    pub location: Option<String>,
+
+    /// Submission date as sent by the API (ISO 8601)
+    #[serde(default)]
+    pub submission_date: Option<String>,
+
+    #[serde(default)]
+    pub ratings: ReviewRatings,
+
+    #[serde(rename = "review_content_scores", default)]
+    pub votes: ReviewVotes,
+}
+
+/// Number of ratings per star value for one rating category
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StarDistribution {
+    #[serde(default)]
+    pub average_rating: Option<f32>,
+    #[serde(default)]
+    pub num_ratings: i32,
+    #[serde(default)]
+    pub num_five_star_ratings: i32,
+    #[serde(default)]
+    pub num_four_star_ratings: i32,
+    #[serde(default)]
+    pub num_three_star_ratings: i32,
+    #[serde(default)]
+    pub num_two_star_ratings: i32,
+    #[serde(default)]
+    pub num_one_star_ratings: i32,
+}
+
+/// Rating summary with per-star counts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RatingBreakdown {
+    #[serde(default)]
+    pub num_reviews: i32,
+    #[serde(default)]
+    pub overall_distribution: Option<StarDistribution>,
+    #[serde(default)]
+    pub performance_distribution: Option<StarDistribution>,
+    #[serde(default)]
+    pub story_distribution: Option<StarDistribution>,
+}
+
+/// One page of reviews for a book
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BookReviews {
+    pub asin: String,
+
+    /// Reviews on this page
+    pub reviews: Vec<Review>,
+
+    /// Rating distribution, if the API included it
+    pub rating: Option<RatingBreakdown>,
+}
+
+/// Raw reviews response; the data is nested under `product`
+#[derive(Debug, Deserialize)]
+struct ReviewsResponse {
+    product: ReviewsProduct,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewsProduct {
+    #[serde(default)]
+    asin: Option<String>,
+    #[serde(default)]
+    customer_reviews: Vec<Review>,
+    #[serde(default)]
+    rating: Option<RatingBreakdown>,
+}
+
+impl AudibleClient {
+    /// Get the most helpful reviews for a book
+    ///
+    /// Shorthand for [`get_reviews_page`](Self::get_reviews_page) with default
+    /// options (first 10 reviews, most helpful first).
+    ///
+    /// # Errors
+    /// Same as [`get_reviews_page`](Self::get_reviews_page)
+    pub async fn get_reviews(&self, asin: &str) -> Result<BookReviews> {
+        self.get_reviews_page(asin, &ReviewOptions::default()).await
+    }
+
+    /// Get a page of customer reviews for a book
+    ///
+    /// # Arguments
+    /// * `asin` - Audible product ID
+    /// * `options` - Page, page size and sort order
+    ///
+    /// # Returns
+    /// The reviews on the requested page and the rating distribution
+    ///
+    /// # Errors
+    /// - `ApiRequestFailed` - API request failed
+    /// - `InvalidApiResponse` - Response could not be parsed
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::api::client::AudibleClient;
+    /// # use rust_core::api::reviews::{ReviewOptions, ReviewSort};
+    /// # async fn example(client: AudibleClient) -> rust_core::error::Result<()> {
+    /// let options = ReviewOptions { sort_by: ReviewSort::MostRecent, ..Default::default() };
+    /// let page = client.get_reviews_page("B002V5D7B0", &options).await?;
+    /// for review in page.reviews {
+    ///     println!("{:?}: {:?}", review.title, review.ratings.overall_rating);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_reviews_page(&self, asin: &str, options: &ReviewOptions) -> Result<BookReviews> {
+        let endpoint = format!("/1.0/catalog/products/{}/reviews", asin);
+        let options = ReviewOptions {
+            number_of_results_per_page: options.number_of_results_per_page.clamp(1, MAX_REVIEWS_PER_PAGE),
+            ..options.clone()
+        };
+
+        let response: ReviewsResponse = self.get_with_query(&endpoint, &options).await?;
+
+        Ok(BookReviews {
+            asin: response.product.asin.unwrap_or_else(|| asin.to_string()),
+            reviews: response.product.customer_reviews,
+            rating: response.product.rating,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::client::fixture::{canned_client, CannedTransport};
+    use std::sync::Arc;
+
+    fn canned_reviews() -> CannedTransport {
+        let body = serde_json::json!({
+            "product": {
+                "asin": "B002V5D7B0",
+                "customer_reviews": [{
+                    "id": "R1",
+                    "title": "Great listen",
+                    "body": "Loved the narration.",
+                    "author_name": "Reader",
+                    "submission_date": "2024-03-01",
+                    "ratings": { "overall_rating": 5, "performance_rating": 5, "story_rating": 4 },
+                    "review_content_scores": { "num_helpful_votes": 12, "num_unhelpful_votes": 1 }
+                }],
+                "rating": {
+                    "num_reviews": 1,
+                    "overall_distribution": {
+                        "average_rating": 4.5,
+                        "num_ratings": 2,
+                        "num_five_star_ratings": 1,
+                        "num_four_star_ratings": 1
+                    }
+                }
+            }
+        })
+        .to_string();
+        CannedTransport::fixed(200, body)
+    }
+
+    #[tokio::test]
+    async fn test_get_reviews() {
+        let transport = Arc::new(canned_reviews());
+        let client = canned_client("reviews@example.com", transport.clone());
+
+        let options = ReviewOptions {
+            number_of_results_per_page: 500,
+            sort_by: ReviewSort::MostRecent,
+            ..Default::default()
+        };
+        let page = client.get_reviews_page("B002V5D7B0", &options).await.unwrap();
+
+        assert_eq!(page.reviews.len(), 1);
+        let review = &page.reviews[0];
+        assert_eq!(review.body.as_deref(), Some("Loved the narration."));
+        assert_eq!(review.ratings.story_rating, Some(4.0));
+        assert_eq!(review.votes.num_helpful_votes, 12);
+
+        let overall = page.rating.unwrap().overall_distribution.unwrap();
+        assert_eq!(overall.num_five_star_ratings, 1);
+        assert_eq!(overall.num_one_star_ratings, 0);
+
+        assert_eq!(
+            transport.sent()[0].path_and_query(),
+            "/1.0/catalog/products/B002V5D7B0/reviews?num_results=50&page=0&sort_by=MostRecent"
+        );
+    }
+}
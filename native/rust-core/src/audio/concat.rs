@@ -0,0 +1,310 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+
+//! Joining the parts of a multi-part audiobook
+//!
+//! # Reference C# Sources
+//! - **`AaxDecrypter/AaxcDownloadMultiConverter.cs`** - Writes the parts of a
+//!   book to one output file when `SplitFilesByChapter` is off
+//!
+//! Audible delivers some long books as several files. Once every part is
+//! decrypted, [`concat_parts`] writes them to one file with FFmpeg's concat
+//! demuxer, copying the audio so nothing is re-encoded:
+//!
+//! `ffmpeg -f concat -safe 0 -i parts.txt -i chapters.txt -map 0:a -map_metadata 0 -map_chapters 1 -c copy output.m4b`
+//!
+//! ## Chapters
+//! Each part's chapters are shifted by the length of the parts before it, so
+//! the joined file can be navigated like a single-file book. A part without
+//! chapters gets one chapter spanning the whole part.
+//!
+//! ## Compatibility
+//! Stream copy only works when every part has the same codec, sample rate
+//! and channel count. Parts that differ are rejected before FFmpeg runs
+//! rather than producing a file that stops playing at the first boundary.
+
+use crate::audio::decoder::{AudioDecoder, Codec};
+use crate::audio::metadata::{Chapter, ChapterEditor};
+use crate::audio::process::{self, Tool};
+use crate::error::{LibationError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// How parts are joined
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example() -> rust_core::error::Result<()> {
+/// use rust_core::audio::concat::{concat_parts_with, ConcatOptions};
+/// use std::path::{Path, PathBuf};
+///
+/// let parts = [PathBuf::from("part1.m4b"), PathBuf::from("part2.m4b")];
+/// let options = ConcatOptions { keep_part_chapters: false, ..ConcatOptions::default() };
+/// concat_parts_with(&parts, Path::new("book.m4b"), &options).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConcatOptions {
+    /// Keep each part's own chapters; otherwise every part becomes one chapter
+    pub keep_part_chapters: bool,
+    /// Title of a chapter spanning a whole part; `{n}` is the part number
+    pub part_title: String,
+}
+
+impl Default for ConcatOptions {
+    fn default() -> Self {
+        Self {
+            keep_part_chapters: true,
+            part_title: "Part {n}".to_string(),
+        }
+    }
+}
+
+/// What is known about one part before joining
+#[derive(Debug, Clone)]
+pub struct PartInfo {
+    pub path: PathBuf,
+    pub codec: Codec,
+    pub sample_rate: u32,
+    pub channels: u32,
+    pub duration_ms: i64,
+    /// The part's chapters, relative to its own start
+    pub chapters: Vec<Chapter>,
+}
+
+impl PartInfo {
+    /// Probe a decrypted part with FFprobe
+    ///
+    /// # Errors
+    /// - `FileNotFound` - The part does not exist
+    /// - `FfmpegNotFound` - FFprobe is not installed
+    /// - `FfmpegError` - FFprobe could not read the part
+    pub async fn probe(path: &Path) -> Result<Self> {
+        let info = AudioDecoder::get_audio_info(path).await?;
+        let chapters = if info.has_chapters {
+            ChapterEditor::extract_chapters(path).await?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            codec: info.codec,
+            sample_rate: info.sample_rate,
+            channels: info.channels,
+            duration_ms: (info.duration_seconds * 1000.0).round() as i64,
+            chapters,
+        })
+    }
+
+    fn stream_description(&self) -> String {
+        format!("{} {} Hz, {} channels", self.codec.as_str(), self.sample_rate, self.channels)
+    }
+}
+
+/// Join decrypted part files into one file with the default options
+///
+/// See [`concat_parts_with`].
+pub async fn concat_parts(parts: &[PathBuf], output: &Path) -> Result<()> {
+    concat_parts_with(parts, output, &ConcatOptions::default()).await
+}
+
+/// Join decrypted part files into one file, in the given order
+///
+/// The part list and chapter file FFmpeg reads are written next to `output`
+/// and removed afterwards. The parts themselves are left in place.
+///
+/// # Arguments
+/// * `parts` - Decrypted parts in listening order
+/// * `output` - File to create; its extension picks the container
+/// * `options` - How chapters are carried over
+///
+/// # Errors
+/// - `InvalidInput` - No parts were given
+/// - `FileNotFound` - A part does not exist
+/// - `UnsupportedAudioFormat` - The parts cannot be joined without re-encoding
+/// - `FfmpegNotFound` - FFmpeg is not installed
+/// - `ConversionFailed` - FFmpeg could not join the parts
+pub async fn concat_parts_with(parts: &[PathBuf], output: &Path, options: &ConcatOptions) -> Result<()> {
+    if parts.is_empty() {
+        return Err(LibationError::InvalidInput("No parts to join".to_string()));
+    }
+
+    let mut infos = Vec::with_capacity(parts.len());
+    for part in parts {
+        infos.push(PartInfo::probe(part).await?);
+    }
+    check_copy_compatible(&infos)?;
+
+    let list_path = output.with_extension("parts.txt");
+    let chapters_path = output.with_extension("ffmetadata.txt");
+    tokio::fs::write(&list_path, concat_list(parts)).await?;
+    tokio::fs::write(&chapters_path, ChapterEditor::generate_ffmetadata(&merge_chapters(&infos, options))).await?;
+
+    let result = process::output(Tool::Ffmpeg, |cmd| {
+        cmd.args(["-y", "-v", "error", "-f", "concat", "-safe", "0", "-i"])
+            .arg(&list_path)
+            .arg("-i")
+            .arg(&chapters_path)
+            .args(["-map", "0:a", "-map_metadata", "0", "-map_chapters", "1", "-c", "copy"])
+            .arg(output);
+    })
+    .await;
+    let _ = tokio::fs::remove_file(&list_path).await;
+    let _ = tokio::fs::remove_file(&chapters_path).await;
+
+    let ffmpeg = result?;
+    if !ffmpeg.status.success() {
+        let _ = tokio::fs::remove_file(output).await;
+        return Err(LibationError::ConversionFailed(format!(
+            "FFmpeg could not join {} parts: {}",
+            parts.len(),
+            String::from_utf8_lossy(&ffmpeg.stderr).trim()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Check that the parts can be joined by stream copy
+///
+/// # Errors
+/// - `UnsupportedAudioFormat` - A part's codec, sample rate or channel count
+///   differs from the first part's
+pub fn check_copy_compatible(parts: &[PartInfo]) -> Result<()> {
+    let Some(first) = parts.first() else {
+        return Ok(());
+    };
+
+    for (index, part) in parts.iter().enumerate().skip(1) {
+        if part.codec != first.codec || part.sample_rate != first.sample_rate || part.channels != first.channels {
+            return Err(LibationError::UnsupportedAudioFormat(format!(
+                "part {} is {} but part 1 is {}; the parts cannot be joined without re-encoding",
+                index + 1,
+                part.stream_description(),
+                first.stream_description()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Chapters of the joined file
+///
+/// Every part starts where the previous one ended. Part chapters that run
+/// past the part's probed duration are cut at the part boundary.
+pub fn merge_chapters(parts: &[PartInfo], options: &ConcatOptions) -> Vec<Chapter> {
+    let mut chapters = Vec::new();
+    let mut offset = 0;
+
+    for (index, part) in parts.iter().enumerate() {
+        let part_end = offset + part.duration_ms;
+        if options.keep_part_chapters && !part.chapters.is_empty() {
+            chapters.extend(part.chapters.iter().map(|chapter| Chapter {
+                title: chapter.title.clone(),
+                start_ms: offset + chapter.start_ms,
+                end_ms: (offset + chapter.end_ms).min(part_end),
+            }));
+        } else {
+            chapters.push(Chapter {
+                title: options.part_title.replace("{n}", &(index + 1).to_string()),
+                start_ms: offset,
+                end_ms: part_end,
+            });
+        }
+        offset = part_end;
+    }
+
+    chapters
+}
+
+/// Input list for the concat demuxer, one quoted path per line
+fn concat_list(parts: &[PathBuf]) -> String {
+    parts
+        .iter()
+        .map(|part| format!("file '{}'\n", part.display().to_string().replace('\'', r"'\''")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(path: &str, sample_rate: u32, duration_ms: i64, chapters: &[(&str, i64, i64)]) -> PartInfo {
+        PartInfo {
+            path: PathBuf::from(path),
+            codec: Codec::AacLc,
+            sample_rate,
+            channels: 2,
+            duration_ms,
+            chapters: chapters
+                .iter()
+                .map(|(title, start_ms, end_ms)| Chapter {
+                    title: title.to_string(),
+                    start_ms: *start_ms,
+                    end_ms: *end_ms,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_concat_list_quotes_paths() {
+        let parts = vec![PathBuf::from("/books/part1.m4b"), PathBuf::from("/books/Ender's Game 2.m4b")];
+        assert_eq!(
+            concat_list(&parts),
+            "file '/books/part1.m4b'\nfile '/books/Ender'\\''s Game 2.m4b'\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_chapters_offsets_parts() {
+        let parts = vec![
+            part("p1.m4b", 44100, 60_000, &[("Opening", 0, 20_000), ("Chapter 1", 20_000, 60_010)]),
+            part("p2.m4b", 44100, 30_000, &[]),
+            part("p3.m4b", 44100, 10_000, &[("Chapter 2", 0, 10_000)]),
+        ];
+        check_copy_compatible(&parts).unwrap();
+
+        let spans: Vec<_> = merge_chapters(&parts, &ConcatOptions::default())
+            .into_iter()
+            .map(|c| (c.title, c.start_ms, c.end_ms))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                ("Opening".to_string(), 0, 20_000),
+                ("Chapter 1".to_string(), 20_000, 60_000),
+                ("Part 2".to_string(), 60_000, 90_000),
+                ("Chapter 2".to_string(), 90_000, 100_000),
+            ]
+        );
+
+        let per_part = ConcatOptions { keep_part_chapters: false, ..ConcatOptions::default() };
+        assert_eq!(merge_chapters(&parts, &per_part).len(), 3);
+
+        let mixed = vec![parts[0].clone(), part("p2.m4b", 22050, 30_000, &[])];
+        let err = check_copy_compatible(&mixed).unwrap_err();
+        assert!(matches!(err, LibationError::UnsupportedAudioFormat(msg) if msg.contains("part 2")));
+    }
+}
@@ -46,15 +46,26 @@
 //! - If different codecs: re-encode (slower, quality loss)
 //! - AAX → M4B: Copy (both AAC)
 //! - M4B → MP3: Re-encode (AAC → MP3)
-
-use crate::audio::decoder::{AudioDecoder, AudioFormat};
+//!
+//! ## Brand Audio Trimming
+//! - Libation's `StripAudibleBrandAudio` cuts the "This is Audible" intro and
+//!   outro using `brand_intro_duration_ms`/`brand_outro_duration_ms`; the
+//!   arithmetic lives in [`BrandSegments`]
+//! - Older titles report an intro of 0; with `detect_missing_intro` the intro
+//!   end is estimated from the first pause found by FFmpeg's `silencedetect`
+//!
+//! ## xHE-AAC and AC-4
+//! - Never converted to MP3 (rejected with `UnsupportedAudioFormat`)
+//! - xHE-AAC is written with the generic `mp4` muxer, since the `ipod` muxer
+//!   FFmpeg picks for `.m4b` does not describe USAC sample entries correctly
+
+use crate::api::content::BrandSegments;
+use crate::audio::decoder::{AudioDecoder, AudioFormat, Codec};
+use crate::audio::process::{self, Tool};
 use crate::error::{LibationError, Result};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
-use tokio::sync::Mutex;
 
 /// Conversion progress callback type
 pub type ProgressCallback = Arc<dyn Fn(f32) + Send + Sync>;
@@ -87,6 +98,14 @@ pub struct ConversionOptions {
     /// Split into separate files by chapter
     pub split_by_chapter: bool,
 
+    /// When splitting, chapters shorter than this (seconds) are merged into
+    /// the next chapter, or the previous one if they come last
+    pub min_chapter_secs: f64,
+
+    /// When splitting, chapters longer than this (seconds) are cut into
+    /// equal parts that fit
+    pub max_chapter_secs: Option<f64>,
+
     /// Preserve original metadata
     pub preserve_metadata: bool,
 
@@ -98,6 +117,9 @@ pub struct ConversionOptions {
 
     /// Downsample to mono (reduce file size)
     pub downsample_mono: bool,
+
+    /// Cut Audible brand audio from the start/end (None keeps the full file)
+    pub brand_trim: Option<BrandTrim>,
 }
 
 impl Default for ConversionOptions {
@@ -106,12 +128,60 @@ impl Default for ConversionOptions {
             output_format: AudioFormat::M4b,
             bitrate: Bitrate::default(),
             split_by_chapter: false,
+            min_chapter_secs: DEFAULT_MIN_CHAPTER_SECS,
+            max_chapter_secs: None,
             preserve_metadata: true,
             preserve_chapters: true,
             overwrite_existing: false,
             downsample_mono: false,
+            brand_trim: None,
+        }
+    }
+}
+
+/// Default shortest chapter written as its own file when splitting
+pub const DEFAULT_MIN_CHAPTER_SECS: f64 = 15.0;
+
+/// Seconds from the start of the file searched for the end of the brand intro
+const INTRO_SEARCH_SECS: f64 = 20.0;
+
+/// Minimum length of audio before a pause counts as the end of the intro
+const MIN_INTRO_SECS: f64 = 1.0;
+
+/// Audible brand intro/outro to remove during conversion
+///
+/// Reference: Libation `StripAudibleBrandAudio` setting, which trims using the
+/// brand durations from the content metadata.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BrandTrim {
+    /// Length of the brand intro in milliseconds
+    pub intro_ms: u64,
+
+    /// Length of the brand outro in milliseconds
+    pub outro_ms: u64,
+
+    /// When `intro_ms` is 0, estimate the intro with FFmpeg `silencedetect`
+    pub detect_missing_intro: bool,
+}
+
+impl BrandTrim {
+    /// Build from the brand durations in the content metadata
+    pub fn from_chapter_info(
+        info: &crate::api::content::ChapterInfo,
+        detect_missing_intro: bool,
+    ) -> Self {
+        let segments = info.brand_segments();
+        Self {
+            intro_ms: segments.intro_ms as u64,
+            outro_ms: segments.outro_ms as u64,
+            detect_missing_intro,
         }
     }
+
+    /// The brand audio to cut, once any missing intro has been detected
+    pub fn segments(&self) -> BrandSegments {
+        BrandSegments::new(self.intro_ms as i64, self.outro_ms as i64)
+    }
 }
 
 /// Audio converter
@@ -161,11 +231,26 @@ impl AudioConverter {
         // Detect input format
         let input_format = AudioDecoder::detect_format(input).await?;
 
-        // Get duration for progress tracking
-        let duration = AudioDecoder::get_duration(input).await?;
+        // Get duration for progress tracking, and the codec to pick a muxer
+        let info = AudioDecoder::get_audio_info(input).await?;
+        let duration = info.duration_seconds;
+
+        if self.options.output_format == AudioFormat::Mp3 && !info.codec.supports_mp3_conversion() {
+            return Err(LibationError::UnsupportedAudioFormat(format!(
+                "{} audio cannot be converted to MP3; keep the M4B output",
+                info.codec.as_str()
+            )));
+        }
+
+        // Work out which part of the file to keep
+        let (start_secs, end_secs) = match self.options.brand_trim {
+            Some(trim) => Self::resolve_brand_trim(input, trim, duration).await,
+            None => (0.0, duration),
+        };
+        let trim = (start_secs > 0.0 || end_secs < duration).then_some((start_secs, end_secs));
 
         // Check if conversion is needed
-        if input_format == self.options.output_format && !self.needs_processing() {
+        if input_format == self.options.output_format && !self.needs_processing() && trim.is_none() {
             // Just copy the file
             tokio::fs::copy(input, output).await.map_err(|e| {
                 LibationError::FileIoError(format!("copy: {} - {}", output.display(), e))
@@ -175,10 +260,10 @@ impl AudioConverter {
         }
 
         // Build FFmpeg command
-        let command = self.build_ffmpeg_command(input, output, input_format)?;
+        let command = self.build_ffmpeg_command(input, output, input_format, info.codec, trim)?;
 
         // Execute conversion with progress tracking
-        self.execute_conversion(&command, duration, progress_callback)
+        self.execute_conversion(&command, end_secs - start_secs, progress_callback)
             .await?;
 
         // Verify output was created
@@ -219,6 +304,12 @@ impl AudioConverter {
             ));
         }
 
+        let chapters = Self::plan_split(
+            &chapters,
+            self.options.min_chapter_secs,
+            self.options.max_chapter_secs,
+        );
+
         let mut output_files = Vec::new();
         let input_stem = input
             .file_stem()
@@ -236,8 +327,8 @@ impl AudioConverter {
             let command = self.build_chapter_split_command(
                 input,
                 &output_path,
-                &chapter.start_time,
-                chapter.duration.as_deref(),
+                &chapter.start_time.to_string(),
+                Some(&chapter.duration_seconds.to_string()),
             )?;
 
             // Execute conversion
@@ -258,12 +349,22 @@ impl AudioConverter {
         input: &Path,
         output: &Path,
         input_format: AudioFormat,
+        codec: Codec,
+        trim: Option<(f64, f64)>,
     ) -> Result<Vec<String>> {
-        let mut cmd = vec![
-            "ffmpeg".to_string(),
-            "-i".to_string(),
-            input.to_string_lossy().to_string(),
-        ];
+        let mut cmd = vec!["ffmpeg".to_string()];
+
+        // Input seeking keeps stream copy possible; the cut lands on the
+        // nearest packet, which is close enough for brand audio
+        if let Some((start, end)) = trim {
+            cmd.push("-ss".to_string());
+            cmd.push(format!("{:.3}", start));
+            cmd.push("-t".to_string());
+            cmd.push(format!("{:.3}", end - start));
+        }
+
+        cmd.push("-i".to_string());
+        cmd.push(input.to_string_lossy().to_string());
 
         // Overwrite output file if requested
         if self.options.overwrite_existing {
@@ -297,6 +398,11 @@ impl AudioConverter {
                 if input_format.is_mp4_container() && !self.needs_processing() {
                     cmd.push("-codec:a".to_string());
                     cmd.push("copy".to_string());
+
+                    if codec == Codec::XheAac {
+                        cmd.push("-f".to_string());
+                        cmd.push("mp4".to_string());
+                    }
                 } else {
                     cmd.push("-codec:a".to_string());
                     cmd.push("aac".to_string());
@@ -330,8 +436,11 @@ impl AudioConverter {
             cmd.push("0".to_string());
         }
 
-        // Chapter preservation (for formats that support it)
-        if self.options.preserve_chapters && self.options.output_format.is_mp4_container() {
+        // Chapter preservation (MP4 chapter atoms, or ID3v2 CHAP/CTOC for MP3)
+        if self.options.preserve_chapters
+            && (self.options.output_format.is_mp4_container()
+                || self.options.output_format == AudioFormat::Mp3)
+        {
             cmd.push("-map_chapters".to_string());
             cmd.push("0".to_string());
         }
@@ -392,55 +501,23 @@ impl AudioConverter {
         total_duration: f64,
         progress_callback: ProgressCallback,
     ) -> Result<()> {
-        let mut child = Command::new(&command[0])
-            .args(&command[1..])
-            .stderr(std::process::Stdio::piped())
-            .spawn()
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    LibationError::FfmpegNotFound
-                } else {
-                    LibationError::FfmpegError(format!("Failed to execute ffmpeg: {}", e))
-                }
-            })?;
-
-        // Read stderr for progress
-        let stderr = child.stderr.take().ok_or_else(|| {
-            LibationError::FfmpegError("Failed to capture ffmpeg stderr".to_string())
-        })?;
-
-        let reader = BufReader::new(stderr);
-        let mut lines = reader.lines();
-
-        let last_progress = Arc::new(Mutex::new(0.0f32));
-
-        // Spawn task to read progress
-        let progress_task = tokio::spawn({
-            let progress_callback = progress_callback.clone();
-            let last_progress = last_progress.clone();
-            async move {
-                while let Ok(Some(line)) = lines.next_line().await {
-                    if let Some(progress) =
-                        Self::parse_ffmpeg_progress(&line, total_duration)
-                    {
-                        let mut last = last_progress.lock().await;
-                        if (progress - *last).abs() > 0.01 {
-                            // Update every 1%
-                            *last = progress;
-                            progress_callback(progress);
-                        }
+        let mut last_progress = 0.0f32;
+        let status = process::stream_stderr(
+            Tool::Ffmpeg,
+            |cmd| {
+                cmd.args(&command[1..]);
+            },
+            |line| {
+                if let Some(progress) = Self::parse_ffmpeg_progress(line, total_duration) {
+                    if (progress - last_progress).abs() > 0.01 {
+                        // Update every 1%
+                        last_progress = progress;
+                        progress_callback(progress);
                     }
                 }
-            }
-        });
-
-        // Wait for FFmpeg to complete
-        let status = child.wait().await.map_err(|e| {
-            LibationError::FfmpegError(format!("FFmpeg process failed: {}", e))
-        })?;
-
-        // Wait for progress task to complete
-        let _ = progress_task.await;
+            },
+        )
+        .await?;
 
         if !status.success() {
             return Err(LibationError::ConversionFailed(format!(
@@ -490,6 +567,90 @@ impl AudioConverter {
         }
     }
 
+    /// Resolve brand trimming to the (start, end) range to keep, in seconds
+    ///
+    /// Falls back to keeping the intro if detection is disabled or fails, so
+    /// a conversion never loses content because of trimming.
+    async fn resolve_brand_trim(input: &Path, mut trim: BrandTrim, duration: f64) -> (f64, f64) {
+        if trim.intro_ms == 0 && trim.detect_missing_intro {
+            match Self::detect_intro_end(input).await {
+                Ok(Some(end)) => trim.intro_ms = (end * 1000.0).round() as u64,
+                Ok(None) => {}
+                Err(e) => eprintln!("Warning: intro detection failed for {}: {}", input.display(), e),
+            }
+        }
+
+        let segments = trim.segments();
+        if segments.is_empty() {
+            return (0.0, duration);
+        }
+        match segments.content_range_ms((duration * 1000.0).round() as i64) {
+            Some((start, end)) => (start as f64 / 1000.0, end as f64 / 1000.0),
+            None => (0.0, duration),
+        }
+    }
+
+    /// Estimate where the Audible brand intro ends using FFmpeg `silencedetect`
+    ///
+    /// Scans the first [`INTRO_SEARCH_SECS`] seconds and returns the end of the
+    /// first pause that follows at least [`MIN_INTRO_SECS`] of audio.
+    ///
+    /// # Returns
+    /// `None` if no such pause was found
+    ///
+    /// # Errors
+    /// - `FfmpegNotFound` if FFmpeg is not installed
+    /// - `FfmpegError` if FFmpeg fails
+    pub async fn detect_intro_end(input: &Path) -> Result<Option<f64>> {
+        let output = process::output(Tool::Ffmpeg, |cmd| {
+            cmd.arg("-hide_banner")
+                .arg("-nostats")
+                .arg("-t")
+                .arg(INTRO_SEARCH_SECS.to_string())
+                .arg("-i")
+                .arg(input)
+                .arg("-af")
+                .arg("silencedetect=noise=-45dB:d=0.4")
+                .arg("-f")
+                .arg("null")
+                .arg("-");
+        })
+        .await?;
+
+        if !output.status.success() {
+            return Err(LibationError::FfmpegError(format!(
+                "silencedetect failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        Ok(Self::parse_intro_end(&String::from_utf8_lossy(&output.stderr)))
+    }
+
+    /// Find the intro end in `silencedetect` log output
+    ///
+    /// Lines look like `[silencedetect @ 0x..] silence_start: 3.42` followed by
+    /// `[silencedetect @ 0x..] silence_end: 4.1 | silence_duration: 0.68`.
+    fn parse_intro_end(log: &str) -> Option<f64> {
+        let value_after = |line: &str, key: &str| -> Option<f64> {
+            let rest = &line[line.find(key)? + key.len()..];
+            rest.split_whitespace().next()?.parse().ok()
+        };
+
+        let mut silence_start = None;
+        for line in log.lines() {
+            if let Some(start) = value_after(line, "silence_start:") {
+                silence_start = Some(start);
+            } else if let Some(end) = value_after(line, "silence_end:") {
+                if silence_start.is_some_and(|start| start >= MIN_INTRO_SECS) {
+                    return Some(end);
+                }
+                silence_start = None;
+            }
+        }
+        None
+    }
+
     /// Check if any processing is needed beyond format change
     fn needs_processing(&self) -> bool {
         self.options.downsample_mono
@@ -514,22 +675,15 @@ impl AudioConverter {
     /// Extract chapters for splitting (simplified)
     async fn extract_chapters_for_splitting(path: &Path) -> Result<Vec<ChapterInfo>> {
         // Use FFprobe to extract chapters
-        let output = Command::new("ffprobe")
-            .arg("-v")
-            .arg("quiet")
-            .arg("-print_format")
-            .arg("json")
-            .arg("-show_chapters")
-            .arg(path.as_os_str())
-            .output()
-            .await
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    LibationError::FfmpegNotFound
-                } else {
-                    LibationError::FfmpegError(format!("Failed to execute ffprobe: {}", e))
-                }
-            })?;
+        let output = process::output(Tool::Ffprobe, |cmd| {
+            cmd.arg("-v")
+                .arg("quiet")
+                .arg("-print_format")
+                .arg("json")
+                .arg("-show_chapters")
+                .arg(path.as_os_str());
+        })
+        .await?;
 
         if !output.status.success() {
             return Err(LibationError::FfmpegError("FFprobe failed".to_string()));
@@ -550,25 +704,69 @@ impl AudioConverter {
             .map(|c| {
                 let start_seconds = c.start_time.parse::<f64>().unwrap_or(0.0);
                 let end_seconds = c.end_time.parse::<f64>().unwrap_or(0.0);
-                let duration_seconds = end_seconds - start_seconds;
 
                 ChapterInfo {
-                    start_time: c.start_time,
-                    duration: Some(duration_seconds.to_string()),
-                    duration_seconds,
+                    start_time: start_seconds,
+                    duration_seconds: end_seconds - start_seconds,
                 }
             })
             .collect();
 
         Ok(chapters)
     }
+
+    /// Apply the minimum/maximum chapter lengths to the chapters being split
+    ///
+    /// Short chapters (credits, "This is Audible") are folded into the next
+    /// chapter, or into the previous one when they are last. Chapters over
+    /// `max_secs` are cut into the fewest equal parts that fit.
+    fn plan_split(chapters: &[ChapterInfo], min_secs: f64, max_secs: Option<f64>) -> Vec<ChapterInfo> {
+        let mut merged: Vec<ChapterInfo> = Vec::with_capacity(chapters.len());
+        let mut pending: Option<ChapterInfo> = None;
+
+        for chapter in chapters {
+            let mut chapter = chapter.clone();
+            if let Some(short) = pending.take() {
+                chapter.duration_seconds += chapter.start_time - short.start_time;
+                chapter.start_time = short.start_time;
+            }
+
+            if chapter.duration_seconds < min_secs {
+                pending = Some(chapter);
+            } else {
+                merged.push(chapter);
+            }
+        }
+
+        if let Some(short) = pending {
+            match merged.last_mut() {
+                Some(last) => last.duration_seconds = short.start_time + short.duration_seconds - last.start_time,
+                None => merged.push(short),
+            }
+        }
+
+        let Some(max_secs) = max_secs.filter(|m| *m > 0.0) else {
+            return merged;
+        };
+
+        merged
+            .into_iter()
+            .flat_map(|chapter| {
+                let parts = (chapter.duration_seconds / max_secs).ceil().max(1.0) as usize;
+                let part_len = chapter.duration_seconds / parts as f64;
+                (0..parts).map(move |i| ChapterInfo {
+                    start_time: chapter.start_time + part_len * i as f64,
+                    duration_seconds: part_len,
+                })
+            })
+            .collect()
+    }
 }
 
 /// Chapter information for splitting
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 struct ChapterInfo {
-    start_time: String,
-    duration: Option<String>,
+    start_time: f64,
     duration_seconds: f64,
 }
 
@@ -613,6 +811,68 @@ mod tests {
         assert!((p - 0.1391).abs() < 0.01); // ~83.45 / 600 = 0.139
     }
 
+    #[test]
+    fn test_xhe_aac_uses_mp4_muxer() {
+        let converter = AudioConverter::new(ConversionOptions::default());
+        let input = Path::new("in.aaxc");
+        let output = Path::new("out.m4b");
+
+        let cmd = converter
+            .build_ffmpeg_command(input, output, AudioFormat::Aaxc, Codec::XheAac, None)
+            .unwrap();
+        assert!(cmd.windows(2).any(|w| w == ["-codec:a", "copy"]));
+        assert!(cmd.windows(2).any(|w| w == ["-f", "mp4"]));
+
+        let cmd = converter
+            .build_ffmpeg_command(input, output, AudioFormat::Aaxc, Codec::AacLc, None)
+            .unwrap();
+        assert!(!cmd.iter().any(|arg| arg == "-f"));
+    }
+
+    #[test]
+    fn test_mp3_keeps_chapters() {
+        let converter = AudioConverter::new(ConversionOptions {
+            output_format: AudioFormat::Mp3,
+            ..ConversionOptions::default()
+        });
+        let cmd = converter
+            .build_ffmpeg_command(Path::new("in.m4b"), Path::new("out.mp3"), AudioFormat::M4b, Codec::AacLc, None)
+            .unwrap();
+        assert!(cmd.windows(2).any(|w| w == ["-map_chapters", "0"]));
+        assert!(cmd.windows(2).any(|w| w == ["-id3v2_version", "3"]));
+    }
+
+    #[test]
+    fn test_trim_seeks_before_input() {
+        let converter = AudioConverter::new(ConversionOptions::default());
+        let cmd = converter
+            .build_ffmpeg_command(
+                Path::new("in.m4b"),
+                Path::new("out.m4b"),
+                AudioFormat::M4b,
+                Codec::AacLc,
+                Some((2.5, 100.0)),
+            )
+            .unwrap();
+        assert_eq!(&cmd[1..6], ["-ss", "2.500", "-t", "97.500", "-i"]);
+        assert!(cmd.windows(2).any(|w| w == ["-codec:a", "copy"]));
+    }
+
+    #[test]
+    fn test_parse_intro_end() {
+        let log = "\
+[silencedetect @ 0x55d] silence_start: 0
+[silencedetect @ 0x55d] silence_end: 0.35 | silence_duration: 0.35
+[silencedetect @ 0x55d] silence_start: 2.84
+[silencedetect @ 0x55d] silence_end: 3.51 | silence_duration: 0.67
+[silencedetect @ 0x55d] silence_start: 9.2
+[silencedetect @ 0x55d] silence_end: 9.8 | silence_duration: 0.6
+";
+        // Leading silence is skipped; the first real pause ends the intro
+        assert_eq!(AudioConverter::parse_intro_end(log), Some(3.51));
+        assert_eq!(AudioConverter::parse_intro_end("size=N/A time=00:00:20.00"), None);
+    }
+
     #[test]
     fn test_vbr_quality_to_bitrate() {
         assert_eq!(AudioConverter::vbr_quality_to_bitrate(0), 320);
@@ -620,6 +880,23 @@ mod tests {
         assert_eq!(AudioConverter::vbr_quality_to_bitrate(4), 128);
     }
 
+    #[test]
+    fn test_plan_split() {
+        let ch = |start: f64, duration_seconds: f64| ChapterInfo { start_time: start, duration_seconds };
+        let chapters = vec![ch(0.0, 5.0), ch(5.0, 600.0), ch(605.0, 900.0), ch(1505.0, 4.0)];
+
+        let planned = AudioConverter::plan_split(&chapters, 15.0, None);
+        assert_eq!(planned, vec![ch(0.0, 605.0), ch(605.0, 904.0)]);
+
+        let planned = AudioConverter::plan_split(&chapters, 15.0, Some(400.0));
+        assert_eq!(planned.len(), 5);
+        assert_eq!(planned[0], ch(0.0, 302.5));
+        assert!((planned[4].start_time + planned[4].duration_seconds - 1509.0).abs() < 1e-9);
+
+        // A book that is one short chapter still produces a file
+        assert_eq!(AudioConverter::plan_split(&[ch(0.0, 3.0)], 15.0, None), vec![ch(0.0, 3.0)]);
+    }
+
     #[test]
     fn test_bitrate_default() {
         assert_eq!(Bitrate::default(), Bitrate::Vbr(2));
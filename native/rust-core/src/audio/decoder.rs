@@ -38,12 +38,14 @@
 //! 4. Detect codec (AAC, MP3, EC-3, AC-4)
 //! 5. Check for encryption markers
 
+use crate::audio::metadata::Chapter;
+use crate::audio::process::{self, Tool};
 use crate::error::{LibationError, Result};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
-use tokio::process::Command;
 
 /// Audio format enum
 /// Based on AudioFormatDecoder.cs format detection
@@ -128,6 +130,34 @@ impl Codec {
             Self::Unknown => "Unknown",
         }
     }
+
+    /// Map an FFprobe `codec_name`/`profile` pair to a codec
+    ///
+    /// FFmpeg reports xHE-AAC (USAC) under the `aac` codec name, so the stream
+    /// profile is needed to tell it apart from AAC-LC.
+    pub fn from_ffprobe(codec_name: Option<&str>, profile: Option<&str>) -> Self {
+        match codec_name {
+            Some("aac") => match profile {
+                Some(p) if p.eq_ignore_ascii_case("xHE-AAC") || p.eq_ignore_ascii_case("USAC") => {
+                    Self::XheAac
+                }
+                _ => Self::AacLc,
+            },
+            Some("mp3") => Self::Mp3,
+            Some("eac3") => Self::Ec3,
+            Some("ac4") => Self::Ac4,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Whether audio in this codec may be transcoded to MP3
+    ///
+    /// Reference: DownloadOptions.cs:75-79 - Libation keeps AC-4 as M4B. xHE-AAC
+    /// is treated the same way: most FFmpeg builds cannot decode USAC, so a
+    /// transcode would fail part-way or produce silence.
+    pub fn supports_mp3_conversion(&self) -> bool {
+        !matches!(self, Self::Ac4 | Self::XheAac)
+    }
 }
 
 /// Detailed audio file information
@@ -152,6 +182,74 @@ pub struct AudioInfo {
     pub has_chapters: bool,
 }
 
+/// A finished, playable audiobook on disk
+///
+/// Returned by the decrypt step so callers can hand the result straight to the
+/// player UI without probing the file again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudiobookFile {
+    /// Path to the audio file
+    pub path: PathBuf,
+    /// Container format (M4B, MP3, etc.)
+    pub format: AudioFormat,
+    /// File size in bytes
+    pub size: u64,
+    /// Duration in seconds
+    pub duration: f64,
+    /// Chapter markers read from the file (empty if it has none)
+    pub chapters: Vec<Chapter>,
+    /// Cover image saved alongside the audio, if any
+    pub cover_path: Option<PathBuf>,
+}
+
+impl AudiobookFile {
+    /// Inspect an audio file with FFprobe and describe it
+    ///
+    /// # Arguments
+    /// * `path` - Decrypted audio file
+    /// * `cover_path` - Cover image for the book, if one was saved
+    ///
+    /// # Errors
+    /// - `FileNotFound` - `path` does not exist
+    /// - `FfmpegNotFound` / `FfmpegError` - FFprobe is missing or failed
+    /// - `InvalidAudioFile` - The file has no audio stream
+    pub async fn from_file(path: &Path, cover_path: Option<PathBuf>) -> Result<Self> {
+        let size = tokio::fs::metadata(path)
+            .await
+            .map_err(|e| LibationError::FileNotFound(format!("{}: {}", path.display(), e)))?
+            .len();
+        let format = AudioDecoder::detect_format(path).await?;
+        let probe_output = AudioDecoder::probe_with_ffprobe(path).await?;
+
+        Self::from_ffprobe_output(path, &probe_output, format, size, cover_path)
+    }
+
+    /// Build from FFprobe JSON (`-show_format -show_streams -show_chapters`)
+    fn from_ffprobe_output(
+        path: &Path,
+        json: &str,
+        format: AudioFormat,
+        size: u64,
+        cover_path: Option<PathBuf>,
+    ) -> Result<Self> {
+        let info = AudioDecoder::parse_ffprobe_output(json, format, size)?;
+        let chapters = if info.has_chapters {
+            AudioDecoder::parse_ffprobe_chapters(json)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            format: info.format,
+            size: info.file_size,
+            duration: info.duration_seconds,
+            chapters,
+            cover_path,
+        })
+    }
+}
+
 /// FFprobe JSON output structures
 #[derive(Debug, Deserialize)]
 struct FfprobeOutput {
@@ -171,6 +269,7 @@ struct FfprobeFormat {
 struct FfprobeStream {
     codec_type: String,
     codec_name: Option<String>,
+    profile: Option<String>,
     sample_rate: Option<String>,
     channels: Option<u32>,
 }
@@ -180,6 +279,7 @@ struct FfprobeChapter {
     id: i64,
     start_time: String,
     end_time: String,
+    tags: Option<HashMap<String, String>>,
 }
 
 /// Audio format decoder
@@ -280,24 +380,17 @@ impl AudioDecoder {
 
     /// Execute FFprobe command
     async fn probe_with_ffprobe(path: &Path) -> Result<String> {
-        let output = Command::new("ffprobe")
-            .arg("-v")
-            .arg("quiet")
-            .arg("-print_format")
-            .arg("json")
-            .arg("-show_format")
-            .arg("-show_streams")
-            .arg("-show_chapters")
-            .arg(path.as_os_str())
-            .output()
-            .await
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    LibationError::FfmpegNotFound
-                } else {
-                    LibationError::FfmpegError(format!("Failed to execute ffprobe: {}", e))
-                }
-            })?;
+        let output = process::output(Tool::Ffprobe, |cmd| {
+            cmd.arg("-v")
+                .arg("quiet")
+                .arg("-print_format")
+                .arg("json")
+                .arg("-show_format")
+                .arg("-show_streams")
+                .arg("-show_chapters")
+                .arg(path.as_os_str());
+        })
+        .await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -349,13 +442,10 @@ impl AudioDecoder {
             })?;
 
         // Extract codec
-        let codec = match audio_stream.codec_name.as_deref() {
-            Some("aac") => Codec::AacLc,
-            Some("mp3") => Codec::Mp3,
-            Some("eac3") => Codec::Ec3,
-            Some("ac4") => Codec::Ac4,
-            _ => Codec::Unknown,
-        };
+        let codec = Codec::from_ffprobe(
+            audio_stream.codec_name.as_deref(),
+            audio_stream.profile.as_deref(),
+        );
 
         // Extract sample rate
         let sample_rate = audio_stream
@@ -382,6 +472,35 @@ impl AudioDecoder {
         })
     }
 
+    /// Parse chapter markers from FFprobe JSON output
+    fn parse_ffprobe_chapters(json: &str) -> Result<Vec<Chapter>> {
+        let probe: FfprobeOutput = serde_json::from_str(json).map_err(|e| {
+            LibationError::AudioFormatDetectionFailed(format!(
+                "Failed to parse FFprobe output: {}",
+                e
+            ))
+        })?;
+
+        Ok(probe
+            .chapters
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| {
+                let start_seconds: f64 = c.start_time.parse().unwrap_or(0.0);
+                let end_seconds: f64 = c.end_time.parse().unwrap_or(0.0);
+
+                Chapter {
+                    title: c
+                        .tags
+                        .and_then(|t| t.get("title").cloned())
+                        .unwrap_or_else(|| format!("Chapter {}", c.id)),
+                    start_ms: (start_seconds * 1000.0) as i64,
+                    end_ms: (end_seconds * 1000.0) as i64,
+                }
+            })
+            .collect())
+    }
+
     /// Check if file is a valid audio file
     pub async fn is_valid_audio_file(path: &Path) -> Result<bool> {
         match Self::get_audio_info(path).await {
@@ -392,22 +511,15 @@ impl AudioDecoder {
 
     /// Get duration in seconds (quick check without full probe)
     pub async fn get_duration(path: &Path) -> Result<f64> {
-        let output = Command::new("ffprobe")
-            .arg("-v")
-            .arg("quiet")
-            .arg("-print_format")
-            .arg("json")
-            .arg("-show_format")
-            .arg(path.as_os_str())
-            .output()
-            .await
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    LibationError::FfmpegNotFound
-                } else {
-                    LibationError::FfmpegError(format!("Failed to execute ffprobe: {}", e))
-                }
-            })?;
+        let output = process::output(Tool::Ffprobe, |cmd| {
+            cmd.arg("-v")
+                .arg("quiet")
+                .arg("-print_format")
+                .arg("json")
+                .arg("-show_format")
+                .arg(path.as_os_str());
+        })
+        .await?;
 
         if !output.status.success() {
             return Err(LibationError::FfmpegError("FFprobe failed".to_string()));
@@ -487,4 +599,53 @@ mod tests {
         assert_eq!(Codec::Mp3.as_str(), "MP3");
         assert_eq!(Codec::Ec3.as_str(), "E-AC-3");
     }
+
+    #[test]
+    fn test_codec_from_ffprobe() {
+        assert_eq!(Codec::from_ffprobe(Some("aac"), Some("LC")), Codec::AacLc);
+        assert_eq!(Codec::from_ffprobe(Some("aac"), None), Codec::AacLc);
+        assert_eq!(Codec::from_ffprobe(Some("aac"), Some("xHE-AAC")), Codec::XheAac);
+        assert_eq!(Codec::from_ffprobe(Some("aac"), Some("USAC")), Codec::XheAac);
+        assert_eq!(Codec::from_ffprobe(Some("ac4"), None), Codec::Ac4);
+        assert_eq!(Codec::from_ffprobe(None, None), Codec::Unknown);
+
+        assert!(Codec::AacLc.supports_mp3_conversion());
+        assert!(Codec::Ec3.supports_mp3_conversion());
+        assert!(!Codec::XheAac.supports_mp3_conversion());
+        assert!(!Codec::Ac4.supports_mp3_conversion());
+    }
+
+    #[test]
+    fn test_audiobook_file_from_ffprobe_output() {
+        let json = r#"{
+            "format": { "duration": "3725.5", "bit_rate": "64000", "size": "29804000" },
+            "streams": [
+                { "codec_type": "video", "codec_name": "mjpeg" },
+                { "codec_type": "audio", "codec_name": "aac", "profile": "LC", "sample_rate": "44100", "channels": 2 }
+            ],
+            "chapters": [
+                { "id": 0, "start_time": "0.000000", "end_time": "1800.250000", "tags": { "title": "Opening Credits" } },
+                { "id": 1, "start_time": "1800.250000", "end_time": "3725.500000" }
+            ]
+        }"#;
+
+        let file = AudiobookFile::from_ffprobe_output(
+            Path::new("/books/B000000001.m4b"),
+            json,
+            AudioFormat::M4b,
+            29_804_000,
+            Some(PathBuf::from("/books/B000000001.jpg")),
+        )
+        .unwrap();
+
+        assert_eq!(file.path, PathBuf::from("/books/B000000001.m4b"));
+        assert_eq!(file.format, AudioFormat::M4b);
+        assert_eq!(file.size, 29_804_000);
+        assert_eq!(file.duration, 3725.5);
+        assert_eq!(file.chapters.len(), 2);
+        assert_eq!(file.chapters[0].title, "Opening Credits");
+        assert_eq!(file.chapters[0].end_ms, 1_800_250);
+        assert_eq!(file.chapters[1].title, "Chapter 1");
+        assert_eq!(file.cover_path, Some(PathBuf::from("/books/B000000001.jpg")));
+    }
 }
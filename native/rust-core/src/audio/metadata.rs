@@ -41,15 +41,29 @@
 //!
 //! # Chapter Markers
 //! - Stored in MP4/M4B: Chapter atom
-//! - Stored in MP3: ID3v2 CHAP frames
+//! - Stored in MP3: ID3v2 CHAP frames with a CTOC table of contents
+//!
+//! # MP3 Tags
+//! MP3 files are written with ID3v2.3 (`-id3v2_version 3`), the newest version
+//! most players read. FFmpeg maps the `-metadata` keys to TIT2/TPE1/TALB etc.,
+//! writes the cover as an APIC front-cover frame, and writes CHAP/CTOC frames
+//! when the output has chapters. See [`MetadataEditor::write_tags`].
 //! - Format: [(title, start_ms, end_ms)]
+//!
+//! # Sidecar Chapter Files
+//! Players that ignore embedded chapters can read a sidecar written next to
+//! the audio (see [`ChapterExportFormat`]):
+//! - `Title.cue` - Cue sheet, `INDEX 01 MM:SS:FF` with 75 frames per second
+//! - `Title.chapters.txt` - One `HH:MM:SS.mmm Title` line per chapter
 
+use crate::audio::process::{self, Tool};
 use crate::error::{LibationError, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
-use tokio::process::Command;
 
 /// Audio metadata structure
 /// Based on Libation's book metadata fields
@@ -60,7 +74,8 @@ pub struct AudioMetadata {
     pub narrators: Vec<String>,
     pub publisher: Option<String>,
     pub publication_date: Option<String>,
-    pub language: Option<String>,
+    /// Content languages; the first is the primary language
+    pub languages: Vec<String>,
     pub series: Option<SeriesInfo>,
     pub description: Option<String>,
     pub genres: Vec<String>,
@@ -80,6 +95,19 @@ impl AudioMetadata {
         self.narrators.join(", ")
     }
 
+    /// First listed content language
+    pub fn primary_language(&self) -> Option<&str> {
+        self.languages.first().map(String::as_str)
+    }
+
+    /// Year part of `publication_date` ("2019-06-04" -> "2019")
+    pub fn publication_year(&self) -> Option<String> {
+        self.publication_date
+            .as_deref()
+            .map(|d| d.trim().chars().take(4).collect::<String>())
+            .filter(|y| y.len() == 4 && y.chars().all(|c| c.is_ascii_digit()))
+    }
+
     /// Format series for display: "Series Name #1"
     pub fn format_series(&self) -> Option<String> {
         self.series.as_ref().map(|s| {
@@ -100,6 +128,129 @@ pub struct SeriesInfo {
     pub position: Option<String>,
 }
 
+impl SeriesInfo {
+    /// Parsed [`position`](Self::position), if it contains a number
+    pub fn sequence(&self) -> Option<SeriesSequence> {
+        self.position.as_deref().and_then(SeriesSequence::parse)
+    }
+}
+
+/// Structured position of a book within a series
+///
+/// Audible sends the position as free text: usually "2", but also novellas
+/// ("2.5"), omnibus editions ("1-3") and the odd "Book 4". Sequences order by
+/// their start and then by their end, so a 2.5 novella sorts between books 2
+/// and 3 and the "1-3" omnibus sorts after book 1.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SeriesSequence {
+    /// Whole-numbered book ("2")
+    Integer { value: u32 },
+    /// Fractional book, usually a novella ("2.5")
+    Decimal { value: f32 },
+    /// Collection spanning several books ("1-3")
+    Range { start: f32, end: f32 },
+}
+
+lazy_static! {
+    /// A number, optionally followed by a dash and a second number
+    static ref SEQUENCE_PATTERN: Regex = Regex::new(r"(\d+(?:\.\d+)?)(?:\s*[-–—]\s*(\d+(?:\.\d+)?))?").unwrap();
+}
+
+impl SeriesSequence {
+    /// Parse a sequence string
+    ///
+    /// Uses the first number in the text, plus a second number when the two
+    /// are joined by a dash ("1-3", "1 – 3"). Ranges given backwards are
+    /// normalised, and a range whose ends match collapses to a single number.
+    ///
+    /// # Returns
+    /// `None` if the text contains no number
+    pub fn parse(text: &str) -> Option<Self> {
+        let captures = SEQUENCE_PATTERN.captures(text)?;
+
+        let first = captures.get(1)?.as_str();
+        let single = Self::parse_number(first)?;
+
+        let Some(second) = captures.get(2) else {
+            return Some(single);
+        };
+        let start = single.start();
+        let end = second.as_str().parse::<f32>().ok().filter(|n| n.is_finite())?;
+        if start == end {
+            return Some(single);
+        }
+
+        Some(SeriesSequence::Range {
+            start: start.min(end),
+            end: start.max(end),
+        })
+    }
+
+    fn parse_number(text: &str) -> Option<Self> {
+        if let Ok(value) = text.parse::<u32>() {
+            return Some(SeriesSequence::Integer { value });
+        }
+        // "2.0" and numbers too large for u32 end up here
+        let value = text.parse::<f32>().ok().filter(|n| n.is_finite())?;
+        Some(SeriesSequence::Decimal { value })
+    }
+
+    /// First book covered
+    pub fn start(&self) -> f32 {
+        match *self {
+            SeriesSequence::Integer { value } => value as f32,
+            SeriesSequence::Decimal { value } => value,
+            SeriesSequence::Range { start, .. } => start,
+        }
+    }
+
+    /// Last book covered (same as [`start`](Self::start) unless a range)
+    pub fn end(&self) -> f32 {
+        match *self {
+            SeriesSequence::Range { end, .. } => end,
+            _ => self.start(),
+        }
+    }
+
+    /// Numeric sort key, stored as the series index in the database
+    pub fn sort_key(&self) -> f32 {
+        self.start()
+    }
+}
+
+impl PartialEq for SeriesSequence {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for SeriesSequence {}
+
+impl PartialOrd for SeriesSequence {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SeriesSequence {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.start()
+            .total_cmp(&other.start())
+            .then_with(|| self.end().total_cmp(&other.end()))
+    }
+}
+
+impl std::fmt::Display for SeriesSequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            SeriesSequence::Integer { value } => write!(f, "{}", value),
+            SeriesSequence::Decimal { value } => write!(f, "{}", value),
+            SeriesSequence::Range { start, end } => write!(f, "{}-{}", start, end),
+        }
+    }
+}
+
 /// Chapter marker structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Chapter {
@@ -110,7 +261,70 @@ pub struct Chapter {
     pub end_ms: i64,
 }
 
+/// Sidecar chapter file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChapterExportFormat {
+    /// Cue sheet (`.cue`)
+    Cue,
+    /// Plain `HH:MM:SS.mmm Title` list (`.chapters.txt`), as read by mp4chaps
+    /// and Overdrive-style players
+    ChaptersTxt,
+}
+
+impl ChapterExportFormat {
+    /// Sidecar path for an audio file
+    pub fn sidecar_path(&self, audio_file: &Path) -> PathBuf {
+        match self {
+            ChapterExportFormat::Cue => audio_file.with_extension("cue"),
+            ChapterExportFormat::ChaptersTxt => audio_file.with_extension("chapters.txt"),
+        }
+    }
+}
+
+/// Where chapter titles come from when a file is tagged
+///
+/// Whichever source is preferred supplies the chapter list; the other fills in
+/// chapters whose titles are missing or placeholders, and is used on its own
+/// when the preferred source has no chapters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ChapterSource {
+    /// Chapter tree from the license `ChapterInfo` (flattened)
+    #[default]
+    Api,
+    /// Chapters already embedded in the audio file
+    Embedded,
+}
+
+/// How far apart two chapter starts can be and still count as the same chapter
+const CHAPTER_MATCH_TOLERANCE_MS: i64 = 1500;
+
 impl Chapter {
+    /// Convert the license chapter tree into flat chapter markers
+    ///
+    /// Nested titles are joined with ": ", as Libation does.
+    pub fn from_api_chapters(info: &crate::api::content::ChapterInfo) -> Vec<Chapter> {
+        crate::api::content::flatten_chapters(info.chapters.clone(), Some(": "))
+            .into_iter()
+            .map(|c| Chapter {
+                title: c.title,
+                start_ms: c.start_offset_ms,
+                end_ms: c.start_offset_ms + c.length_ms,
+            })
+            .collect()
+    }
+
+    /// Whether the title is missing or a generated "Chapter N" placeholder
+    pub fn has_placeholder_title(&self) -> bool {
+        let title = self.title.trim();
+        match title.get(..8) {
+            _ if title.is_empty() => true,
+            Some(prefix) if prefix.eq_ignore_ascii_case("chapter ") => {
+                title[8..].trim().chars().all(|c| c.is_ascii_digit())
+            }
+            _ => false,
+        }
+    }
+
     /// Get chapter duration in milliseconds
     pub fn duration_ms(&self) -> i64 {
         self.end_ms - self.start_ms
@@ -146,10 +360,12 @@ pub struct MetadataEditor;
 impl MetadataEditor {
     /// Embed metadata into audio file
     ///
-    /// Based on AudioDecodable.cs metadata writing
+    /// Based on AudioDecodable.cs metadata writing. Streams are copied, so
+    /// chapters and cover art are kept. See [`metadata_args`](Self::metadata_args)
+    /// for the tag mapping.
     pub async fn embed_metadata(file: &Path, metadata: &AudioMetadata) -> Result<()> {
         // Create temporary file for output
-        let temp_file = file.with_extension("tmp.m4b");
+        let temp_file = temp_output_path(file);
 
         // Build FFmpeg command
         let mut cmd = vec![
@@ -159,90 +375,206 @@ impl MetadataEditor {
             "-codec".to_string(),
             "copy".to_string(),
         ];
+        cmd.extend(Self::metadata_args(metadata));
+        cmd.extend(id3_args(file));
+
+        // Overwrite temp file
+        cmd.push("-y".to_string());
+        cmd.push(temp_file.to_string_lossy().to_string());
+
+        // Execute FFmpeg
+        Self::execute_ffmpeg(&cmd).await?;
+
+        // Replace original with temp file
+        fs::rename(&temp_file, file).await.map_err(|e| {
+            LibationError::FileIoError(format!("{}: {} - {}", "rename".to_string(), file.to_string_lossy().to_string(), e.to_string(),
+            ))
+        })?;
 
-        // Add metadata flags
-        cmd.push("-metadata".to_string());
-        cmd.push(format!("title={}", metadata.title));
+        Ok(())
+    }
+
+    /// FFmpeg `-metadata` flags for an audiobook
+    ///
+    /// Uses the tag layout library players expect for M4B files:
+    /// - `title` - Book title
+    /// - `artist` / `album_artist` - Authors
+    /// - `composer` - Narrators
+    /// - `album` - Series name (book title for standalone books)
+    /// - `track` - Position in the series
+    /// - `date` - Publication year
+    /// - `comment` / `description` - Publisher's summary
+    /// - `genre`, `publisher`, `series`, `asin` - When known
+    pub fn metadata_args(metadata: &AudioMetadata) -> Vec<String> {
+        let mut tags: Vec<(&str, String)> = vec![("title", metadata.title.clone())];
 
         if !metadata.authors.is_empty() {
-            cmd.push("-metadata".to_string());
-            cmd.push(format!("artist={}", metadata.format_authors()));
+            tags.push(("artist", metadata.format_authors()));
+            tags.push(("album_artist", metadata.format_authors()));
         }
 
-        cmd.push("-metadata".to_string());
-        cmd.push(format!("album={}", metadata.title));
-
         if !metadata.narrators.is_empty() {
-            cmd.push("-metadata".to_string());
-            cmd.push(format!("album_artist={}", metadata.format_narrators()));
+            tags.push(("composer", metadata.format_narrators()));
         }
 
-        if let Some(publisher) = &metadata.publisher {
-            cmd.push("-metadata".to_string());
-            cmd.push(format!("publisher={}", publisher));
+        match &metadata.series {
+            Some(series) => {
+                tags.push(("album", series.name.clone()));
+                if let Some(position) = &series.position {
+                    tags.push(("track", position.clone()));
+                }
+            }
+            None => tags.push(("album", metadata.title.clone())),
         }
 
-        if let Some(date) = &metadata.publication_date {
-            cmd.push("-metadata".to_string());
-            cmd.push(format!("date={}", date));
+        if let Some(year) = metadata.publication_year() {
+            tags.push(("date", year));
+        }
+
+        if let Some(description) = &metadata.description {
+            tags.push(("comment", description.clone()));
+            tags.push(("description", description.clone()));
         }
 
         if !metadata.genres.is_empty() {
-            cmd.push("-metadata".to_string());
-            cmd.push(format!("genre={}", metadata.genres.join("; ")));
+            tags.push(("genre", metadata.genres.join("; ")));
         }
 
-        if let Some(description) = &metadata.description {
-            cmd.push("-metadata".to_string());
-            cmd.push(format!("comment={}", description));
+        if let Some(publisher) = &metadata.publisher {
+            tags.push(("publisher", publisher.clone()));
         }
 
         if let Some(series) = metadata.format_series() {
-            cmd.push("-metadata".to_string());
-            cmd.push(format!("series={}", series));
+            tags.push(("series", series));
         }
 
         if let Some(asin) = &metadata.asin {
-            cmd.push("-metadata".to_string());
-            cmd.push(format!("asin={}", asin));
+            tags.push(("asin", asin.clone()));
         }
 
-        // Overwrite temp file
-        cmd.push("-y".to_string());
-        cmd.push(temp_file.to_string_lossy().to_string());
+        tags.into_iter()
+            .flat_map(|(key, value)| ["-metadata".to_string(), format!("{}={}", key, value)])
+            .collect()
+    }
 
-        // Execute FFmpeg
-        Self::execute_ffmpeg(&cmd).await?;
+    /// Write tags, cover art and chapters in one pass
+    ///
+    /// Streams are copied, not re-encoded. For MP3 output this produces an
+    /// ID3v2.3 tag with text frames, an APIC front cover and CHAP/CTOC
+    /// chapter frames; for M4B the same data goes into MP4 atoms.
+    ///
+    /// # Arguments
+    /// * `file` - Audio file to tag in place
+    /// * `metadata` - Book metadata (see [`metadata_args`](Self::metadata_args))
+    /// * `cover_art` - Image to embed; `None` keeps any existing cover
+    /// * `chapters` - Chapters to write; empty keeps any existing chapters
+    ///
+    /// # Errors
+    /// - `FfmpegNotFound` - FFmpeg is not installed
+    /// - `FfmpegError` - FFmpeg failed
+    /// - `FileIoError` - Temporary files could not be written or renamed
+    pub async fn write_tags(
+        file: &Path,
+        metadata: &AudioMetadata,
+        cover_art: Option<&Path>,
+        chapters: &[Chapter],
+    ) -> Result<()> {
+        let chapters_file = if chapters.is_empty() {
+            None
+        } else {
+            let path = file.with_extension("ffmetadata.txt");
+            fs::write(&path, ChapterEditor::generate_ffmetadata(chapters))
+                .await
+                .map_err(|e| LibationError::FileIoError(format!("write: {} - {}", path.display(), e)))?;
+            Some(path)
+        };
 
-        // Replace original with temp file
-        fs::rename(&temp_file, file).await.map_err(|e| {
-            LibationError::FileIoError(format!("{}: {} - {}", "rename".to_string(), file.to_string_lossy().to_string(), e.to_string(),
-            ))
-        })?;
+        let temp_file = temp_output_path(file);
+        let cmd = Self::write_tags_command(file, &temp_file, metadata, cover_art, chapters_file.as_deref());
+        let result = Self::execute_ffmpeg(&cmd).await;
 
-        Ok(())
+        if let Some(path) = &chapters_file {
+            let _ = fs::remove_file(path).await;
+        }
+        if let Err(e) = result {
+            let _ = fs::remove_file(&temp_file).await;
+            return Err(e);
+        }
+
+        fs::rename(&temp_file, file)
+            .await
+            .map_err(|e| LibationError::FileIoError(format!("rename: {} - {}", file.display(), e)))
+    }
+
+    /// FFmpeg command used by [`write_tags`](Self::write_tags)
+    ///
+    /// Inputs are the audio, then the chapter file, then the cover, each only
+    /// when given.
+    pub fn write_tags_command(
+        file: &Path,
+        output: &Path,
+        metadata: &AudioMetadata,
+        cover_art: Option<&Path>,
+        chapters_file: Option<&Path>,
+    ) -> Vec<String> {
+        let mut cmd = vec!["ffmpeg".to_string(), "-i".to_string(), file.to_string_lossy().to_string()];
+        let mut next_input = 1;
+
+        let chapters_input = chapters_file.map(|path| {
+            cmd.push("-i".to_string());
+            cmd.push(path.to_string_lossy().to_string());
+            next_input += 1;
+            next_input - 1
+        });
+        let cover_input = cover_art.map(|path| {
+            cmd.push("-i".to_string());
+            cmd.push(path.to_string_lossy().to_string());
+            next_input += 1;
+            next_input - 1
+        });
+
+        cmd.push("-map".to_string());
+        cmd.push("0:a".to_string());
+        cmd.push("-map".to_string());
+        cmd.push(match cover_input {
+            Some(index) => format!("{}:v", index),
+            None => "0:v?".to_string(),
+        });
+        cmd.push("-map_metadata".to_string());
+        cmd.push("0".to_string());
+        if let Some(index) = chapters_input {
+            cmd.push("-map_chapters".to_string());
+            cmd.push(index.to_string());
+        }
+
+        cmd.push("-codec".to_string());
+        cmd.push("copy".to_string());
+        if cover_input.is_some() {
+            cmd.push("-disposition:v:0".to_string());
+            cmd.push("attached_pic".to_string());
+            cmd.extend(cover_art_args(file));
+        }
+
+        cmd.extend(Self::metadata_args(metadata));
+        cmd.extend(id3_args(file));
+        cmd.push("-y".to_string());
+        cmd.push(output.to_string_lossy().to_string());
+        cmd
     }
 
     /// Extract metadata from audio file
     ///
     /// Uses FFprobe to read metadata tags
     pub async fn extract_metadata(file: &Path) -> Result<AudioMetadata> {
-        let output = Command::new("ffprobe")
-            .arg("-v")
-            .arg("quiet")
-            .arg("-print_format")
-            .arg("json")
-            .arg("-show_format")
-            .arg(file.as_os_str())
-            .output()
-            .await
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    LibationError::FfmpegNotFound
-                } else {
-                    LibationError::FfmpegError(format!("Failed to execute ffprobe: {}", e))
-                }
-            })?;
+        let output = process::output(Tool::Ffprobe, |cmd| {
+            cmd.arg("-v")
+                .arg("quiet")
+                .arg("-print_format")
+                .arg("json")
+                .arg("-show_format")
+                .arg(file.as_os_str());
+        })
+        .await?;
 
         if !output.status.success() {
             return Err(LibationError::FfmpegError("FFprobe failed".to_string()));
@@ -265,12 +597,16 @@ impl MetadataEditor {
                 .map(|s| vec![s.clone()])
                 .unwrap_or_default(),
             narrators: tags
-                .get("album_artist")
+                .get("composer")
+                .or_else(|| tags.get("album_artist"))
                 .map(|s| vec![s.clone()])
                 .unwrap_or_default(),
             publisher: tags.get("publisher").cloned(),
             publication_date: tags.get("date").cloned(),
-            language: tags.get("language").cloned(),
+            languages: tags
+                .get("language")
+                .map(|s| crate::storage::models::split_languages(s))
+                .unwrap_or_default(),
             series: tags.get("series").map(|s| SeriesInfo {
                 name: s.clone(),
                 position: None,
@@ -291,9 +627,9 @@ impl MetadataEditor {
     /// Based on AudioDecodable.cs cover art embedding
     pub async fn embed_cover_art(file: &Path, cover_art_path: &Path) -> Result<()> {
         // Create temporary file for output
-        let temp_file = file.with_extension("tmp.m4b");
+        let temp_file = temp_output_path(file);
 
-        let cmd = vec![
+        let mut cmd = vec![
             "ffmpeg".to_string(),
             "-i".to_string(),
             file.to_string_lossy().to_string(),
@@ -309,9 +645,11 @@ impl MetadataEditor {
             "copy".to_string(),
             "-disposition:v:0".to_string(),
             "attached_pic".to_string(),
-            "-y".to_string(),
-            temp_file.to_string_lossy().to_string(),
         ];
+        cmd.extend(cover_art_args(file));
+        cmd.extend(id3_args(file));
+        cmd.push("-y".to_string());
+        cmd.push(temp_file.to_string_lossy().to_string());
 
         // Execute FFmpeg
         Self::execute_ffmpeg(&cmd).await?;
@@ -366,18 +704,13 @@ impl MetadataEditor {
     }
 
     /// Execute FFmpeg command and handle errors
+    ///
+    /// `command[0]` is the program name and is replaced by [`Tool::Ffmpeg`].
     async fn execute_ffmpeg(command: &[String]) -> Result<()> {
-        let output = Command::new(&command[0])
-            .args(&command[1..])
-            .output()
-            .await
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    LibationError::FfmpegNotFound
-                } else {
-                    LibationError::FfmpegError(format!("Failed to execute ffmpeg: {}", e))
-                }
-            })?;
+        let output = process::output(Tool::Ffmpeg, |cmd| {
+            cmd.args(&command[1..]);
+        })
+        .await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -391,10 +724,97 @@ impl MetadataEditor {
     }
 }
 
+/// Temporary output next to `file` with the same container extension
+///
+/// FFmpeg picks the muxer from the extension, so "book.mp3" must be rewritten
+/// through "book.tmp.mp3", not an `.m4b` file.
+pub(crate) fn temp_output_path(file: &Path) -> PathBuf {
+    match file.extension().and_then(|e| e.to_str()) {
+        Some(ext) => file.with_extension(format!("tmp.{}", ext)),
+        None => file.with_extension("tmp"),
+    }
+}
+
+fn is_mp3(file: &Path) -> bool {
+    file.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("mp3"))
+}
+
+/// Muxer flags for ID3v2.3 tags on MP3 output (none for other formats)
+pub fn id3_args(file: &Path) -> Vec<String> {
+    if is_mp3(file) {
+        vec!["-id3v2_version".to_string(), "3".to_string()]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Stream tags that make FFmpeg write the cover as an APIC front cover
+///
+/// The MP3 muxer derives the APIC picture type from the stream's `comment`.
+fn cover_art_args(file: &Path) -> Vec<String> {
+    if is_mp3(file) {
+        vec![
+            "-metadata:s:v".to_string(),
+            "title=Album cover".to_string(),
+            "-metadata:s:v".to_string(),
+            "comment=Cover (front)".to_string(),
+        ]
+    } else {
+        Vec::new()
+    }
+}
+
 /// Chapter editor for managing chapter markers
 pub struct ChapterEditor;
 
 impl ChapterEditor {
+    /// Combine API and embedded chapters
+    ///
+    /// The `preferred` source supplies the chapter list. Chapters in it with a
+    /// missing or placeholder title take the title of the other source's
+    /// chapter starting at the same point. If the preferred source is empty,
+    /// the other one is used as-is.
+    ///
+    /// # Arguments
+    /// * `api` - Chapters from the license (see [`Chapter::from_api_chapters`])
+    /// * `embedded` - Chapters read from the file
+    /// * `preferred` - Which source wins
+    pub fn resolve_chapters(
+        api: &[Chapter],
+        embedded: &[Chapter],
+        preferred: ChapterSource,
+    ) -> Vec<Chapter> {
+        let (primary, secondary) = match preferred {
+            ChapterSource::Api => (api, embedded),
+            ChapterSource::Embedded => (embedded, api),
+        };
+
+        if primary.is_empty() {
+            return secondary.to_vec();
+        }
+
+        primary
+            .iter()
+            .map(|chapter| {
+                if !chapter.has_placeholder_title() {
+                    return chapter.clone();
+                }
+
+                let replacement = secondary.iter().find(|other| {
+                    !other.has_placeholder_title()
+                        && (other.start_ms - chapter.start_ms).abs() <= CHAPTER_MATCH_TOLERANCE_MS
+                });
+
+                Chapter {
+                    title: replacement.map_or_else(|| chapter.title.clone(), |o| o.title.clone()),
+                    ..chapter.clone()
+                }
+            })
+            .collect()
+    }
+
     /// Embed chapters into audio file
     ///
     /// Uses FFmpeg with ffmetadata file format
@@ -425,10 +845,11 @@ impl ChapterEditor {
         drop(file_handle);
 
         // Create temporary output file
-        let temp_file = file.with_extension("tmp.m4b");
+        let temp_file = temp_output_path(file);
 
-        // Build FFmpeg command
-        let cmd = vec![
+        // Build FFmpeg command; chapters are taken from the metadata file
+        // even if the input already has some
+        let mut cmd = vec![
             "ffmpeg".to_string(),
             "-i".to_string(),
             file.to_string_lossy().to_string(),
@@ -436,11 +857,14 @@ impl ChapterEditor {
             metadata_file.to_string_lossy().to_string(),
             "-map_metadata".to_string(),
             "1".to_string(),
+            "-map_chapters".to_string(),
+            "1".to_string(),
             "-codec".to_string(),
             "copy".to_string(),
-            "-y".to_string(),
-            temp_file.to_string_lossy().to_string(),
         ];
+        cmd.extend(id3_args(file));
+        cmd.push("-y".to_string());
+        cmd.push(temp_file.to_string_lossy().to_string());
 
         // Execute FFmpeg
         MetadataEditor::execute_ffmpeg(&cmd).await?;
@@ -459,22 +883,15 @@ impl ChapterEditor {
 
     /// Extract chapters from audio file
     pub async fn extract_chapters(file: &Path) -> Result<Vec<Chapter>> {
-        let output = Command::new("ffprobe")
-            .arg("-v")
-            .arg("quiet")
-            .arg("-print_format")
-            .arg("json")
-            .arg("-show_chapters")
-            .arg(file.as_os_str())
-            .output()
-            .await
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    LibationError::FfmpegNotFound
-                } else {
-                    LibationError::FfmpegError(format!("Failed to execute ffprobe: {}", e))
-                }
-            })?;
+        let output = process::output(Tool::Ffprobe, |cmd| {
+            cmd.arg("-v")
+                .arg("quiet")
+                .arg("-print_format")
+                .arg("json")
+                .arg("-show_chapters")
+                .arg(file.as_os_str());
+        })
+        .await?;
 
         if !output.status.success() {
             return Err(LibationError::FfmpegError("FFprobe failed".to_string()));
@@ -519,8 +936,8 @@ impl ChapterEditor {
         let mut cue = String::new();
 
         // Header
-        cue.push_str(&format!("PERFORMER \"{}\"\n", metadata.format_authors()));
-        cue.push_str(&format!("TITLE \"{}\"\n", metadata.title));
+        cue.push_str(&format!("PERFORMER \"{}\"\n", cue_escape(&metadata.format_authors())));
+        cue.push_str(&format!("TITLE \"{}\"\n", cue_escape(&metadata.title)));
 
         // Determine file type from extension
         let file_type = if audio_filename.ends_with(".mp3") {
@@ -535,7 +952,7 @@ impl ChapterEditor {
         for (idx, chapter) in chapters.iter().enumerate() {
             let track_num = idx + 1;
             cue.push_str(&format!("  TRACK {:02} AUDIO\n", track_num));
-            cue.push_str(&format!("    TITLE \"{}\"\n", chapter.title));
+            cue.push_str(&format!("    TITLE \"{}\"\n", cue_escape(&chapter.title)));
             cue.push_str(&format!(
                 "    INDEX 01 {}\n",
                 Chapter::format_cue_timestamp(chapter.start_ms)
@@ -570,10 +987,79 @@ impl ChapterEditor {
         })
     }
 
+    /// Generate a `chapters.txt` listing
+    ///
+    /// One line per chapter: start as `HH:MM:SS.mmm`, a space, then the title.
+    pub fn generate_chapters_txt(chapters: &[Chapter]) -> String {
+        chapters
+            .iter()
+            .map(|c| {
+                format!(
+                    "{} {}\n",
+                    Chapter::format_ffmpeg_timestamp(c.start_ms),
+                    c.title.replace(['\r', '\n'], " ")
+                )
+            })
+            .collect()
+    }
+
+    /// Write chapter sidecar files next to an audio file
+    ///
+    /// # Arguments
+    /// * `file` - Audio file the sidecars describe
+    /// * `metadata` - Book metadata (used for the cue sheet header)
+    /// * `chapters` - Chapters to export
+    /// * `formats` - Sidecar formats to write
+    ///
+    /// # Returns
+    /// Paths of the written sidecars, in the order of `formats`
+    ///
+    /// # Errors
+    /// - `InvalidPath` - `file` has no usable file name
+    /// - `FileIoError` - A sidecar could not be written
+    pub async fn export_chapters(
+        file: &Path,
+        metadata: &AudioMetadata,
+        chapters: &[Chapter],
+        formats: &[ChapterExportFormat],
+    ) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::with_capacity(formats.len());
+
+        for format in formats {
+            let path = format.sidecar_path(file);
+            match format {
+                ChapterExportFormat::Cue => Self::save_cue_sheet(file, metadata, chapters).await?,
+                ChapterExportFormat::ChaptersTxt => {
+                    fs::write(&path, Self::generate_chapters_txt(chapters)).await.map_err(|e| {
+                        LibationError::FileIoError(format!("write: {} - {}", path.display(), e))
+                    })?
+                }
+            }
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+
+    /// Write chapter sidecar files from a license `ChapterInfo`
+    ///
+    /// Nested chapters are flattened as in [`Chapter::from_api_chapters`].
+    ///
+    /// # Errors
+    /// Same as [`export_chapters`](Self::export_chapters)
+    pub async fn export_chapter_info(
+        file: &Path,
+        metadata: &AudioMetadata,
+        info: &crate::api::content::ChapterInfo,
+        formats: &[ChapterExportFormat],
+    ) -> Result<Vec<PathBuf>> {
+        Self::export_chapters(file, metadata, &Chapter::from_api_chapters(info), formats).await
+    }
+
     /// Generate FFmetadata format content
     ///
     /// Used by FFmpeg for chapter embedding
-    fn generate_ffmetadata(chapters: &[Chapter]) -> String {
+    pub(crate) fn generate_ffmetadata(chapters: &[Chapter]) -> String {
         let mut content = String::from(";FFMETADATA1\n");
 
         for chapter in chapters {
@@ -588,6 +1074,11 @@ impl ChapterEditor {
     }
 }
 
+/// Make a value safe inside a double-quoted cue sheet field
+fn cue_escape(value: &str) -> String {
+    value.replace('"', "'").replace(['\r', '\n'], " ")
+}
+
 /// FFprobe metadata output structures
 #[derive(Debug, Deserialize)]
 struct MetadataProbe {
@@ -660,7 +1151,7 @@ mod tests {
             narrators: vec![],
             publisher: None,
             publication_date: None,
-            language: None,
+            languages: vec![],
             series: None,
             description: None,
             genres: vec![],
@@ -679,7 +1170,7 @@ mod tests {
             narrators: vec![],
             publisher: None,
             publication_date: None,
-            language: None,
+            languages: vec![],
             series: Some(SeriesInfo {
                 name: "Test Series".to_string(),
                 position: Some("1".to_string()),
@@ -693,6 +1184,40 @@ mod tests {
         assert_eq!(metadata.format_series(), Some("Test Series #1".to_string()));
     }
 
+    #[test]
+    fn test_series_sequence_parse() {
+        assert_eq!(SeriesSequence::parse("2"), Some(SeriesSequence::Integer { value: 2 }));
+        assert_eq!(SeriesSequence::parse("2.5"), Some(SeriesSequence::Decimal { value: 2.5 }));
+        assert_eq!(SeriesSequence::parse("Book 4"), Some(SeriesSequence::Integer { value: 4 }));
+        assert_eq!(
+            SeriesSequence::parse("1-3"),
+            Some(SeriesSequence::Range { start: 1.0, end: 3.0 })
+        );
+        assert_eq!(
+            SeriesSequence::parse("3 – 1"),
+            Some(SeriesSequence::Range { start: 1.0, end: 3.0 })
+        );
+        assert_eq!(SeriesSequence::parse("5-5"), Some(SeriesSequence::Integer { value: 5 }));
+        assert_eq!(SeriesSequence::parse(""), None);
+        assert_eq!(SeriesSequence::parse("Prequel"), None);
+        assert!(SeriesSequence::parse("99999999999999999999-").is_some());
+
+        assert_eq!(SeriesSequence::parse("1-3").unwrap().to_string(), "1-3");
+        assert_eq!(SeriesSequence::parse("2.5").unwrap().to_string(), "2.5");
+    }
+
+    #[test]
+    fn test_series_sequence_ordering() {
+        let mut sequences: Vec<SeriesSequence> = ["3", "1-3", "2.5", "2", "1"]
+            .iter()
+            .filter_map(|s| SeriesSequence::parse(s))
+            .collect();
+        sequences.sort();
+
+        let ordered: Vec<String> = sequences.iter().map(|s| s.to_string()).collect();
+        assert_eq!(ordered, vec!["1", "1-3", "2", "2.5", "3"]);
+    }
+
     #[test]
     fn test_generate_ffmetadata() {
         let chapters = vec![
@@ -728,7 +1253,7 @@ mod tests {
             narrators: vec![],
             publisher: None,
             publication_date: None,
-            language: None,
+            languages: vec![],
             series: None,
             description: None,
             genres: vec![],
@@ -760,4 +1285,163 @@ mod tests {
         assert!(cue.contains("TRACK 02 AUDIO"));
         assert!(cue.contains("TITLE \"Chapter 1\""));
     }
+
+    #[tokio::test]
+    async fn test_export_chapters() {
+        let chapters = vec![
+            Chapter { title: "Opening Credits".to_string(), start_ms: 0, end_ms: 30500 },
+            Chapter { title: "The \"Shire\"".to_string(), start_ms: 30500, end_ms: 3665123 },
+            Chapter { title: "Epilogue".to_string(), start_ms: 3665123, end_ms: 3700000 },
+        ];
+        assert_eq!(
+            ChapterEditor::generate_chapters_txt(&chapters),
+            "00:00:00.000 Opening Credits\n00:00:30.500 The \"Shire\"\n01:01:05.123 Epilogue\n"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let audio = dir.path().join("Book.m4b");
+        let metadata = AudioMetadata {
+            title: "Book".to_string(),
+            authors: vec!["Jane Doe".to_string()],
+            narrators: vec![],
+            publisher: None,
+            publication_date: None,
+            languages: vec![],
+            series: None,
+            description: None,
+            genres: vec![],
+            runtime_minutes: None,
+            asin: None,
+            cover_art_url: None,
+        };
+
+        let written = ChapterEditor::export_chapters(
+            &audio,
+            &metadata,
+            &chapters,
+            &[ChapterExportFormat::Cue, ChapterExportFormat::ChaptersTxt],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(written, vec![dir.path().join("Book.cue"), dir.path().join("Book.chapters.txt")]);
+        let cue = std::fs::read_to_string(&written[0]).unwrap();
+        assert!(cue.contains("TITLE \"The 'Shire'\""));
+        assert!(cue.contains("INDEX 01 61:05:09"));
+        assert!(std::fs::read_to_string(&written[1]).unwrap().starts_with("00:00:00.000 Opening Credits"));
+    }
+
+    #[test]
+    fn test_resolve_chapters() {
+        let chapter = |title: &str, start_ms, end_ms| Chapter {
+            title: title.to_string(),
+            start_ms,
+            end_ms,
+        };
+        let api = vec![
+            chapter("Opening Credits", 0, 30_000),
+            chapter("", 30_000, 600_000),
+            chapter("The Return", 600_000, 1_200_000),
+        ];
+        let embedded = vec![
+            chapter("Chapter 1", 0, 30_500),
+            chapter("Part One: The Departure", 30_500, 600_400),
+            chapter("Chapter 3", 600_400, 1_200_000),
+        ];
+
+        let resolved = ChapterEditor::resolve_chapters(&api, &embedded, ChapterSource::Api);
+        let titles: Vec<&str> = resolved.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["Opening Credits", "Part One: The Departure", "The Return"]);
+        assert_eq!(resolved[1].start_ms, 30_000);
+
+        let resolved = ChapterEditor::resolve_chapters(&api, &embedded, ChapterSource::Embedded);
+        let titles: Vec<&str> = resolved.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["Opening Credits", "Part One: The Departure", "The Return"]);
+        assert_eq!(resolved[1].start_ms, 30_500);
+
+        // Empty preferred source falls back to the other one
+        let resolved = ChapterEditor::resolve_chapters(&[], &embedded, ChapterSource::Api);
+        assert_eq!(resolved.len(), 3);
+        assert_eq!(resolved[0].title, "Chapter 1");
+    }
+
+    #[test]
+    fn test_metadata_args() {
+        let metadata = AudioMetadata {
+            title: "The Way of Kings".to_string(),
+            authors: vec!["Brandon Sanderson".to_string()],
+            narrators: vec!["Michael Kramer".to_string(), "Kate Reading".to_string()],
+            publisher: Some("Macmillan Audio".to_string()),
+            publication_date: Some("2010-08-31".to_string()),
+            languages: vec![],
+            series: Some(SeriesInfo {
+                name: "The Stormlight Archive".to_string(),
+                position: Some("1".to_string()),
+            }),
+            description: Some("Roshar is a world of stone and storms.".to_string()),
+            genres: vec![],
+            runtime_minutes: None,
+            asin: None,
+            cover_art_url: None,
+        };
+
+        let args = MetadataEditor::metadata_args(&metadata);
+        let tags: Vec<&str> = args.iter().skip(1).step_by(2).map(String::as_str).collect();
+
+        assert!(args.iter().step_by(2).all(|a| a == "-metadata"));
+        assert!(tags.contains(&"title=The Way of Kings"));
+        assert!(tags.contains(&"artist=Brandon Sanderson"));
+        assert!(tags.contains(&"composer=Michael Kramer, Kate Reading"));
+        assert!(tags.contains(&"album=The Stormlight Archive"));
+        assert!(tags.contains(&"track=1"));
+        assert!(tags.contains(&"date=2010"));
+        assert!(tags.contains(&"comment=Roshar is a world of stone and storms."));
+    }
+
+    #[test]
+    fn test_write_tags_command_mp3() {
+        let metadata = AudioMetadata {
+            title: "Book".to_string(),
+            authors: vec!["Author".to_string()],
+            narrators: vec![],
+            publisher: None,
+            publication_date: None,
+            languages: vec![],
+            series: None,
+            description: None,
+            genres: vec![],
+            runtime_minutes: None,
+            asin: None,
+            cover_art_url: None,
+        };
+        let has = |cmd: &[String], pair: [&str; 2]| cmd.windows(2).any(|w| w == pair);
+
+        let cmd = MetadataEditor::write_tags_command(
+            Path::new("book.mp3"),
+            Path::new("book.tmp.mp3"),
+            &metadata,
+            Some(Path::new("cover.jpg")),
+            Some(Path::new("book.ffmetadata.txt")),
+        );
+        assert!(has(&cmd, ["-i", "book.ffmetadata.txt"]));
+        assert!(has(&cmd, ["-map", "2:v"]));
+        assert!(has(&cmd, ["-map_chapters", "1"]));
+        assert!(has(&cmd, ["-metadata:s:v", "comment=Cover (front)"]));
+        assert!(has(&cmd, ["-id3v2_version", "3"]));
+        assert!(has(&cmd, ["-metadata", "album=Book"]));
+        assert_eq!(cmd.last().unwrap(), "book.tmp.mp3");
+
+        // M4B without a new cover keeps the existing one and gets no ID3 flags
+        let cmd = MetadataEditor::write_tags_command(
+            Path::new("book.m4b"),
+            Path::new("book.tmp.m4b"),
+            &metadata,
+            None,
+            None,
+        );
+        assert!(has(&cmd, ["-map", "0:v?"]));
+        assert!(!cmd.iter().any(|a| a == "-id3v2_version" || a == "-map_chapters"));
+
+        assert_eq!(temp_output_path(Path::new("/x/book.mp3")), Path::new("/x/book.tmp.mp3"));
+    }
 }
@@ -37,6 +37,7 @@
 //! - `AudioFormat` - Supported formats (AAX, AAXC, M4B, MP3, M4A)
 //! - `AudioDecoder` - Format detection from files or bytes
 //! - `AudioInfo` - Detailed file information (codec, bitrate, duration, etc.)
+//! - `AudiobookFile` - Finished audiobook (path, size, duration, chapters, cover)
 //! - `Codec` - Audio codec types (AAC-LC, MP3, E-AC-3, AC-4)
 //!
 //! ## converter
@@ -44,6 +45,7 @@
 //! - `AudioConverter` - Main conversion engine
 //! - `ConversionOptions` - Conversion settings (format, quality, chapters)
 //! - `Bitrate` - VBR or CBR encoding options
+//! - `BrandTrim` - Audible intro/outro removal, with silence-based intro detection
 //! - Progress tracking support
 //! - Chapter-based splitting
 //!
@@ -53,7 +55,15 @@
 //! - `MetadataEditor` - Embed/extract metadata and cover art
 //! - `Chapter` - Chapter marker structure
 //! - `ChapterEditor` - Embed/extract chapters, generate cue sheets
+//! - `ChapterSource` - Prefer license or embedded chapter titles
 //! - `SeriesInfo` - Series information
+//! - `SeriesSequence` - Parsed position in a series ("2", "2.5", "1-3")
+//!
+//! ## process
+//! FFmpeg/ffprobe invocation:
+//! - `Tool` - Which program to run, with its configurable `ProcessLimits`
+//! - `output` - Run to completion under a timeout, retrying hung runs
+//! - `stream_stderr` - Stream progress lines, killing a stalled process
 //!
 //! # FFmpeg Integration
 //!
@@ -69,11 +79,18 @@
 //! ## Minimum Version
 //! FFmpeg 4.0 or higher is recommended for full feature support.
 
+pub mod concat;
 pub mod converter;
 pub mod decoder;
 pub mod metadata;
+pub mod process;
 
 // Re-export commonly used types for convenience
-pub use converter::{AudioConverter, Bitrate, ConversionOptions, ProgressCallback};
-pub use decoder::{AudioDecoder, AudioFormat, AudioInfo, Codec};
-pub use metadata::{AudioMetadata, Chapter, ChapterEditor, MetadataEditor, SeriesInfo};
+pub use concat::{concat_parts, concat_parts_with, ConcatOptions};
+pub use converter::{AudioConverter, Bitrate, BrandTrim, ConversionOptions, ProgressCallback};
+pub use decoder::{AudioDecoder, AudioFormat, AudioInfo, AudiobookFile, Codec};
+pub use process::{ProcessLimits, Tool};
+pub use metadata::{
+    AudioMetadata, Chapter, ChapterEditor, ChapterExportFormat, ChapterSource, MetadataEditor,
+    SeriesInfo, SeriesSequence,
+};
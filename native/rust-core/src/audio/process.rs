@@ -0,0 +1,379 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Running FFmpeg and ffprobe with timeouts and retries
+//!
+//! # Reference C# Sources
+//! - **`AAXClean.Codecs/FfmpegProcess.cs`** - Starts FFmpeg and waits for it
+//!   to exit
+//!
+//! A corrupt file or a stuck filesystem can leave FFmpeg waiting forever,
+//! which blocks the whole download/convert pipeline. Every invocation goes
+//! through this module so that it runs under the [`ProcessLimits`] of its
+//! [`Tool`]:
+//!
+//! - [`output`] waits for the process with a wall-clock timeout. A run that
+//!   times out or is killed by a signal is retried after [`PROCESS_BACKOFF`];
+//!   once the retries are used up the error is `DecodeFailed`.
+//! - [`stream_stderr`] is for long runs that report progress on stderr. The
+//!   timeout applies to silence on stderr rather than to the whole run, and
+//!   a stalled process is killed and reported as `DecodeFailed` without
+//!   being restarted.
+//!
+//! A process that exits normally with a non-zero status is not retried: its
+//! output is handed back so callers can report FFmpeg's own error message.
+//!
+//! A program missing from PATH is always reported as `FfmpegNotFound`, never
+//! as a raw I/O error. [`check_installed`] finds out up front, before a long
+//! download whose decrypt step would fail.
+
+use crate::backoff::PROCESS_BACKOFF;
+use crate::error::{LibationError, Result};
+use lazy_static::lazy_static;
+use std::process::{ExitStatus, Output, Stdio};
+use std::sync::RwLock;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+/// Default limits for ffprobe, which only reads headers
+pub const DEFAULT_FFPROBE_LIMITS: ProcessLimits = ProcessLimits {
+    timeout: Duration::from_secs(60),
+    retries: 2,
+};
+
+/// Default limits for FFmpeg
+///
+/// The timeout is generous because [`output`] runs such as cover embedding
+/// remux the whole file. For [`stream_stderr`] it is the longest allowed
+/// silence.
+pub const DEFAULT_FFMPEG_LIMITS: ProcessLimits = ProcessLimits {
+    timeout: Duration::from_secs(10 * 60),
+    retries: 1,
+};
+
+lazy_static! {
+    static ref LIMITS: RwLock<[ProcessLimits; 2]> =
+        RwLock::new([DEFAULT_FFMPEG_LIMITS, DEFAULT_FFPROBE_LIMITS]);
+}
+
+/// Timeout and retry budget for one tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessLimits {
+    /// Longest a single run may take (or stay silent, when streaming)
+    pub timeout: Duration,
+    /// Extra attempts after a run that timed out or was killed
+    pub retries: u32,
+}
+
+/// External program run by the audio pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    Ffmpeg,
+    Ffprobe,
+}
+
+impl Tool {
+    /// Executable name looked up in PATH
+    pub fn program(self) -> &'static str {
+        match self {
+            Tool::Ffmpeg => "ffmpeg",
+            Tool::Ffprobe => "ffprobe",
+        }
+    }
+
+    /// Limits currently applied to this tool
+    pub fn limits(self) -> ProcessLimits {
+        LIMITS.read().map(|l| l[self as usize]).unwrap_or(match self {
+            Tool::Ffmpeg => DEFAULT_FFMPEG_LIMITS,
+            Tool::Ffprobe => DEFAULT_FFPROBE_LIMITS,
+        })
+    }
+
+    /// Change the limits for every later run of this tool
+    pub fn set_limits(self, limits: ProcessLimits) {
+        if let Ok(mut all) = LIMITS.write() {
+            all[self as usize] = limits;
+        }
+    }
+}
+
+/// Run `tool` to completion and collect its output
+///
+/// `configure` adds the arguments. It is called again for every attempt, so
+/// it must not depend on state consumed by an earlier run.
+///
+/// # Returns
+/// The output of the first run that exited on its own, whatever its status
+///
+/// # Errors
+/// - `FfmpegNotFound` - The program is not installed
+/// - `FfmpegError` - The process could not be started
+/// - `DecodeFailed` - Every attempt timed out or was killed
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example() -> rust_core::error::Result<()> {
+/// use rust_core::audio::process::{self, Tool};
+///
+/// let output = process::output(Tool::Ffprobe, |cmd| {
+///     cmd.args(["-v", "quiet", "-show_format", "book.m4b"]);
+/// })
+/// .await?;
+/// println!("{}", String::from_utf8_lossy(&output.stdout));
+/// # Ok(())
+/// # }
+/// ```
+pub async fn output(tool: Tool, configure: impl Fn(&mut Command)) -> Result<Output> {
+    run_with_limits(tool.program(), tool.limits(), configure).await
+}
+
+async fn run_with_limits(
+    program: &str,
+    limits: ProcessLimits,
+    configure: impl Fn(&mut Command),
+) -> Result<Output> {
+    let mut attempt = 0;
+    loop {
+        let mut cmd = Command::new(program);
+        configure(&mut cmd);
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let child = cmd.spawn().map_err(|e| spawn_error(program, e))?;
+
+        // Dropping the future on timeout drops the child, which kills it
+        let failure = match tokio::time::timeout(limits.timeout, child.wait_with_output()).await {
+            Ok(Ok(output)) if output.status.code().is_some() => return Ok(output),
+            Ok(Ok(output)) => format!("{} was killed ({})", program, output.status),
+            Ok(Err(e)) => format!("{} failed: {}", program, e),
+            Err(_) => format!("{} timed out after {}s", program, limits.timeout.as_secs()),
+        };
+
+        if attempt >= limits.retries {
+            return Err(LibationError::DecodeFailed(format!(
+                "{} (gave up after {} attempts)",
+                failure,
+                attempt + 1
+            )));
+        }
+        eprintln!("Warning: {}, retrying", failure);
+        PROCESS_BACKOFF.wait(attempt).await;
+        attempt += 1;
+    }
+}
+
+/// Check that `tool` can be started
+///
+/// Runs `<tool> -version`, which returns at once on a working install.
+///
+/// # Errors
+/// - `FfmpegNotFound` - The program is not on PATH
+/// - `FfmpegError` - The program exists but could not be run or failed
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example() -> rust_core::error::Result<()> {
+/// use rust_core::audio::process::{self, Tool};
+///
+/// process::check_installed(Tool::Ffmpeg).await?;
+/// process::check_installed(Tool::Ffprobe).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn check_installed(tool: Tool) -> Result<()> {
+    let output = output(tool, |cmd| {
+        cmd.arg("-version");
+    })
+    .await?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(LibationError::FfmpegError(format!(
+            "{} -version exited with {}; the installation looks broken",
+            tool.program(),
+            output.status
+        )))
+    }
+}
+
+/// Run `tool` while handing each stderr line to `on_line`
+///
+/// Lines end at `\n` or `\r`, since FFmpeg rewrites its progress line in
+/// place. The tool's timeout is the longest gap allowed between two reads
+/// of stderr, so a long conversion is fine as long as it keeps reporting.
+///
+/// # Returns
+/// The exit status of the process
+///
+/// # Errors
+/// - `FfmpegNotFound` - The program is not installed
+/// - `FfmpegError` - The process could not be started or its stderr read
+/// - `DecodeFailed` - The process went silent for longer than the timeout
+///   (it is killed) or was killed by a signal
+pub async fn stream_stderr(
+    tool: Tool,
+    configure: impl FnOnce(&mut Command),
+    on_line: impl FnMut(&str),
+) -> Result<ExitStatus> {
+    let mut cmd = Command::new(tool.program());
+    configure(&mut cmd);
+    stream_with_limits(cmd, tool.limits(), on_line).await
+}
+
+/// Like [`stream_stderr`], for a command that was already built
+///
+/// The command's program should be `tool`'s; only its limits are used.
+pub async fn stream_command(
+    tool: Tool,
+    cmd: Command,
+    on_line: impl FnMut(&str),
+) -> Result<ExitStatus> {
+    stream_with_limits(cmd, tool.limits(), on_line).await
+}
+
+async fn stream_with_limits(
+    mut cmd: Command,
+    limits: ProcessLimits,
+    mut on_line: impl FnMut(&str),
+) -> Result<ExitStatus> {
+    let program = cmd.as_std().get_program().to_string_lossy().into_owned();
+    let program = program.as_str();
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = cmd.spawn().map_err(|e| spawn_error(program, e))?;
+    let mut stderr = child.stderr.take().ok_or_else(|| {
+        LibationError::FfmpegError(format!("Failed to capture {} stderr", program))
+    })?;
+
+    let mut pending = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let read = match tokio::time::timeout(limits.timeout, stderr.read(&mut buf)).await {
+            Ok(read) => read.map_err(|e| {
+                LibationError::FfmpegError(format!("Failed to read {} output: {}", program, e))
+            })?,
+            Err(_) => {
+                let _ = child.kill().await;
+                return Err(LibationError::DecodeFailed(format!(
+                    "{} produced no output for {}s and was killed",
+                    program,
+                    limits.timeout.as_secs()
+                )));
+            }
+        };
+        if read == 0 {
+            break;
+        }
+
+        pending.extend_from_slice(&buf[..read]);
+        while let Some(end) = pending.iter().position(|&b| b == b'\n' || b == b'\r') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line[..end]);
+            if !line.is_empty() {
+                on_line(&line);
+            }
+        }
+    }
+    if !pending.is_empty() {
+        on_line(&String::from_utf8_lossy(&pending));
+    }
+
+    // stderr is closed, so the process is exiting
+    let status = child.wait().await.map_err(|e| {
+        LibationError::FfmpegError(format!("Failed to wait for {}: {}", program, e))
+    })?;
+    if status.code().is_none() {
+        return Err(LibationError::DecodeFailed(format!("{} was killed ({})", program, status)));
+    }
+    Ok(status)
+}
+
+fn spawn_error(program: &str, e: std::io::Error) -> LibationError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        LibationError::FfmpegNotFound
+    } else {
+        LibationError::FfmpegError(format!("Failed to execute {}: {}", program, e))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    const SHORT: ProcessLimits = ProcessLimits {
+        timeout: Duration::from_millis(200),
+        retries: 0,
+    };
+
+    #[tokio::test]
+    async fn test_hung_process_is_killed() {
+        let started = std::time::Instant::now();
+        let result = run_with_limits("sleep", SHORT, |cmd| {
+            cmd.arg("30");
+        })
+        .await;
+
+        assert!(matches!(result, Err(LibationError::DecodeFailed(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_missing_program_is_ffmpeg_not_found() {
+        let result = run_with_limits("librisync-no-such-ffmpeg", SHORT, |_| {}).await;
+        assert!(matches!(result, Err(LibationError::FfmpegNotFound)));
+
+        let cmd = Command::new("librisync-no-such-ffmpeg");
+        let result = stream_with_limits(cmd, SHORT, |_| {}).await;
+        assert!(matches!(result, Err(LibationError::FfmpegNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_exit_status_is_returned_without_retry() {
+        let output = run_with_limits("sh", SHORT, |cmd| {
+            cmd.args(["-c", "echo probe; exit 3"]);
+        })
+        .await
+        .unwrap();
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(output.stdout, b"probe\n");
+    }
+
+    #[tokio::test]
+    async fn test_stream_splits_progress_lines_and_detects_stalls() {
+        let mut lines = Vec::new();
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "printf 'Duration: 1\\ntime=0\\rtime=1\\n' >&2"]);
+        let status = stream_with_limits(cmd, SHORT, |line| lines.push(line.to_string()))
+            .await
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(lines, ["Duration: 1", "time=0", "time=1"]);
+
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo start >&2; sleep 30"]);
+        let stalled = stream_with_limits(cmd, SHORT, |_| {}).await;
+        assert!(matches!(stalled, Err(LibationError::DecodeFailed(_))));
+    }
+}
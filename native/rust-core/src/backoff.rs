@@ -0,0 +1,166 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Retry delays with exponential backoff and jitter
+//!
+//! # Reference C# Sources
+//! - `AudibleUtilities/ApiExtended.cs:70-73` - Polly retry policy with
+//!   exponential waits for API calls
+//! - `AaxDecrypter/NetworkFileStream.cs` - Reconnects after a dropped download
+//!
+//! Every retry loop in the crate takes its delays from a [`Backoff`]. The
+//! policies for each kind of operation are the constants below, so tuning
+//! retry timing means changing one value here.
+//!
+//! Jitter spreads retries out so that parallel downloads or requests that
+//! failed together do not all retry at the same instant.
+
+use rand::Rng;
+use std::time::Duration;
+
+/// Exponential backoff policy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    /// Delay before the first retry
+    pub initial: Duration,
+    /// Upper bound on any delay
+    pub max: Duration,
+    /// Growth factor per attempt (1.0 gives a constant delay)
+    pub multiplier: f64,
+    /// Fraction of the delay that may be randomly removed (0.0 to 1.0)
+    pub jitter: f64,
+}
+
+/// API requests (5xx responses and dropped connections): 1s, 2s, 4s...
+pub const API_BACKOFF: Backoff = Backoff {
+    initial: Duration::from_secs(1),
+    max: Duration::from_secs(30),
+    multiplier: 2.0,
+    jitter: 0.25,
+};
+
+/// Reconnecting an interrupted download: 2s, 4s, 8s... up to 32s
+pub const DOWNLOAD_BACKOFF: Backoff = Backoff {
+    initial: Duration::from_secs(2),
+    max: Duration::from_secs(32),
+    multiplier: 2.0,
+    jitter: 0.25,
+};
+
+/// Local file operations blocked by another process: a short constant wait
+pub const FILE_BACKOFF: Backoff = Backoff {
+    initial: Duration::from_millis(100),
+    max: Duration::from_millis(100),
+    multiplier: 1.0,
+    jitter: 0.0,
+};
+
+/// Re-running FFmpeg or ffprobe after a timeout or crash: 1s, 2s, 4s...
+pub const PROCESS_BACKOFF: Backoff = Backoff {
+    initial: Duration::from_secs(1),
+    max: Duration::from_secs(8),
+    multiplier: 2.0,
+    jitter: 0.0,
+};
+
+impl Backoff {
+    /// Exponential backoff doubling from `initial` up to `max`, without jitter
+    pub const fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            multiplier: 2.0,
+            jitter: 0.0,
+        }
+    }
+
+    /// Set the jitter fraction (clamped to 0.0..=1.0)
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the growth factor per attempt (at least 1.0)
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Delay before retry number `attempt` without jitter
+    ///
+    /// `attempt` counts from 0 for the first retry. The result never exceeds
+    /// `max`, however large `attempt` gets.
+    pub fn base_delay(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.max(1.0).powi(attempt.min(i32::MAX as u32) as i32);
+        let secs = self.initial.as_secs_f64() * factor;
+        if !secs.is_finite() || secs >= self.max.as_secs_f64() {
+            self.max
+        } else {
+            Duration::from_secs_f64(secs)
+        }
+    }
+
+    /// Delay before retry number `attempt`, with random jitter applied
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.delay_with(attempt, rand::thread_rng().gen::<f64>())
+    }
+
+    /// Delay with jitter drawn from `sample` in `0.0..1.0`
+    ///
+    /// `sample` 0.0 gives the full base delay and values towards 1.0 remove
+    /// up to `jitter` of it.
+    pub fn delay_with(&self, attempt: u32, sample: f64) -> Duration {
+        let base = self.base_delay(attempt);
+        let cut = self.jitter.clamp(0.0, 1.0) * sample.clamp(0.0, 1.0);
+        base.mul_f64(1.0 - cut)
+    }
+
+    /// Sleep for the delay before retry number `attempt`
+    pub async fn wait(&self, attempt: u32) {
+        tokio::time::sleep(self.delay(attempt)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_delay_grows_and_caps() {
+        let backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(10));
+        let delays: Vec<u64> = (0..6).map(|a| backoff.base_delay(a).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 10, 10]);
+        assert_eq!(backoff.base_delay(u32::MAX), Duration::from_secs(10));
+
+        assert_eq!(FILE_BACKOFF.base_delay(5), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let backoff = Backoff::new(Duration::from_secs(4), Duration::from_secs(60)).with_jitter(0.5);
+        assert_eq!(backoff.delay_with(0, 0.0), Duration::from_secs(4));
+        assert_eq!(backoff.delay_with(0, 1.0), Duration::from_secs(2));
+
+        for _ in 0..100 {
+            let delay = backoff.delay(1);
+            assert!(delay >= Duration::from_secs(4) && delay <= Duration::from_secs(8));
+        }
+    }
+}
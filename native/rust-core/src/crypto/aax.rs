@@ -53,11 +53,17 @@
 //!   5. Write decrypted MP4
 //! - FFmpeg approach is simpler and battle-tested
 
+use crate::audio::decoder::AudiobookFile;
+use crate::audio::process::{self, Tool};
+use crate::api::content::ChapterInfo;
+use crate::api::license::OutputFormat;
+use crate::audio::metadata::{AudioMetadata, Chapter, ChapterEditor, ChapterSource, MetadataEditor, temp_output_path};
 use crate::crypto::activation::{ActivationBytes, format_activation_bytes};
+use crate::crypto::verify::verify_decrypted;
 use crate::error::{LibationError, Result};
+use crate::file::{post_write, FileManager};
 use std::path::{Path, PathBuf};
-use std::process::Stdio;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 
 /// AAX file decrypter using FFmpeg
@@ -65,6 +71,10 @@ use tokio::process::Command;
 /// # C# Reference
 /// Similar functionality to FileLiberator/AudioDecodable.cs
 ///
+/// # Memory
+/// FFmpeg streams the file from disk to disk; this process only reads its
+/// progress output line by line, so memory use does not grow with file size.
+///
 /// # Example
 /// ```no_run
 /// use rust_core::crypto::aax::AaxDecrypter;
@@ -85,6 +95,14 @@ use tokio::process::Command;
 #[derive(Debug, Clone)]
 pub struct AaxDecrypter {
     activation_bytes: ActivationBytes,
+    /// Delete the encrypted source after `decrypt_audiobook` succeeds
+    delete_encrypted: bool,
+    /// Where kept sources are moved (left in place when `None`)
+    encrypted_backup_dir: Option<PathBuf>,
+    /// Chapters from the license, written over the embedded ones
+    api_chapters: Option<ChapterInfo>,
+    /// Which chapter source wins when both exist
+    chapter_source: ChapterSource,
 }
 
 impl AaxDecrypter {
@@ -93,7 +111,45 @@ impl AaxDecrypter {
     /// # Arguments
     /// * `activation_bytes` - The 4-byte activation key
     pub fn new(activation_bytes: ActivationBytes) -> Self {
-        Self { activation_bytes }
+        Self {
+            activation_bytes,
+            delete_encrypted: false,
+            encrypted_backup_dir: None,
+            api_chapters: None,
+            chapter_source: ChapterSource::default(),
+        }
+    }
+
+    /// Write chapters from the license into the decrypted file
+    ///
+    /// Without this, `decrypt_audiobook` keeps whatever chapters the AAX has
+    /// embedded. See [`ChapterEditor::resolve_chapters`] for how the two
+    /// sources are merged.
+    ///
+    /// # Arguments
+    /// * `chapter_info` - `ChapterInfo` from the download license
+    /// * `source` - Which source to prefer when both have chapters
+    pub fn with_chapters(mut self, chapter_info: ChapterInfo, source: ChapterSource) -> Self {
+        self.api_chapters = Some(chapter_info);
+        self.chapter_source = source;
+        self
+    }
+
+    /// Delete the encrypted source file once `decrypt_audiobook` succeeds
+    ///
+    /// Off by default, so the `.aax` is only removed when the caller opts in.
+    pub fn delete_encrypted_after(mut self, delete: bool) -> Self {
+        self.delete_encrypted = delete;
+        self
+    }
+
+    /// Move the kept encrypted source into `backup_dir` after decryption
+    ///
+    /// Without this, kept files stay where they were downloaded. Ignored when
+    /// [`delete_encrypted_after`](Self::delete_encrypted_after) is set.
+    pub fn with_encrypted_backup_dir(mut self, backup_dir: PathBuf) -> Self {
+        self.encrypted_backup_dir = Some(backup_dir);
+        self
     }
 
     /// Decrypt an AAX file to M4B format using FFmpeg
@@ -110,6 +166,7 @@ impl AaxDecrypter {
     /// - InvalidActivationBytes if the activation bytes are incorrect
     /// - FileNotFound if the input file doesn't exist
     /// - FfmpegError for other FFmpeg errors
+    /// - DecodeFailed if FFmpeg stalls and is killed
     ///
     /// # FFmpeg Command
     /// ```bash
@@ -160,7 +217,7 @@ impl AaxDecrypter {
         F: Fn(f32) + Send + 'static,
     {
         // Check if FFmpeg is available
-        check_ffmpeg_available().await?;
+        process::check_installed(Tool::Ffmpeg).await?;
 
         // Validate input file exists
         if !input.exists() {
@@ -169,39 +226,110 @@ impl AaxDecrypter {
 
         // Build FFmpeg command
         let activation_hex = self.activation_bytes.to_hex();
-        let mut cmd = build_ffmpeg_command(input, output, &activation_hex)?;
+        let cmd = build_ffmpeg_command(input, output, &activation_hex)?;
 
         // Execute FFmpeg with progress tracking
-        execute_ffmpeg(&mut cmd, progress_callback).await
+        execute_ffmpeg(cmd, progress_callback).await
     }
 
-    /// Get the activation bytes as a hex string
-    pub fn activation_bytes_hex(&self) -> String {
-        self.activation_bytes.to_hex()
+    /// Decrypt an AAX file and describe the result
+    ///
+    /// Runs [`decrypt_file`](Self::decrypt_file), writes license chapters when
+    /// set with [`with_chapters`](Self::with_chapters) and the book's tags when
+    /// `metadata` is given, then probes the output so the caller gets
+    /// everything the player needs in one value.
+    ///
+    /// The encrypted input is kept (or moved, see
+    /// [`with_encrypted_backup_dir`](Self::with_encrypted_backup_dir)) unless
+    /// [`delete_encrypted_after`](Self::delete_encrypted_after) was set, and
+    /// the output is handed to [`post_write::notify_file_written`].
+    ///
+    /// # Arguments
+    /// * `input` - Path to the input AAX file
+    /// * `output` - Path to the output M4B file
+    /// * `metadata` - Book metadata to tag the output with (see
+    ///   [`MetadataEditor::metadata_args`])
+    /// * `cover_path` - Cover image saved for the book, if any
+    ///
+    /// # Returns
+    /// The decrypted [`AudiobookFile`] with format, size, duration and chapters
+    ///
+    /// # Errors
+    /// Same as `decrypt_file`, plus FFprobe errors while inspecting the output
+    /// - `DecryptionFailed` - The output is still encrypted (see
+    ///   [`verify_decrypted`]); it is deleted and the input is kept
+    ///
+    /// On any error an existing file at `output` is left untouched.
+    pub async fn decrypt_audiobook(
+        &self,
+        input: &Path,
+        output: &Path,
+        metadata: Option<&AudioMetadata>,
+        cover_path: Option<PathBuf>,
+    ) -> Result<AudiobookFile> {
+        // Finish the book beside the output and replace it only once it is
+        // complete, so an earlier copy survives a failed decrypt
+        let staging = temp_output_path(output);
+        if let Err(e) = self.decrypt_staged(input, &staging, metadata).await {
+            let _ = tokio::fs::remove_file(&staging).await;
+            return Err(e);
+        }
+        tokio::fs::rename(&staging, output).await?;
+        let audiobook = AudiobookFile::from_file(output, cover_path).await?;
+
+        self.dispose_encrypted(input).await?;
+        post_write::notify_file_written(output).await;
+        Ok(audiobook)
     }
-}
 
-/// Check if FFmpeg is available on the system
-///
-/// # Errors
-/// - FfmpegNotFound if FFmpeg is not in PATH
-async fn check_ffmpeg_available() -> Result<()> {
-    match Command::new("ffmpeg")
-        .arg("-version")
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .status()
-        .await
-    {
-        Ok(status) if status.success() => Ok(()),
-        Ok(_) => Err(LibationError::FfmpegNotFound),
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            Err(LibationError::FfmpegNotFound)
+    /// Decrypt, verify, and write chapters and tags into `staging`
+    async fn decrypt_staged(&self, input: &Path, staging: &Path, metadata: Option<&AudioMetadata>) -> Result<()> {
+        self.decrypt_file(input, staging).await?;
+        verify_decrypted(staging, OutputFormat::M4b).await?;
+
+        // Chapters first: embedding them replaces the file's global tags
+        if let Some(info) = &self.api_chapters {
+            let embedded = ChapterEditor::extract_chapters(staging).await.unwrap_or_default();
+            let chapters = ChapterEditor::resolve_chapters(
+                &Chapter::from_api_chapters(info),
+                &embedded,
+                self.chapter_source,
+            );
+            if !chapters.is_empty() {
+                ChapterEditor::embed_chapters(staging, &chapters).await?;
+            }
         }
-        Err(e) => Err(LibationError::FfmpegError(format!(
-            "Failed to check FFmpeg availability: {}",
-            e
-        ))),
+
+        if let Some(metadata) = metadata {
+            MetadataEditor::embed_metadata(staging, metadata).await?;
+        }
+        Ok(())
+    }
+
+    /// Delete or keep the encrypted source after a successful decrypt
+    ///
+    /// # Returns
+    /// Where the encrypted file now lives, or `None` if it was deleted
+    async fn dispose_encrypted(&self, input: &Path) -> Result<Option<PathBuf>> {
+        if self.delete_encrypted {
+            let dir = input.parent().map(Path::to_path_buf).unwrap_or_default();
+            FileManager::new(dir).safe_delete(input).await?;
+            return Ok(None);
+        }
+
+        match (&self.encrypted_backup_dir, input.file_name()) {
+            (Some(dir), Some(name)) => {
+                let destination = dir.join(name);
+                FileManager::new(dir.clone()).safe_move(input, &destination).await?;
+                Ok(Some(destination))
+            }
+            _ => Ok(Some(input.to_path_buf())),
+        }
+    }
+
+    /// Get the activation bytes as a hex string
+    pub fn activation_bytes_hex(&self) -> String {
+        self.activation_bytes.to_hex()
     }
 }
 
@@ -239,10 +367,7 @@ fn build_ffmpeg_command(
         .arg("-c:a")
         .arg("copy")
         // Output file
-        .arg(output)
-        // Capture stderr for progress parsing
-        .stderr(Stdio::piped())
-        .stdout(Stdio::null());
+        .arg(output);
 
     Ok(cmd)
 }
@@ -258,6 +383,7 @@ fn build_ffmpeg_command(
 ///
 /// # Errors
 /// - FfmpegError if FFmpeg fails
+/// - DecodeFailed if FFmpeg stops reporting progress and is killed
 /// - InvalidActivationBytes if activation bytes are wrong
 ///
 /// # FFmpeg Progress Format
@@ -265,53 +391,37 @@ fn build_ffmpeg_command(
 /// ```text
 /// frame=  123 fps= 45 q=-1.0 size=   12345kB time=00:12:34.56 bitrate= 123.4kbits/s speed=45.6x
 /// ```
-async fn execute_ffmpeg<F>(cmd: &mut Command, progress_callback: F) -> Result<()>
+async fn execute_ffmpeg<F>(cmd: Command, progress_callback: F) -> Result<()>
 where
     F: Fn(f32) + Send + 'static,
 {
-    let mut child = cmd.spawn().map_err(|e| {
-        LibationError::FfmpegError(format!("Failed to spawn FFmpeg process: {}", e))
-    })?;
-
-    // Capture stderr for progress parsing
-    let stderr = child.stderr.take().ok_or_else(|| {
-        LibationError::FfmpegError("Failed to capture FFmpeg stderr".to_string())
-    })?;
-
-    let mut reader = BufReader::new(stderr).lines();
     let mut error_output = String::new();
     let mut duration_seconds: Option<f32> = None;
 
-    // Read FFmpeg output line by line
-    while let Some(line) = reader.next_line().await.map_err(|e| {
-        LibationError::FfmpegError(format!("Failed to read FFmpeg output: {}", e))
-    })? {
+    // Read FFmpeg output line by line; a stalled FFmpeg is killed
+    let status = process::stream_command(Tool::Ffmpeg, cmd, |line| {
         // Accumulate error output for debugging
-        error_output.push_str(&line);
+        error_output.push_str(line);
         error_output.push('\n');
 
         // Parse duration from FFmpeg output (appears early in output)
         // Format: "Duration: 01:23:45.67, start: 0.000000, bitrate: 64 kb/s"
         if duration_seconds.is_none() {
-            if let Some(duration) = parse_duration_from_line(&line) {
+            if let Some(duration) = parse_duration_from_line(line) {
                 duration_seconds = Some(duration);
             }
         }
 
         // Parse progress from FFmpeg output
         // Format: "time=00:12:34.56"
-        if let Some(elapsed) = parse_time_from_line(&line) {
+        if let Some(elapsed) = parse_time_from_line(line) {
             if let Some(total) = duration_seconds {
                 let progress = (elapsed / total).min(1.0).max(0.0);
                 progress_callback(progress);
             }
         }
-    }
-
-    // Wait for FFmpeg to complete
-    let status = child.wait().await.map_err(|e| {
-        LibationError::FfmpegError(format!("Failed to wait for FFmpeg process: {}", e))
-    })?;
+    })
+    .await?;
 
     // Check exit status
     if !status.success() {
@@ -443,12 +553,10 @@ pub async fn verify_activation_bytes(file: &Path, activation_bytes: &ActivationB
         .arg("-vn")
         .arg("-c:a")
         .arg("copy")
-        .arg(&temp_output)
-        .stderr(Stdio::piped())
-        .stdout(Stdio::null());
+        .arg(&temp_output);
 
     // Execute FFmpeg
-    let result = execute_ffmpeg(&mut cmd, |_| {}).await;
+    let result = execute_ffmpeg(cmd, |_| {}).await;
 
     // Clean up temporary file
     let _ = tokio::fs::remove_file(&temp_output).await;
@@ -480,14 +588,16 @@ pub async fn is_aax_file(path: &Path) -> Result<bool> {
         if ext.eq_ignore_ascii_case("aax") {
             // Check if file exists and is readable
             if path.exists() {
-                // Read first 8 bytes to check for MP4 signature
-                match tokio::fs::read(path).await {
-                    Ok(data) if data.len() >= 8 => {
-                        // MP4 files typically start with ftyp box
-                        // Bytes 4-7 should be "ftyp"
-                        Ok(data.len() >= 8 && &data[4..8] == b"ftyp")
-                    }
-                    Ok(_) => Ok(false), // File too small
+                // Read only the first 8 bytes; AAX files can be gigabytes
+                let mut header = [0u8; 8];
+                let mut file = tokio::fs::File::open(path).await.map_err(|e| {
+                    LibationError::FileIoError(format!("Failed to read file: {}", e))
+                })?;
+                match file.read_exact(&mut header).await {
+                    // MP4 files typically start with ftyp box
+                    // Bytes 4-7 should be "ftyp"
+                    Ok(_) => Ok(&header[4..8] == b"ftyp"),
+                    Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false), // File too small
                     Err(e) => Err(LibationError::FileIoError(format!(
                         "Failed to read file: {}",
                         e
@@ -508,6 +618,42 @@ pub async fn is_aax_file(path: &Path) -> Result<bool> {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_dispose_encrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let bytes = ActivationBytes::from_hex("1CEB00DA").unwrap();
+
+        // Default: the source stays where it is
+        let input = dir.path().join("kept.aax");
+        tokio::fs::write(&input, b"encrypted").await.unwrap();
+        let kept = AaxDecrypter::new(bytes).dispose_encrypted(&input).await.unwrap();
+        assert_eq!(kept, Some(input.clone()));
+        assert!(input.exists());
+
+        // Deleted only when asked
+        let input = dir.path().join("deleted.aax");
+        tokio::fs::write(&input, b"encrypted").await.unwrap();
+        let kept = AaxDecrypter::new(bytes)
+            .delete_encrypted_after(true)
+            .dispose_encrypted(&input)
+            .await
+            .unwrap();
+        assert_eq!(kept, None);
+        assert!(!input.exists());
+
+        // Kept and moved into the backup directory
+        let input = dir.path().join("kept.aax");
+        let backup_dir = dir.path().join("originals");
+        let kept = AaxDecrypter::new(bytes)
+            .with_encrypted_backup_dir(backup_dir.clone())
+            .dispose_encrypted(&input)
+            .await
+            .unwrap();
+        assert_eq!(kept, Some(backup_dir.join("kept.aax")));
+        assert!(!input.exists());
+        assert_eq!(std::fs::read(backup_dir.join("kept.aax")).unwrap(), b"encrypted");
+    }
+
     #[test]
     fn test_parse_timestamp() {
         assert_eq!(parse_timestamp("00:00:01.50"), Some(1.5));
@@ -53,6 +53,11 @@
 //! 7. Concatenate decrypted chunks
 //! 8. Write to M4B file
 //!
+//! Steps 5-8 must stream: each chunk is decrypted in place and appended to the
+//! output before the next is fetched, so a multi-gigabyte book never sits in
+//! memory. A downloaded single-file AAXC is decrypted per audio sample instead
+//! (see [`crate::crypto::stream`]).
+//!
 //! # Widevine CDM (Content Decryption Module)
 //! - Client-side library for Widevine DRM
 //! - Libation uses Python's pywidevine equivalent
@@ -64,6 +69,7 @@
 //! - PSSH box with Widevine data
 //! - See AudibleUtilities/Widevine/MpegDash.cs for parsing
 
+use crate::crypto::stream::{DEFAULT_DECRYPT_BUFFER_SIZE, decrypt_samples};
 use crate::error::Result;
 use std::path::Path;
 
@@ -155,9 +161,35 @@ impl AaxcDecrypter {
         unimplemented!("Request Widevine license")
     }
 
+    /// Decrypt a downloaded AAXC file with the voucher key and IV
+    ///
+    /// Only the audio samples in `mdat` are encrypted, each on its own with
+    /// the voucher IV, so the file is decrypted sample by sample through a
+    /// [`DEFAULT_DECRYPT_BUFFER_SIZE`] buffer and the `aavd` sample entry is
+    /// rewritten to `mp4a` (see [`decrypt_samples`]). Memory use stays the
+    /// same for a 20 MB or a 2 GB book. If an earlier decrypt to `output` was
+    /// interrupted, it continues after the last finished sample.
+    ///
+    /// # Arguments
+    /// * `input` - Encrypted file
+    /// * `output` - Decrypted file
+    /// * `key` - 16-byte content key from the license voucher
+    /// * `iv` - 16-byte IV from the license voucher
+    ///
+    /// # Returns
+    /// Number of bytes written
+    ///
+    /// # Errors
+    /// - `InvalidInput` - Key or IV is not 16 bytes
+    /// - `InvalidAudioFile` - The input has no encrypted MP4 audio track
+    /// - `FileNotFound` / `FileIoError` - Reading or writing failed
+    pub async fn decrypt_file_with_key(input: &Path, output: &Path, key: &[u8], iv: &[u8]) -> Result<u64> {
+        decrypt_samples(input, output, key, iv, DEFAULT_DECRYPT_BUFFER_SIZE).await
+    }
+
     // TODO: Port chunk decryption
-    // AES-128 CTR mode decryption
-    async fn decrypt_chunk(&self, encrypted: &[u8], license: &WidevineLicense) -> Result<Vec<u8>> {
+    // AES-128 CTR mode decryption, in place so chunks are never copied
+    async fn decrypt_chunk(&self, encrypted: &mut [u8], license: &WidevineLicense) -> Result<()> {
         // Use content_key and iv from license
         // AES-128-CTR decryption
         // Use Rust crypto crates (aes, ctr)
@@ -0,0 +1,342 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+
+//! One entry point for decrypting any downloaded Audible file
+//!
+//! # Reference C# Sources
+//! - **`FileLiberator/DownloadDecryptBook.cs`** - Picks the converter from the
+//!   license's `FileType` and key set
+//! - **`AaxDecrypter/AaxcDownloadConvertBase.cs`** - AAX and AAXC share one
+//!   code path in AAXClean, keyed by how many key parts are present
+//!
+//! [`decrypt`] routes a file to the decryptor for its format, so callers no
+//! longer branch on AAX vs AAXC themselves:
+//! - **AAX** - FFmpeg with the 4-byte activation bytes ([`AaxDecrypter`])
+//! - **AAXC** - In-process AES-CBC over each audio sample in `mdat` with the
+//!   voucher key and IV, resumable ([`decrypt_samples`])
+//! - **MP3** - Not encrypted; copied to the output
+//! - **DASH** - Widevine content keys are not supported yet
+//!
+//! When the file type is `Unknown`, the shape of the keys decides.
+
+use crate::api::license::{FileType, KeyData, OutputFormat};
+use crate::audio::metadata::temp_output_path;
+use crate::audio::{AudioConverter, ConversionOptions};
+use crate::crypto::aax::AaxDecrypter;
+use crate::crypto::activation::ActivationBytes;
+use crate::crypto::stream::{decrypt_samples, DEFAULT_DECRYPT_BUFFER_SIZE};
+use crate::crypto::verify::verify_decrypted;
+use crate::error::{LibationError, Result};
+use crate::file::post_write;
+use std::path::{Path, PathBuf};
+
+/// Options for [`decrypt`]
+#[derive(Debug, Clone)]
+pub struct DecryptOptions {
+    /// Check the output with [`verify_decrypted`], deleting it if DRM remains
+    /// or it is not a playable M4B (MP3 for podcasts)
+    pub verify: bool,
+    /// Buffer size for formats decrypted in-process
+    pub buffer_size: usize,
+    /// Format conversion, brand trim and chapter split applied to the
+    /// decrypted M4B, usually from
+    /// [`DownloadSettings::conversion_options`](crate::download::settings::DownloadSettings::conversion_options).
+    /// Not applied to MP3 podcasts.
+    pub conversion: Option<ConversionOptions>,
+}
+
+impl Default for DecryptOptions {
+    fn default() -> Self {
+        Self {
+            verify: true,
+            buffer_size: DEFAULT_DECRYPT_BUFFER_SIZE,
+            conversion: None,
+        }
+    }
+}
+
+/// Result of [`decrypt`], the same for every format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecryptResult {
+    /// Format the input was decrypted as (never `Unknown`)
+    pub file_type: FileType,
+    /// The decrypted file
+    pub output: PathBuf,
+    /// Size of the decrypted file in bytes
+    pub bytes_written: u64,
+    /// One file per chapter beside `output`, when the conversion splits chapters
+    pub chapter_files: Vec<PathBuf>,
+}
+
+/// Decrypt a downloaded file with the decryptor its format needs
+///
+/// The input is left in place; deleting or keeping it is up to the caller.
+/// An existing `output` is only replaced once the new file is written and
+/// verified.
+/// With [`DecryptOptions::conversion`] the decrypted book is then converted
+/// to `output`, whose extension should match the conversion's format.
+/// The finished output and any chapter files are handed to
+/// [`post_write::notify_file_written`].
+///
+/// # Arguments
+/// * `input` - Downloaded (encrypted) file
+/// * `file_type` - Format from the license; `Unknown` to infer it from `keys`
+/// * `keys` - Keys from the license: activation bytes for AAX, key and IV for AAXC
+/// * `output` - Where the decrypted file is written
+/// * `options` - See [`DecryptOptions`]
+///
+/// # Returns
+/// The format used and the decrypted file's path and size
+///
+/// # Errors
+/// - `InvalidActivationBytes` - AAX without 4-byte activation bytes
+/// - `InvalidInput` - AAXC without a 16-byte key and IV, or `Unknown` with
+///   keys that match no format
+/// - `InvalidAudioFile` - AAXC input that is not an MP4 with an encrypted
+///   audio track
+/// - `NotImplemented` - DASH (Widevine) files
+/// - `DecryptionFailed` - The output still carries DRM or is not a playable
+///   file (see [`verify_decrypted`])
+/// - Any error of the underlying decryptor, or of the conversion when
+///   [`DecryptOptions::conversion`] is set
+///
+/// # Example
+/// ```rust,no_run
+/// use rust_core::api::license::{FileType, KeyData};
+/// use rust_core::crypto::{decrypt, DecryptOptions};
+/// use std::path::Path;
+/// # async fn example(keys: Vec<KeyData>) -> rust_core::Result<()> {
+/// let result = decrypt(
+///     Path::new("B07T2F8VJM.aaxc"),
+///     FileType::Aaxc,
+///     &keys,
+///     Path::new("B07T2F8VJM.m4b"),
+///     &DecryptOptions::default(),
+/// )
+/// .await?;
+/// println!("Wrote {} bytes", result.bytes_written);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn decrypt(
+    input: &Path,
+    file_type: FileType,
+    keys: &[KeyData],
+    output: &Path,
+    options: &DecryptOptions,
+) -> Result<DecryptResult> {
+    let file_type = match file_type {
+        FileType::Unknown => infer_file_type(keys)?,
+        known => known,
+    };
+
+    // Write beside the output and replace it only once the new file is
+    // complete, so an earlier copy survives a failed decrypt. A book that is
+    // converted afterwards is decrypted to an M4B of its own first.
+    let in_place = file_type == FileType::Mp3 && input == output;
+    let conversion = options.conversion.as_ref().filter(|_| file_type != FileType::Mp3);
+    let staging = match conversion {
+        _ if in_place => output.to_path_buf(),
+        Some(_) => output.with_extension("decrypted.m4b"),
+        None => temp_output_path(output),
+    };
+
+    match file_type {
+        FileType::Aax => {
+            AaxDecrypter::new(activation_bytes(keys)?).decrypt_file(input, &staging).await?;
+        }
+        FileType::Aaxc => {
+            let (key, iv) = content_key(keys)?;
+            decrypt_samples(input, &staging, key, iv, options.buffer_size).await?;
+        }
+        FileType::Mp3 => {
+            if !in_place {
+                tokio::fs::copy(input, &staging).await?;
+            }
+        }
+        FileType::Dash => {
+            return Err(LibationError::not_implemented("Widevine (DASH) decryption"));
+        }
+        FileType::Unknown => unreachable!("Unknown is resolved above"),
+    }
+
+    if options.verify {
+        let expected = match file_type {
+            FileType::Mp3 => OutputFormat::Mp3,
+            _ => OutputFormat::M4b,
+        };
+        if let Err(e) = verify_decrypted(&staging, expected).await {
+            if !in_place {
+                let _ = tokio::fs::remove_file(&staging).await;
+            }
+            return Err(e);
+        }
+    }
+    let mut chapter_files = Vec::new();
+    if let Some(conversion) = conversion {
+        let converted = convert_decrypted(&staging, output, conversion).await;
+        let _ = tokio::fs::remove_file(&staging).await;
+        chapter_files = converted?;
+    } else if !in_place {
+        tokio::fs::rename(&staging, output).await?;
+    }
+
+    post_write::notify_file_written(output).await;
+    for chapter_file in &chapter_files {
+        post_write::notify_file_written(chapter_file).await;
+    }
+
+    Ok(DecryptResult {
+        file_type,
+        output: output.to_path_buf(),
+        bytes_written: tokio::fs::metadata(output).await?.len(),
+        chapter_files,
+    })
+}
+
+/// Convert a decrypted M4B as `conversion` asks, replacing `output`
+///
+/// The converted book is written beside `output` and renamed over it only
+/// once complete. With `split_by_chapter` the chapters are then also written
+/// as separate files in `output`'s folder.
+///
+/// # Returns
+/// The chapter files, empty unless splitting
+pub(crate) async fn convert_decrypted(
+    decrypted: &Path,
+    output: &Path,
+    conversion: &ConversionOptions,
+) -> Result<Vec<PathBuf>> {
+    let converter = AudioConverter::new(ConversionOptions {
+        overwrite_existing: true,
+        ..conversion.clone()
+    });
+    let converted = temp_output_path(output);
+    if let Err(e) = converter.convert(decrypted, &converted).await {
+        let _ = tokio::fs::remove_file(&converted).await;
+        return Err(e);
+    }
+    tokio::fs::rename(&converted, output).await?;
+
+    if !conversion.split_by_chapter {
+        return Ok(Vec::new());
+    }
+    let folder = output.parent().unwrap_or_else(|| Path::new(""));
+    converter.split_by_chapters(output, folder).await
+}
+
+/// Pick the format from the key shape, as AAXClean does
+fn infer_file_type(keys: &[KeyData]) -> Result<FileType> {
+    match keys.first() {
+        Some(KeyData { key_part_1, key_part_2: None }) if key_part_1.len() == 4 => Ok(FileType::Aax),
+        Some(KeyData { key_part_1, key_part_2: Some(iv) }) if key_part_1.len() == 16 && iv.len() == 16 => {
+            Ok(FileType::Aaxc)
+        }
+        _ => Err(LibationError::InvalidInput(
+            "Cannot tell the file format: no file type and no recognizable keys".to_string(),
+        )),
+    }
+}
+
+/// The 4-byte activation bytes an AAX file is decrypted with
+fn activation_bytes(keys: &[KeyData]) -> Result<ActivationBytes> {
+    let key = keys.first().map(|k| k.key_part_1.as_slice()).unwrap_or_default();
+    let bytes: [u8; 4] = key.try_into().map_err(|_| {
+        LibationError::InvalidActivationBytes(format!(
+            "AAX needs 4 activation bytes, got {}",
+            key.len()
+        ))
+    })?;
+    Ok(ActivationBytes::new(bytes))
+}
+
+/// The 16-byte key and IV an AAXC file is decrypted with
+fn content_key(keys: &[KeyData]) -> Result<(&[u8], &[u8])> {
+    match keys.first() {
+        Some(KeyData { key_part_1, key_part_2: Some(iv) }) if key_part_1.len() == 16 && iv.len() == 16 => {
+            Ok((key_part_1, iv))
+        }
+        _ => Err(LibationError::InvalidInput(
+            "AAXC needs a 16-byte key and a 16-byte IV".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::mp4::fixture::protected_mp4;
+
+    #[tokio::test]
+    async fn test_decrypt_dispatches_by_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let aaxc_keys = [KeyData { key_part_1: vec![0x11; 16], key_part_2: Some(vec![0x22; 16]) }];
+
+        // ftyp, moov with an aavd track, and mdat with per-sample encryption
+        let samples: Vec<Vec<u8>> = (0..7u8).map(|i| vec![i; 100 + 31 * i as usize]).collect();
+        let (encrypted, expected) = protected_mp4(&[0x11; 16], &[0x22; 16], &samples);
+        let input = dir.path().join("book.aaxc");
+        std::fs::write(&input, &encrypted).unwrap();
+
+        // Unknown type: the key shape says AAXC
+        let output = dir.path().join("book.m4b");
+        let result = decrypt(&input, FileType::Unknown, &aaxc_keys, &output, &DecryptOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(result.file_type, FileType::Aaxc);
+        assert_eq!(result.bytes_written, expected.len() as u64);
+        assert_eq!(std::fs::read(&output).unwrap(), expected);
+
+        // Ciphertext without MP4 structure is not mistaken for a book
+        let blob = dir.path().join("blob.aaxc");
+        std::fs::write(&blob, &encrypted[encrypted.len() - 512..]).unwrap();
+        assert!(matches!(
+            decrypt(&blob, FileType::Aaxc, &aaxc_keys, &output, &DecryptOptions::default()).await,
+            Err(LibationError::InvalidAudioFile(_))
+        ));
+        // and a failed decrypt leaves the earlier book in place
+        assert_eq!(std::fs::read(&output).unwrap(), expected);
+
+        let mp3 = dir.path().join("episode.mp3");
+        std::fs::write(&mp3, b"ID3 podcast").unwrap();
+        let copied = dir.path().join("out.mp3");
+        let result = decrypt(&mp3, FileType::Mp3, &[], &copied, &DecryptOptions::default()).await.unwrap();
+        assert_eq!(result.bytes_written, 11);
+
+        let options = DecryptOptions::default();
+        assert!(matches!(
+            decrypt(&input, FileType::Aax, &aaxc_keys, &output, &options).await,
+            Err(LibationError::InvalidActivationBytes(_))
+        ));
+        assert!(matches!(
+            decrypt(&input, FileType::Aaxc, &[], &output, &options).await,
+            Err(LibationError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            decrypt(&input, FileType::Dash, &aaxc_keys, &output, &options).await,
+            Err(LibationError::NotImplemented(_))
+        ));
+        assert_eq!(
+            infer_file_type(&[KeyData { key_part_1: vec![1, 2, 3, 4], key_part_2: None }]).unwrap(),
+            FileType::Aax
+        );
+    }
+}
@@ -31,10 +31,16 @@
 //! - **AAX** (legacy): AES encryption with activation bytes
 //! - **AAXC** (current): Widevine DRM with chunked MPEG-DASH delivery
 //! - **Unencrypted**: Direct MP3/M4B for podcasts
+//!
+//! [`decrypt`] picks the right decryptor from the license's file type and keys.
 
 pub mod activation;
 pub mod aax;
 pub mod aaxc;
+pub mod dispatch;
+mod mp4;
+pub mod stream;
+pub mod verify;
 pub mod widevine;
 
 // Re-export commonly used types from activation module
@@ -54,3 +60,18 @@ pub use aax::{
 
 // Re-export AAXC decrypter (placeholder for now)
 pub use aaxc::AaxcDecrypter;
+
+// Re-export bounded, sample-aware streaming decryption
+pub use stream::{
+    Aes128CbcChunkCipher,
+    ChunkCipher,
+    DEFAULT_DECRYPT_BUFFER_SIZE,
+    DecryptCheckpoint,
+    decrypt_samples,
+};
+
+// Re-export the format-independent entry point
+pub use dispatch::{decrypt, DecryptOptions, DecryptResult};
+
+// Re-export the check that a decrypt removed the DRM
+pub use verify::verify_decrypted;
@@ -0,0 +1,465 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! MP4 atom parsing for in-process decryption
+//!
+//! # Reference C# Sources
+//! - **`AAXClean/Mpeg4File.cs`** - Reads `ftyp` and `moov` and walks the
+//!   sample table to find each audio frame in `mdat`
+//! - **`AaxDecrypter/AaxcDownloadConvertBase.cs`** - Replaces the `aavd`
+//!   sample entry with `mp4a` and drops the `adrm` box in the output
+//!
+//! Audible encrypts the audio frames inside `mdat`, not the file: `ftyp`,
+//! `moov` and everything else stays readable. [`read_layout`] loads just the
+//! small header atoms, [`ProtectedSamples`] lists where every encrypted frame
+//! sits, and [`strip_protection`] rewrites the headers so the output describes
+//! plain AAC. Every rewrite keeps atom sizes the same, so the chunk offsets in
+//! `stco`/`co64` stay valid without being recomputed.
+
+use crate::error::{LibationError, Result};
+use std::io::SeekFrom;
+use std::ops::Range;
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// Largest `moov` atom read into memory (chapter-heavy books reach a few MB)
+pub(crate) const MAX_MOOV_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Largest `ftyp` atom read into memory
+const MAX_FTYP_SIZE: u64 = 4096;
+
+/// Atoms under `moov` that lead to the sample descriptions
+pub(crate) const CONTAINER_ATOMS: [&[u8; 4]; 5] = [b"moov", b"trak", b"mdia", b"minf", b"stbl"];
+
+/// Fixed fields of an audio sample entry before its child boxes
+pub(crate) const AUDIO_SAMPLE_ENTRY_FIELDS: usize = 28;
+
+/// Header atoms of an MP4 file, read without touching `mdat`
+#[derive(Debug, Clone)]
+pub(crate) struct Mp4Layout {
+    /// Size of the whole file
+    pub len: u64,
+    /// The `ftyp` atom, which always starts the file
+    pub ftyp: Vec<u8>,
+    /// Offset of the `moov` atom in the file
+    pub moov_offset: u64,
+    /// The whole `moov` atom, header included
+    pub moov: Vec<u8>,
+}
+
+/// Read the `ftyp` and `moov` atoms of `path`
+///
+/// # Errors
+/// - `InvalidAudioFile` - Not an MP4 file, no `moov`, or atoms that do not fit
+/// - `FileNotFound` / `FileIoError` - The file cannot be read
+pub(crate) async fn read_layout(path: &Path) -> Result<Mp4Layout> {
+    let mut file = File::open(path)
+        .await
+        .map_err(|e| LibationError::FileNotFound(format!("{}: {}", path.display(), e)))?;
+    let io_error = |e: std::io::Error| LibationError::FileIoError(format!("{}: {}", path.display(), e));
+    let invalid = |reason: String| LibationError::InvalidAudioFile(format!("{}: {}", path.display(), reason));
+    let len = file.metadata().await.map_err(io_error)?.len();
+
+    let mut ftyp = None;
+    let mut offset = 0;
+    while offset + 8 <= len {
+        file.seek(SeekFrom::Start(offset)).await.map_err(io_error)?;
+        let mut header = [0u8; 16];
+        file.read_exact(&mut header[..8]).await.map_err(io_error)?;
+        let kind: [u8; 4] = header[4..8].try_into().unwrap_or_default();
+        if offset == 0 && &kind != b"ftyp" {
+            return Err(invalid("not an MP4 file (no ftyp atom)".to_string()));
+        }
+
+        let size = match u32::from_be_bytes(header[..4].try_into().unwrap_or_default()) {
+            0 => len - offset,
+            1 => {
+                file.read_exact(&mut header[8..16]).await.map_err(io_error)?;
+                u64::from_be_bytes(header[8..16].try_into().unwrap_or_default())
+            }
+            size => u64::from(size),
+        };
+        if size < 8 || offset + size > len {
+            return Err(invalid(format!(
+                "atom '{}' at {} does not fit the file",
+                String::from_utf8_lossy(&kind),
+                offset
+            )));
+        }
+
+        let limit = match &kind {
+            b"ftyp" => Some(MAX_FTYP_SIZE),
+            b"moov" => Some(MAX_MOOV_SIZE),
+            _ => None,
+        };
+        if let Some(limit) = limit {
+            if size > limit {
+                return Err(invalid(format!(
+                    "{} atom of {} bytes is too large",
+                    String::from_utf8_lossy(&kind),
+                    size
+                )));
+            }
+            let mut atom = vec![0u8; size as usize];
+            file.seek(SeekFrom::Start(offset)).await.map_err(io_error)?;
+            file.read_exact(&mut atom).await.map_err(io_error)?;
+            if &kind == b"ftyp" {
+                ftyp = Some(atom);
+            } else {
+                return Ok(Mp4Layout {
+                    len,
+                    ftyp: ftyp.unwrap_or_default(),
+                    moov_offset: offset,
+                    moov: atom,
+                });
+            }
+        }
+
+        offset += size;
+    }
+
+    Err(invalid("no moov atom".to_string()))
+}
+
+/// An atom inside a buffer: its type and where its body is
+#[derive(Debug, Clone)]
+pub(crate) struct Atom {
+    pub kind: [u8; 4],
+    /// Offset of the type field, for rewriting it in place
+    pub kind_at: usize,
+    pub body: Range<usize>,
+}
+
+/// Child atoms of `data[range]`; stops at the first malformed atom
+pub(crate) fn child_atoms(data: &[u8], range: Range<usize>) -> impl Iterator<Item = Atom> + '_ {
+    let mut at = range.start;
+    let end = range.end.min(data.len());
+    std::iter::from_fn(move || {
+        if at + 8 > end {
+            return None;
+        }
+        let kind: [u8; 4] = data[at + 4..at + 8].try_into().ok()?;
+        let (size, header) = match u32::from_be_bytes(data[at..at + 4].try_into().ok()?) {
+            0 => (end - at, 8),
+            1 => {
+                let large = data.get(at + 8..at + 16)?;
+                (usize::try_from(u64::from_be_bytes(large.try_into().ok()?)).ok()?, 16)
+            }
+            size => (size as usize, 8),
+        };
+        if size < header || size > end - at {
+            return None;
+        }
+        let atom = Atom { kind, kind_at: at + 4, body: at + header..at + size };
+        at += size;
+        Some(atom)
+    })
+}
+
+/// Sample tables (`stbl` bodies) of every track in a whole `moov` atom
+pub(crate) fn sample_tables(moov: &[u8]) -> Vec<Range<usize>> {
+    fn walk(data: &[u8], atom: Atom, out: &mut Vec<Range<usize>>) {
+        if &atom.kind == b"stbl" {
+            out.push(atom.body);
+        } else if CONTAINER_ATOMS.contains(&&atom.kind) {
+            for child in child_atoms(data, atom.body) {
+                walk(data, child, out);
+            }
+        }
+    }
+
+    let mut tables = Vec::new();
+    for atom in child_atoms(moov, 0..moov.len()) {
+        walk(moov, atom, &mut tables);
+    }
+    tables
+}
+
+/// Sample entries in the `stsd` of a sample table
+pub(crate) fn sample_entries(moov: &[u8], stbl: Range<usize>) -> Vec<Atom> {
+    child_atoms(moov, stbl)
+        .find(|atom| &atom.kind == b"stsd")
+        // Version, flags and entry count come before the sample entries
+        .map(|stsd| child_atoms(moov, stsd.body.start + 8..stsd.body.end).collect())
+        .unwrap_or_default()
+}
+
+/// Rewrite the headers of a protected file so they describe plain audio
+///
+/// `aavd` sample entries become `mp4a` and their `adrm` boxes become `free`
+/// padding, and an `aax` major brand becomes `M4B `. Sizes never change.
+///
+/// # Returns
+/// Whether any sample entry was protected
+pub(crate) fn strip_protection(layout: &mut Mp4Layout) -> bool {
+    if layout.ftyp.len() >= 12 && layout.ftyp[8..11] == *b"aax" {
+        layout.ftyp[8..12].copy_from_slice(b"M4B ");
+    }
+
+    let mut renames = Vec::new();
+    for stbl in sample_tables(&layout.moov) {
+        for entry in sample_entries(&layout.moov, stbl) {
+            if &entry.kind != b"aavd" {
+                continue;
+            }
+            renames.push((entry.kind_at, *b"mp4a"));
+            let children = entry.body.start + AUDIO_SAMPLE_ENTRY_FIELDS..entry.body.end;
+            for child in child_atoms(&layout.moov, children) {
+                if &child.kind == b"adrm" {
+                    renames.push((child.kind_at, *b"free"));
+                }
+            }
+        }
+    }
+
+    for (at, kind) in &renames {
+        layout.moov[*at..*at + 4].copy_from_slice(kind);
+    }
+    !renames.is_empty()
+}
+
+/// Location of every encrypted audio frame, in file order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ProtectedSamples {
+    /// File offset of each chunk and the range of its samples in `sizes`
+    chunks: Vec<(u64, Range<usize>)>,
+    /// Size of every sample of the protected tracks
+    sizes: Vec<u32>,
+}
+
+impl ProtectedSamples {
+    /// Collect the samples of every track whose sample entry is `aavd`
+    ///
+    /// `file_len` bounds how many fixed-size samples a track can claim.
+    ///
+    /// # Errors
+    /// - `InvalidAudioFile` - A protected track's `stsz`, `stsc` or
+    ///   `stco`/`co64` is missing, truncated or counts more samples than fit
+    pub(crate) fn from_moov(moov: &[u8], file_len: u64) -> Result<Self> {
+        let mut samples = Self::default();
+        for stbl in sample_tables(moov) {
+            if sample_entries(moov, stbl.clone()).iter().any(|entry| &entry.kind == b"aavd") {
+                samples.add_track(moov, stbl, file_len)?;
+            }
+        }
+        samples.chunks.sort_by_key(|(offset, _)| *offset);
+        Ok(samples)
+    }
+
+    fn add_track(&mut self, moov: &[u8], stbl: Range<usize>, file_len: u64) -> Result<()> {
+        let table = |kinds: &[&[u8; 4]]| {
+            child_atoms(moov, stbl.clone())
+                .find(|atom| kinds.contains(&&atom.kind))
+                .map(|atom| (atom.kind, &moov[atom.body]))
+        };
+        let malformed = |what: &str| LibationError::InvalidAudioFile(format!("Malformed {} in protected track", what));
+        let u32_at = |data: &[u8], at: usize| data.get(at..at + 4).map(|b| u32::from_be_bytes(b.try_into().unwrap_or_default()));
+
+        // stsz: one size for every sample, or a table of sizes
+        let (_, stsz) = table(&[b"stsz"]).ok_or_else(|| malformed("stsz"))?;
+        let fixed_size = u32_at(stsz, 4).ok_or_else(|| malformed("stsz"))?;
+        let sample_count = u32_at(stsz, 8).ok_or_else(|| malformed("stsz"))? as usize;
+        // A size table must fit in the box and fixed-size samples in the file,
+        // so a corrupt count can't make us allocate for billions of samples
+        let max_samples = match fixed_size {
+            0 => stsz.len().saturating_sub(12) / 4,
+            size => usize::try_from(file_len / u64::from(size)).unwrap_or(usize::MAX),
+        };
+        if sample_count > max_samples {
+            return Err(malformed("stsz"));
+        }
+        let first_sample = self.sizes.len();
+        if fixed_size == 0 {
+            self.sizes.reserve(sample_count);
+            for i in 0..sample_count {
+                self.sizes.push(u32_at(stsz, 12 + 4 * i).ok_or_else(|| malformed("stsz"))?);
+            }
+        } else {
+            self.sizes.resize(first_sample + sample_count, fixed_size);
+        }
+
+        // stco/co64: where each chunk starts
+        let (kind, chunk_table) = table(&[b"stco", b"co64"]).ok_or_else(|| malformed("stco"))?;
+        let chunk_count = u32_at(chunk_table, 4).ok_or_else(|| malformed("stco"))? as usize;
+        let mut chunk_offsets = Vec::with_capacity(chunk_count.min(chunk_table.len() / 4));
+        for i in 0..chunk_count {
+            let offset = if &kind == b"co64" {
+                chunk_table
+                    .get(8 + 8 * i..16 + 8 * i)
+                    .map(|b| u64::from_be_bytes(b.try_into().unwrap_or_default()))
+            } else {
+                u32_at(chunk_table, 8 + 4 * i).map(u64::from)
+            };
+            chunk_offsets.push(offset.ok_or_else(|| malformed("stco"))?);
+        }
+
+        // stsc: runs of chunks with the same number of samples
+        let (_, stsc) = table(&[b"stsc"]).ok_or_else(|| malformed("stsc"))?;
+        let run_count = u32_at(stsc, 4).ok_or_else(|| malformed("stsc"))? as usize;
+        let mut runs = Vec::with_capacity(run_count.min(stsc.len() / 12));
+        for i in 0..run_count {
+            let first_chunk = u32_at(stsc, 8 + 12 * i).ok_or_else(|| malformed("stsc"))? as usize;
+            let per_chunk = u32_at(stsc, 12 + 12 * i).ok_or_else(|| malformed("stsc"))? as usize;
+            runs.push((first_chunk.max(1) - 1, per_chunk));
+        }
+
+        let mut next_sample = first_sample;
+        let end_sample = first_sample + sample_count;
+        for (i, (first_chunk, per_chunk)) in runs.iter().enumerate() {
+            let last_chunk = runs.get(i + 1).map_or(chunk_offsets.len(), |(next, _)| *next);
+            for offset in chunk_offsets.get(*first_chunk..last_chunk.min(chunk_offsets.len())).unwrap_or_default() {
+                let samples = next_sample..(next_sample + per_chunk).min(end_sample);
+                next_sample = samples.end;
+                if !samples.is_empty() {
+                    self.chunks.push((*offset, samples));
+                }
+            }
+        }
+        if next_sample != end_sample {
+            return Err(malformed("stsc"));
+        }
+        Ok(())
+    }
+
+    /// File offset and size of every sample, in file order
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (u64, u32)> + '_ {
+        self.chunks.iter().flat_map(move |(offset, samples)| {
+            let mut at = *offset;
+            self.sizes[samples.clone()].iter().map(move |&size| {
+                let sample = (at, size);
+                at += u64::from(size);
+                sample
+            })
+        })
+    }
+}
+
+/// Build small MP4 files with Audible-style sample encryption for tests
+#[cfg(test)]
+pub(crate) mod fixture {
+    use aes::Aes128;
+    use cbc::cipher::{generic_array::GenericArray, BlockEncryptMut, KeyIvInit};
+
+    pub(crate) fn atom(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut out = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(body);
+        out
+    }
+
+    fn full_atom(kind: &[u8; 4], fields: &[u32]) -> Vec<u8> {
+        let body: Vec<u8> = [0u32].iter().chain(fields).flat_map(|v| v.to_be_bytes()).collect();
+        atom(kind, &body)
+    }
+
+    /// Encrypt whole blocks of `sample` with a fresh CBC chain, like Audible
+    pub(crate) fn encrypt_sample(key: &[u8], iv: &[u8], sample: &[u8]) -> Vec<u8> {
+        let mut encryptor = cbc::Encryptor::<Aes128>::new_from_slices(key, iv).unwrap();
+        let mut data = sample.to_vec();
+        let aligned = data.len() - data.len() % 16;
+        for block in data[..aligned].chunks_exact_mut(16) {
+            encryptor.encrypt_block_mut(GenericArray::from_mut_slice(block));
+        }
+        data
+    }
+
+    /// An `aax `-branded file with `moov` first and one audio track whose
+    /// samples (two per chunk) are encrypted with `key` and `iv`
+    ///
+    /// Returns the encrypted file and the file the decrypt should produce.
+    pub(crate) fn protected_mp4(key: &[u8], iv: &[u8], samples: &[Vec<u8>]) -> (Vec<u8>, Vec<u8>) {
+        let build = |entry: &[u8; 4], drm: &[u8; 4], brand: &[u8; 4], encrypt: bool| {
+            let entry_body = [&[0u8; super::AUDIO_SAMPLE_ENTRY_FIELDS][..], &atom(drm, &[0x5A; 56])].concat();
+            let stsd = [&[0, 0, 0, 0, 0, 0, 0, 1][..], &atom(entry, &entry_body)].concat();
+            let sizes: Vec<u32> = samples.iter().map(|s| s.len() as u32).collect();
+            let chunk_count = samples.len().div_ceil(2);
+
+            let moov_for = |first_offset: u32| {
+                let mut offsets = Vec::new();
+                let mut at = first_offset;
+                for pair in sizes.chunks(2) {
+                    offsets.push(at);
+                    at += pair.iter().sum::<u32>();
+                }
+                let stbl = [
+                    atom(b"stsd", &stsd),
+                    full_atom(b"stsz", &[&[0, sizes.len() as u32][..], &sizes].concat()),
+                    full_atom(b"stsc", &[1, 1, 2, 1]),
+                    full_atom(b"stco", &[&[chunk_count as u32][..], &offsets].concat()),
+                ]
+                .concat();
+                let trak = atom(b"trak", &atom(b"mdia", &atom(b"minf", &atom(b"stbl", &stbl))));
+                atom(b"moov", &trak)
+            };
+
+            let ftyp = atom(b"ftyp", &[&brand[..], &[0; 4]].concat());
+            let moov_len = moov_for(0).len();
+            let moov = moov_for((ftyp.len() + moov_len + 8) as u32);
+            let mdat: Vec<u8> = samples
+                .iter()
+                .flat_map(|s| if encrypt { encrypt_sample(key, iv, s) } else { s.clone() })
+                .collect();
+            [ftyp, moov, atom(b"mdat", &mdat)].concat()
+        };
+
+        (build(b"aavd", b"adrm", b"aax ", true), build(b"mp4a", b"free", b"M4B ", false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixture::protected_mp4;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_protected_samples_and_strip() {
+        let samples: Vec<Vec<u8>> = (0..5).map(|i| vec![i as u8; 30 + 17 * i]).collect();
+        let (encrypted, expected) = protected_mp4(&[1; 16], &[2; 16], &samples);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("book.aax");
+        std::fs::write(&path, &encrypted).unwrap();
+
+        let mut layout = read_layout(&path).await.unwrap();
+        let found = ProtectedSamples::from_moov(&layout.moov, layout.len).unwrap();
+        let located: Vec<Vec<u8>> = found
+            .iter()
+            .map(|(offset, size)| expected[offset as usize..offset as usize + size as usize].to_vec())
+            .collect();
+        assert_eq!(located, samples);
+
+        assert!(strip_protection(&mut layout));
+        let header = [layout.ftyp.as_slice(), &layout.moov].concat();
+        assert_eq!(header, expected[..header.len()]);
+        assert!(ProtectedSamples::from_moov(&layout.moov, layout.len).unwrap().iter().next().is_none());
+
+        // A sample count the file can't hold is rejected before allocating
+        let layout = read_layout(&path).await.unwrap();
+        let mut moov = layout.moov.clone();
+        let stsz = moov.windows(4).position(|w| w == b"stsz").unwrap();
+        moov[stsz + 8..stsz + 16].copy_from_slice(&[0, 0, 0, 1, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert!(matches!(ProtectedSamples::from_moov(&moov, layout.len), Err(LibationError::InvalidAudioFile(_))));
+        moov[stsz + 8..stsz + 12].copy_from_slice(&[0; 4]);
+        assert!(matches!(ProtectedSamples::from_moov(&moov, layout.len), Err(LibationError::InvalidAudioFile(_))));
+
+        std::fs::write(&path, b"not an mp4 at all").unwrap();
+        assert!(matches!(read_layout(&path).await, Err(LibationError::InvalidAudioFile(_))));
+    }
+}
@@ -0,0 +1,410 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Memory-bounded, sample-aware streaming decryption
+//!
+//! Audiobooks can be several gigabytes, so the in-process decryptors never
+//! hold a whole file in memory. Only the `ftyp` and `moov` atoms are loaded;
+//! the audio is read through one fixed-size buffer, decrypted in place and
+//! written out before the next read, so apart from the headers peak memory is
+//! about [`DEFAULT_DECRYPT_BUFFER_SIZE`] regardless of file size.
+//!
+//! # Reference C# Sources
+//! - `AaxDecrypter/NetworkFileStream.cs` - Libation also decrypts from a stream
+//!   rather than a buffered file
+//! - `AAXClean/AavdChunkHandler.cs` - Decrypts each audio frame separately,
+//!   restarting the CBC chain with the file IV
+//!
+//! # Block Alignment
+//! Block ciphers only operate on whole blocks. Every encrypted sample is its
+//! own CBC chain; each piece handed to the [`ChunkCipher`] is a multiple of
+//! its block size, and the trailing partial block of each sample is written
+//! unchanged because Audible never encrypts it.
+//!
+//! # Resuming
+//! Every sample starts a fresh CBC chain with the same IV, so decrypting a
+//! sample never needs anything from the one before it. [`decrypt_samples`]
+//! keeps a small [`DecryptCheckpoint`] with the number of finished samples
+//! next to the output while it runs; if the decrypt is cut short, the next
+//! call with the same input and key keeps the output up to the last finished
+//! sample and continues with the one after it.
+
+use crate::crypto::mp4::{self, ProtectedSamples};
+use crate::error::{LibationError, Result};
+use aes::Aes128;
+use cbc::cipher::{BlockDecryptMut, KeyIvInit, generic_array::GenericArray};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt, BufReader, BufWriter};
+
+/// Default buffer size for streaming decryption (4 MiB)
+pub const DEFAULT_DECRYPT_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+
+/// In-place decryption of consecutive chunks of a stream
+///
+/// Implementations keep any chaining state between calls, so decrypting a
+/// stream chunk by chunk gives the same result as decrypting it in one go.
+pub trait ChunkCipher: Send {
+    /// Cipher block size in bytes; chunks are always a multiple of this
+    fn block_size(&self) -> usize;
+
+    /// Decrypt `data` in place
+    fn decrypt_chunk(&mut self, data: &mut [u8]) -> Result<()>;
+}
+
+/// AES-128-CBC decryption that chains across chunks
+pub struct Aes128CbcChunkCipher {
+    decryptor: cbc::Decryptor<Aes128>,
+}
+
+impl Aes128CbcChunkCipher {
+    /// Create a cipher from a 16-byte key and IV
+    ///
+    /// # Errors
+    /// - `InvalidInput` - Key or IV is not 16 bytes
+    pub fn new(key: &[u8], iv: &[u8]) -> Result<Self> {
+        let decryptor = cbc::Decryptor::<Aes128>::new_from_slices(key, iv)
+            .map_err(|e| LibationError::InvalidInput(format!("Failed to create cipher: {:?}", e)))?;
+        Ok(Self { decryptor })
+    }
+}
+
+impl ChunkCipher for Aes128CbcChunkCipher {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn decrypt_chunk(&mut self, data: &mut [u8]) -> Result<()> {
+        if !data.len().is_multiple_of(16) {
+            return Err(LibationError::InvalidInput(format!(
+                "Chunk of {} bytes is not block aligned",
+                data.len()
+            )));
+        }
+        for block in data.chunks_exact_mut(16) {
+            self.decryptor.decrypt_block_mut(GenericArray::from_mut_slice(block));
+        }
+        Ok(())
+    }
+}
+
+/// Progress of an interrupted [`decrypt_samples`]
+///
+/// Stored as JSON at [`checkpoint_path`] and removed once the output is
+/// complete.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecryptCheckpoint {
+    /// Size of the encrypted input, to notice a re-downloaded file
+    pub input_len: u64,
+    /// Hash of the key and IV, to notice a different license
+    pub key_id: String,
+    /// Encrypted samples, in file order, fully written to the output when
+    /// the checkpoint was last updated
+    pub samples_done: u64,
+}
+
+impl DecryptCheckpoint {
+    fn key_id(key: &[u8], iv: &[u8]) -> String {
+        let digest = Sha256::new().chain_update(key).chain_update(iv).finalize();
+        hex::encode(&digest[..8])
+    }
+
+    /// Read the checkpoint for `output`, if a decrypt of it was interrupted
+    pub async fn load(output: &Path) -> Option<Self> {
+        let json = tokio::fs::read(checkpoint_path(output)).await.ok()?;
+        serde_json::from_slice(&json).ok()
+    }
+
+    fn save(&self, output: &Path) -> Result<()> {
+        let json = serde_json::to_vec(self)
+            .map_err(|e| LibationError::InternalError(format!("Failed to encode checkpoint: {}", e)))?;
+        std::fs::write(checkpoint_path(output), json)
+            .map_err(|e| LibationError::FileIoError(format!("Failed to write decrypt checkpoint: {}", e)))
+    }
+}
+
+/// Where the checkpoint for `output` is kept
+pub fn checkpoint_path(output: &Path) -> PathBuf {
+    let mut path = output.as_os_str().to_owned();
+    path.push(".checkpoint");
+    PathBuf::from(path)
+}
+
+/// Decrypt the encrypted audio samples of an AAX/AAXC file, continuing an
+/// earlier interrupted run
+///
+/// Audible encrypts each audio frame in `mdat` on its own: AES-128-CBC from
+/// the start of the frame with the voucher IV, whole blocks only, so the last
+/// `size % 16` bytes of every frame are in the clear. The sample table in
+/// `moov` (`stsz`, `stsc`, `stco`/`co64`) says where each frame is. Frames are
+/// decrypted one after another with a fresh cipher, everything between them
+/// is copied unchanged, and the headers are rewritten by
+/// [`strip_protection`](mp4::strip_protection) so the output is a plain M4B.
+///
+/// If a checkpoint for `output` matches this input and key, the output is cut
+/// back to the end of the last sample that is both recorded and on disk, and
+/// decryption continues with the next sample. Otherwise the output is written
+/// from the start. The checkpoint is updated after every buffer's worth of
+/// output and deleted when the output is complete.
+///
+/// # Arguments
+/// * `input` - Encrypted file
+/// * `output` - Decrypted file
+/// * `key` - 16-byte content key
+/// * `iv` - 16-byte IV every sample starts with
+/// * `buffer_size` - Bytes of audio held in memory at once (rounded down to a
+///   whole number of blocks, minimum one block)
+///
+/// # Returns
+/// Size of the complete output, which always equals the size of the input
+///
+/// # Errors
+/// - `InvalidInput` - Key or IV is not 16 bytes
+/// - `InvalidAudioFile` - Not an MP4 file, no encrypted audio track, or a
+///   sample table that points outside the file
+/// - `FileNotFound` / `FileIoError` - Reading or writing failed
+pub async fn decrypt_samples(
+    input: &Path,
+    output: &Path,
+    key: &[u8],
+    iv: &[u8],
+    buffer_size: usize,
+) -> Result<u64> {
+    if !input.exists() {
+        return Err(LibationError::FileNotFound(input.display().to_string()));
+    }
+    // Reject a bad key before any output is created
+    Aes128CbcChunkCipher::new(key, iv)?;
+    let io_error = |action: &str, path: &Path, e: std::io::Error| {
+        LibationError::FileIoError(format!("Failed to {} {}: {}", action, path.display(), e))
+    };
+
+    let mut layout = mp4::read_layout(input).await?;
+    let samples = ProtectedSamples::from_moov(&layout.moov, layout.len)?;
+    if !mp4::strip_protection(&mut layout) {
+        return Err(LibationError::InvalidAudioFile(format!(
+            "{} has no encrypted audio track",
+            input.display()
+        )));
+    }
+    let mut position = 0;
+    for (offset, size) in samples.iter() {
+        if offset < position || offset + u64::from(size) > layout.len {
+            return Err(LibationError::InvalidAudioFile(format!(
+                "{}: sample at {} overlaps another atom or the end of the file",
+                input.display(),
+                offset
+            )));
+        }
+        position = offset + u64::from(size);
+    }
+
+    // Resume after the last sample that is both recorded and actually on disk
+    let key_id = DecryptCheckpoint::key_id(key, iv);
+    let on_disk = tokio::fs::metadata(output).await.map(|m| m.len()).unwrap_or(0);
+    let recorded = match DecryptCheckpoint::load(output).await {
+        Some(checkpoint) if checkpoint.input_len == layout.len && checkpoint.key_id == key_id => {
+            checkpoint.samples_done as usize
+        }
+        _ => 0,
+    };
+    let (samples_done, resume_from) = samples
+        .iter()
+        .take(recorded)
+        .map(|(offset, size)| offset + u64::from(size))
+        .take_while(|&end| end <= on_disk)
+        .enumerate()
+        .last()
+        .map_or((0, 0), |(index, end)| (index + 1, end));
+
+    let mut reader = tokio::fs::File::open(input)
+        .await
+        .map_err(|e| io_error("open", input, e))?;
+    reader
+        .seek(SeekFrom::Start(resume_from))
+        .await
+        .map_err(|e| io_error("seek", input, e))?;
+    let mut reader = BufReader::with_capacity(buffer_size, reader);
+
+    let mut writer = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(output)
+        .await
+        .map_err(|e| io_error("create", output, e))?;
+    writer.set_len(resume_from).await.map_err(|e| io_error("truncate", output, e))?;
+    writer
+        .seek(SeekFrom::Start(resume_from))
+        .await
+        .map_err(|e| io_error("seek", output, e))?;
+    let mut writer = BufWriter::with_capacity(buffer_size, writer);
+    if samples_done > 0 {
+        eprintln!("Resuming decryption of {} at sample {}", output.display(), samples_done);
+    }
+
+    let mut checkpoint = DecryptCheckpoint { input_len: layout.len, key_id, samples_done: samples_done as u64 };
+    checkpoint.save(output)?;
+    let mut buffer = vec![0u8; (buffer_size / 16).max(1) * 16];
+    let mut position = resume_from;
+    let mut saved_at = resume_from;
+
+    for (index, (offset, size)) in samples.iter().enumerate().skip(samples_done) {
+        copy_section(&mut reader, &mut writer, offset - position, &mut buffer, None).await?;
+        let mut cipher = Aes128CbcChunkCipher::new(key, iv)?;
+        copy_section(&mut reader, &mut writer, u64::from(size), &mut buffer, Some(&mut cipher)).await?;
+        position = offset + u64::from(size);
+
+        if position - saved_at >= buffer.len() as u64 {
+            writer.flush().await.map_err(|e| io_error("write", output, e))?;
+            checkpoint.samples_done = index as u64 + 1;
+            // A stale checkpoint only costs re-decrypting some samples on resume
+            if let Err(e) = checkpoint.save(output) {
+                eprintln!("Warning: {}", e);
+            }
+            saved_at = position;
+        }
+    }
+    copy_section(&mut reader, &mut writer, layout.len - position, &mut buffer, None).await?;
+
+    writer.flush().await.map_err(|e| io_error("write", output, e))?;
+    let mut file = writer.into_inner();
+    for (offset, atom) in [(0, &layout.ftyp), (layout.moov_offset, &layout.moov)] {
+        file.seek(SeekFrom::Start(offset)).await.map_err(|e| io_error("seek", output, e))?;
+        file.write_all(atom).await.map_err(|e| io_error("write", output, e))?;
+    }
+    file.flush().await.map_err(|e| io_error("write", output, e))?;
+
+    let _ = tokio::fs::remove_file(checkpoint_path(output)).await;
+    Ok(layout.len)
+}
+
+/// Copy `len` bytes through `buffer`, decrypting them if a cipher is given
+///
+/// `buffer` is a whole number of blocks, so only the final piece can end in a
+/// partial block, which is copied as is.
+async fn copy_section<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    mut len: u64,
+    buffer: &mut [u8],
+    mut cipher: Option<&mut dyn ChunkCipher>,
+) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    while len > 0 {
+        let piece = (buffer.len() as u64).min(len) as usize;
+        reader
+            .read_exact(&mut buffer[..piece])
+            .await
+            .map_err(|e| LibationError::FileIoError(format!("Failed to read encrypted file: {}", e)))?;
+        if let Some(cipher) = cipher.as_deref_mut() {
+            let aligned = piece - piece % cipher.block_size();
+            cipher.decrypt_chunk(&mut buffer[..aligned])?;
+        }
+        writer
+            .write_all(&buffer[..piece])
+            .await
+            .map_err(|e| LibationError::FileIoError(format!("Failed to write decrypted file: {}", e)))?;
+        len -= piece as u64;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::mp4::fixture::protected_mp4;
+    use cbc::cipher::BlockEncryptMut;
+
+    const KEY: [u8; 16] = [0x11; 16];
+    const IV: [u8; 16] = [0x22; 16];
+
+    fn encrypt(plain: &[u8]) -> Vec<u8> {
+        let mut encryptor = cbc::Encryptor::<Aes128>::new_from_slices(&KEY, &IV).unwrap();
+        let mut data = plain.to_vec();
+        let aligned = data.len() - data.len() % 16;
+        for block in data[..aligned].chunks_exact_mut(16) {
+            encryptor.encrypt_block_mut(GenericArray::from_mut_slice(block));
+        }
+        data
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_samples_resumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("book.aaxc");
+        let output = dir.path().join("book.m4b");
+
+        // Odd sizes leave a clear tail on every sample; the large one spans
+        // several buffers
+        let samples: Vec<Vec<u8>> = [45usize, 16, 7, 5000, 301]
+            .iter()
+            .map(|&len| (0..len).map(|i| (i % 239) as u8).collect())
+            .collect();
+        let (encrypted, expected) = protected_mp4(&KEY, &IV, &samples);
+        std::fs::write(&input, &encrypted).unwrap();
+
+        let written = decrypt_samples(&input, &output, &KEY, &IV, 1000).await.unwrap();
+        assert_eq!(written, encrypted.len() as u64);
+        assert_eq!(std::fs::read(&output).unwrap(), expected);
+        assert!(!checkpoint_path(&output).exists());
+
+        // An interrupted run: three samples recorded, but only two fully on disk
+        let layout = mp4::read_layout(&input).await.unwrap();
+        let ends: Vec<usize> = ProtectedSamples::from_moov(&layout.moov, layout.len)
+            .unwrap()
+            .iter()
+            .map(|(offset, size)| (offset + u64::from(size)) as usize)
+            .collect();
+        std::fs::write(&output, &expected[..ends[1] + 5]).unwrap();
+        let checkpoint = DecryptCheckpoint {
+            input_len: encrypted.len() as u64,
+            key_id: DecryptCheckpoint::key_id(&KEY, &IV),
+            samples_done: 3,
+        };
+        checkpoint.save(&output).unwrap();
+
+        // Corrupt the second sample; it must not be decrypted again
+        let mut tampered = encrypted.clone();
+        tampered[ends[1] - 16..ends[1]].fill(0);
+        std::fs::write(&input, &tampered).unwrap();
+
+        decrypt_samples(&input, &output, &KEY, &IV, 1000).await.unwrap();
+        assert_eq!(std::fs::read(&output).unwrap(), expected);
+        assert!(!checkpoint_path(&output).exists());
+
+        // A whole-file blob has no sample table to decrypt by
+        std::fs::write(&input, encrypt(&[0u8; 4096])).unwrap();
+        assert!(matches!(
+            decrypt_samples(&input, &output, &KEY, &IV, 1000).await,
+            Err(LibationError::InvalidAudioFile(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_bad_key() {
+        assert!(Aes128CbcChunkCipher::new(&[0u8; 8], &IV).is_err());
+    }
+}
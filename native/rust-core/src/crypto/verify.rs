@@ -0,0 +1,221 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Check that a decrypted file no longer carries Audible DRM
+//!
+//! # Reference C# Sources
+//! - **`AaxDecrypter/AaxcDownloadConvertBase.cs`** - Replaces the `aavd`
+//!   sample entry with `mp4a` and drops the `adrm` box when writing the output
+//!
+//! A decrypt that went wrong does not always fail: FFmpeg can copy the
+//! encrypted stream through, and a key that does not match still yields a
+//! file of the right size. [`verify_decrypted`] checks the output against the
+//! format it should be in. For M4B it reads only the MP4 atom tree, skipping
+//! the audio data, and rejects a file that is not MP4 at all or whose track is
+//! still described as protected.
+
+use crate::api::license::OutputFormat;
+use crate::crypto::mp4::{self, AUDIO_SAMPLE_ENTRY_FIELDS};
+use crate::error::{LibationError, Result};
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
+
+/// Sample entries of protected audio: Audible (`aavd`), CENC (`enca`) and
+/// iTunes FairPlay (`drms`)
+const PROTECTED_ENTRIES: [&[u8; 4]; 3] = [b"aavd", b"enca", b"drms"];
+
+/// Boxes inside a sample entry that only protected audio has
+const PROTECTION_ATOMS: [&[u8; 4]; 2] = [b"adrm", b"sinf"];
+
+/// Confirm that `path` is a playable file of the `expected` format
+///
+/// An M4B must start with `ftyp`, must not be branded `aax `, and must have a
+/// `moov` atom with at least one `mp4a` audio track and no Audible, CENC or
+/// FairPlay protection in its sample descriptions. An MP3 must start with an
+/// ID3 tag or an MPEG frame header.
+///
+/// # Arguments
+/// * `path` - Output of a decrypt
+/// * `expected` - Format the decrypt was meant to produce (M4B for AAX and
+///   AAXC, MP3 for podcasts)
+///
+/// # Errors
+/// - `DecryptionFailed` - The file is still encrypted or is not a playable
+///   file of the expected format
+/// - `FileNotFound` / `FileIoError` - The file cannot be read
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example() -> rust_core::error::Result<()> {
+/// use rust_core::api::license::OutputFormat;
+/// use rust_core::crypto::verify_decrypted;
+///
+/// verify_decrypted(std::path::Path::new("book.m4b"), OutputFormat::M4b).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn verify_decrypted(path: &Path, expected: OutputFormat) -> Result<()> {
+    match expected {
+        OutputFormat::M4b => verify_m4b(path).await,
+        OutputFormat::Mp3 => verify_mp3(path).await,
+    }
+}
+
+async fn verify_m4b(path: &Path) -> Result<()> {
+    let layout = mp4::read_layout(path).await.map_err(|e| match e {
+        LibationError::InvalidAudioFile(reason) => LibationError::DecryptionFailed(format!(
+            "{}; the decrypt did not produce a playable M4B",
+            reason
+        )),
+        other => other,
+    })?;
+    if layout.ftyp.get(8..11) == Some(b"aax".as_slice()) {
+        return Err(still_encrypted(path, b"ftyp"));
+    }
+
+    let mut has_audio = false;
+    for stbl in mp4::sample_tables(&layout.moov) {
+        for entry in mp4::sample_entries(&layout.moov, stbl) {
+            if PROTECTED_ENTRIES.contains(&&entry.kind) {
+                return Err(still_encrypted(path, &entry.kind));
+            }
+            let children = entry.body.start + AUDIO_SAMPLE_ENTRY_FIELDS..entry.body.end;
+            if let Some(child) = mp4::child_atoms(&layout.moov, children).find(|c| PROTECTION_ATOMS.contains(&&c.kind)) {
+                return Err(still_encrypted(path, &child.kind));
+            }
+            has_audio |= &entry.kind == b"mp4a";
+        }
+    }
+
+    if !has_audio {
+        return Err(LibationError::DecryptionFailed(format!(
+            "{} has no mp4a audio track; the decrypt did not produce a playable M4B",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+async fn verify_mp3(path: &Path) -> Result<()> {
+    let mut file = File::open(path)
+        .await
+        .map_err(|e| LibationError::FileNotFound(format!("{}: {}", path.display(), e)))?;
+    let mut start = [0u8; 3];
+    let read = file
+        .read(&mut start)
+        .await
+        .map_err(|e| LibationError::FileIoError(format!("{}: {}", path.display(), e)))?;
+
+    // An ID3v2 tag, or the 11-bit sync word of an MPEG audio frame
+    let is_mp3 = &start == b"ID3" || (read >= 2 && start[0] == 0xFF && start[1] & 0xE0 == 0xE0);
+    if !is_mp3 {
+        return Err(LibationError::DecryptionFailed(format!(
+            "{} does not start with an ID3 tag or MPEG frame; it is not a playable MP3",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+fn still_encrypted(path: &Path, atom: &[u8; 4]) -> LibationError {
+    LibationError::DecryptionFailed(format!(
+        "{} is still encrypted ('{}' atom found)",
+        path.display(),
+        String::from_utf8_lossy(atom)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::mp4::fixture::atom;
+
+    /// ftyp, mdat and a moov whose only sample entry is `entry`
+    fn mp4(brand: &[u8; 4], entry: Vec<u8>) -> Vec<u8> {
+        let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1];
+        stsd.extend(entry);
+        let stbl = atom(b"stbl", &atom(b"stsd", &stsd));
+        let trak = atom(b"trak", &atom(b"mdia", &atom(b"minf", &stbl)));
+
+        let mut file = atom(b"ftyp", &[brand.as_slice(), &[0; 4]].concat());
+        file.extend(atom(b"mdat", &[0xAB; 64]));
+        file.extend(atom(b"moov", &trak));
+        file
+    }
+
+    fn sample_entry(kind: &[u8; 4], children: &[u8]) -> Vec<u8> {
+        atom(kind, &[&[0u8; AUDIO_SAMPLE_ENTRY_FIELDS][..], children].concat())
+    }
+
+    #[tokio::test]
+    async fn test_verify_decrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let check = |name: &str, bytes: Vec<u8>| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, bytes).unwrap();
+            async move { verify_decrypted(&path, OutputFormat::M4b).await }
+        };
+
+        check("clear.m4b", mp4(b"M4B ", sample_entry(b"mp4a", &atom(b"esds", &[0; 4])))).await.unwrap();
+
+        let still_aax = mp4(b"M4B ", sample_entry(b"aavd", &atom(b"adrm", &[0; 8])));
+        let err = check("aavd.m4b", still_aax).await.unwrap_err();
+        assert!(matches!(err, LibationError::DecryptionFailed(ref m) if m.contains("'aavd'")), "{}", err);
+
+        let adrm_only = mp4(b"M4B ", sample_entry(b"mp4a", &atom(b"adrm", &[0; 8])));
+        assert!(check("adrm.m4b", adrm_only).await.is_err());
+        assert!(check("brand.m4b", mp4(b"aax ", sample_entry(b"mp4a", &[]))).await.is_err());
+
+        let mut no_moov = atom(b"ftyp", b"M4B \0\0\0\0");
+        no_moov.extend(atom(b"mdat", &[0; 16]));
+        assert!(matches!(check("empty.m4b", no_moov).await, Err(LibationError::DecryptionFailed(_))));
+
+        // A chapter track alone is not audio
+        let text_only = mp4(b"M4B ", atom(b"text", &[0; 16]));
+        assert!(matches!(check("text.m4b", text_only).await, Err(LibationError::DecryptionFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_random_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("garbage.m4b");
+        // Deterministic noise that starts with neither ftyp, ID3 nor a frame sync
+        let mut state = 0x2545_F491u32;
+        let noise: Vec<u8> = (0..8192)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 24) as u8 & 0x7F
+            })
+            .collect();
+        std::fs::write(&path, &noise).unwrap();
+
+        for format in [OutputFormat::M4b, OutputFormat::Mp3] {
+            let result = verify_decrypted(&path, format).await;
+            assert!(matches!(result, Err(LibationError::DecryptionFailed(_))), "{:?}: {:?}", format, result);
+        }
+
+        std::fs::write(&path, b"ID3\x03\x00\x00\x00\x00\x00\x00audio").unwrap();
+        verify_decrypted(&path, OutputFormat::Mp3).await.unwrap();
+        assert!(verify_decrypted(&path, OutputFormat::M4b).await.is_err());
+    }
+}
@@ -0,0 +1,263 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Batch downloads with per-book outcome reporting
+//!
+//! # Reference C# Sources
+//! - `LibationUiBase/ProcessQueue/` - Libation's process queue moves on to the
+//!   next book when one fails and keeps the failed entry for review
+//!
+//! A [`DownloadBatch`] records what happened to each book when a batch was
+//! enqueued: the task it became, or why it never got one (license refused,
+//! duplicate, already downloaded). Once the downloads run,
+//! [`PersistentDownloadManager::batch_summary`](crate::download::PersistentDownloadManager::batch_summary)
+//! folds in the task states to give a [`BatchSummary`] with a reason for every
+//! book that did not succeed. The batch is plain JSON, so the app can hold on
+//! to it across bridge calls.
+//!
+//! Before a batch is started,
+//! [`estimate_batch_size`](crate::download::PersistentDownloadManager::estimate_batch_size)
+//! gives a [`BatchSizeEstimate`] of the space it will take.
+
+use crate::download::persistent_manager::TaskStatus;
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One book to download as part of a batch
+///
+/// Same fields as [`enqueue_download`](crate::download::PersistentDownloadManager::enqueue_download).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadRequest {
+    pub asin: String,
+    pub title: String,
+    pub download_url: String,
+    pub total_bytes: u64,
+    pub download_path: String,
+    pub output_path: String,
+    #[serde(default)]
+    pub request_headers: HashMap<String, String>,
+}
+
+/// What happened to one book when the batch was enqueued
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchEntry {
+    pub asin: String,
+    pub title: String,
+    /// Task tracking the download, if one was created or reused
+    pub task_id: Option<String>,
+    /// Why no download is needed
+    pub skipped: Option<String>,
+    /// Why the book could not be enqueued
+    pub error: Option<String>,
+}
+
+/// Books submitted together, as returned by
+/// [`enqueue_batch`](crate::download::PersistentDownloadManager::enqueue_batch)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DownloadBatch {
+    pub entries: Vec<BatchEntry>,
+}
+
+impl DownloadBatch {
+    /// Record a book that failed before it could be enqueued
+    ///
+    /// Use this for failures on the caller's side, such as a refused license
+    /// request, so they appear in the summary with the rest of the batch.
+    pub fn record_failure(&mut self, asin: impl Into<String>, title: impl Into<String>, reason: impl Into<String>) {
+        self.entries.push(BatchEntry {
+            asin: asin.into(),
+            title: title.into(),
+            task_id: None,
+            skipped: None,
+            error: Some(reason.into()),
+        });
+    }
+}
+
+/// Final state of one book in a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchOutcome {
+    Succeeded,
+    Failed,
+    Skipped,
+    /// Still queued, downloading or paused
+    Pending,
+}
+
+/// Outcome of one book with the reason when it did not succeed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchItemResult {
+    pub asin: String,
+    pub title: String,
+    pub task_id: Option<String>,
+    pub outcome: BatchOutcome,
+    pub reason: Option<String>,
+}
+
+/// Succeeded/failed/skipped breakdown of a batch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchSummary {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub pending: usize,
+    /// Every book in submission order
+    pub items: Vec<BatchItemResult>,
+}
+
+impl BatchSummary {
+    pub(crate) fn from_items(items: Vec<BatchItemResult>) -> Self {
+        let count = |outcome| items.iter().filter(|i| i.outcome == outcome).count();
+        Self {
+            succeeded: count(BatchOutcome::Succeeded),
+            failed: count(BatchOutcome::Failed),
+            skipped: count(BatchOutcome::Skipped),
+            pending: count(BatchOutcome::Pending),
+            items,
+        }
+    }
+
+    /// Whether every book has reached a final state
+    pub fn is_finished(&self) -> bool {
+        self.pending == 0
+    }
+
+    /// Books that failed, with their reasons
+    pub fn failures(&self) -> impl Iterator<Item = &BatchItemResult> {
+        self.items.iter().filter(|i| i.outcome == BatchOutcome::Failed)
+    }
+}
+
+impl BatchItemResult {
+    /// Outcome of a book whose task was found, from its status and error
+    pub(crate) fn from_task_status(entry: &BatchEntry, status: &TaskStatus, error: Option<String>) -> Self {
+        let (outcome, reason) = match status {
+            TaskStatus::Completed => (BatchOutcome::Succeeded, None),
+            TaskStatus::Failed => (
+                BatchOutcome::Failed,
+                Some(error.unwrap_or_else(|| "Download failed".to_string())),
+            ),
+            TaskStatus::Cancelled => (BatchOutcome::Skipped, Some("Cancelled".to_string())),
+            TaskStatus::Queued | TaskStatus::Downloading | TaskStatus::Paused => {
+                (BatchOutcome::Pending, None)
+            }
+        };
+        Self::new(entry, outcome, reason)
+    }
+
+    pub(crate) fn new(entry: &BatchEntry, outcome: BatchOutcome, reason: Option<String>) -> Self {
+        Self {
+            asin: entry.asin.clone(),
+            title: entry.title.clone(),
+            task_id: entry.task_id.clone(),
+            outcome,
+            reason,
+        }
+    }
+}
+
+/// Download size of one book in a [`BatchSizeEstimate`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookSize {
+    pub asin: String,
+    /// Size of the encrypted download
+    pub bytes: Option<u64>,
+    /// Why the size could not be found
+    pub error: Option<String>,
+}
+
+/// Space a batch of downloads will take
+///
+/// Books that could not be sized are listed with the reason and left out of
+/// `total_bytes`, so the total is a lower bound when any failed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchSizeEstimate {
+    pub total_bytes: u64,
+    /// Every book in submission order
+    pub books: Vec<BookSize>,
+}
+
+impl BatchSizeEstimate {
+    pub(crate) fn from_results(results: Vec<(String, Result<u64>)>) -> Self {
+        let books: Vec<BookSize> = results
+            .into_iter()
+            .map(|(asin, result)| match result {
+                Ok(bytes) => BookSize { asin, bytes: Some(bytes), error: None },
+                Err(e) => BookSize { asin, bytes: None, error: Some(e.to_string()) },
+            })
+            .collect();
+        Self {
+            total_bytes: books.iter().filter_map(|b| b.bytes).sum(),
+            books,
+        }
+    }
+
+    /// Whether every book was sized, so `total_bytes` is the full batch
+    pub fn is_complete(&self) -> bool {
+        self.books.iter().all(|b| b.bytes.is_some())
+    }
+
+    /// Books that could not be sized
+    pub fn failures(&self) -> impl Iterator<Item = &BookSize> {
+        self.books.iter().filter(|b| b.error.is_some())
+    }
+}
+
+impl std::fmt::Display for BatchSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} succeeded, {} failed, {} skipped, {} pending",
+            self.succeeded, self.failed, self.skipped, self.pending
+        )?;
+        for item in self.items.iter().filter(|i| i.reason.is_some()) {
+            writeln!(
+                f,
+                "  {} ({}) {:?}: {}",
+                item.asin,
+                item.title,
+                item.outcome,
+                item.reason.as_deref().unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::LibationError;
+
+    #[test]
+    fn test_batch_size_estimate() {
+        let estimate = BatchSizeEstimate::from_results(vec![
+            ("B001".to_string(), Ok(2_100_000_000)),
+            ("B002".to_string(), Err(LibationError::MissingOfflineUrl)),
+            ("B003".to_string(), Ok(2_100_000_000)),
+        ]);
+        assert_eq!(estimate.total_bytes, 4_200_000_000);
+        assert!(!estimate.is_complete());
+        let failed: Vec<_> = estimate.failures().map(|b| b.asin.as_str()).collect();
+        assert_eq!(failed, ["B002"]);
+    }
+}
@@ -0,0 +1,123 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Request headers for the CDN a download URL points at
+//!
+//! # Reference C# Sources
+//! - **`AaxDecrypter/NetworkFileStream.cs`** - Sends the stored `RequestHeaders`
+//!   with every range request
+//!
+//! Audible signs download URLs for more than one CDN, and the license does
+//! not say which. CloudFront and Audible's own hosts only check the
+//! signature and the app's `User-Agent`; Akamai edge hosts also refuse
+//! requests without an Audible `Referer`. [`download_headers`] picks the set
+//! from the URL's host, so the same task works whichever CDN it was given.
+
+use std::collections::HashMap;
+
+/// User agent of the iOS app, which every Audible CDN accepts
+pub const DOWNLOAD_USER_AGENT: &str = "Audible/671 CFNetwork/1240.0.4 Darwin/20.6.0";
+
+/// Referer sent to CDNs that check where a request came from
+const AUDIBLE_REFERER: &str = "https://www.audible.com/";
+
+/// CDN serving a download URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cdn {
+    /// Amazon CloudFront (`*.cloudfront.net`, `*-cf.*` hosts)
+    CloudFront,
+    /// Akamai (`*.akamaihd.net`, `*.akamaized.net`, `*-ak.*` hosts)
+    Akamai,
+    /// Any other host on an Audible domain
+    Audible,
+    /// Not an Audible CDN, e.g. a test server
+    Other,
+}
+
+impl Cdn {
+    /// Identify the CDN from a download URL
+    ///
+    /// URLs that cannot be parsed are [`Cdn::Other`].
+    pub fn from_url(url: &str) -> Self {
+        let host = match reqwest::Url::parse(url) {
+            Ok(parsed) => parsed.host_str().unwrap_or_default().to_ascii_lowercase(),
+            Err(_) => return Cdn::Other,
+        };
+        let first_label = host.split('.').next().unwrap_or_default();
+
+        if host.ends_with(".cloudfront.net") || first_label.ends_with("-cf") {
+            Cdn::CloudFront
+        } else if host.ends_with(".akamaihd.net")
+            || host.ends_with(".akamaized.net")
+            || first_label.ends_with("-ak")
+        {
+            Cdn::Akamai
+        } else if host.split('.').any(|label| label == "audible") {
+            Cdn::Audible
+        } else {
+            Cdn::Other
+        }
+    }
+
+    /// Headers to send with every request to this CDN
+    pub fn headers(self) -> HashMap<String, String> {
+        let mut headers = HashMap::from([("User-Agent".to_string(), DOWNLOAD_USER_AGENT.to_string())]);
+        if self == Cdn::Akamai {
+            headers.insert("Referer".to_string(), AUDIBLE_REFERER.to_string());
+        }
+        headers
+    }
+}
+
+/// Headers to download `url` with, chosen by the CDN it points at
+///
+/// Store them with the task; when the URL is refreshed the new one may be
+/// on another CDN, so compute them again.
+///
+/// # Example
+/// ```rust,no_run
+/// use rust_core::download::cdn::download_headers;
+///
+/// let headers = download_headers("https://d1jobzhhm62zby.cloudfront.net/book.aaxc?Signature=...");
+/// assert!(headers.contains_key("User-Agent"));
+/// ```
+pub fn download_headers(url: &str) -> HashMap<String, String> {
+    Cdn::from_url(url).headers()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headers_follow_cdn() {
+        assert_eq!(Cdn::from_url("https://d1jobzhhm62zby.cloudfront.net/a.aaxc?Expires=1"), Cdn::CloudFront);
+        assert_eq!(Cdn::from_url("https://dcs-cf.cdn.audible.com/a.aaxc"), Cdn::CloudFront);
+        assert_eq!(Cdn::from_url("https://DCS-AK.cdn.audible.co.uk/a.aaxc"), Cdn::Akamai);
+        assert_eq!(Cdn::from_url("https://cds.audible.de/download"), Cdn::Audible);
+        assert_eq!(Cdn::from_url("http://127.0.0.1:8080/book.aaxc"), Cdn::Other);
+        assert_eq!(Cdn::from_url("not a url"), Cdn::Other);
+
+        let akamai = download_headers("https://audible.akamaized.net/a.aaxc");
+        assert_eq!(akamai["Referer"], AUDIBLE_REFERER);
+        assert_eq!(akamai["User-Agent"], DOWNLOAD_USER_AGENT);
+        assert!(!download_headers("https://d1jobzhhm62zby.cloudfront.net/a.aaxc").contains_key("Referer"));
+    }
+}
@@ -0,0 +1,202 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Awaitable final result of a book's download
+//!
+//! # Reference C# Sources
+//! - **`FileLiberator/Processable.cs`** - `ProcessSingleAsync` returns a
+//!   `StatusHandler` once the book is done, separate from the progress events
+//!
+//! Progress callbacks fire many times per download and say nothing final.
+//! [`PersistentDownloadManager::wait_for_result`](super::PersistentDownloadManager::wait_for_result)
+//! hands out a [`DownloadResult`] instead: a future that resolves once, when
+//! the book's download completes, fails or is cancelled. Pausing does not
+//! resolve it; the future keeps waiting for the resumed download.
+
+use crate::audio::decoder::{AudioDecoder, AudioFormat, AudiobookFile};
+use crate::error::{LibationError, Result};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use tokio::sync::oneshot;
+
+/// Final result of one book's download
+///
+/// Resolves to the downloaded file, or to `DownloadFailed` with the reason.
+/// A cancelled download, or a manager dropped while the book was still
+/// downloading, resolves to `Cancelled`.
+#[derive(Debug)]
+pub struct DownloadResult {
+    receiver: oneshot::Receiver<Result<AudiobookFile>>,
+}
+
+impl DownloadResult {
+    /// A result that is already known
+    pub(crate) fn ready(result: Result<AudiobookFile>) -> Self {
+        let (sender, receiver) = oneshot::channel();
+        let _ = sender.send(result);
+        Self { receiver }
+    }
+}
+
+impl Future for DownloadResult {
+    type Output = Result<AudiobookFile>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver)
+            .poll(cx)
+            .map(|received| received.unwrap_or(Err(LibationError::Cancelled)))
+    }
+}
+
+/// Callers waiting for a book, by ASIN
+#[derive(Debug, Default)]
+pub(crate) struct CompletionWaiters {
+    waiters: Mutex<HashMap<String, Vec<oneshot::Sender<Result<AudiobookFile>>>>>,
+}
+
+impl CompletionWaiters {
+    fn take(&self, asin: &str) -> Vec<oneshot::Sender<Result<AudiobookFile>>> {
+        self.waiters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(asin)
+            .unwrap_or_default()
+    }
+
+    /// Wait for the next final result of `asin`
+    pub(crate) fn subscribe(&self, asin: &str) -> DownloadResult {
+        let (sender, receiver) = oneshot::channel();
+        self.waiters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(asin.to_string())
+            .or_default()
+            .push(sender);
+        DownloadResult { receiver }
+    }
+
+    pub(crate) fn complete(&self, asin: &str, file: &AudiobookFile) {
+        for waiter in self.take(asin) {
+            let _ = waiter.send(Ok(file.clone()));
+        }
+    }
+
+    pub(crate) fn fail(&self, asin: &str, reason: &str) {
+        for waiter in self.take(asin) {
+            let _ = waiter.send(Err(LibationError::DownloadFailed(reason.to_string())));
+        }
+    }
+
+    /// Resolve every waiter for `asin` with `Cancelled`
+    pub(crate) fn cancel(&self, asin: &str) {
+        self.take(asin);
+    }
+
+    /// Forget waiters for `asin` whose result was dropped unawaited
+    pub(crate) fn release(&self, asin: &str) {
+        let mut waiters = self.waiters.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(senders) = waiters.get_mut(asin) {
+            senders.retain(|sender| !sender.is_closed());
+            if senders.is_empty() {
+                waiters.remove(asin);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn waiting(&self, asin: &str) -> usize {
+        self.waiters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(asin)
+            .map_or(0, Vec::len)
+    }
+}
+
+/// Describe the file a finished download produced
+///
+/// Playable files are probed for duration and chapters. Encrypted downloads,
+/// or any file when FFprobe is not available, are described from the file
+/// system alone with a duration of 0.
+pub(crate) async fn describe_download(path: &Path) -> Result<AudiobookFile> {
+    let size = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| LibationError::FileNotFound(format!("{}: {}", path.display(), e)))?
+        .len();
+    let format = AudioDecoder::detect_format(path).await.unwrap_or(AudioFormat::Unknown);
+
+    if !format.is_encrypted() && format != AudioFormat::Unknown {
+        match AudiobookFile::from_file(path, None).await {
+            Err(LibationError::FfmpegNotFound) => {}
+            probed => return probed,
+        }
+    }
+
+    Ok(AudiobookFile {
+        path: path.to_path_buf(),
+        format,
+        size,
+        duration: 0.0,
+        chapters: Vec::new(),
+        cover_path: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_waiters_resolve_once() {
+        let waiters = CompletionWaiters::default();
+        let first = waiters.subscribe("B001");
+        let second = waiters.subscribe("B001");
+        let other = waiters.subscribe("B002");
+
+        waiters.fail("B001", "HTTP 403");
+        for result in [first.await, second.await] {
+            assert!(matches!(result, Err(LibationError::DownloadFailed(reason)) if reason == "HTTP 403"));
+        }
+
+        waiters.cancel("B002");
+        assert!(matches!(other.await, Err(LibationError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_release_keeps_live_waiters() {
+        let waiters = CompletionWaiters::default();
+        let dropped = waiters.subscribe("B001");
+        let kept = waiters.subscribe("B001");
+
+        drop(dropped);
+        waiters.release("B001");
+        assert_eq!(waiters.waiting("B001"), 1);
+
+        waiters.fail("B001", "HTTP 403");
+        assert!(matches!(kept.await, Err(LibationError::DownloadFailed(_))));
+
+        waiters.release("B001");
+        assert_eq!(waiters.waiting("B001"), 0);
+    }
+}
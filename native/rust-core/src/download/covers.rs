@@ -0,0 +1,240 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Cover art prefetching with bounded concurrency and pacing
+//!
+//! # Reference C# Sources
+//! - **`LibationFileManager/PictureStorage.cs`** - Downloads covers one at a
+//!   time on a background thread
+//!
+//! The image CDN throttles clients that fetch hundreds of covers in a burst,
+//! which happens on the first sync of a large library. A [`CoverPrefetcher`]
+//! caps the number of requests in flight with a semaphore and spaces request
+//! starts by a small delay. `429` and `503` answers are retried with
+//! [`API_BACKOFF`] instead of failing the cover.
+//!
+//! Clones share the same semaphore and schedule, so every caller that
+//! prefetches through one prefetcher stays within the same limits.
+
+use crate::backoff::API_BACKOFF;
+use crate::error::{LibationError, Result};
+use futures_util::stream::{self, Stream, StreamExt};
+use reqwest::{Client, StatusCode};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::time::{sleep_until, Instant};
+
+/// Default number of covers downloaded at once
+pub const DEFAULT_COVER_CONCURRENCY: usize = 4;
+
+/// Default minimum gap between the starts of two cover requests
+pub const DEFAULT_COVER_DELAY: Duration = Duration::from_millis(100);
+
+/// Retries for a cover the CDN answered with `429` or `503`
+const MAX_THROTTLE_RETRIES: u32 = 3;
+
+/// Outcome of prefetching one cover
+#[derive(Debug)]
+pub struct CoverFetch {
+    pub asin: String,
+    pub url: String,
+    /// Image bytes, or why they could not be downloaded
+    pub image: Result<Vec<u8>>,
+}
+
+/// Downloads cover images politely
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example(covers: Vec<(String, String)>) {
+/// use futures_util::StreamExt;
+/// use rust_core::download::covers::CoverPrefetcher;
+/// use std::time::Duration;
+///
+/// let prefetcher = CoverPrefetcher::new(reqwest::Client::new(), 4, Duration::from_millis(150));
+/// let fetched: Vec<_> = prefetcher.prefetch(covers).collect().await;
+/// let failed = fetched.iter().filter(|c| c.image.is_err()).count();
+/// println!("{} of {} covers failed", failed, fetched.len());
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CoverPrefetcher {
+    http: Client,
+    concurrency: usize,
+    delay: Duration,
+    permits: Arc<Semaphore>,
+    next_start: Arc<Mutex<Instant>>,
+}
+
+impl CoverPrefetcher {
+    /// Create a prefetcher
+    ///
+    /// # Arguments
+    /// * `http` - Client for the requests (covers need no authentication)
+    /// * `concurrency` - Covers downloaded at once (0 is treated as 1)
+    /// * `delay` - Minimum gap between request starts
+    pub fn new(http: Client, concurrency: usize, delay: Duration) -> Self {
+        let concurrency = concurrency.max(1);
+        Self {
+            http,
+            concurrency,
+            delay,
+            permits: Arc::new(Semaphore::new(concurrency)),
+            next_start: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Prefetcher with [`DEFAULT_COVER_CONCURRENCY`] and [`DEFAULT_COVER_DELAY`]
+    pub fn with_defaults(http: Client) -> Self {
+        Self::new(http, DEFAULT_COVER_CONCURRENCY, DEFAULT_COVER_DELAY)
+    }
+
+    /// Download one cover, waiting for a free slot and its turn first
+    ///
+    /// # Errors
+    /// - `RateLimitExceeded` - Still throttled after the retries
+    /// - `UnexpectedStatusCode` - Any other non-success status
+    /// - `ReqwestError` - The request failed
+    pub async fn fetch(&self, url: &str) -> Result<Vec<u8>> {
+        let _permit = self.permits.acquire().await.map_err(|e| {
+            LibationError::InternalError(format!("Semaphore acquire failed: {}", e))
+        })?;
+
+        let mut attempt = 0;
+        loop {
+            self.wait_turn().await;
+            let response = self.http.get(url).send().await?;
+            let status = response.status();
+
+            if status.is_success() {
+                return Ok(response.bytes().await?.to_vec());
+            }
+            let throttled = matches!(status, StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE);
+            if !throttled {
+                return Err(LibationError::UnexpectedStatusCode {
+                    status_code: status.as_u16(),
+                    host: response.url().host_str().unwrap_or_default().to_string(),
+                });
+            }
+            if attempt >= MAX_THROTTLE_RETRIES {
+                return Err(LibationError::RateLimitExceeded {
+                    retry_after_seconds: API_BACKOFF.base_delay(attempt).as_secs(),
+                    endpoint: url.to_string(),
+                });
+            }
+
+            API_BACKOFF.wait(attempt).await;
+            attempt += 1;
+        }
+    }
+
+    /// Download covers for `(asin, url)` pairs
+    ///
+    /// Yields each cover as it completes, so callers can report progress. A
+    /// failed cover is reported in its [`CoverFetch`] and does not stop the
+    /// others.
+    pub fn prefetch(&self, covers: Vec<(String, String)>) -> impl Stream<Item = CoverFetch> + '_ {
+        stream::iter(covers)
+            .map(move |(asin, url)| async move {
+                let image = self.fetch(&url).await;
+                CoverFetch { asin, url, image }
+            })
+            .buffer_unordered(self.concurrency)
+    }
+
+    /// Sleep until this request may start, and reserve the next slot
+    async fn wait_turn(&self) {
+        let start = {
+            let mut next_start = self.next_start.lock().await;
+            let start = (*next_start).max(Instant::now());
+            *next_start = start + self.delay;
+            start
+        };
+        sleep_until(start).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve a tiny cover on every request, tracking the peak number of open requests
+    async fn serve_covers(throttle_first: bool) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let peak = Arc::new(AtomicUsize::new(0));
+        let open = Arc::new(AtomicUsize::new(0));
+        let served = Arc::new(AtomicUsize::new(0));
+        let seen_peak = peak.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let (peak, open, served) = (seen_peak.clone(), open.clone(), served.clone());
+                tokio::spawn(async move {
+                    let now_open = open.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now_open, Ordering::SeqCst);
+                    let mut buf = [0u8; 1024];
+                    let _ = socket.read(&mut buf).await;
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+
+                    let response = if throttle_first && served.fetch_add(1, Ordering::SeqCst) == 0 {
+                        "HTTP/1.1 429 Too Many Requests\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    } else {
+                        "HTTP/1.1 200 OK\r\nContent-Length: 3\r\nConnection: close\r\n\r\njpg"
+                    };
+                    open.fetch_sub(1, Ordering::SeqCst);
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+        (format!("http://{}", addr), peak)
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_limits_concurrency() {
+        let (base, peak) = serve_covers(false).await;
+        let covers: Vec<(String, String)> = (0..12)
+            .map(|i| (format!("B{:03}", i), format!("{}/{}.jpg", base, i)))
+            .collect();
+
+        let prefetcher = CoverPrefetcher::new(Client::new(), 3, Duration::from_millis(5));
+        let started = Instant::now();
+        let fetched: Vec<CoverFetch> = prefetcher.prefetch(covers).collect().await;
+
+        assert_eq!(fetched.len(), 12);
+        assert!(fetched.iter().all(|c| c.image.as_deref().ok() == Some(&b"jpg"[..])));
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        // Request starts are spaced by the delay
+        assert!(started.elapsed() >= Duration::from_millis(55));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fetch_retries_when_throttled() {
+        let (base, _) = serve_covers(true).await;
+        let prefetcher = CoverPrefetcher::with_defaults(Client::new());
+
+        let image = prefetcher.fetch(&format!("{}/cover.jpg", base)).await.unwrap();
+        assert_eq!(image, b"jpg");
+    }
+}
@@ -0,0 +1,224 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Download throughput diagnostics
+//!
+//! # Reference C# Sources
+//! - `AaxDecrypter/AverageSpeed.cs` - Libation tracks speed per download for
+//!   its progress bar; this adds a batch-wide view for debugging
+//!
+//! A [`DownloadDiagnostics`] report is a snapshot of the manager: concurrency
+//! limits, open connections, queue depth, and per-download throughput measured
+//! over the current session. It serializes to JSON so the app can attach it to
+//! a bug report, and implements `Display` for logging.
+
+use crate::download::persistent_manager::{DownloadTask, TaskStatus};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// In-memory transfer counters for one download in this session
+#[derive(Debug, Clone)]
+pub(crate) struct TransferStats {
+    pub started: Instant,
+    pub finished: Option<Instant>,
+    /// Bytes received since `started` (excludes bytes from earlier sessions)
+    pub session_bytes: u64,
+}
+
+impl TransferStats {
+    pub(crate) fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            finished: None,
+            session_bytes: 0,
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(Instant::now) - self.started
+    }
+}
+
+/// Throughput of a single download
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadThroughput {
+    pub task_id: String,
+    pub asin: String,
+    pub status: TaskStatus,
+    pub bytes_downloaded: u64,
+    pub total_bytes: u64,
+    /// Bytes received in this session
+    pub session_bytes: u64,
+    /// Seconds since this session's transfer started
+    pub elapsed_secs: f64,
+    /// Average speed over this session
+    pub bytes_per_sec: f64,
+    pub retry_count: i32,
+    pub error: Option<String>,
+}
+
+/// Snapshot of download manager activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadDiagnostics {
+    /// When the snapshot was taken (RFC 3339)
+    pub generated_at: String,
+    /// Download slots
+    pub max_concurrent: usize,
+    /// Decrypt slots
+    pub max_concurrent_decrypts: usize,
+    /// Downloads with an open HTTP connection
+    pub active_connections: usize,
+    /// Tasks waiting for a download slot
+    pub queued: usize,
+    /// Sum of `session_bytes` over all downloads
+    pub session_bytes: u64,
+    /// Combined speed of the downloads currently transferring
+    pub aggregate_bytes_per_sec: f64,
+    /// Sum of retry counts over the reported downloads
+    pub total_retries: i32,
+    /// Downloads that transferred data this session, slowest first
+    pub downloads: Vec<DownloadThroughput>,
+}
+
+impl DownloadThroughput {
+    pub(crate) fn from_task(task: &DownloadTask, stats: &TransferStats) -> Self {
+        let elapsed_secs = stats.elapsed().as_secs_f64();
+        let bytes_per_sec = if elapsed_secs > 0.0 {
+            stats.session_bytes as f64 / elapsed_secs
+        } else {
+            0.0
+        };
+
+        Self {
+            task_id: task.task_id.clone(),
+            asin: task.asin.clone(),
+            status: task.status.clone(),
+            bytes_downloaded: task.bytes_downloaded,
+            total_bytes: task.total_bytes,
+            session_bytes: stats.session_bytes,
+            elapsed_secs,
+            bytes_per_sec,
+            retry_count: task.retry_count,
+            error: task.error.clone(),
+        }
+    }
+}
+
+impl DownloadDiagnostics {
+    /// Assemble a report; `downloads` is sorted slowest first
+    pub(crate) fn new(
+        max_concurrent: usize,
+        max_concurrent_decrypts: usize,
+        active_connections: usize,
+        queued: usize,
+        mut downloads: Vec<DownloadThroughput>,
+    ) -> Self {
+        downloads.sort_by(|a, b| a.bytes_per_sec.total_cmp(&b.bytes_per_sec));
+
+        Self {
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            max_concurrent,
+            max_concurrent_decrypts,
+            active_connections,
+            queued,
+            session_bytes: downloads.iter().map(|d| d.session_bytes).sum(),
+            aggregate_bytes_per_sec: downloads
+                .iter()
+                .filter(|d| d.status == TaskStatus::Downloading)
+                .map(|d| d.bytes_per_sec)
+                .sum(),
+            total_retries: downloads.iter().map(|d| d.retry_count).sum(),
+            downloads,
+        }
+    }
+}
+
+impl std::fmt::Display for DownloadDiagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Downloads: {}/{} connections, {} queued, {} decrypt slots",
+            self.active_connections, self.max_concurrent, self.queued, self.max_concurrent_decrypts
+        )?;
+        writeln!(
+            f,
+            "Aggregate: {:.2} MB/s, {:.1} MB this session, {} retries",
+            self.aggregate_bytes_per_sec / 1_048_576.0,
+            self.session_bytes as f64 / 1_048_576.0,
+            self.total_retries
+        )?;
+        for d in &self.downloads {
+            writeln!(
+                f,
+                "  {} [{}] {:.2} MB/s over {:.0}s, {}/{} bytes, {} retries{}",
+                d.asin,
+                d.status.as_str(),
+                d.bytes_per_sec / 1_048_576.0,
+                d.elapsed_secs,
+                d.bytes_downloaded,
+                d.total_bytes,
+                d.retry_count,
+                d.error.as_ref().map(|e| format!(" ({})", e)).unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throughput(asin: &str, status: TaskStatus, bytes_per_sec: f64, retries: i32) -> DownloadThroughput {
+        DownloadThroughput {
+            task_id: asin.to_string(),
+            asin: asin.to_string(),
+            status,
+            bytes_downloaded: 0,
+            total_bytes: 0,
+            session_bytes: 1000,
+            elapsed_secs: 1.0,
+            bytes_per_sec,
+            retry_count: retries,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_report_aggregates() {
+        let report = DownloadDiagnostics::new(
+            3,
+            2,
+            2,
+            4,
+            vec![
+                throughput("FAST", TaskStatus::Downloading, 900.0, 0),
+                throughput("SLOW", TaskStatus::Downloading, 100.0, 2),
+                throughput("DONE", TaskStatus::Completed, 500.0, 1),
+            ],
+        );
+
+        assert_eq!(report.downloads[0].asin, "SLOW");
+        assert_eq!(report.aggregate_bytes_per_sec, 1000.0);
+        assert_eq!(report.session_bytes, 3000);
+        assert_eq!(report.total_retries, 3);
+        assert!(report.to_string().contains("2/3 connections, 4 queued"));
+    }
+}
@@ -54,6 +54,46 @@
 //! - Automatically recovers from app restarts
 //! - Supports cancellation with proper task cleanup
 //!
+//! ### DownloadBatch (batch.rs)
+//! Enqueues many books without stopping at the first failure and reports
+//! which succeeded, failed or were skipped, with a reason for each
+//!
+//! ### DownloadDiagnostics (diagnostics.rs)
+//! Throughput, connection and retry snapshot of the manager for debugging
+//! slow batches
+//!
+//! ### download_headers (cdn.rs)
+//! Picks the request headers for the CDN a signed download URL points at
+//!
+//! ### probe_url (probe.rs)
+//! HEAD request helper returning size, content type, range support and
+//! last-modified for a download URL
+//!
+//! ### Response checks (validate.rs)
+//! Rejects HTML/XML error pages served with a 200 status before they are
+//! written to disk
+//!
+//! ### Companion PDFs (supplements.rs)
+//! Reference: DownloadPdf.cs - downloads accompanying PDFs next to the audio,
+//! named with the same path template
+//!
+//! ### CoverPrefetcher (covers.rs)
+//! Downloads many cover images with a concurrency cap and spacing between
+//! requests, so a first library sync does not get throttled by the image CDN
+//!
+//! ### DownloadSettings (settings.rs)
+//! Default quality, output format, naming pattern, trimming, chapter
+//! splitting and directories, set once on the manager and applied by
+//! `enqueue_book`
+//!
+//! ### MultiPartDownload (parts.rs)
+//! Books delivered as several files: one task per part, joined into one file
+//! afterwards or kept separate depending on the settings
+//!
+//! ### Title cache (titles.rs)
+//! ASIN to title lookup loaded from the database, so progress reports can be
+//! built from the ASIN alone
+//!
 //! ## Download Flow
 //!
 //! 1. **License Request** - Get download voucher/license from API
@@ -89,9 +129,31 @@
 //!    - Report 100% complete
 
 pub mod stream;
+pub mod batch;
+pub mod cdn;
+pub mod completion;
+pub mod covers;
+pub mod diagnostics;
+pub mod parts;
 pub mod progress;
 pub mod persistent_manager;
+pub mod probe;
+pub mod settings;
+pub mod supplements;
+pub mod titles;
+pub mod validate;
 
 // Re-export commonly used types
 pub use progress::DownloadProgress;
-pub use persistent_manager::{PersistentDownloadManager, DownloadTask, TaskStatus};
+pub use batch::{BatchOutcome, BatchSizeEstimate, BatchSummary, DownloadBatch, DownloadRequest};
+pub use completion::DownloadResult;
+pub use covers::CoverPrefetcher;
+pub use diagnostics::{DownloadDiagnostics, DownloadThroughput};
+pub use parts::MultiPartDownload;
+pub use persistent_manager::{
+    DownloadRepairReport, PersistentDownloadManager, DownloadTask, MasterUpdate, TaskStatus,
+};
+pub use probe::{probe_url, probe_url_with_headers, UrlInfo};
+pub use settings::DownloadSettings;
+pub use validate::{check_file_integrity, IntegrityIssue};
+pub use supplements::download_companion_pdfs;
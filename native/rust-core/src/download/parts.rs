@@ -0,0 +1,60 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+
+//! Books Audible delivers as several files
+//!
+//! # Reference C# Sources
+//! - **`AaxDecrypter/AaxcDownloadMultiConverter.cs`** - Converts a book made
+//!   of several parts
+//!
+//! A `MultiPartBook` in the library has no audio of its own; each part has
+//! its own ASIN and license.
+//! [`enqueue_book_parts`](super::PersistentDownloadManager::enqueue_book_parts)
+//! downloads every part as a separate task and returns a [`MultiPartDownload`].
+//! Once the host has decrypted the parts,
+//! [`finish_book_parts`](super::PersistentDownloadManager::finish_book_parts)
+//! joins them into one file or keeps one file per part, following
+//! [`DownloadSettings::join_parts`](super::DownloadSettings::join_parts).
+
+use serde::{Deserialize, Serialize};
+
+/// The download tasks that together make up one book
+///
+/// Serializable so the host can keep it until every part has finished.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultiPartDownload {
+    /// ASIN of the book in the library
+    pub asin: String,
+    /// Part ASINs in listening order; just `asin` for a single-file book
+    pub part_asins: Vec<String>,
+    /// Download task of each part, in the same order. Empty when the joined
+    /// file already exists.
+    pub task_ids: Vec<String>,
+    /// File the parts are joined into, or `None` to keep one file per part
+    pub joined_path: Option<String>,
+}
+
+impl MultiPartDownload {
+    /// Whether the book is made of more than one part
+    pub fn is_multi_part(&self) -> bool {
+        self.part_asins.len() > 1
+    }
+}
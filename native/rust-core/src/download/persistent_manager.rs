@@ -5,19 +5,40 @@
 //! - Supports pause/resume with byte-range resumption
 //! - Provides real-time progress tracking
 //! - Handles concurrent downloads with semaphore-based control
+//! - Limits concurrent decrypts separately, since decryption is CPU-bound
 //! - Automatically recovers from app restarts
 
+use crate::api::auth::Account;
+use crate::api::client::{binary_download_client_builder, AudibleClient};
+use crate::api::content::{Codec, DownloadQuality};
+use crate::api::library::{ensure_released, parse_lenient_date, SyncStats};
+use crate::api::license::{FileType, KeyData};
+use crate::audio::concat_parts_with;
+use crate::audio::metadata::{AudioMetadata, ChapterEditor, ChapterExportFormat, MetadataEditor};
 use crate::error::{LibationError, Result};
 use crate::download::progress::{DownloadProgress, DownloadState};
+use crate::download::cdn::download_headers;
+use crate::download::probe::probe_url_with_headers;
+use crate::download::validate;
+use crate::download::completion::{self, CompletionWaiters, DownloadResult};
+use crate::download::batch::{
+    BatchEntry, BatchItemResult, BatchOutcome, BatchSizeEstimate, BatchSummary, DownloadBatch, DownloadRequest,
+};
+use crate::download::parts::MultiPartDownload;
+use crate::download::diagnostics::{DownloadDiagnostics, DownloadThroughput, TransferStats};
+use crate::download::settings::DownloadSettings;
+use crate::download::titles;
+use crate::file::{post_write, FileManager};
+use crate::storage::{queries, Database};
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use sqlx::{SqlitePool, Row};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
-use tokio::sync::{RwLock, Semaphore};
+use tokio::sync::{Mutex, RwLock, Semaphore};
 use tokio::task::JoinHandle;
 use uuid::Uuid;
 
@@ -83,6 +104,21 @@ pub struct DownloadTask {
     pub created_at: String,
     pub started_at: Option<String>,
     pub completed_at: Option<String>,
+    /// ACR of the master this download was licensed for
+    #[serde(default)]
+    pub acr: Option<String>,
+    /// Version of that master
+    #[serde(default)]
+    pub content_version: Option<String>,
+    /// Quality the download was licensed at
+    #[serde(default)]
+    pub quality: Option<DownloadQuality>,
+    /// AAC codec the download was licensed in
+    #[serde(default)]
+    pub aac_codec: Option<Codec>,
+    /// Account the book was downloaded for; `None` if enqueued without one
+    #[serde(default)]
+    pub account_id: Option<String>,
 }
 
 impl DownloadTask {
@@ -102,6 +138,12 @@ impl DownloadTask {
         )
     }
 
+    /// The finished book if it was decrypted, else the download itself
+    fn finished_path(&self) -> &Path {
+        let output = Path::new(&self.output_path);
+        if output.exists() { output } else { Path::new(&self.download_path) }
+    }
+
     /// Check if task can be resumed
     pub fn can_resume(&self) -> bool {
         matches!(self.status, TaskStatus::Paused | TaskStatus::Failed)
@@ -109,6 +151,40 @@ impl DownloadTask {
     }
 }
 
+/// A downloaded book whose audio Audible has replaced
+///
+/// Returned by [`PersistentDownloadManager::find_updated_masters`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MasterUpdate {
+    /// Completed task holding the old master
+    pub task_id: String,
+    pub asin: String,
+    pub title: String,
+    pub downloaded_acr: String,
+    pub downloaded_version: Option<String>,
+    pub current_acr: String,
+    pub current_version: String,
+}
+
+/// What [`PersistentDownloadManager::repair_corrupt_downloads`] did
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadRepairReport {
+    /// Tasks requeued with a fresh license
+    pub requeued: Vec<String>,
+    /// Completed tasks whose output no longer exists. They are only
+    /// reported, since the user may have moved or deleted the book on purpose.
+    pub missing: Vec<String>,
+    /// Tasks whose repair failed, e.g. because the license was refused
+    pub failed: Vec<String>,
+    /// Decryption keys from each requeued task's new license, by task ID,
+    /// for decrypting it once downloaded
+    #[serde(default)]
+    pub keys: HashMap<String, Vec<KeyData>>,
+}
+
+/// Default number of decrypts allowed to run at once
+pub const DEFAULT_MAX_CONCURRENT_DECRYPTS: usize = 2;
+
 /// Progress callback function type
 pub type ProgressCallback = Box<dyn Fn(DownloadTask) + Send + Sync>;
 
@@ -123,23 +199,110 @@ pub struct PersistentDownloadManager {
     pool: Arc<SqlitePool>,
     max_concurrent: usize,
     semaphore: Arc<Semaphore>,
+    /// Limits decrypts independently of downloads
+    decrypt_semaphore: Arc<Semaphore>,
+    max_concurrent_decrypts: usize,
     active_downloads: Arc<RwLock<HashMap<String, ActiveDownload>>>,
     progress_callbacks: Arc<RwLock<HashMap<String, ProgressCallback>>>,
+    /// Serializes the in-flight check and insert in `enqueue_download`
+    enqueue_lock: Mutex<()>,
+    /// Per-download transfer counters for this session, for diagnostics
+    transfer_stats: Arc<RwLock<HashMap<String, TransferStats>>>,
+    /// Host defaults used by `enqueue_book`
+    settings: RwLock<DownloadSettings>,
+    /// Callers awaiting a book's final result
+    completions: Arc<CompletionWaiters>,
 }
 
 impl PersistentDownloadManager {
     /// Create a new manager with existing database pool
+    ///
+    /// Decrypts are limited to [`DEFAULT_MAX_CONCURRENT_DECRYPTS`]; use
+    /// [`with_limits`](Self::with_limits) to choose both limits.
     pub async fn new(pool: Arc<SqlitePool>, max_concurrent: usize) -> Result<Self> {
+        Self::with_limits(pool, max_concurrent, DEFAULT_MAX_CONCURRENT_DECRYPTS).await
+    }
+
+    /// Create a new manager with separate download and decrypt limits
+    ///
+    /// # Arguments
+    /// * `pool` - Database pool holding the `DownloadTasks` table
+    /// * `max_concurrent` - Downloads allowed to run at once (network-bound)
+    /// * `max_concurrent_decrypts` - Decrypts allowed to run at once (CPU-bound)
+    pub async fn with_limits(
+        pool: Arc<SqlitePool>,
+        max_concurrent: usize,
+        max_concurrent_decrypts: usize,
+    ) -> Result<Self> {
+        // Warm the title cache so progress for a bare ASIN has a title
+        if let Err(e) = titles::load_titles(&pool).await {
+            eprintln!("Warning: could not load book titles: {}", e);
+        }
+
         Ok(Self {
             pool,
             max_concurrent,
             semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            decrypt_semaphore: Arc::new(Semaphore::new(max_concurrent_decrypts)),
+            max_concurrent_decrypts,
             active_downloads: Arc::new(RwLock::new(HashMap::new())),
             progress_callbacks: Arc::new(RwLock::new(HashMap::new())),
+            enqueue_lock: Mutex::new(()),
+            transfer_stats: Arc::new(RwLock::new(HashMap::new())),
+            settings: RwLock::new(DownloadSettings::default()),
+            completions: Arc::new(CompletionWaiters::default()),
         })
     }
 
+    /// Start with `settings` instead of [`DownloadSettings::default`]
+    ///
+    /// # Errors
+    /// - `InvalidInput` - The settings fail [`DownloadSettings::validate`]
+    pub fn with_settings(self, settings: DownloadSettings) -> Result<Self> {
+        settings.validate()?;
+        *self.settings.try_write().expect("manager is not shared yet") = settings;
+        Ok(self)
+    }
+
+    /// Current download settings
+    pub async fn settings(&self) -> DownloadSettings {
+        self.settings.read().await.clone()
+    }
+
+    /// The settings with the paths of `client`'s account
+    ///
+    /// See [`DownloadSettings::for_account`].
+    async fn account_settings(&self, client: &AudibleClient) -> DownloadSettings {
+        let account_id = client.account().lock().await.account_id.clone();
+        self.settings().await.for_account(&account_id)
+    }
+
+    /// Replace the download settings
+    ///
+    /// Applies to books enqueued afterwards; queued tasks keep their paths.
+    ///
+    /// # Errors
+    /// - `InvalidInput` - The settings fail [`DownloadSettings::validate`]
+    pub async fn set_settings(&self, settings: DownloadSettings) -> Result<()> {
+        settings.validate()?;
+        *self.settings.write().await = settings;
+        Ok(())
+    }
+
     /// Enqueue a new download
+    ///
+    /// If the book's `output_path` already holds a downloaded and decrypted file,
+    /// the task is recorded as `Completed` straight away and nothing is fetched,
+    /// so re-running a batch only downloads the missing titles. Pass `force` to
+    /// download again regardless.
+    ///
+    /// Only one task per ASIN can be in flight. If a queued, downloading or
+    /// paused task already exists for `asin`, its task ID is returned and no new
+    /// task is created (even with `force`), so a double-tapped download button
+    /// cannot start two writers for the same file.
+    ///
+    /// An empty `title` is looked up in the [title cache](crate::download::titles).
+    #[allow(clippy::too_many_arguments)]
     pub async fn enqueue_download(
         &self,
         asin: String,
@@ -149,10 +312,68 @@ impl PersistentDownloadManager {
         download_path: String,
         output_path: String,
         request_headers: HashMap<String, String>,
+        force: bool,
     ) -> Result<String> {
+        self.enqueue_for_account(
+            None,
+            asin,
+            title,
+            download_url,
+            total_bytes,
+            download_path,
+            output_path,
+            request_headers,
+            force,
+        )
+        .await
+    }
+
+    /// [`enqueue_download`](Self::enqueue_download) for one account's copy of a book
+    ///
+    /// In-flight downloads are matched on the account and the ASIN, so two
+    /// accounts owning the same title each get their own task.
+    #[allow(clippy::too_many_arguments)]
+    async fn enqueue_for_account(
+        &self,
+        account_id: Option<String>,
+        asin: String,
+        title: String,
+        download_url: String,
+        total_bytes: u64,
+        download_path: String,
+        output_path: String,
+        request_headers: HashMap<String, String>,
+        force: bool,
+    ) -> Result<String> {
+        // Callers that only know the ASIN may pass an empty title
+        let title = if title.trim().is_empty() {
+            titles::title_or_asin(&asin)
+        } else {
+            titles::remember_title(&asin, &title);
+            title
+        };
+
+        let enqueue_guard = self.enqueue_lock.lock().await;
+
+        if let Some(existing) = self.find_in_flight_task(account_id.as_deref(), &asin).await? {
+            eprintln!(
+                "Download for {} already in progress (task {}, {})",
+                asin, existing.task_id, existing.status.as_str()
+            );
+            return Ok(existing.task_id);
+        }
+
         let task_id = Uuid::new_v4().to_string();
         let now = chrono::Utc::now().to_rfc3339();
 
+        let already_downloaded =
+            !force && FileManager::verify_downloaded(Path::new(&output_path)).await;
+        let (status, bytes_downloaded, completed_at) = if already_downloaded {
+            (TaskStatus::Completed, total_bytes, Some(now.clone()))
+        } else {
+            (TaskStatus::Queued, 0, None)
+        };
+
         // Insert into database
         let headers_json = serde_json::to_string(&request_headers)
             .map_err(|e| LibationError::InvalidInput(format!("Invalid headers: {}", e)))?;
@@ -161,23 +382,35 @@ impl PersistentDownloadManager {
             r#"
             INSERT INTO DownloadTasks (
                 task_id, asin, title, status, bytes_downloaded, total_bytes,
-                download_url, download_path, output_path, request_headers, created_at
+                download_url, download_path, output_path, request_headers, created_at,
+                completed_at, account_id
             )
-            VALUES (?, ?, ?, ?, 0, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&task_id)
         .bind(&asin)
         .bind(&title)
-        .bind(TaskStatus::Queued.as_str())
+        .bind(status.as_str())
+        .bind(bytes_downloaded as i64)
         .bind(total_bytes as i64)
         .bind(&download_url)
         .bind(&download_path)
         .bind(&output_path)
         .bind(&headers_json)
         .bind(&now)
+        .bind(&completed_at)
+        .bind(&account_id)
         .execute(&*self.pool)
         .await?;
+        drop(enqueue_guard);
+
+        if already_downloaded {
+            eprintln!("Skipping {} ({}): already downloaded at {}", asin, title, output_path);
+            let task = self.get_task(&task_id).await?;
+            Self::announce_completed(&self.progress_callbacks, &self.completions, &task).await;
+            return Ok(task_id);
+        }
 
         // Auto-start download if slots available
         self.try_start_next_download().await?;
@@ -185,6 +418,513 @@ impl PersistentDownloadManager {
         Ok(task_id)
     }
 
+    /// Enqueue a library book using the manager's [`DownloadSettings`]
+    ///
+    /// Requests the license at the configured quality (falling back to a
+    /// lower tier if allowed), sizes the download and places the files
+    /// according to the naming pattern and directories, then behaves like
+    /// [`enqueue_download`](Self::enqueue_download).
+    ///
+    /// # Arguments
+    /// * `client` - Authenticated client for the book's account
+    /// * `asin` - Book to download; must be in the library database
+    /// * `force` - Download again even if the output file exists
+    ///
+    /// # Returns
+    /// Task ID of the new or already in-flight download
+    ///
+    /// # Errors
+    /// - `RecordNotFound` - The book is not in the library database
+    /// - `NotYetReleased` - The book is a pre-order that isn't out yet
+    /// - Any error from the license request or the size probe
+    pub async fn enqueue_book(&self, client: &AudibleClient, asin: &str, force: bool) -> Result<String> {
+        let book = queries::find_book_with_relations_by_asin(&self.pool, asin)
+            .await?
+            .ok_or_else(|| LibationError::not_found(format!("Book {}", asin)))?;
+        ensure_book_released(&book)?;
+        let settings = self.account_settings(client).await;
+        let output_path = settings.output_path(&book.to_audio_metadata())?;
+
+        self.enqueue_licensed(client, asin, &book.title, &output_path, &settings, force).await
+    }
+
+    /// Enqueue a library book, downloading each part if it has several
+    ///
+    /// A book whose `content_delivery_type` is `MultiPartBook` is looked up
+    /// with [`get_book_parts`](AudibleClient::get_book_parts) and every part
+    /// is enqueued like a book of its own, titled "Title (Part n of m)" and
+    /// decrypted to [`DownloadSettings::part_output_path`]. Any other book is
+    /// enqueued with [`enqueue_book`](Self::enqueue_book).
+    ///
+    /// When parts are joined and the joined file already exists, nothing is
+    /// downloaded unless `force` is set.
+    ///
+    /// # Arguments
+    /// * `client` - Authenticated client for the book's account
+    /// * `asin` - Book to download; must be in the library database
+    /// * `force` - Download again even if the output exists
+    ///
+    /// # Returns
+    /// The part tasks, to pass to [`finish_book_parts`](Self::finish_book_parts)
+    /// once the host has decrypted them
+    ///
+    /// # Errors
+    /// - `RecordNotFound` - The book is not in the library database
+    /// - `NotYetReleased` - The book is a pre-order that isn't out yet
+    /// - Any error from the catalog lookup, a part's license or size probe
+    pub async fn enqueue_book_parts(
+        &self,
+        client: &AudibleClient,
+        asin: &str,
+        force: bool,
+    ) -> Result<MultiPartDownload> {
+        let book = queries::find_book_with_relations_by_asin(&self.pool, asin)
+            .await?
+            .ok_or_else(|| LibationError::not_found(format!("Book {}", asin)))?;
+        ensure_book_released(&book)?;
+
+        let part_asins = if book.content_delivery_type.as_deref() == Some("MultiPartBook") {
+            client.get_book_parts(asin).await?
+        } else {
+            Vec::new()
+        };
+        if part_asins.len() < 2 {
+            let task_id = self.enqueue_book(client, asin, force).await?;
+            return Ok(MultiPartDownload {
+                asin: asin.to_string(),
+                part_asins: vec![asin.to_string()],
+                task_ids: vec![task_id],
+                joined_path: None,
+            });
+        }
+
+        let settings = self.account_settings(client).await;
+        let metadata = book.to_audio_metadata();
+        let joined_path = if settings.join_parts {
+            Some(settings.output_path(&metadata)?)
+        } else {
+            None
+        };
+        let mut download = MultiPartDownload {
+            asin: asin.to_string(),
+            part_asins: part_asins.clone(),
+            task_ids: Vec::new(),
+            joined_path: joined_path.as_ref().map(|p| p.to_string_lossy().into_owned()),
+        };
+
+        if let Some(joined) = &joined_path {
+            if !force && FileManager::verify_downloaded(joined).await {
+                eprintln!("Skipping {} ({}): already downloaded at {}", asin, book.title, joined.display());
+                match completion::describe_download(joined).await {
+                    Ok(file) => self.completions.complete(asin, &file),
+                    Err(e) => self.completions.fail(asin, &e.to_string()),
+                }
+                return Ok(download);
+            }
+        }
+
+        for (index, part_asin) in part_asins.iter().enumerate() {
+            let title = format!("{} (Part {} of {})", book.title, index + 1, part_asins.len());
+            let output_path = settings.part_output_path(&metadata, asin, index + 1)?;
+            let task_id = self
+                .enqueue_licensed(client, part_asin, &title, &output_path, &settings, force)
+                .await?;
+            download.task_ids.push(task_id);
+        }
+
+        Ok(download)
+    }
+
+    /// Put the decrypted parts of a book in their final place
+    ///
+    /// With a joined path the parts are joined with
+    /// [`concat_parts_with`](crate::audio::concat_parts_with), using the
+    /// settings' [`join_options`](DownloadSettings::join_options), and then
+    /// deleted; otherwise they already are the finished files.
+    ///
+    /// # Arguments
+    /// * `download` - Returned by [`enqueue_book_parts`](Self::enqueue_book_parts)
+    ///
+    /// # Returns
+    /// The finished files: the joined book, or every part in order
+    ///
+    /// # Errors
+    /// - `InvalidState` - A part has not finished downloading
+    /// - `FileNotFound` - A part has not been decrypted yet
+    /// - `UnsupportedAudioFormat` - The parts differ in codec, sample rate or
+    ///   channels and cannot be joined by stream copy
+    /// - Any other error from joining the parts
+    pub async fn finish_book_parts(&self, download: &MultiPartDownload) -> Result<Vec<PathBuf>> {
+        let mut parts = Vec::with_capacity(download.task_ids.len());
+        for task_id in &download.task_ids {
+            let task = self.get_task(task_id).await?;
+            if task.status != TaskStatus::Completed {
+                return Err(LibationError::InvalidState(format!(
+                    "{} is {}, not completed",
+                    task.title,
+                    task.status.as_str()
+                )));
+            }
+            let output = PathBuf::from(&task.output_path);
+            if !FileManager::file_exists(&output).await {
+                return Err(LibationError::FileNotFound(task.output_path));
+            }
+            parts.push(output);
+        }
+
+        let joined = match &download.joined_path {
+            Some(joined) if download.is_multi_part() => PathBuf::from(joined),
+            _ => return Ok(parts),
+        };
+        // Nothing was enqueued because the joined file was already there
+        if parts.is_empty() {
+            return if FileManager::file_exists(&joined).await {
+                Ok(vec![joined])
+            } else {
+                Err(LibationError::FileNotFound(joined.display().to_string()))
+            };
+        }
+
+        if let Some(parent) = joined.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let options = self.settings().await.join_options;
+        concat_parts_with(&parts, &joined, &options).await?;
+        for part in &parts {
+            if let Err(e) = fs::remove_file(part).await {
+                eprintln!("Warning: could not remove joined part {}: {}", part.display(), e);
+            }
+        }
+        post_write::notify_file_written(&joined).await;
+
+        Ok(vec![joined])
+    }
+
+    /// License `asin`, size its download and enqueue it with the given output
+    async fn enqueue_licensed(
+        &self,
+        client: &AudibleClient,
+        asin: &str,
+        title: &str,
+        output_path: &Path,
+        settings: &DownloadSettings,
+        force: bool,
+    ) -> Result<String> {
+        let license = if settings.quality_fallback {
+            client
+                .build_download_license_with_fallback(asin, settings.quality, false, settings.aac_codec)
+                .await?
+        } else {
+            client
+                .build_download_license_with_codec(asin, settings.quality, false, settings.aac_codec)
+                .await?
+        };
+        if let Some(downgrade) = &license.quality_downgrade {
+            eprintln!("{}: {}", asin, downgrade);
+        }
+        let file_type = license
+            .decryption_keys
+            .as_ref()
+            .and_then(|keys| keys.first())
+            .map_or(FileType::Unknown, |key| key.file_type(license.drm_type));
+
+        let request_headers = download_headers(&license.download_url);
+        let total_bytes = probe_url_with_headers(&license.download_url, &request_headers)
+            .await?
+            .size
+            .unwrap_or(0);
+        let download_path = settings.download_path(asin, file_type);
+        let account_id = client.account().lock().await.account_id.clone();
+
+        let task_id = self
+            .enqueue_for_account(
+                Some(account_id),
+                asin.to_string(),
+                title.to_string(),
+                license.download_url,
+                total_bytes,
+                download_path.to_string_lossy().into_owned(),
+                output_path.to_string_lossy().into_owned(),
+                request_headers,
+                force,
+            )
+            .await?;
+
+        // A file found already on disk came from an unknown master, so only a
+        // task that will actually download gets the license's reference
+        if self.get_task(&task_id).await?.status != TaskStatus::Completed {
+            if let Some(reference) = &license.content_metadata.content_reference {
+                self.set_content_reference(&task_id, &reference.acr, &reference.version).await?;
+            }
+            self.set_license_format(&task_id, license.licensed_quality, license.licensed_codec).await?;
+        }
+
+        Ok(task_id)
+    }
+
+    /// Estimate the space a set of books will take before downloading them
+    ///
+    /// Requests a license for each book at the configured quality and sizes
+    /// its download URL with a HEAD request, running up to
+    /// [`MAX_CONCURRENCY`](crate::api::client::MAX_CONCURRENCY) books at a
+    /// time. The licenses stay in the client's license cache, so enqueueing
+    /// the same books right after does not request them again.
+    ///
+    /// # Arguments
+    /// * `client` - Authenticated client for the books' account
+    /// * `asins` - Books to size, e.g. every title of a series
+    ///
+    /// # Returns
+    /// The total and each book's size; a book that cannot be sized is listed
+    /// with its error rather than failing the estimate
+    pub async fn estimate_batch_size(&self, client: &AudibleClient, asins: &[String]) -> BatchSizeEstimate {
+        let settings = self.settings().await;
+
+        let results = futures_util::stream::iter(asins)
+            .map(|asin| {
+                let settings = &settings;
+                async move {
+                    let size = async {
+                        let license = if settings.quality_fallback {
+                            client
+                                .build_download_license_with_fallback(asin, settings.quality, false, settings.aac_codec)
+                                .await?
+                        } else {
+                            client
+                                .build_download_license_with_codec(asin, settings.quality, false, settings.aac_codec)
+                                .await?
+                        };
+                        probe_url_with_headers(&license.download_url, &download_headers(&license.download_url))
+                            .await?
+                            .size
+                            .ok_or_else(|| LibationError::InvalidApiResponse {
+                                message: "Download server did not report a size".to_string(),
+                                response_body: None,
+                            })
+                    }
+                    .await;
+                    (asin.clone(), size)
+                }
+            })
+            .buffered(crate::api::client::MAX_CONCURRENCY)
+            .collect()
+            .await;
+
+        BatchSizeEstimate::from_results(results)
+    }
+
+    /// Record which master a task downloads
+    ///
+    /// [`enqueue_book`](Self::enqueue_book) and
+    /// [`repair_download`](Self::repair_download) do this themselves; callers
+    /// that build the license and use [`enqueue_download`](Self::enqueue_download)
+    /// pass `content_reference.acr` / `.version` from it here, so that
+    /// [`find_updated_masters`](Self::find_updated_masters) can check the book.
+    pub async fn set_content_reference(&self, task_id: &str, acr: &str, version: &str) -> Result<()> {
+        sqlx::query("UPDATE DownloadTasks SET acr = ?, content_version = ? WHERE task_id = ?")
+            .bind(acr)
+            .bind(version)
+            .bind(task_id)
+            .execute(&*self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Record the quality and codec a task was licensed at
+    ///
+    /// [`refresh_task_url`](Self::refresh_task_url) requests the new URL with
+    /// exactly these, so that it serves the same file as the bytes already
+    /// downloaded. [`enqueue_book`](Self::enqueue_book) does this itself;
+    /// callers using [`enqueue_download`](Self::enqueue_download) pass
+    /// `licensed_quality` / `licensed_codec` from their license here.
+    pub async fn set_license_format(&self, task_id: &str, quality: DownloadQuality, aac_codec: Codec) -> Result<()> {
+        let quality = serde_json::to_string(&quality)
+            .map_err(|e| LibationError::InvalidInput(format!("Invalid quality: {}", e)))?;
+        let aac_codec = serde_json::to_string(&aac_codec)
+            .map_err(|e| LibationError::InvalidInput(format!("Invalid codec: {}", e)))?;
+        sqlx::query("UPDATE DownloadTasks SET quality = ?, aac_codec = ? WHERE task_id = ?")
+            .bind(quality)
+            .bind(aac_codec)
+            .bind(task_id)
+            .execute(&*self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Enqueue many downloads, recording each book's outcome instead of stopping
+    ///
+    /// A book that cannot be enqueued is recorded with its error and the rest
+    /// of the batch carries on. Books already downloaded and repeated ASINs are
+    /// recorded as skipped. Pass the returned batch to
+    /// [`batch_summary`](Self::batch_summary) to follow it to completion.
+    ///
+    /// # Arguments
+    /// * `requests` - Books to download, in order
+    /// * `force` - Download again even if the output file exists
+    pub async fn enqueue_batch(&self, requests: Vec<DownloadRequest>, force: bool) -> DownloadBatch {
+        let mut batch = DownloadBatch::default();
+        let mut seen = std::collections::HashSet::new();
+
+        for request in requests {
+            let mut entry = BatchEntry {
+                asin: request.asin.clone(),
+                title: request.title.clone(),
+                task_id: None,
+                skipped: None,
+                error: None,
+            };
+
+            if !seen.insert(request.asin.clone()) {
+                entry.skipped = Some("Listed earlier in this batch".to_string());
+                batch.entries.push(entry);
+                continue;
+            }
+
+            let result = self
+                .enqueue_download(
+                    request.asin,
+                    request.title,
+                    request.download_url,
+                    request.total_bytes,
+                    request.download_path,
+                    request.output_path,
+                    request.request_headers,
+                    force,
+                )
+                .await;
+
+            match result {
+                Ok(task_id) => {
+                    if let Ok(task) = self.get_task(&task_id).await {
+                        if task.status == TaskStatus::Completed {
+                            entry.skipped = Some("Already downloaded".to_string());
+                        }
+                    }
+                    entry.task_id = Some(task_id);
+                }
+                Err(e) => {
+                    eprintln!("Failed to enqueue {} ({}): {}", entry.asin, entry.title, e);
+                    entry.error = Some(e.to_string());
+                }
+            }
+            batch.entries.push(entry);
+        }
+
+        batch
+    }
+
+    /// Current outcome of every book in a batch
+    ///
+    /// Books still queued, downloading or paused are reported as pending; call
+    /// again once [`BatchSummary::is_finished`] to get the final result.
+    /// Cancelled tasks are removed from the database, so a task that can no
+    /// longer be found is reported as skipped.
+    pub async fn batch_summary(&self, batch: &DownloadBatch) -> Result<BatchSummary> {
+        let mut items = Vec::with_capacity(batch.entries.len());
+
+        for entry in &batch.entries {
+            let item = if let Some(ref error) = entry.error {
+                BatchItemResult::new(entry, BatchOutcome::Failed, Some(error.clone()))
+            } else if let Some(ref reason) = entry.skipped {
+                BatchItemResult::new(entry, BatchOutcome::Skipped, Some(reason.clone()))
+            } else if let Some(ref task_id) = entry.task_id {
+                match self.get_task(task_id).await {
+                    Ok(task) => BatchItemResult::from_task_status(entry, &task.status, task.error),
+                    Err(LibationError::RecordNotFound(_)) => {
+                        BatchItemResult::new(entry, BatchOutcome::Skipped, Some("Cancelled".to_string()))
+                    }
+                    Err(e) => return Err(e),
+                }
+            } else {
+                BatchItemResult::new(entry, BatchOutcome::Failed, Some("Not enqueued".to_string()))
+            };
+            items.push(item);
+        }
+
+        Ok(BatchSummary::from_items(items))
+    }
+
+    /// Find the queued, downloading or paused task for an account's ASIN, if any
+    ///
+    /// `account_id` is `None` for tasks enqueued without an account.
+    pub async fn find_in_flight_task(&self, account_id: Option<&str>, asin: &str) -> Result<Option<DownloadTask>> {
+        let row = sqlx::query(
+            "SELECT * FROM DownloadTasks WHERE account_id IS ? AND asin = ? AND status IN (?, ?, ?)
+             ORDER BY created_at DESC LIMIT 1"
+        )
+        .bind(account_id)
+        .bind(asin)
+        .bind(TaskStatus::Queued.as_str())
+        .bind(TaskStatus::Downloading.as_str())
+        .bind(TaskStatus::Paused.as_str())
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        row.map(|row| self.row_to_task(row)).transpose()
+    }
+
+    /// Wait for the final result of a book's download
+    ///
+    /// Separate from progress callbacks: the returned future resolves once,
+    /// with the downloaded file or the reason the download failed. If the
+    /// book's latest download has already finished, it resolves right away.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::download::PersistentDownloadManager;
+    /// # async fn example(manager: &PersistentDownloadManager) -> rust_core::Result<()> {
+    /// let result = manager.wait_for_result("B08G9PRS1K").await?;
+    /// let book = result.await?;
+    /// println!("{} is ready ({} bytes)", book.path.display(), book.size);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// - `RecordNotFound` - No download was ever enqueued for `asin`
+    pub async fn wait_for_result(&self, asin: &str) -> Result<DownloadResult> {
+        // Subscribe before looking, so a download finishing in between is not missed
+        let pending = self.completions.subscribe(asin);
+
+        let task = match self.latest_task(asin).await {
+            Ok(Some(task)) => task,
+            Ok(None) => {
+                self.unsubscribe(asin, pending);
+                return Err(LibationError::RecordNotFound(format!("No download for {}", asin)));
+            }
+            Err(e) => {
+                self.unsubscribe(asin, pending);
+                return Err(e);
+            }
+        };
+
+        let ready = match task.status {
+            TaskStatus::Queued | TaskStatus::Downloading | TaskStatus::Paused => return Ok(pending),
+            TaskStatus::Completed => completion::describe_download(task.finished_path()).await,
+            TaskStatus::Failed => Err(LibationError::DownloadFailed(
+                task.error.unwrap_or_else(|| "Download failed".to_string()),
+            )),
+            TaskStatus::Cancelled => Err(LibationError::Cancelled),
+        };
+        self.unsubscribe(asin, pending);
+        Ok(DownloadResult::ready(ready))
+    }
+
+    /// Newest task for `asin`, whatever its status
+    async fn latest_task(&self, asin: &str) -> Result<Option<DownloadTask>> {
+        let row = sqlx::query("SELECT * FROM DownloadTasks WHERE asin = ? ORDER BY created_at DESC LIMIT 1")
+            .bind(asin)
+            .fetch_optional(&*self.pool)
+            .await?;
+        row.map(|row| self.row_to_task(row)).transpose()
+    }
+
+    /// Give up a subscription that will not be handed out, leaving other waiters for `asin` in place
+    fn unsubscribe(&self, asin: &str, pending: DownloadResult) {
+        drop(pending);
+        self.completions.release(asin);
+    }
+
     /// Get a task by ID
     pub async fn get_task(&self, task_id: &str) -> Result<DownloadTask> {
         let row = sqlx::query(
@@ -221,6 +961,40 @@ impl PersistentDownloadManager {
         self.active_downloads.read().await.len()
     }
 
+    /// Maximum number of decrypts allowed to run at once
+    pub fn max_concurrent_decrypts(&self) -> usize {
+        self.max_concurrent_decrypts
+    }
+
+    /// Run a decrypt job once a decrypt slot is free
+    ///
+    /// Decrypt slots are separate from download slots, so finished downloads
+    /// queue here without holding up the network side.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::download::PersistentDownloadManager;
+    /// # use rust_core::crypto::aax::AaxDecrypter;
+    /// # use std::path::Path;
+    /// # async fn example(manager: &PersistentDownloadManager, decrypter: AaxDecrypter) -> rust_core::Result<()> {
+    /// let book = manager
+    ///     .run_decrypt(decrypter.decrypt_audiobook(Path::new("book.aax"), Path::new("book.m4b"), None, None))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn run_decrypt<F, T>(&self, job: F) -> T
+    where
+        F: std::future::Future<Output = T>,
+    {
+        let _permit = self
+            .decrypt_semaphore
+            .acquire()
+            .await
+            .expect("decrypt semaphore is never closed");
+        job.await
+    }
+
     /// Pause a download
     pub async fn pause_download(&self, task_id: &str) -> Result<()> {
         // Check if actively downloading
@@ -269,6 +1043,77 @@ impl PersistentDownloadManager {
         Ok(())
     }
 
+    /// Give a paused or failed task a new download URL
+    ///
+    /// Signed CDN URLs expire after about a day, so a download resumed later
+    /// is refused. This asks for a new URL with
+    /// [`refresh_download_url`](AudibleClient::refresh_download_url) at the
+    /// quality and codec the task was licensed at (the current settings for
+    /// tasks that did not record them). The bytes already downloaded are kept
+    /// only if the new URL serves the same master at the same size; otherwise
+    /// the partial file is deleted and the download starts over. The request
+    /// headers are chosen again for the new URL's CDN. Call it before
+    /// [`resume_download`](Self::resume_download).
+    ///
+    /// # Returns
+    /// The new URL
+    ///
+    /// # Errors
+    /// - `RecordNotFound` - No such task
+    /// - `InvalidState` - The task is downloading or already finished
+    /// - Any error from the license request
+    pub async fn refresh_task_url(&self, client: &AudibleClient, task_id: &str) -> Result<String> {
+        let task = self.get_task(task_id).await?;
+        if !matches!(task.status, TaskStatus::Paused | TaskStatus::Failed | TaskStatus::Queued) {
+            return Err(LibationError::InvalidState(format!(
+                "Cannot refresh the URL of a {} download",
+                task.status.as_str()
+            )));
+        }
+
+        let settings = self.settings().await;
+        let quality = task.quality.unwrap_or(settings.quality);
+        let aac_codec = task.aac_codec.unwrap_or(settings.aac_codec);
+        let refreshed = client.refresh_download_url(&task.asin, quality, aac_codec).await?;
+        let url = refreshed.url;
+
+        // The new URL may be on another CDN
+        let headers = download_headers(&url);
+        let total_bytes = probe_url_with_headers(&url, &headers).await?.size;
+        let headers_json = serde_json::to_string(&headers)
+            .map_err(|e| LibationError::InvalidInput(format!("Invalid headers: {}", e)))?;
+        sqlx::query("UPDATE DownloadTasks SET download_url = ?, request_headers = ? WHERE task_id = ?")
+            .bind(&url)
+            .bind(&headers_json)
+            .bind(task_id)
+            .execute(&*self.pool)
+            .await?;
+
+        // Appending to bytes of another master or another encode corrupts the
+        // file, so anything that cannot be shown to match starts over
+        let acr = refreshed.content_reference.as_ref().map(|reference| reference.acr.as_str());
+        let same_master = task.acr.is_none() || task.acr.as_deref() == acr;
+        let same_size = task.total_bytes > 0 && total_bytes == Some(task.total_bytes);
+        if !(same_master && same_size) {
+            if task.bytes_downloaded > 0 {
+                eprintln!(
+                    "Warning: {}: the new URL serves a different file, restarting the download",
+                    task.asin
+                );
+                let _ = fs::remove_file(&task.download_path).await;
+            }
+            sqlx::query("UPDATE DownloadTasks SET bytes_downloaded = 0, total_bytes = ? WHERE task_id = ?")
+                .bind(total_bytes.unwrap_or(0) as i64)
+                .bind(task_id)
+                .execute(&*self.pool)
+                .await?;
+            if let Some(reference) = &refreshed.content_reference {
+                self.set_content_reference(task_id, &reference.acr, &reference.version).await?;
+            }
+        }
+        Ok(url)
+    }
+
     /// Cancel a download
     pub async fn cancel_download(&self, task_id: &str) -> Result<()> {
         // Stop if actively downloading
@@ -293,6 +1138,7 @@ impl PersistentDownloadManager {
             .bind(task_id)
             .execute(&*self.pool)
             .await?;
+        self.completions.cancel(&task.asin);
 
         Ok(())
     }
@@ -321,6 +1167,397 @@ impl PersistentDownloadManager {
         Ok(())
     }
 
+    /// Find completed downloads whose files no longer pass verification
+    ///
+    /// Each completed task's output is checked with
+    /// [`validate::check_file_integrity`]: it must exist, be non-empty and start
+    /// with an audio signature. When the output is the downloaded file itself
+    /// (no decrypt step), its size must also equal `total_bytes`.
+    ///
+    /// # Returns
+    /// Each failing task with the problem found
+    pub async fn find_corrupt_downloads(&self) -> Result<Vec<(DownloadTask, validate::IntegrityIssue)>> {
+        let mut corrupt = Vec::new();
+
+        for task in self.list_tasks(Some(TaskStatus::Completed)).await? {
+            let expected_size = (task.output_path == task.download_path && task.total_bytes > 0)
+                .then_some(task.total_bytes);
+            if let Some(issue) =
+                validate::check_file_integrity(Path::new(&task.output_path), expected_size).await?
+            {
+                corrupt.push((task, issue));
+            }
+        }
+
+        Ok(corrupt)
+    }
+
+    /// Re-download a single book with a fresh license
+    ///
+    /// Requests a new license (the stored CDN URL has usually expired), deletes
+    /// the old download and requeues the task from byte 0. Other tasks are not
+    /// touched. The old decrypted book stays in place until the host decrypts
+    /// the new download over it (see [`crypto::decrypt`](crate::crypto::decrypt)),
+    /// unless the download is the book itself.
+    ///
+    /// # Arguments
+    /// * `task_id` - Task to repair
+    /// * `client` - Authenticated client for the book's account
+    /// * `quality` - Quality to request the license at
+    ///
+    /// # Returns
+    /// The new license's decryption keys, needed to decrypt the new download
+    /// (`None` for unencrypted and Widevine books)
+    ///
+    /// # Errors
+    /// - `InvalidState` - Task is still in flight
+    /// - Any error from requesting the license
+    pub async fn repair_download(
+        &self,
+        task_id: &str,
+        client: &AudibleClient,
+        quality: DownloadQuality,
+    ) -> Result<Option<Vec<KeyData>>> {
+        let task = self.get_task(task_id).await?;
+        if matches!(task.status, TaskStatus::Queued | TaskStatus::Downloading) {
+            return Err(LibationError::InvalidState(format!(
+                "Cannot repair task {} while it is {}",
+                task_id,
+                task.status.as_str()
+            )));
+        }
+
+        if let Some(cache) = client.license_cache() {
+            cache.invalidate(&task.asin);
+        }
+        let license = client.build_download_license(&task.asin, quality, false).await?;
+        let reference = license.content_metadata.content_reference.as_ref();
+        let request_headers = download_headers(&license.download_url);
+        let total_bytes = probe_url_with_headers(&license.download_url, &request_headers)
+            .await?
+            .size
+            .unwrap_or(0);
+        let headers_json = serde_json::to_string(&request_headers)
+            .map_err(|e| LibationError::InvalidInput(format!("Invalid headers: {}", e)))?;
+
+        let _ = fs::remove_file(&task.download_path).await;
+
+        sqlx::query(
+            r#"
+            UPDATE DownloadTasks
+            SET status = ?, download_url = ?, request_headers = ?, bytes_downloaded = 0,
+                total_bytes = ?, error = NULL, completed_at = NULL, retry_count = retry_count + 1,
+                acr = COALESCE(?, acr), content_version = COALESCE(?, content_version)
+            WHERE task_id = ?
+            "#,
+        )
+        .bind(TaskStatus::Queued.as_str())
+        .bind(&license.download_url)
+        .bind(&headers_json)
+        .bind(total_bytes as i64)
+        .bind(reference.map(|r| r.acr.as_str()))
+        .bind(reference.map(|r| r.version.as_str()))
+        .bind(task_id)
+        .execute(&*self.pool)
+        .await?;
+        self.set_license_format(task_id, license.licensed_quality, license.licensed_codec).await?;
+
+        eprintln!("Repairing download for {} ({})", task.asin, task.title);
+        self.try_start_next_download().await?;
+
+        Ok(license.decryption_keys)
+    }
+
+    /// Detect and repair every corrupt completed download
+    ///
+    /// Runs after every library sync (see [`sync_library`](Self::sync_library)).
+    /// Files that exist but are damaged are downloaded again; missing files
+    /// are only reported. A book whose repair fails (e.g. the license request
+    /// is refused) is logged and skipped so the rest still run.
+    ///
+    /// # Returns
+    /// Which tasks were requeued, which are missing and which failed
+    pub async fn repair_corrupt_downloads(
+        &self,
+        client: &AudibleClient,
+        quality: DownloadQuality,
+    ) -> Result<DownloadRepairReport> {
+        let mut report = DownloadRepairReport::default();
+
+        for (task, issue) in self.find_corrupt_downloads().await? {
+            if issue == validate::IntegrityIssue::Missing {
+                report.missing.push(task.task_id);
+                continue;
+            }
+
+            eprintln!("Download for {} failed verification: {}", task.asin, issue);
+            match self.repair_download(&task.task_id, client, quality).await {
+                Ok(keys) => {
+                    if let Some(keys) = keys {
+                        report.keys.insert(task.task_id.clone(), keys);
+                    }
+                    report.requeued.push(task.task_id);
+                }
+                Err(e) => {
+                    eprintln!("Failed to repair {}: {}", task.asin, e);
+                    report.failed.push(task.task_id);
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Sync the library, then repair corrupt downloads
+    ///
+    /// Repairs use the quality from the manager's settings. A repair problem
+    /// is logged rather than returned, so a sync that succeeded is never
+    /// reported as failed.
+    ///
+    /// # Arguments
+    /// * `client` - Authenticated client for `account`
+    /// * `db` - Database to sync into
+    /// * `account` - Account whose library is synced
+    ///
+    /// # Errors
+    /// Any error from [`AudibleClient::sync_library`]
+    pub async fn sync_library(
+        &self,
+        client: &mut AudibleClient,
+        db: &Database,
+        account: &Account,
+    ) -> Result<(SyncStats, DownloadRepairReport)> {
+        let stats = client.sync_library(db, account).await?;
+        let repairs = self.repair_after_sync(client).await;
+        Ok((stats, repairs))
+    }
+
+    /// Repair corrupt downloads once a sync has finished, logging any error
+    ///
+    /// For callers that sync page by page; call it after the last page.
+    pub async fn repair_after_sync(&self, client: &AudibleClient) -> DownloadRepairReport {
+        let quality = self.settings().await.quality;
+        match self.repair_corrupt_downloads(client, quality).await {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("Warning: could not check downloads after sync: {}", e);
+                DownloadRepairReport::default()
+            }
+        }
+    }
+
+    /// Find downloaded books that Audible has since replaced with a new master
+    ///
+    /// Compares the ACR stored with each book's latest completed download
+    /// against the one `/1.0/content/{asin}/metadata` reports now. Downloads
+    /// made before the ACR was recorded cannot be compared and are skipped, as
+    /// are books whose metadata request fails (logged). Meant to run after a
+    /// library sync; pass an update's `task_id` to
+    /// [`repair_download`](Self::repair_download) to fetch the new master.
+    ///
+    /// # Returns
+    /// One entry per book with a newer master
+    pub async fn find_updated_masters(&self, client: &AudibleClient) -> Result<Vec<MasterUpdate>> {
+        let mut updates = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+
+        // Newest first, so the first task per ASIN is the file the user has
+        for task in self.list_tasks(Some(TaskStatus::Completed)).await? {
+            if !seen.insert(task.asin.clone()) {
+                continue;
+            }
+            let Some(downloaded_acr) = task.acr.clone() else {
+                continue;
+            };
+
+            let current = match client.get_content_metadata(&task.asin).await {
+                Ok(metadata) => metadata.content_reference,
+                Err(e) => {
+                    eprintln!("Could not check {} for a new master: {}", task.asin, e);
+                    continue;
+                }
+            };
+            if let Some(current) = current.filter(|r| r.acr != downloaded_acr) {
+                eprintln!(
+                    "New master for {} ({}): {} -> {}",
+                    task.asin, task.title, downloaded_acr, current.acr
+                );
+                updates.push(MasterUpdate {
+                    task_id: task.task_id,
+                    asin: task.asin,
+                    title: task.title,
+                    downloaded_acr,
+                    downloaded_version: task.content_version,
+                    current_acr: current.acr,
+                    current_version: current.version,
+                });
+            }
+        }
+
+        Ok(updates)
+    }
+
+    /// Re-tag and rename a downloaded book without downloading it again
+    ///
+    /// Reads the file of the book's latest completed download, writes the
+    /// current library metadata and its existing chapters back into it, then
+    /// moves it (and any `.cue` / `.chapters.txt` sidecars) to where the
+    /// current [`DownloadSettings`] put it, in the downloading account's tree
+    /// when `storage_root` is set. The file keeps its format; only the name
+    /// and folder follow the settings. The task's `output_path` is updated so
+    /// later lookups find the file.
+    ///
+    /// # Returns
+    /// Where the file is now
+    ///
+    /// # Errors
+    /// - `RecordNotFound` - The book is not in the library or was never downloaded
+    /// - `FileNotFound` - The downloaded file is no longer on disk
+    /// - `FileAlreadyExists` - Another file already occupies the new path
+    /// - Any error from FFmpeg while tagging
+    pub async fn reprocess(&self, asin: &str) -> Result<String> {
+        let book = queries::find_book_with_relations_by_asin(&self.pool, asin)
+            .await?
+            .ok_or_else(|| LibationError::not_found(format!("Book {}", asin)))?;
+        let task = self
+            .latest_completed_task(asin)
+            .await?
+            .ok_or_else(|| LibationError::not_found(format!("Downloaded file for {}", asin)))?;
+        let current = Path::new(&task.output_path);
+        if !FileManager::file_exists(current).await {
+            return Err(LibationError::FileNotFound(current.display().to_string()));
+        }
+
+        let metadata = book.to_audio_metadata();
+        let chapters = ChapterEditor::extract_chapters(current).await?;
+        MetadataEditor::write_tags(current, &metadata, None, &chapters).await?;
+
+        let target = self.move_to_settings_path(&task, &metadata).await?;
+        eprintln!("Reprocessed {} ({}): {} -> {}", asin, book.title, current.display(), target);
+        Ok(target)
+    }
+
+    /// Latest completed download of `asin`
+    async fn latest_completed_task(&self, asin: &str) -> Result<Option<DownloadTask>> {
+        let row = sqlx::query(
+            "SELECT * FROM DownloadTasks WHERE asin = ? AND status = ? ORDER BY completed_at DESC LIMIT 1",
+        )
+        .bind(asin)
+        .bind(TaskStatus::Completed.as_str())
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        row.map(|row| self.row_to_task(row)).transpose()
+    }
+
+    /// Move a finished task's file and sidecars to where the settings for its
+    /// account name it, and record the new path
+    ///
+    /// Folders emptied by the move are removed up to the library root the file
+    /// was found under: the account's tree or the shared output folder. A file
+    /// under neither leaves its folders alone.
+    ///
+    /// # Returns
+    /// Where the file is now
+    async fn move_to_settings_path(&self, task: &DownloadTask, metadata: &AudioMetadata) -> Result<String> {
+        let current = Path::new(&task.output_path);
+        let shared = self.settings().await;
+        let settings = match &task.account_id {
+            Some(account_id) => shared.for_account(account_id),
+            None => shared.clone(),
+        };
+
+        let extension = current.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        let target = settings.output_path_with_extension(metadata, extension)?;
+        if target == current {
+            // Re-tagged in place; the scanner still has the old tags
+            post_write::notify_file_written(current).await;
+            return Ok(current.display().to_string());
+        }
+        if FileManager::file_exists(&target).await {
+            return Err(LibationError::FileAlreadyExists(target.display().to_string()));
+        }
+
+        let root = [&settings.output_dir, &shared.output_dir]
+            .into_iter()
+            .find(|root| current.starts_with(root));
+        let files = FileManager::new(root.cloned().unwrap_or_else(|| settings.output_dir.clone()));
+        files.safe_move(current, &target).await?;
+        for format in [ChapterExportFormat::Cue, ChapterExportFormat::ChaptersTxt] {
+            let sidecar = format.sidecar_path(current);
+            if FileManager::file_exists(&sidecar).await {
+                files.safe_move(&sidecar, &format.sidecar_path(&target)).await?;
+            }
+        }
+        if root.is_some() {
+            if let Err(e) = files.cleanup_empty_directories(current).await {
+                eprintln!("Warning: could not remove empty folders above {}: {}", current.display(), e);
+            }
+        }
+        post_write::notify_file_written(&target).await;
+
+        let target = target.display().to_string();
+        sqlx::query("UPDATE DownloadTasks SET output_path = ? WHERE asin = ? AND output_path = ?")
+            .bind(&target)
+            .bind(&task.asin)
+            .bind(&task.output_path)
+            .execute(&*self.pool)
+            .await?;
+        Ok(target)
+    }
+
+    /// [`reprocess`](Self::reprocess) every downloaded book
+    ///
+    /// Meant to run after the naming or tagging settings change. A book that
+    /// fails is logged and skipped so the rest of the library still updates.
+    ///
+    /// # Returns
+    /// ASINs that were reprocessed
+    pub async fn reprocess_library(&self) -> Result<Vec<String>> {
+        let mut reprocessed = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+
+        for task in self.list_tasks(Some(TaskStatus::Completed)).await? {
+            if !seen.insert(task.asin.clone()) {
+                continue;
+            }
+            match self.reprocess(&task.asin).await {
+                Ok(_) => reprocessed.push(task.asin),
+                Err(e) => eprintln!("Failed to reprocess {}: {}", task.asin, e),
+            }
+        }
+
+        Ok(reprocessed)
+    }
+
+    /// Snapshot of concurrency and throughput for debugging slow batches
+    ///
+    /// Covers every download that transferred data since the manager was
+    /// created, with speeds measured over this session only. Log it with
+    /// `eprintln!("{}", report)` or serialize it to JSON.
+    pub async fn diagnostics(&self) -> Result<DownloadDiagnostics> {
+        let stats = self.transfer_stats.read().await.clone();
+
+        let mut downloads = Vec::with_capacity(stats.len());
+        for (task_id, stats) in &stats {
+            match self.get_task(task_id).await {
+                Ok(task) => downloads.push(DownloadThroughput::from_task(&task, stats)),
+                // Cancelled tasks are deleted from the database
+                Err(_) => continue,
+            }
+        }
+
+        let queued = self.list_tasks(Some(TaskStatus::Queued)).await?.len();
+
+        Ok(DownloadDiagnostics::new(
+            self.max_concurrent,
+            self.max_concurrent_decrypts,
+            self.get_active_count().await,
+            queued,
+            downloads,
+        ))
+    }
+
     /// Register a progress callback for a task
     pub async fn register_progress_callback(&self, task_id: String, callback: ProgressCallback) {
         let mut callbacks = self.progress_callbacks.write().await;
@@ -380,6 +1617,8 @@ impl PersistentDownloadManager {
         let semaphore = Arc::clone(&self.semaphore);
         let callbacks = Arc::clone(&self.progress_callbacks);
         let active = Arc::clone(&self.active_downloads);
+        let transfer_stats = Arc::clone(&self.transfer_stats);
+        let completions = Arc::clone(&self.completions);
 
         // Create cancellation channel
         let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel();
@@ -394,9 +1633,14 @@ impl PersistentDownloadManager {
                 task.clone(),
                 pool.clone(),
                 callbacks.clone(),
+                transfer_stats.clone(),
                 cancel_rx,
             ).await;
 
+            if let Some(stats) = transfer_stats.write().await.get_mut(&task.task_id) {
+                stats.finished = Some(std::time::Instant::now());
+            }
+
             // Handle result
             match result {
                 Ok(()) => {
@@ -410,13 +1654,15 @@ impl PersistentDownloadManager {
                     .execute(&*pool)
                     .await;
 
-                    // Notify callback
-                    if let Some(cb) = callbacks.read().await.get(&task.task_id) {
-                        let mut completed_task = task.clone();
-                        completed_task.status = TaskStatus::Completed;
-                        cb(completed_task);
+                    // Without a decrypt step the download is the finished book
+                    if task.output_path == task.download_path {
+                        post_write::notify_file_written(Path::new(&task.output_path)).await;
                     }
+
+                    Self::announce_completed(&callbacks, &completions, &task).await;
                 }
+                // Paused or cancelled; whoever stopped it records the status
+                Err(LibationError::Cancelled) => {}
                 Err(e) => {
                     // Mark as failed
                     let _ = sqlx::query(
@@ -435,6 +1681,8 @@ impl PersistentDownloadManager {
                         failed_task.error = Some(e.to_string());
                         cb(failed_task);
                     }
+
+                    completions.fail(&task.asin, &e.to_string());
                 }
             }
 
@@ -450,52 +1698,72 @@ impl PersistentDownloadManager {
         active_map.insert(task_id, ActiveDownload { handle, cancel_tx });
     }
 
-    /// Download worker coroutine
-    async fn download_worker(
-        mut task: DownloadTask,
-        pool: Arc<SqlitePool>,
-        callbacks: Arc<RwLock<HashMap<String, ProgressCallback>>>,
-        mut cancel_rx: tokio::sync::oneshot::Receiver<()>,
-    ) -> Result<()> {
-        // Update status to downloading
-        sqlx::query(
-            "UPDATE DownloadTasks SET status = ?, started_at = COALESCE(started_at, ?) WHERE task_id = ?"
-        )
-        .bind(TaskStatus::Downloading.as_str())
-        .bind(chrono::Utc::now().to_rfc3339())
-        .bind(&task.task_id)
-        .execute(&*pool)
-        .await?;
-
-        task.status = TaskStatus::Downloading;
+    /// Resolve waiters for `task`'s book and tell its progress callback it
+    /// finished
+    ///
+    /// Shared by finished downloads and enqueues skipped because the book was
+    /// already on disk, so callers see both the same way.
+    async fn announce_completed(
+        callbacks: &RwLock<HashMap<String, ProgressCallback>>,
+        completions: &CompletionWaiters,
+        task: &DownloadTask,
+    ) {
+        match completion::describe_download(task.finished_path()).await {
+            Ok(file) => completions.complete(&task.asin, &file),
+            Err(e) => completions.fail(&task.asin, &e.to_string()),
+        }
 
-        // Create HTTP client
-        let client = reqwest::Client::new();
+        if let Some(cb) = callbacks.read().await.get(&task.task_id) {
+            let mut completed_task = task.clone();
+            completed_task.status = TaskStatus::Completed;
+            cb(completed_task);
+        }
+    }
 
-        // Build request with headers
+    /// Download worker coroutine
+    /// Request a task's file, from `bytes_downloaded` onwards when resuming
+    async fn send_download_request(client: &reqwest::Client, task: &DownloadTask) -> Result<reqwest::Response> {
         let mut request = client.get(&task.download_url);
         for (key, value) in &task.request_headers {
             request = request.header(key, value);
         }
-
-        // Add Range header for resumption
         if task.bytes_downloaded > 0 {
             request = request.header("Range", format!("bytes={}-", task.bytes_downloaded));
         }
 
-        // Send request
         let response = request.send().await
             .map_err(|e| LibationError::NetworkError {
                 message: format!("Request failed: {}", e),
                 is_transient: true,
             })?;
 
-        if !response.status().is_success() && response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        if !response.status().is_success() {
             return Err(LibationError::NetworkError {
                 message: format!("HTTP {}", response.status()),
                 is_transient: false,
             });
         }
+        Ok(response)
+    }
+
+    async fn download_worker(
+        mut task: DownloadTask,
+        pool: Arc<SqlitePool>,
+        callbacks: Arc<RwLock<HashMap<String, ProgressCallback>>>,
+        transfer_stats: Arc<RwLock<HashMap<String, TransferStats>>>,
+        mut cancel_rx: tokio::sync::oneshot::Receiver<()>,
+    ) -> Result<()> {
+        // Update status to downloading
+        sqlx::query(
+            "UPDATE DownloadTasks SET status = ?, started_at = COALESCE(started_at, ?) WHERE task_id = ?"
+        )
+        .bind(TaskStatus::Downloading.as_str())
+        .bind(chrono::Utc::now().to_rfc3339())
+        .bind(&task.task_id)
+        .execute(&*pool)
+        .await?;
+
+        task.status = TaskStatus::Downloading;
 
         // CRITICAL: Verify file size matches bytes_downloaded before resuming
         if task.bytes_downloaded > 0 {
@@ -535,6 +1803,30 @@ impl PersistentDownloadManager {
             }
         }
 
+        // Create HTTP client (uncompressed, so byte counts match the file)
+        let client = binary_download_client_builder().build()?;
+        let mut response = Self::send_download_request(&client, &task).await?;
+
+        // A resumed request must continue the same file where it stopped;
+        // anything else cannot be appended to the bytes on disk
+        if task.bytes_downloaded > 0 {
+            if let Some(reason) = resume_mismatch(&response, &task) {
+                eprintln!("Warning: {}: {}, restarting the download", task.asin, reason);
+                task.bytes_downloaded = 0;
+                sqlx::query("UPDATE DownloadTasks SET bytes_downloaded = 0 WHERE task_id = ?")
+                    .bind(&task.task_id)
+                    .execute(&*pool)
+                    .await?;
+                response = Self::send_download_request(&client, &task).await?;
+            }
+        }
+
+        // Reject error pages sent with a success status before writing anything
+        validate::check_content_type(
+            response.headers().get(reqwest::header::CONTENT_TYPE).and_then(|v| v.to_str().ok()),
+        )?;
+        let mut check_body = task.bytes_downloaded == 0;
+
         // Open file for writing (append mode if resuming)
         let mut file = if task.bytes_downloaded > 0 {
             fs::OpenOptions::new()
@@ -549,12 +1841,16 @@ impl PersistentDownloadManager {
         // Download stream
         let mut stream = response.bytes_stream();
         let mut last_update = tokio::time::Instant::now();
+        let mut session_bytes = 0u64;
+        transfer_stats
+            .write()
+            .await
+            .insert(task.task_id.clone(), TransferStats::new());
 
         while let Some(chunk_result) = tokio::select! {
             chunk = stream.next() => chunk,
             _ = &mut cancel_rx => {
-                // Cancelled
-                return Ok(());
+                return Err(LibationError::Cancelled);
             }
         } {
             let chunk = chunk_result.map_err(|e| LibationError::NetworkError {
@@ -562,9 +1858,19 @@ impl PersistentDownloadManager {
                 is_transient: true,
             })?;
 
+            if check_body {
+                if let Err(e) = validate::check_body_start(&chunk) {
+                    drop(file);
+                    let _ = fs::remove_file(&task.download_path).await;
+                    return Err(e);
+                }
+                check_body = false;
+            }
+
             // Write chunk
             file.write_all(&chunk).await?;
             task.bytes_downloaded += chunk.len() as u64;
+            session_bytes += chunk.len() as u64;
 
             // Update database periodically (every 1 second)
             if last_update.elapsed() >= tokio::time::Duration::from_secs(1) {
@@ -581,6 +1887,10 @@ impl PersistentDownloadManager {
                     cb(task.clone());
                 }
 
+                if let Some(stats) = transfer_stats.write().await.get_mut(&task.task_id) {
+                    stats.session_bytes = session_bytes;
+                }
+
                 last_update = tokio::time::Instant::now();
             }
         }
@@ -588,6 +1898,10 @@ impl PersistentDownloadManager {
         // Flush file
         file.flush().await?;
 
+        if let Some(stats) = transfer_stats.write().await.get_mut(&task.task_id) {
+            stats.session_bytes = session_bytes;
+        }
+
         // Final database update
         sqlx::query(
             "UPDATE DownloadTasks SET bytes_downloaded = ? WHERE task_id = ?"
@@ -636,13 +1950,74 @@ impl PersistentDownloadManager {
             created_at: row.try_get("created_at")?,
             started_at: row.try_get("started_at").ok(),
             completed_at: row.try_get("completed_at").ok(),
+            acr: row.try_get::<Option<String>, _>("acr").ok().flatten(),
+            content_version: row.try_get::<Option<String>, _>("content_version").ok().flatten(),
+            quality: row
+                .try_get::<Option<String>, _>("quality")
+                .ok()
+                .flatten()
+                .and_then(|text| serde_json::from_str(&text).ok()),
+            aac_codec: row
+                .try_get::<Option<String>, _>("aac_codec")
+                .ok()
+                .flatten()
+                .and_then(|text| serde_json::from_str(&text).ok()),
+            account_id: row.try_get::<Option<String>, _>("account_id").ok().flatten(),
         })
     }
 }
 
+/// Why a response to a resumed request cannot be appended to the partial file
+///
+/// The server must answer with `206 Partial Content` starting at the byte the
+/// task stopped at, and the `Content-Range` total must be the size the task
+/// was started with.
+fn resume_mismatch(response: &reqwest::Response, task: &DownloadTask) -> Option<String> {
+    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Some("the server ignored the range request".to_string());
+    }
+    let content_range = response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)
+        .and_then(|value| value.to_str().ok());
+    let Some((start, total)) = content_range.and_then(parse_content_range) else {
+        return Some("the partial response has no valid Content-Range".to_string());
+    };
+    if start != task.bytes_downloaded {
+        return Some(format!(
+            "the server resumed at byte {} instead of {}",
+            start, task.bytes_downloaded
+        ));
+    }
+    if task.total_bytes > 0 && total != Some(task.total_bytes) {
+        return Some(format!(
+            "the file is now {} bytes instead of {}",
+            total.map_or_else(|| "an unknown number of".to_string(), |total| total.to_string()),
+            task.total_bytes
+        ));
+    }
+    None
+}
+
+/// Start and total size from `Content-Range: bytes 1000-1999/2000`
+///
+/// The total is `None` when the server sends `*`.
+fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let start = range.split_once('-')?.0.trim().parse().ok()?;
+    Some((start, total.trim().parse().ok()))
+}
+
+/// Refuse pre-orders before a license is requested for them
+fn ensure_book_released(book: &queries::BookWithRelations) -> Result<()> {
+    let release_date = book.date_published.as_deref().and_then(parse_lenient_date);
+    ensure_released(release_date, chrono::Utc::now().date_naive())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::client::fixture::{canned_client, respond, CannedTransport};
     use crate::storage::Database;
 
     #[tokio::test]
@@ -658,11 +2033,44 @@ mod tests {
             "/tmp/book.aax".to_string(),
             "/tmp/book.m4b".to_string(),
             HashMap::new(),
+            false,
         ).await.unwrap();
 
         let task = manager.get_task(&task_id).await.unwrap();
         assert_eq!(task.asin, "B001");
         assert_eq!(task.status, TaskStatus::Queued);
+        assert_eq!(task.quality, None);
+
+        manager.set_license_format(&task_id, DownloadQuality::Extreme, Codec::XHeAac).await.unwrap();
+        let task = manager.get_task(&task_id).await.unwrap();
+        assert_eq!(task.quality, Some(DownloadQuality::Extreme));
+        assert_eq!(task.aac_codec, Some(Codec::XHeAac));
+    }
+
+    #[tokio::test]
+    async fn test_resume_mismatch() {
+        let db = Database::new_in_memory().await.unwrap();
+        let manager = PersistentDownloadManager::new(Arc::new(db.pool().clone()), 3).await.unwrap();
+        let task_id = manager.enqueue_download(
+            "B001".to_string(), "Book".to_string(), "https://example.com/1".to_string(),
+            2000, "/tmp/1.aax".to_string(), "/tmp/1.m4b".to_string(), HashMap::new(), false,
+        ).await.unwrap();
+        let mut task = manager.get_task(&task_id).await.unwrap();
+        task.bytes_downloaded = 1000;
+
+        let response = |status: u16, content_range: Option<&str>| -> reqwest::Response {
+            let mut builder = http::Response::builder().status(status);
+            if let Some(content_range) = content_range {
+                builder = builder.header("Content-Range", content_range);
+            }
+            builder.body("").unwrap().into()
+        };
+        assert_eq!(resume_mismatch(&response(206, Some("bytes 1000-1999/2000")), &task), None);
+        assert!(resume_mismatch(&response(200, None), &task).is_some());
+        assert!(resume_mismatch(&response(206, None), &task).is_some());
+        assert!(resume_mismatch(&response(206, Some("bytes 0-1999/2000")), &task).is_some());
+        assert!(resume_mismatch(&response(206, Some("bytes 1000-2499/2500")), &task).is_some());
+        assert!(resume_mismatch(&response(206, Some("bytes 1000-1999/*")), &task).is_some());
     }
 
     #[tokio::test]
@@ -672,12 +2080,12 @@ mod tests {
 
         manager.enqueue_download(
             "B001".to_string(), "Book 1".to_string(), "https://example.com/1".to_string(),
-            1000, "/tmp/1.aax".to_string(), "/tmp/1.m4b".to_string(), HashMap::new(),
+            1000, "/tmp/1.aax".to_string(), "/tmp/1.m4b".to_string(), HashMap::new(), false,
         ).await.unwrap();
 
         manager.enqueue_download(
             "B002".to_string(), "Book 2".to_string(), "https://example.com/2".to_string(),
-            2000, "/tmp/2.aax".to_string(), "/tmp/2.m4b".to_string(), HashMap::new(),
+            2000, "/tmp/2.aax".to_string(), "/tmp/2.m4b".to_string(), HashMap::new(), false,
         ).await.unwrap();
 
         let tasks = manager.list_tasks(None).await.unwrap();
@@ -691,7 +2099,7 @@ mod tests {
 
         let task_id = manager.enqueue_download(
             "B001".to_string(), "Test Book".to_string(), "https://example.com/book.aax".to_string(),
-            1000, "/tmp/book.aax".to_string(), "/tmp/book.m4b".to_string(), HashMap::new(),
+            1000, "/tmp/book.aax".to_string(), "/tmp/book.m4b".to_string(), HashMap::new(), false,
         ).await.unwrap();
 
         manager.pause_download(&task_id).await.unwrap();
@@ -699,4 +2107,460 @@ mod tests {
         let task = manager.get_task(&task_id).await.unwrap();
         assert_eq!(task.status, TaskStatus::Paused);
     }
+
+    #[tokio::test]
+    async fn test_batch_reports_each_outcome() {
+        let db = Database::new_in_memory().await.unwrap();
+        // No download slots, so tasks stay where the test puts them
+        let manager = PersistentDownloadManager::new(Arc::new(db.pool().clone()), 0).await.unwrap();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let existing = temp_dir.path().join("done.m4b");
+        fs::write(&existing, b"decrypted audio").await.unwrap();
+
+        let request = |asin: &str, output: String| DownloadRequest {
+            asin: asin.to_string(),
+            title: format!("Book {}", asin),
+            download_url: format!("https://example.com/{}", asin),
+            total_bytes: 1000,
+            download_path: format!("/tmp/{}.aax", asin),
+            output_path: output,
+            request_headers: HashMap::new(),
+        };
+        let mut batch = manager
+            .enqueue_batch(
+                vec![
+                    request("B001", "/tmp/B001.m4b".to_string()),
+                    request("B002", "/tmp/B002.m4b".to_string()),
+                    request("B003", existing.to_string_lossy().to_string()),
+                    request("B001", "/tmp/B001.m4b".to_string()),
+                    request("B004", "/tmp/B004.m4b".to_string()),
+                ],
+                false,
+            )
+            .await;
+        batch.record_failure("B005", "Book B005", "Title not available in this marketplace");
+
+        let summary = manager.batch_summary(&batch).await.unwrap();
+        assert_eq!(summary.pending, 3);
+        assert!(!summary.is_finished());
+
+        let task_of = |asin: &str| batch.entries.iter().find(|e| e.asin == asin).unwrap().task_id.clone().unwrap();
+        sqlx::query("UPDATE DownloadTasks SET status = 'completed' WHERE task_id = ?")
+            .bind(task_of("B001"))
+            .execute(db.pool())
+            .await
+            .unwrap();
+        sqlx::query("UPDATE DownloadTasks SET status = 'failed', error = 'HTTP 403' WHERE task_id = ?")
+            .bind(task_of("B002"))
+            .execute(db.pool())
+            .await
+            .unwrap();
+        manager.cancel_download(&task_of("B004")).await.unwrap();
+
+        let summary = manager.batch_summary(&batch).await.unwrap();
+        assert!(summary.is_finished());
+        assert_eq!((summary.succeeded, summary.failed, summary.skipped), (1, 2, 3));
+
+        let failures: Vec<(&str, &str)> = summary
+            .failures()
+            .map(|f| (f.asin.as_str(), f.reason.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            failures,
+            vec![("B002", "HTTP 403"), ("B005", "Title not available in this marketplace")]
+        );
+        assert!(summary.to_string().starts_with("1 succeeded, 2 failed, 3 skipped"));
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_skips_already_downloaded() {
+        let db = Database::new_in_memory().await.unwrap();
+        let manager = PersistentDownloadManager::new(Arc::new(db.pool().clone()), 3).await.unwrap();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("book.m4b");
+        fs::write(&output_path, b"decrypted audio").await.unwrap();
+        let output_path = output_path.to_string_lossy().to_string();
+        let pending = manager.completions.subscribe("B001");
+
+        let task_id = manager.enqueue_download(
+            "B001".to_string(), "Test Book".to_string(), "https://example.com/book.aax".to_string(),
+            1000, "/tmp/book.aax".to_string(), output_path.clone(), HashMap::new(), false,
+        ).await.unwrap();
+
+        // Waiters hear about the skipped book as if it had just downloaded
+        let book = pending.await.unwrap();
+        assert_eq!(book.path.to_string_lossy(), output_path);
+        let task = manager.get_task(&task_id).await.unwrap();
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert_eq!(task.bytes_downloaded, 1000);
+        assert!(task.completed_at.is_some());
+        assert_eq!(manager.get_active_count().await, 0);
+
+        // Forcing queues a fresh download
+        let task_id = manager.enqueue_download(
+            "B001".to_string(), "Test Book".to_string(), "https://example.com/book.aax".to_string(),
+            1000, "/tmp/book.aax".to_string(), output_path, HashMap::new(), true,
+        ).await.unwrap();
+
+        let task = manager.get_task(&task_id).await.unwrap();
+        assert_ne!(task.status, TaskStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_find_corrupt_downloads() {
+        let db = Database::new_in_memory().await.unwrap();
+        let manager = PersistentDownloadManager::new(Arc::new(db.pool().clone()), 3).await.unwrap();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let good = temp_dir.path().join("good.m4b");
+        fs::write(&good, b"\x00\x00\x00\x20ftypM4B \x00\x00\x00\x00").await.unwrap();
+        let legacy = temp_dir.path().join("legacy.m4b");
+        fs::write(&legacy, b"<?xml version=\"1.0\"?><Error>AccessDenied</Error>").await.unwrap();
+
+        for (asin, path) in [("B001", &good), ("B002", &legacy)] {
+            manager.enqueue_download(
+                asin.to_string(), asin.to_string(), "https://example.com/book.aax".to_string(),
+                1000, "/tmp/book.aax".to_string(), path.to_string_lossy().to_string(), HashMap::new(), false,
+            ).await.unwrap();
+        }
+
+        let corrupt = manager.find_corrupt_downloads().await.unwrap();
+        assert_eq!(corrupt.len(), 1);
+        assert_eq!(corrupt[0].0.asin, "B002");
+        assert!(matches!(corrupt[0].1, validate::IntegrityIssue::NotAudio(_)));
+    }
+
+    #[tokio::test]
+    async fn test_sync_library_checks_downloads() {
+        let db = Database::new_in_memory().await.unwrap();
+        let manager = PersistentDownloadManager::new(Arc::new(db.pool().clone()), 3).await.unwrap();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let deleted = temp_dir.path().join("deleted.m4b");
+        let corrupt = temp_dir.path().join("corrupt.m4b");
+        fs::write(&deleted, b"\x00\x00\x00\x20ftypM4B \x00\x00\x00\x00").await.unwrap();
+        fs::write(&corrupt, b"\x00\x00\x00\x20ftypM4B \x00\x00\x00\x00").await.unwrap();
+        let mut task_ids = Vec::new();
+        for (asin, path) in [("B001", &deleted), ("B002", &corrupt)] {
+            task_ids.push(manager.enqueue_download(
+                asin.to_string(), asin.to_string(), "https://example.com/book.aax".to_string(),
+                1000, "/tmp/book.aax".to_string(), path.to_string_lossy().to_string(), HashMap::new(), false,
+            ).await.unwrap());
+        }
+        // The user deleted one book; the other was damaged on disk
+        fs::remove_file(&deleted).await.unwrap();
+        fs::write(&corrupt, b"<html>Access Denied</html>").await.unwrap();
+
+        let account = Account::new("sync@example.com".to_string()).unwrap();
+        // Serves a one-book library page and answers everything else with 404
+        let transport = CannedTransport::new(|request| {
+            if request.url.path() == "/1.0/library" {
+                respond(200, r#"{"items": [{"asin": "B001", "title": "Synced"}], "total_results": 1}"#)
+            } else {
+                respond(404, "{}")
+            }
+        });
+        let mut client = AudibleClient::with_transport(
+            account.clone(),
+            crate::api::client::ClientConfig::default(),
+            Arc::new(transport),
+        )
+        .unwrap();
+        let (stats, repairs) = manager.sync_library(&mut client, &db, &account).await.unwrap();
+
+        assert_eq!(stats.books_added, 1);
+        // Missing files are reported, not downloaded again; the license for
+        // the damaged one is refused by the mock, so its repair fails
+        assert_eq!(repairs.missing, vec![task_ids[0].clone()]);
+        assert_eq!(repairs.failed, vec![task_ids[1].clone()]);
+        assert!(repairs.requeued.is_empty());
+        assert_eq!(manager.get_task(&task_ids[0]).await.unwrap().status, TaskStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_deduplicates_in_flight_asin() {
+        let db = Database::new_in_memory().await.unwrap();
+        // No download slots, so tasks stay queued
+        let manager = PersistentDownloadManager::new(Arc::new(db.pool().clone()), 0).await.unwrap();
+
+        let enqueue = || manager.enqueue_download(
+            "B002".to_string(), "Test Book".to_string(), "https://example.com/book.aax".to_string(),
+            1000, "/tmp/b002.aax".to_string(), "/tmp/b002.m4b".to_string(), HashMap::new(), false,
+        );
+        let (first, second) = tokio::join!(enqueue(), enqueue());
+        let first = first.unwrap();
+        assert_eq!(first, second.unwrap());
+        assert_eq!(manager.list_tasks(None).await.unwrap().len(), 1);
+
+        // Once the task is finished, a new download can be queued
+        manager.cancel_download(&first).await.unwrap();
+        let third = enqueue().await.unwrap();
+        assert_ne!(first, third);
+
+        // Another account owning the same title gets its own task
+        let other = manager.enqueue_for_account(
+            Some("amzn1.account.OTHER".to_string()), "B002".to_string(), "Test Book".to_string(),
+            "https://example.com/book.aax".to_string(), 1000, "/tmp/other/b002.aax".to_string(),
+            "/tmp/other/b002.m4b".to_string(), HashMap::new(), false,
+        ).await.unwrap();
+        assert_ne!(other, third);
+        let task = manager.find_in_flight_task(Some("amzn1.account.OTHER"), "B002").await.unwrap().unwrap();
+        assert_eq!(task.task_id, other);
+        assert_eq!(task.account_id.as_deref(), Some("amzn1.account.OTHER"));
+        assert_eq!(manager.find_in_flight_task(None, "B002").await.unwrap().unwrap().task_id, third);
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_limit_is_separate_from_downloads() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let db = Database::new_in_memory().await.unwrap();
+        let manager = Arc::new(
+            PersistentDownloadManager::with_limits(Arc::new(db.pool().clone()), 4, 2)
+                .await
+                .unwrap(),
+        );
+        assert_eq!(manager.max_concurrent_decrypts(), 2);
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let jobs: Vec<_> = (0..4)
+            .map(|_| {
+                let manager = Arc::clone(&manager);
+                let running = Arc::clone(&running);
+                let peak = Arc::clone(&peak);
+                tokio::spawn(async move {
+                    manager
+                        .run_decrypt(async {
+                            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                            peak.fetch_max(now, Ordering::SeqCst);
+                            tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+                            running.fetch_sub(1, Ordering::SeqCst);
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        for job in jobs {
+            job.await.unwrap();
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(manager.semaphore.available_permits(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_requires_downloaded_file() {
+        let db = Database::new_in_memory().await.unwrap();
+        let manager = PersistentDownloadManager::new(Arc::new(db.pool().clone()), 3).await.unwrap();
+        let book = crate::storage::models::NewBook::new("B0REPROC1".to_string(), "Dune".to_string(), "us".to_string());
+        queries::insert_book(db.pool(), &book).await.unwrap();
+
+        let err = manager.reprocess("B0REPROC1").await.unwrap_err();
+        assert!(matches!(err, LibationError::RecordNotFound(_)));
+
+        // Recorded as completed directly so no download is started
+        sqlx::query(
+            "INSERT INTO DownloadTasks (task_id, asin, title, status, bytes_downloaded, total_bytes,
+             download_url, download_path, output_path, request_headers, created_at, completed_at)
+             VALUES ('t1', 'B0REPROC1', 'Dune', 'completed', 1000, 1000, 'https://example.com/dune',
+             '/nonexistent/dune.aaxc', '/nonexistent/Dune.m4b', '{}', '2025-01-01', '2025-01-01')",
+        )
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        let err = manager.reprocess("B0REPROC1").await.unwrap_err();
+        assert!(matches!(err, LibationError::FileNotFound(_)));
+        assert!(manager.reprocess_library().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_moves_file_within_account_tree() {
+        let db = Database::new_in_memory().await.unwrap();
+        let manager = PersistentDownloadManager::new(Arc::new(db.pool().clone()), 3).await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        manager
+            .set_settings(DownloadSettings { storage_root: Some(dir.path().to_path_buf()), ..Default::default() })
+            .await
+            .unwrap();
+        let books = crate::file::AccountPaths::new(dir.path(), "reader@example.com").books;
+
+        let old_folder = books.join("Old Folder");
+        std::fs::create_dir_all(&old_folder).unwrap();
+        let current = old_folder.join("Old Name.m4b");
+        std::fs::write(&current, b"audio").unwrap();
+        std::fs::write(ChapterExportFormat::Cue.sidecar_path(&current), b"cue").unwrap();
+        sqlx::query(
+            "INSERT INTO DownloadTasks (task_id, asin, title, status, bytes_downloaded, total_bytes,
+             download_url, download_path, output_path, request_headers, created_at, completed_at, account_id)
+             VALUES ('t1', 'B0REPROC2', 'Dune', 'completed', 5, 5, 'https://example.com/dune',
+             '/nonexistent/dune.aaxc', ?, '{}', '2025-01-01', '2025-01-01', 'reader@example.com')",
+        )
+        .bind(current.to_string_lossy().to_string())
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        let book = crate::storage::models::NewBook::new("B0REPROC2".to_string(), "Dune".to_string(), "us".to_string());
+        queries::insert_book(db.pool(), &book).await.unwrap();
+        let metadata = queries::find_book_with_relations_by_asin(db.pool(), "B0REPROC2")
+            .await
+            .unwrap()
+            .unwrap()
+            .to_audio_metadata();
+        let task = manager.latest_completed_task("B0REPROC2").await.unwrap().unwrap();
+
+        let target = PathBuf::from(manager.move_to_settings_path(&task, &metadata).await.unwrap());
+        assert!(target.starts_with(&books));
+        assert_eq!(std::fs::read(&target).unwrap(), b"audio");
+        assert!(ChapterExportFormat::Cue.sidecar_path(&target).exists());
+        assert!(!current.exists());
+        // The emptied folder goes; the account's library root stays
+        assert!(!old_folder.exists());
+        assert!(books.exists());
+        assert_eq!(manager.get_task("t1").await.unwrap().output_path, target.to_string_lossy());
+    }
+
+    #[tokio::test]
+    async fn test_finish_book_parts() {
+        let db = Database::new_in_memory().await.unwrap();
+        let manager = PersistentDownloadManager::new(Arc::new(db.pool().clone()), 3).await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        // Recorded directly so no download is started
+        let mut parts = Vec::new();
+        for (task_id, status) in [("p1", "completed"), ("p2", "downloading")] {
+            let output = dir.path().join(format!("Long Book - Part {}.m4b", task_id));
+            std::fs::write(&output, b"audio").unwrap();
+            sqlx::query(
+                "INSERT INTO DownloadTasks (task_id, asin, title, status, bytes_downloaded, total_bytes,
+                 download_url, download_path, output_path, request_headers, created_at)
+                 VALUES (?, ?, 'Long Book (Part)', ?, 0, 5, 'https://example.com', '/nonexistent/part.aaxc', ?,
+                 '{}', '2025-01-01')",
+            )
+            .bind(task_id)
+            .bind(format!("B0{}", task_id))
+            .bind(status)
+            .bind(output.to_string_lossy().to_string())
+            .execute(db.pool())
+            .await
+            .unwrap();
+            parts.push(output);
+        }
+
+        let mut download = MultiPartDownload {
+            asin: "B0LONG".to_string(),
+            part_asins: vec!["B0p1".to_string(), "B0p2".to_string()],
+            task_ids: vec!["p1".to_string(), "p2".to_string()],
+            joined_path: None,
+        };
+        let err = manager.finish_book_parts(&download).await.unwrap_err();
+        assert!(matches!(err, LibationError::InvalidState(_)));
+
+        manager.update_task_status("p2", TaskStatus::Completed).await.unwrap();
+        assert_eq!(manager.finish_book_parts(&download).await.unwrap(), parts);
+
+        // Joined file found on disk when enqueueing, so no parts were downloaded
+        let joined = dir.path().join("Long Book.m4b");
+        std::fs::write(&joined, b"audio").unwrap();
+        download.task_ids.clear();
+        download.joined_path = Some(joined.to_string_lossy().into_owned());
+        assert_eq!(manager.finish_book_parts(&download).await.unwrap(), vec![joined]);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_result() {
+        let db = Database::new_in_memory().await.unwrap();
+        let manager = PersistentDownloadManager::new(Arc::new(db.pool().clone()), 3).await.unwrap();
+
+        let err = manager.wait_for_result("B0NONE").await.unwrap_err();
+        assert!(matches!(err, LibationError::RecordNotFound(_)));
+
+        let dir = tempfile::tempdir().unwrap();
+        let download = dir.path().join("B0DONE.aaxc");
+        std::fs::write(&download, vec![0u8; 2048]).unwrap();
+
+        // Recorded directly so no download is started
+        for (task_id, asin, status, error) in [
+            ("t1", "B0DONE", "completed", None),
+            ("t2", "B0FAIL", "failed", Some("HTTP 403")),
+            ("t3", "B0WAIT", "paused", None),
+        ] {
+            sqlx::query(
+                "INSERT INTO DownloadTasks (task_id, asin, title, status, bytes_downloaded, total_bytes,
+                 download_url, download_path, output_path, request_headers, error, created_at)
+                 VALUES (?, ?, 'Book', ?, 0, 2048, 'https://example.com', ?, '/nonexistent/Book.m4b', '{}', ?,
+                 '2025-01-01')",
+            )
+            .bind(task_id)
+            .bind(asin)
+            .bind(status)
+            .bind(download.to_string_lossy().to_string())
+            .bind(error)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        }
+
+        let book = manager.wait_for_result("B0DONE").await.unwrap().await.unwrap();
+        assert_eq!((book.path, book.size), (download, 2048));
+
+        let err = manager.wait_for_result("B0FAIL").await.unwrap().await.unwrap_err();
+        assert!(matches!(err, LibationError::DownloadFailed(reason) if reason == "HTTP 403"));
+
+        // Answered from the database, so nothing is left waiting
+        for asin in ["B0NONE", "B0DONE", "B0FAIL"] {
+            assert_eq!(manager.completions.waiting(asin), 0);
+        }
+
+        // A paused download resolves only once it is finished or cancelled
+        let pending = manager.wait_for_result("B0WAIT").await.unwrap();
+        manager.cancel_download("t3").await.unwrap();
+        assert!(matches!(pending.await, Err(LibationError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_find_updated_masters() {
+        let db = Database::new_in_memory().await.unwrap();
+        let manager = PersistentDownloadManager::new(Arc::new(db.pool().clone()), 3).await.unwrap();
+        let current_master = serde_json::json!({
+            "content_metadata": {
+                "content_reference": {
+                    "acr": "CR!NEW", "sku": "BK_ADBL_000001", "version": "2", "codec": "AAC_LC"
+                },
+                "content_url": { "offline_url": null }
+            }
+        });
+        let client = canned_client("x@example.com", Arc::new(CannedTransport::fixed(200, current_master.to_string())));
+
+        // B0OLD was downloaded from an older master, B0SAME from the current
+        // one, and B0UNKNOWN before ACRs were recorded
+        for (task_id, asin, acr) in [("t1", "B0OLD", Some("CR!OLD")), ("t2", "B0SAME", Some("CR!NEW")), ("t3", "B0UNKNOWN", None)] {
+            sqlx::query(
+                "INSERT INTO DownloadTasks (task_id, asin, title, status, bytes_downloaded, total_bytes,
+                 download_url, download_path, output_path, request_headers, created_at, completed_at, acr)
+                 VALUES (?, ?, 'Book', 'completed', 1, 1, 'https://example.com', '/a', '/b', '{}',
+                 '2025-01-01', '2025-01-01', ?)",
+            )
+            .bind(task_id)
+            .bind(asin)
+            .bind(acr)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        }
+        assert_eq!(manager.get_task("t1").await.unwrap().acr.as_deref(), Some("CR!OLD"));
+
+        let updates = manager.find_updated_masters(&client).await.unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].task_id, "t1");
+        assert_eq!(updates[0].current_acr, "CR!NEW");
+        assert_eq!(updates[0].current_version, "2");
+        assert_eq!(updates[0].downloaded_version, None);
+    }
 }
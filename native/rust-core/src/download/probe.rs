@@ -0,0 +1,183 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! HEAD probe for download URLs
+//!
+//! # Reference C# Sources
+//! - **`AaxDecrypter/NetworkFileStream.cs`** - Reads `ContentLength` and checks
+//!   the status code before starting a download (lines 182-218)
+//!
+//! Used to learn a file's size before queueing a download and to check that a
+//! CDN URL is still valid (they expire after about an hour).
+
+use crate::api::client::binary_download_client_builder;
+use crate::error::{LibationError, Result};
+use reqwest::header::{HeaderMap, ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_TYPE, LAST_MODIFIED, USER_AGENT};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// What a HEAD request reveals about a download URL
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrlInfo {
+    /// File size from `Content-Length`, if the server sent it
+    pub size: Option<u64>,
+
+    /// MIME type from `Content-Type`
+    pub content_type: Option<String>,
+
+    /// Whether the server accepts byte ranges (needed to resume downloads)
+    pub accept_ranges: bool,
+
+    /// Raw `Last-Modified` header value
+    pub last_modified: Option<String>,
+}
+
+impl UrlInfo {
+    /// Extract URL info from response headers
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.trim().to_string())
+        };
+
+        Self {
+            size: header(CONTENT_LENGTH).and_then(|s| s.parse().ok()),
+            content_type: header(CONTENT_TYPE),
+            accept_ranges: header(ACCEPT_RANGES)
+                .is_some_and(|v| v.split(',').any(|unit| unit.trim().eq_ignore_ascii_case("bytes"))),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+}
+
+/// Send a HEAD request to `url` and report size and capabilities
+///
+/// # Arguments
+/// * `url` - URL to probe (usually a CDN download URL)
+/// * `user_agent` - User-Agent header; Audible's CDN rejects some defaults
+///
+/// # Errors
+/// - `InvalidDownloadUrl` - URL cannot be parsed
+/// - `NetworkError` - Request could not be sent
+/// - `UnexpectedStatusCode` - Server answered with a non-success status
+///   (e.g. 403 for an expired CDN URL)
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example() -> rust_core::error::Result<()> {
+/// use rust_core::download::probe_url;
+///
+/// let info = probe_url("https://example.com/book.aaxc", "Audible/671").await?;
+/// println!("{:?} bytes, resumable: {}", info.size, info.accept_ranges);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn probe_url(url: &str, user_agent: &str) -> Result<UrlInfo> {
+    probe_url_with_headers(url, &HashMap::from([(USER_AGENT.to_string(), user_agent.to_string())])).await
+}
+
+/// Like [`probe_url`], sending the headers the download itself will use
+///
+/// Pass [`download_headers`](super::cdn::download_headers) for the URL, so a
+/// CDN that checks more than the user agent answers the probe too.
+///
+/// # Errors
+/// Same as [`probe_url`]
+pub async fn probe_url_with_headers(url: &str, headers: &HashMap<String, String>) -> Result<UrlInfo> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| LibationError::InvalidDownloadUrl(format!("{}: {}", url, e)))?;
+
+    // Without Accept-Encoding, Content-Length is the size of the file itself
+    let mut request = binary_download_client_builder().build()?.head(parsed.clone());
+    for (key, value) in headers {
+        request = request.header(key, value);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| LibationError::network_error(format!("HEAD request failed: {}", e), true))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(LibationError::UnexpectedStatusCode {
+            status_code: status.as_u16(),
+            host: parsed.host_str().unwrap_or_default().to_string(),
+        });
+    }
+
+    Ok(UrlInfo::from_headers(response.headers()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Answer one request on a local port with `response`, returning the URL
+    async fn serve_once(response: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+        format!("http://{}/book.aaxc", addr)
+    }
+
+    #[tokio::test]
+    async fn test_probe_url_reads_headers() {
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Length: 123456\r\n\
+             Content-Type: audio/vnd.audible.aax\r\n\
+             Accept-Ranges: bytes\r\n\
+             Last-Modified: Tue, 01 Oct 2024 10:00:00 GMT\r\n\
+             Connection: close\r\n\r\n",
+        )
+        .await;
+
+        let info = probe_url(&url, "Test/1.0").await.unwrap();
+        assert_eq!(info.size, Some(123456));
+        assert_eq!(info.content_type.as_deref(), Some("audio/vnd.audible.aax"));
+        assert!(info.accept_ranges);
+        assert_eq!(info.last_modified.as_deref(), Some("Tue, 01 Oct 2024 10:00:00 GMT"));
+    }
+
+    #[tokio::test]
+    async fn test_probe_url_rejects_error_status() {
+        let url = serve_once("HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+
+        let err = probe_url(&url, "Test/1.0").await.unwrap_err();
+        assert!(matches!(err, LibationError::UnexpectedStatusCode { status_code: 403, .. }));
+    }
+
+    #[test]
+    fn test_url_info_without_range_support() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT_RANGES, "none".parse().unwrap());
+        let info = UrlInfo::from_headers(&headers);
+        assert_eq!(info, UrlInfo::default());
+    }
+}
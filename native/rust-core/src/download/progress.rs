@@ -35,6 +35,7 @@
 //! }
 //! ```
 
+use crate::download::titles;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
@@ -126,6 +127,14 @@ impl DownloadProgress {
         }
     }
 
+    /// Create a progress report for `asin`, with the title from the
+    /// [title cache](super::titles)
+    ///
+    /// Books missing from the cache are reported under their ASIN.
+    pub fn for_asin(asin: &str, bytes_received: u64, total_bytes: u64) -> Self {
+        Self::new(asin.to_string(), titles::title_or_asin(asin), bytes_received, total_bytes)
+    }
+
     /// Update with speed and time remaining estimates
     pub fn with_estimates(mut self, bytes_per_second: u64) -> Self {
         self.bytes_per_second = bytes_per_second;
@@ -252,6 +261,11 @@ impl ProgressTracker {
         }
     }
 
+    /// Create a progress tracker for `asin`, looking up its title
+    pub fn for_asin(asin: &str, total_bytes: u64) -> Self {
+        Self::new(asin.to_string(), titles::title_or_asin(asin), total_bytes)
+    }
+
     /// Update progress with new position
     /// Returns true if enough time has passed to trigger a callback
     pub fn update(&mut self, bytes_received: u64, total_bytes: u64) {
@@ -0,0 +1,344 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Download defaults set once by the host app
+//!
+//! # Reference C# Sources
+//! - **`LibationFileManager/Configuration.PersistentSettings.cs`** - `FileDownloadQuality`,
+//!   `DecryptToLossy`, `SplitFilesByChapter`, `StripAudibleBrandAudio`, `Books`
+//! - **`FileLiberator/DownloadOptions.Factory.cs`** - Reads those settings when
+//!   building the options for one book
+//!
+//! Libation reads quality, format and naming from its global configuration
+//! every time a book is liberated. [`DownloadSettings`] is the equivalent:
+//! the host hands it to the
+//! [`PersistentDownloadManager`](super::PersistentDownloadManager) once, and
+//! [`enqueue_book`](super::PersistentDownloadManager::enqueue_book) derives
+//! the license quality, file paths and conversion options from it instead of
+//! taking them with every call.
+
+use crate::api::content::{ChapterInfo, Codec, DownloadQuality};
+use crate::api::license::FileType;
+use crate::audio::decoder::AudioFormat;
+use crate::audio::metadata::AudioMetadata;
+use crate::audio::{BrandTrim, ConcatOptions, ConversionOptions};
+use crate::error::{LibationError, Result};
+use crate::file::AccountPaths;
+use crate::file::paths::{
+    build_file_path_with_limits, get_default_library_path, truncate_component, FilesystemLimits, MissingNames,
+    NamingPattern,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Quality, format and naming applied to every download
+///
+/// Missing fields take their default when deserialized, so the host only
+/// sends what the user changed.
+///
+/// # Example
+/// ```rust,no_run
+/// use rust_core::download::settings::DownloadSettings;
+///
+/// let settings: DownloadSettings = serde_json::from_str(
+///     r#"{"quality": "Normal", "naming_pattern": "author_book_folder", "split_chapters": true}"#,
+/// ).unwrap();
+/// settings.validate().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadSettings {
+    /// Quality requested in the license
+    pub quality: DownloadQuality,
+    /// Accept a lower tier when the book is not offered at `quality`
+    pub quality_fallback: bool,
+    /// AAC codec requested in the license (`AAC_LC` or `xHE_AAC`)
+    pub aac_codec: Codec,
+    /// Format of the decrypted file
+    pub output_format: AudioFormat,
+    /// Folder and file layout under `output_dir`
+    pub naming_pattern: NamingPattern,
+    /// Placeholders for books without an author, narrator or title
+    pub missing_names: MissingNames,
+    /// Name length limits of the filesystem under `output_dir`
+    pub filesystem_limits: FilesystemLimits,
+    /// Cut the Audible brand intro and outro
+    pub trim_intro: bool,
+    /// Write one file per chapter
+    pub split_chapters: bool,
+    /// Join the parts of a multi-part book into one file instead of keeping
+    /// one file per part
+    pub join_parts: bool,
+    /// Chapter handling when parts are joined
+    pub join_options: ConcatOptions,
+    /// Library root for decrypted books
+    pub output_dir: PathBuf,
+    /// Where encrypted downloads are kept until decrypted (`output_dir` if unset)
+    pub download_dir: Option<PathBuf>,
+    /// Root shared by several accounts on one device. When set, each
+    /// account's books and downloads go to its own [`AccountPaths`] tree
+    /// instead of `output_dir` and `download_dir`.
+    pub storage_root: Option<PathBuf>,
+}
+
+/// Shortest file name limit accepted: room for a few characters of title,
+/// a part number and an extension
+const MIN_COMPONENT_BYTES: usize = 32;
+
+impl Default for DownloadSettings {
+    fn default() -> Self {
+        Self {
+            quality: DownloadQuality::High,
+            quality_fallback: true,
+            aac_codec: Codec::AacLc,
+            output_format: AudioFormat::M4b,
+            naming_pattern: NamingPattern::AuthorSeriesBook,
+            missing_names: MissingNames::default(),
+            filesystem_limits: FilesystemLimits::default(),
+            trim_intro: false,
+            split_chapters: false,
+            join_parts: true,
+            join_options: ConcatOptions::default(),
+            output_dir: get_default_library_path(),
+            download_dir: None,
+            storage_root: None,
+        }
+    }
+}
+
+impl DownloadSettings {
+    /// Check that the settings describe a usable output
+    ///
+    /// # Errors
+    /// - `InvalidInput` - Encrypted or unknown output format, empty output
+    ///   directory, `aac_codec` is not an AAC codec, or a name limit too short
+    ///   to hold a part number and extension
+    pub fn validate(&self) -> Result<()> {
+        if self.filesystem_limits.max_component_bytes < MIN_COMPONENT_BYTES {
+            return Err(LibationError::InvalidInput(format!(
+                "File name limit of {} bytes is too short",
+                self.filesystem_limits.max_component_bytes
+            )));
+        }
+        if !matches!(self.aac_codec, Codec::AacLc | Codec::XHeAac) {
+            return Err(LibationError::InvalidInput(format!("{:?} is not an AAC codec", self.aac_codec)));
+        }
+        if self.output_format.is_encrypted() || self.output_format == AudioFormat::Unknown {
+            return Err(LibationError::InvalidInput(format!(
+                "{:?} cannot be used as output format",
+                self.output_format
+            )));
+        }
+        if self.output_dir.as_os_str().is_empty() {
+            return Err(LibationError::InvalidInput("Output directory is empty".to_string()));
+        }
+        Ok(())
+    }
+
+    /// The same settings with the library and downloads in one account's tree
+    ///
+    /// Use when several accounts share a device, so the same title owned by
+    /// two of them is written to two places.
+    pub fn with_account_paths(self, paths: &AccountPaths) -> Self {
+        Self {
+            output_dir: paths.books.clone(),
+            download_dir: Some(paths.downloads.clone()),
+            ..self
+        }
+    }
+
+    /// The settings to download with for one account
+    ///
+    /// With `storage_root` set, the library and downloads move to the
+    /// account's tree (see [`with_account_paths`](Self::with_account_paths));
+    /// otherwise the settings are returned unchanged.
+    pub fn for_account(&self, account_id: &str) -> Self {
+        match &self.storage_root {
+            Some(root) => self.clone().with_account_paths(&AccountPaths::new(root, account_id)),
+            None => self.clone(),
+        }
+    }
+
+    /// Where the decrypted book goes
+    ///
+    /// # Errors
+    /// - `InvalidPath` - The rendered name cannot be used
+    pub fn output_path(&self, metadata: &AudioMetadata) -> Result<PathBuf> {
+        self.output_path_with_extension(metadata, self.output_format.to_extension())
+    }
+
+    /// Like [`output_path`](Self::output_path), keeping a file's own extension
+    ///
+    /// Used when an existing file is renamed rather than converted.
+    pub fn output_path_with_extension(&self, metadata: &AudioMetadata, extension: &str) -> Result<PathBuf> {
+        let relative = build_file_path_with_limits(
+            metadata,
+            self.naming_pattern,
+            extension,
+            &self.missing_names,
+            self.filesystem_limits,
+        )?;
+        Ok(self.output_dir.join(relative))
+    }
+
+    /// Where one decrypted part of a multi-part book goes
+    ///
+    /// With [`join_parts`](Self::join_parts) the part is only an input to the
+    /// join, so it is kept with the encrypted downloads. Otherwise it is a
+    /// finished file, named like the book with the part number appended.
+    ///
+    /// # Arguments
+    /// * `metadata` - Metadata of the whole book
+    /// * `asin` - ASIN of the whole book
+    /// * `part` - 1-based part number
+    ///
+    /// # Errors
+    /// - `InvalidPath` - The rendered name cannot be used
+    pub fn part_output_path(&self, metadata: &AudioMetadata, asin: &str, part: usize) -> Result<PathBuf> {
+        let extension = self.output_format.to_extension();
+        if self.join_parts {
+            return Ok(self
+                .download_dir
+                .as_ref()
+                .unwrap_or(&self.output_dir)
+                .join(format!("{}-part{:02}.{}", asin, part, extension)));
+        }
+
+        let book = self.output_path(metadata)?;
+        let stem = book.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let suffix = format!(" - Part {:02}.{}", part, extension);
+        let stem = truncate_component(
+            &stem,
+            self.filesystem_limits.max_component_bytes.saturating_sub(suffix.len()),
+        );
+        Ok(book.with_file_name(format!("{}{}", stem, suffix)))
+    }
+
+    /// Where the encrypted download for `asin` is written
+    pub fn download_path(&self, asin: &str, file_type: FileType) -> PathBuf {
+        let extension = match file_type {
+            FileType::Aax => "aax",
+            FileType::Dash => "mp4",
+            FileType::Mp3 => "mp3",
+            FileType::Aaxc | FileType::Unknown => "aaxc",
+        };
+        self.download_dir
+            .as_ref()
+            .unwrap_or(&self.output_dir)
+            .join(format!("{}.{}", asin, extension))
+    }
+
+    /// Conversion applied after the decrypt step
+    ///
+    /// Pass it as [`DecryptOptions::conversion`](crate::crypto::DecryptOptions::conversion).
+    ///
+    /// # Arguments
+    /// * `chapter_info` - Chapters from the license, needed to trim brand audio
+    ///
+    /// # Returns
+    /// `None` when the decrypted M4B is kept as it is
+    pub fn conversion_options(&self, chapter_info: Option<&ChapterInfo>) -> Option<ConversionOptions> {
+        let options = ConversionOptions {
+            output_format: self.output_format,
+            split_by_chapter: self.split_chapters,
+            brand_trim: if self.trim_intro {
+                chapter_info.map(|info| BrandTrim::from_chapter_info(info, true))
+            } else {
+                None
+            },
+            ..ConversionOptions::default()
+        };
+        let needed = options.output_format != AudioFormat::M4b || options.split_by_chapter || options.brand_trim.is_some();
+        needed.then_some(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_from_partial_json() {
+        let settings: DownloadSettings = serde_json::from_str(
+            r#"{"quality": "Normal", "output_format": "Mp3", "naming_pattern": "flat",
+                "output_dir": "/books", "split_chapters": true}"#,
+        )
+        .unwrap();
+        settings.validate().unwrap();
+        assert_eq!(settings.quality, DownloadQuality::Normal);
+        assert!(settings.quality_fallback);
+        assert_eq!(settings.aac_codec, Codec::AacLc);
+        assert_eq!(settings.naming_pattern, NamingPattern::FlatFile);
+
+        let metadata = AudioMetadata {
+            title: "Project Hail Mary".to_string(),
+            authors: vec!["Andy Weir".to_string()],
+            narrators: vec![],
+            publisher: None,
+            publication_date: None,
+            languages: vec![],
+            series: None,
+            description: None,
+            genres: vec![],
+            runtime_minutes: None,
+            asin: None,
+            cover_art_url: None,
+        };
+        assert_eq!(
+            settings.output_path(&metadata).unwrap(),
+            PathBuf::from("/books/Project Hail Mary.mp3")
+        );
+        assert_eq!(settings.download_path("B08G9PRS1K", FileType::Aaxc), PathBuf::from("/books/B08G9PRS1K.aaxc"));
+        assert_eq!(
+            settings.part_output_path(&metadata, "B08G9PRS1K", 2).unwrap(),
+            PathBuf::from("/books/B08G9PRS1K-part02.mp3")
+        );
+        let separate = DownloadSettings { join_parts: false, ..settings.clone() };
+        assert_eq!(
+            separate.part_output_path(&metadata, "B08G9PRS1K", 2).unwrap(),
+            PathBuf::from("/books/Project Hail Mary - Part 02.mp3")
+        );
+        let long = AudioMetadata { title: "Hail Mary ".repeat(30), ..metadata.clone() };
+        let ecryptfs = DownloadSettings { filesystem_limits: FilesystemLimits::ECRYPTFS, ..separate };
+        let part = ecryptfs.part_output_path(&long, "B08G9PRS1K", 2).unwrap();
+        assert!(part.file_name().unwrap().len() <= 143);
+        assert!(part.to_string_lossy().ends_with("… - Part 02.mp3"));
+        let tiny = FilesystemLimits { max_component_bytes: 16, ..FilesystemLimits::POSIX };
+        assert!(DownloadSettings { filesystem_limits: tiny, ..ecryptfs }.validate().is_err());
+
+        let options = settings.conversion_options(None).unwrap();
+        assert_eq!(options.output_format, AudioFormat::Mp3);
+        assert!(options.split_by_chapter && options.brand_trim.is_none());
+        assert!(DownloadSettings::default().conversion_options(None).is_none());
+
+        let shared = DownloadSettings { storage_root: Some(PathBuf::from("/storage")), ..settings.clone() };
+        let (alice, bob) = (shared.for_account("alice"), shared.for_account("bob"));
+        assert!(alice.output_path(&metadata).unwrap().starts_with("/storage/accounts"));
+        assert_ne!(alice.output_path(&metadata).unwrap(), bob.output_path(&metadata).unwrap());
+        assert_ne!(
+            alice.download_path("B08G9PRS1K", FileType::Aaxc),
+            bob.download_path("B08G9PRS1K", FileType::Aaxc)
+        );
+        assert_eq!(settings.for_account("alice"), settings);
+
+        let encrypted = DownloadSettings { output_format: AudioFormat::Aax, ..settings };
+        assert!(encrypted.validate().is_err());
+    }
+}
@@ -37,13 +37,36 @@
 //! 2. Server responds with 206 Partial Content
 //! 3. Verify ContentRange.Length matches expected total size
 //! 4. Continue writing from WritePosition
-
+//!
+//! # Background Resume
+//! The state file holds everything needed to continue a download: URL, byte
+//! offset, target path, request headers and, when set, the [`LicenseRefresh`]
+//! used to get a fresh CDN URL. A WorkManager/BGTask worker in a new process
+//! only needs the state file path (see [`resume_download`]).
+//!
+//! # Segmented Downloads
+//! With [`ResumableStream::segmented_download`] the file is split into byte
+//! ranges fetched over parallel connections, which helps on links where a
+//! single CDN connection is throttled. Each task writes its range in place in
+//! a preallocated file. The state file records how far every [`Segment`] got,
+//! so after a drop or a restart only the unfinished ranges are requested
+//! again. Servers without range support fall back to one sequential stream.
+
+use crate::api::client::{binary_download_client_builder, AudibleClient};
+use crate::api::content::DownloadQuality;
+use crate::api::license::DownloadLicense;
+use crate::backoff::DOWNLOAD_BACKOFF;
 use crate::error::{LibationError, Result};
 use crate::download::progress::{DownloadProgress, ProgressTracker, DownloadState as ProgressState};
+use crate::download::validate;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
+use std::io::SeekFrom;
 use tokio::fs::{File, OpenOptions};
-use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt, BufWriter};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use reqwest::header::CONTENT_TYPE;
 use reqwest::{Client, StatusCode};
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
@@ -76,6 +99,87 @@ pub struct StreamState {
     /// Request headers to include
     #[serde(default)]
     pub request_headers: std::collections::HashMap<String, String>,
+
+    /// License to request again when the CDN URL has expired
+    #[serde(default)]
+    pub license: Option<LicenseRefresh>,
+
+    /// Byte ranges of a segmented download (empty for a sequential one)
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+}
+
+/// What is needed to request a new download license for a [`StreamState`]
+///
+/// CDN URLs expire after about an hour, so a download resumed later by a
+/// background worker usually has to fetch a new one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseRefresh {
+    /// Account that owns the title
+    pub account_id: String,
+
+    /// Audible product ID
+    pub asin: String,
+
+    /// Quality originally requested
+    pub quality: DownloadQuality,
+
+    /// Whether Widevine was requested instead of Audible DRM
+    #[serde(default)]
+    pub prefer_widevine: bool,
+}
+
+/// One byte range of a segmented download
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Segment {
+    /// First byte of the range
+    pub start: u64,
+
+    /// Last byte of the range, inclusive as in the `Range` header
+    pub end: u64,
+
+    /// Bytes of this range written and flushed to disk
+    pub written: u64,
+}
+
+impl Segment {
+    /// Split `content_length` bytes into up to `count` contiguous ranges
+    ///
+    /// The first ranges take one extra byte each when the length does not
+    /// divide evenly. Never returns more ranges than there are bytes.
+    pub fn plan(content_length: u64, count: usize) -> Vec<Segment> {
+        let count = (count.max(1) as u64).min(content_length);
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let base = content_length / count;
+        let extra = content_length % count;
+        let mut start = 0;
+        (0..count)
+            .map(|i| {
+                let size = base + u64::from(i < extra);
+                let segment = Segment { start, end: start + size - 1, written: 0 };
+                start += size;
+                segment
+            })
+            .collect()
+    }
+
+    /// Number of bytes in the range
+    pub fn size(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Whether the whole range is on disk
+    pub fn is_complete(&self) -> bool {
+        self.written >= self.size()
+    }
+
+    /// File offset of the next byte to fetch
+    fn position(&self) -> u64 {
+        self.start + self.written
+    }
 }
 
 impl StreamState {
@@ -88,6 +192,8 @@ impl StreamState {
             write_position: 0,
             timestamp: chrono::Utc::now().to_rfc3339(),
             request_headers: std::collections::HashMap::new(),
+            license: None,
+            segments: Vec::new(),
         }
     }
 
@@ -136,6 +242,9 @@ pub struct ResumableStream {
 
     /// Retry configuration
     max_retries: u32,
+
+    /// Parallel connections to use (1 downloads sequentially)
+    segment_count: usize,
 }
 
 impl ResumableStream {
@@ -165,7 +274,7 @@ impl ResumableStream {
             state.write_position = metadata.len();
         }
 
-        let client = Client::builder()
+        let client = binary_download_client_builder()
             .timeout(Duration::from_secs(300)) // 5 minute timeout
             .build()?;
 
@@ -174,46 +283,136 @@ impl ResumableStream {
             state,
             progress_tracker: None,
             max_retries: MAX_RETRIES,
+            segment_count: 1,
         })
     }
 
     /// Resume from saved state
     ///
     /// Based on NetworkFileStreamPersister (NetworkFileStreamPersister.cs)
+    ///
+    /// Only the state file is needed, so this works from a fresh process.
+    /// Bytes written after the last state save (the process was killed between
+    /// a flush and the save) are truncated so the file matches `write_position`.
+    /// Segmented downloads keep their preallocated file; unsaved bytes of each
+    /// range are fetched again and overwritten in place.
+    ///
+    /// # Errors
+    /// - `FileNotFound` - Partial file is gone although bytes were recorded
+    /// - `InvalidData` - Partial file is shorter than the recorded position
     pub async fn from_state(state_path: &Path) -> Result<Self> {
         let state = StreamState::load(state_path).await?;
 
         // Validate file still exists
         if !state.save_file_path.exists() {
-            return Err(LibationError::FileNotFound(
-                "Download file no longer exists".to_string()
-            ));
-        }
-
-        // Verify write position matches file size
-        let metadata = tokio::fs::metadata(&state.save_file_path).await?;
-        if metadata.len() != state.write_position {
-            return Err(LibationError::InvalidData(
-                format!(
-                    "File size mismatch: expected {}, got {}",
-                    state.write_position,
-                    metadata.len()
-                )
-            ));
+            if state.write_position > 0 {
+                return Err(LibationError::FileNotFound(
+                    "Download file no longer exists".to_string()
+                ));
+            }
+        } else {
+            // Verify write position matches file size
+            let metadata = tokio::fs::metadata(&state.save_file_path).await?;
+            if metadata.len() < state.write_position {
+                return Err(LibationError::InvalidData(
+                    format!(
+                        "File size mismatch: expected {}, got {}",
+                        state.write_position,
+                        metadata.len()
+                    )
+                ));
+            }
+            // A segmented download's file is preallocated to its full size
+            if metadata.len() > state.write_position && state.segments.is_empty() {
+                let file = OpenOptions::new().write(true).open(&state.save_file_path).await?;
+                file.set_len(state.write_position).await?;
+            }
         }
 
-        let client = Client::builder()
+        let client = binary_download_client_builder()
             .timeout(Duration::from_secs(300))
             .build()?;
 
         Ok(Self {
             client,
+            segment_count: state.segments.len().max(1),
             state,
             progress_tracker: None,
             max_retries: MAX_RETRIES,
         })
     }
 
+    /// Record how to refresh the download URL and save the state file
+    ///
+    /// Call this before handing the download to a background worker so the
+    /// worker can resume it from disk alone.
+    pub async fn with_license_refresh(&mut self, license: LicenseRefresh) -> Result<()> {
+        self.state.license = Some(license);
+        self.state.save().await
+    }
+
+    /// Request a new license and switch to its download URL
+    ///
+    /// The byte offset is kept, so the download continues where it stopped.
+    ///
+    /// # Arguments
+    /// * `client` - Client for the account in [`LicenseRefresh::account_id`]
+    ///
+    /// # Returns
+    /// The new license, which also carries the decryption keys
+    ///
+    /// # Errors
+    /// - `InvalidState` - No license refresh info was recorded
+    /// - Errors from [`AudibleClient::build_download_license`]
+    pub async fn refresh_url(&mut self, client: &AudibleClient) -> Result<DownloadLicense> {
+        let refresh = self.state.license.clone().ok_or_else(|| {
+            LibationError::InvalidState("Download has no license refresh info".to_string())
+        })?;
+
+        // The cached license carries the URL that just stopped working
+        if let Some(cache) = client.license_cache() {
+            cache.invalidate(&refresh.asin);
+        }
+        let license = client
+            .build_download_license(&refresh.asin, refresh.quality, refresh.prefer_widevine)
+            .await?;
+
+        self.state.url = license.download_url.clone();
+        self.state.timestamp = chrono::Utc::now().to_rfc3339();
+        self.state.save().await?;
+        Ok(license)
+    }
+
+    /// Download over `segments` parallel connections
+    ///
+    /// Takes effect for a download that has not started yet; a sequential
+    /// download already in progress continues sequentially, and a resumed
+    /// segmented download keeps the ranges it was planned with. Values of 0
+    /// and 1 both mean a single sequential stream.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn example() -> rust_core::error::Result<()> {
+    /// use rust_core::download::stream::ResumableStream;
+    /// use std::collections::HashMap;
+    /// use std::path::PathBuf;
+    ///
+    /// let mut stream = ResumableStream::new(
+    ///     "https://cdn.example.com/book.aaxc".to_string(),
+    ///     PathBuf::from("/data/downloads/book.aaxc"),
+    ///     HashMap::new(),
+    /// ).await?;
+    /// stream.segmented_download(4);
+    /// stream.download(|_| {}).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn segmented_download(&mut self, segments: usize) {
+        if self.state.segments.is_empty() {
+            self.segment_count = segments.max(1);
+        }
+    }
+
     /// Initialize progress tracking
     pub fn with_progress(&mut self, asin: String, title: String) {
         self.progress_tracker = Some(ProgressTracker::new(
@@ -223,6 +422,11 @@ impl ResumableStream {
         ));
     }
 
+    /// Initialize progress tracking with the cached title for `asin`
+    pub fn with_progress_for(&mut self, asin: &str) {
+        self.progress_tracker = Some(ProgressTracker::for_asin(asin, self.state.content_length));
+    }
+
     /// Download file with optional progress callback
     ///
     /// Port of NetworkFileStream.BeginDownloadingAsync and DownloadLoopInternal
@@ -247,10 +451,18 @@ impl ResumableStream {
             tracker.set_state(ProgressState::Downloading);
         }
 
+        // Persist before the first byte so a killed process can be resumed
+        self.state.save().await?;
+
         // Retry loop for connection drops
         let mut retries = 0;
         loop {
-            match self.download_internal(&mut progress_callback).await {
+            let result = if self.is_segmented() {
+                self.download_segmented(&mut progress_callback).await
+            } else {
+                self.download_internal(&mut progress_callback).await
+            };
+            match result {
                 Ok(()) => {
                     // Success - delete state file and return
                     self.state.delete().await?;
@@ -261,6 +473,10 @@ impl ResumableStream {
                     return Ok(());
                 }
                 Err(e) => {
+                    if matches!(e, LibationError::InvalidDownload(_)) {
+                        self.discard_partial().await;
+                    }
+
                     // Check if we should retry
                     if retries >= self.max_retries {
                         if let Some(ref mut tracker) = self.progress_tracker {
@@ -273,8 +489,7 @@ impl ResumableStream {
                     // Check if error is retryable
                     if self.is_retryable_error(&e) {
                         retries += 1;
-                        let backoff = Duration::from_secs(2u64.pow(retries.min(5)));
-                        tokio::time::sleep(backoff).await;
+                        DOWNLOAD_BACKOFF.wait(retries - 1).await;
 
                         // Save current state before retry
                         self.state.save().await?;
@@ -302,6 +517,12 @@ impl ResumableStream {
         // Request next byte range
         let response = self.request_next_byte_range().await?;
 
+        // Reject error pages sent with a success status before writing anything
+        validate::check_content_type(
+            response.headers().get(CONTENT_TYPE).and_then(|v| v.to_str().ok()),
+        )?;
+        let mut check_body = self.state.write_position == 0;
+
         // Open file for appending
         let file = OpenOptions::new()
             .create(true)
@@ -323,6 +544,11 @@ impl ResumableStream {
             let chunk = chunk_result?;
             let chunk_len = chunk.len() as u64;
 
+            if check_body {
+                validate::check_body_start(&chunk)?;
+                check_body = false;
+            }
+
             // Write chunk to file
             writer.write_all(&chunk).await?;
 
@@ -368,6 +594,129 @@ impl ResumableStream {
         Ok(())
     }
 
+    /// Whether this download runs as parallel byte ranges
+    fn is_segmented(&self) -> bool {
+        !self.state.segments.is_empty()
+            || (self.segment_count > 1 && self.state.write_position == 0)
+    }
+
+    /// Download the unfinished segments concurrently
+    ///
+    /// Plans the ranges on the first call. Every segment task reports its
+    /// flushed byte count, which is saved to the state file as it arrives.
+    /// When a task fails the others are allowed to finish, so a retry only
+    /// has to fetch what is still missing.
+    async fn download_segmented<F>(&mut self, progress_callback: &mut F) -> Result<()>
+    where
+        F: FnMut(DownloadProgress) + Send,
+    {
+        if self.state.segments.is_empty() && !self.plan_segments().await? {
+            // No range support: one sequential stream instead
+            self.segment_count = 1;
+            return self.download_internal(progress_callback).await;
+        }
+
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+        let mut tasks = JoinSet::new();
+        for (index, segment) in self.state.segments.iter().enumerate() {
+            if segment.is_complete() {
+                continue;
+            }
+            let fetch = SegmentFetch {
+                client: self.client.clone(),
+                url: self.state.url.clone(),
+                request_headers: self.state.request_headers.clone(),
+                path: self.state.save_file_path.clone(),
+                content_length: self.state.content_length,
+                index,
+                segment: *segment,
+                progress: progress_tx.clone(),
+            };
+            tasks.spawn(fetch.run());
+        }
+        drop(progress_tx);
+
+        while let Some((index, written)) = progress_rx.recv().await {
+            self.state.segments[index].written = written;
+            self.state.write_position = self.state.segments.iter().map(|s| s.written).sum();
+            self.state.save().await?;
+
+            if let Some(ref mut tracker) = self.progress_tracker {
+                tracker.update(self.state.write_position, self.state.content_length);
+                if tracker.should_update() {
+                    progress_callback(tracker.clone_progress());
+                }
+            }
+        }
+
+        let mut first_error = None;
+        while let Some(joined) = tasks.join_next().await {
+            let result = joined
+                .map_err(|e| LibationError::DownloadFailed(format!("Segment task failed: {}", e)))
+                .and_then(|r| r);
+            if let Err(e) = result {
+                first_error.get_or_insert(e);
+            }
+        }
+
+        if let Some(ref mut tracker) = self.progress_tracker {
+            tracker.force_update(self.state.write_position);
+            progress_callback(tracker.clone_progress());
+        }
+
+        if let Some(e) = first_error {
+            return Err(e);
+        }
+        if self.state.write_position < self.state.content_length {
+            return Err(LibationError::DownloadFailed(format!(
+                "Download incomplete: {}/{} bytes",
+                self.state.write_position, self.state.content_length
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Find the file size and split it into segments
+    ///
+    /// Asks for the first byte only; a `206` carries the total size in its
+    /// `Content-Range`. The output file is preallocated to that size so every
+    /// segment can write at its own offset.
+    ///
+    /// # Returns
+    /// `false` if the server ignored the range request
+    async fn plan_segments(&mut self) -> Result<bool> {
+        let mut request = self.client.get(&self.state.url);
+        for (key, value) in &self.state.request_headers {
+            if key.to_lowercase() != "range" {
+                request = request.header(key, value);
+            }
+        }
+        let response = request.header("Range", "bytes=0-0").send().await?;
+
+        let total_size = match response.status() {
+            StatusCode::PARTIAL_CONTENT => content_range_total(&response)?,
+            StatusCode::OK => return Ok(false),
+            _ => return Err(unexpected_status(&response)),
+        };
+
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.state.save_file_path)
+            .await?;
+        file.set_len(total_size).await?;
+
+        self.state.content_length = total_size;
+        self.state.segments = Segment::plan(total_size, self.segment_count);
+        if let Some(ref mut tracker) = self.progress_tracker {
+            tracker.progress.total_bytes = total_size;
+        }
+        self.state.save().await?;
+        Ok(true)
+    }
+
     /// Request next byte range from server
     ///
     /// Based on RequestNextByteRangeAsync (lines 220-244)
@@ -413,18 +762,7 @@ impl ResumableStream {
             }
             StatusCode::PARTIAL_CONTENT => {
                 // Successful range request (line 234)
-                let content_range = response
-                    .headers()
-                    .get("content-range")
-                    .and_then(|v| v.to_str().ok())
-                    .ok_or_else(|| LibationError::DownloadFailed("No Content-Range header".to_string()))?;
-
-                // Parse Content-Range: bytes 1000-1999/2000
-                let total_size = content_range
-                    .split('/')
-                    .nth(1)
-                    .and_then(|s| s.parse::<u64>().ok())
-                    .ok_or_else(|| LibationError::DownloadFailed("Invalid Content-Range format".to_string()))?;
+                let total_size = content_range_total(&response)?;
 
                 // Verify total size matches
                 if self.state.content_length > 0 && self.state.content_length != total_size {
@@ -441,15 +779,21 @@ impl ResumableStream {
                 // Range not satisfiable - file may have changed
                 Err(LibationError::DownloadFailed("Range not satisfiable - file may have changed".to_string()))
             }
-            _ => {
-                Err(LibationError::DownloadFailed(format!(
-                    "Unexpected status code: {}",
-                    response.status()
-                )))
-            }
+            _ => Err(unexpected_status(&response)),
         }
     }
 
+    /// Remove the partial file and its state after the server sent something
+    /// other than audio, so a retry starts from scratch
+    ///
+    /// Same cleanup as `PersistentDownloadManager` does for a rejected body.
+    async fn discard_partial(&mut self) {
+        let _ = tokio::fs::remove_file(&self.state.save_file_path).await;
+        let _ = self.state.delete().await;
+        self.state.write_position = 0;
+        self.state.segments.clear();
+    }
+
     /// Check if error is retryable
     fn is_retryable_error(&self, error: &LibationError) -> bool {
         match error {
@@ -458,6 +802,10 @@ impl ResumableStream {
                 // Retry on connection errors, not on client errors
                 !msg.contains("404") && !msg.contains("403") && !msg.contains("401")
             }
+            // Expired URLs (403/410) need a new license, not another attempt
+            LibationError::UnexpectedStatusCode { status_code, .. } => {
+                !matches!(status_code, 401 | 403 | 404 | 410)
+            }
             _ => false,
         }
     }
@@ -468,6 +816,113 @@ impl ResumableStream {
     }
 }
 
+/// Total file size from a `Content-Range: bytes 1000-1999/2000` header
+fn content_range_total(response: &reqwest::Response) -> Result<u64> {
+    let content_range = response
+        .headers()
+        .get("content-range")
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| LibationError::DownloadFailed("No Content-Range header".to_string()))?;
+
+    content_range
+        .split('/')
+        .nth(1)
+        .and_then(|s| s.parse::<u64>().ok())
+        .ok_or_else(|| LibationError::DownloadFailed("Invalid Content-Range format".to_string()))
+}
+
+/// One segment of a segmented download, run as its own task
+struct SegmentFetch {
+    client: Client,
+    url: String,
+    request_headers: std::collections::HashMap<String, String>,
+    path: PathBuf,
+    content_length: u64,
+    index: usize,
+    segment: Segment,
+    /// Receives `(index, written)` after every flush
+    progress: mpsc::UnboundedSender<(usize, u64)>,
+}
+
+impl SegmentFetch {
+    /// Fetch the rest of the range and write it at its offset in the file
+    async fn run(self) -> Result<()> {
+        let size = self.segment.size();
+        let mut request = self.client.get(&self.url);
+        for (key, value) in &self.request_headers {
+            if key.to_lowercase() != "range" {
+                request = request.header(key, value);
+            }
+        }
+        let response = request
+            .header("Range", format!("bytes={}-{}", self.segment.position(), self.segment.end))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(unexpected_status(&response));
+        }
+        if response.status() != StatusCode::PARTIAL_CONTENT {
+            return Err(LibationError::DownloadFailed(format!(
+                "Unexpected status code for segment {}: {}",
+                self.index,
+                response.status()
+            )));
+        }
+        let total_size = content_range_total(&response)?;
+        if total_size != self.content_length {
+            return Err(LibationError::DownloadFailed(format!(
+                "Content length mismatch: expected {}, got {}",
+                self.content_length, total_size
+            )));
+        }
+        validate::check_content_type(
+            response.headers().get(CONTENT_TYPE).and_then(|v| v.to_str().ok()),
+        )?;
+        let mut check_body = self.segment.position() == 0;
+
+        let mut file = OpenOptions::new().write(true).open(&self.path).await?;
+        file.seek(SeekFrom::Start(self.segment.position())).await?;
+        let mut writer = BufWriter::with_capacity(DOWNLOAD_BUFF_SZ, file);
+
+        let mut written = self.segment.written;
+        let mut next_flush = written + DATA_FLUSH_SZ;
+        let mut stream = response.bytes_stream();
+        while let Some(chunk_result) = stream.next().await {
+            let chunk = chunk_result?;
+            if check_body {
+                validate::check_body_start(&chunk)?;
+                check_body = false;
+            }
+
+            // Never write past the range into the next segment
+            let take = chunk.len().min((size - written) as usize);
+            writer.write_all(&chunk[..take]).await?;
+            written += take as u64;
+
+            if written >= next_flush {
+                writer.flush().await?;
+                let _ = self.progress.send((self.index, written));
+                next_flush = written + DATA_FLUSH_SZ;
+            }
+            if written >= size {
+                break;
+            }
+        }
+
+        writer.flush().await?;
+        let _ = self.progress.send((self.index, written));
+
+        if written < size {
+            return Err(LibationError::DownloadFailed(format!(
+                "Segment {} incomplete: {}/{} bytes",
+                self.index, written, size
+            )));
+        }
+        Ok(())
+    }
+}
+
 /// Convenience function to download a file with progress tracking
 ///
 /// Port of the common download pattern from DownloadDecryptBook.cs
@@ -495,9 +950,212 @@ where
     stream.download(progress_callback).await
 }
 
+/// Resume a download from its state file alone
+///
+/// Intended for background workers (Android WorkManager, iOS BGTask) that run
+/// in a new process. If the saved CDN URL has expired and `client` is given,
+/// a new license is requested using the state's [`LicenseRefresh`] and the
+/// download continues from the saved offset.
+///
+/// # Arguments
+/// * `state_path` - Path of the `.download_state.json` file
+/// * `client` - Client for the download's account, used to refresh the URL
+/// * `progress_callback` - Called with progress updates
+///
+/// # Returns
+/// The refreshed license if the URL had to be renewed, otherwise `None`
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example(client: rust_core::api::client::AudibleClient) -> rust_core::error::Result<()> {
+/// use rust_core::download::stream::resume_download;
+/// use std::path::Path;
+///
+/// let state = Path::new("/data/downloads/B002V5D7B0.download_state.json");
+/// resume_download(state, Some(&client), |p| println!("{:?}", p.bytes_downloaded)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn resume_download<F>(
+    state_path: &Path,
+    client: Option<&AudibleClient>,
+    mut progress_callback: F,
+) -> Result<Option<DownloadLicense>>
+where
+    F: FnMut(DownloadProgress) + Send,
+{
+    let mut stream = ResumableStream::from_state(state_path).await?;
+    if let Some(asin) = stream.state.license.as_ref().map(|l| l.asin.clone()) {
+        stream.with_progress_for(&asin);
+    }
+
+    match stream.download(&mut progress_callback).await {
+        Ok(()) => Ok(None),
+        Err(e) if is_expired_url_error(&e) => match client {
+            Some(client) if stream.state.license.is_some() => {
+                let license = stream.refresh_url(client).await?;
+                stream.download(&mut progress_callback).await?;
+                Ok(Some(license))
+            }
+            _ => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/// Error for a CDN response whose status the download can't use
+fn unexpected_status(response: &reqwest::Response) -> LibationError {
+    LibationError::UnexpectedStatusCode {
+        status_code: response.status().as_u16(),
+        host: response.url().host_str().unwrap_or_default().to_string(),
+    }
+}
+
+/// Whether a download error means the CDN URL is no longer valid
+fn is_expired_url_error(error: &LibationError) -> bool {
+    matches!(
+        error,
+        LibationError::UnexpectedStatusCode { status_code: 403 | 410, .. }
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    /// Audio-looking body: MP4 `ftyp` box followed by filler
+    fn test_body(len: usize) -> Vec<u8> {
+        let mut body = b"\0\0\0\x20ftypM4B \0\0\0\0".to_vec();
+        body.extend((0..len - body.len()).map(|i| (i % 251) as u8));
+        body
+    }
+
+    /// Serve `body` with Range support, recording each `Range` header
+    async fn serve_ranges(body: Vec<u8>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let seen = ranges.clone();
+        let body = Arc::new(body);
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let body = body.clone();
+                let seen = seen.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap();
+                    let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                    let range = request
+                        .lines()
+                        .find_map(|l| l.strip_prefix("range: bytes="))
+                        .map(|r| r.trim().to_string());
+
+                    let (head, slice) = match range {
+                        Some(range) => {
+                            seen.lock().unwrap().push(range.clone());
+                            let (start, end) = range.split_once('-').unwrap();
+                            let start: usize = start.parse().unwrap();
+                            let end: usize = end.parse().unwrap_or(body.len() - 1);
+                            let head = format!(
+                                "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {}-{}/{}\r\n",
+                                start, end, body.len()
+                            );
+                            (head, &body[start..=end])
+                        }
+                        None => ("HTTP/1.1 200 OK\r\n".to_string(), &body[..]),
+                    };
+                    let head = format!(
+                        "{}Content-Length: {}\r\nContent-Type: application/octet-stream\r\nConnection: close\r\n\r\n",
+                        head,
+                        slice.len()
+                    );
+                    socket.write_all(head.as_bytes()).await.unwrap();
+                    socket.write_all(slice).await.unwrap();
+                });
+            }
+        });
+        (format!("http://{}/book.aaxc", addr), ranges)
+    }
+
+    #[test]
+    fn test_segment_plan_covers_file() {
+        let segments = Segment::plan(10, 3);
+        let bounds: Vec<(u64, u64)> = segments.iter().map(|s| (s.start, s.end)).collect();
+        assert_eq!(bounds, vec![(0, 3), (4, 6), (7, 9)]);
+        assert_eq!(segments.iter().map(Segment::size).sum::<u64>(), 10);
+
+        assert_eq!(Segment::plan(2, 8).len(), 2);
+        assert!(Segment::plan(0, 4).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_segmented_download_fetches_ranges_in_parallel() {
+        let body = test_body(300_000);
+        let (url, ranges) = serve_ranges(body.clone()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("book.aaxc");
+
+        let mut stream = ResumableStream::new(url, path.clone(), Default::default()).await.unwrap();
+        stream.segmented_download(4);
+        stream.download(|_| {}).await.unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert!(!stream.get_state().state_file_path().exists());
+        let mut ranges = ranges.lock().unwrap().clone();
+        ranges.sort();
+        assert_eq!(
+            ranges,
+            vec!["0-0", "0-74999", "150000-224999", "225000-299999", "75000-149999"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_segmented_download_resumes_unfinished_ranges() {
+        let body = test_body(1000);
+        let (url, ranges) = serve_ranges(body.clone()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("book.aaxc");
+
+        // First segment done, second half-way, third not started
+        let mut state = StreamState::new(url, path.clone());
+        state.content_length = 1000;
+        state.segments = Segment::plan(1000, 3);
+        state.segments[0].written = state.segments[0].size();
+        state.segments[1].written = 100;
+        state.write_position = state.segments[0].size() + 100;
+        let mut partial = vec![0u8; 1000];
+        partial[..434].copy_from_slice(&body[..434]);
+        std::fs::write(&path, &partial).unwrap();
+        state.save().await.unwrap();
+
+        let mut stream = ResumableStream::from_state(&state.state_file_path()).await.unwrap();
+        stream.download(|_| {}).await.unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        let mut ranges = ranges.lock().unwrap().clone();
+        ranges.sort();
+        assert_eq!(ranges, vec!["434-666", "667-999"]);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_body_removes_partial_file() {
+        let mut body = b"<html><body>Access Denied</body></html>".to_vec();
+        body.resize(1000, b' ');
+        let (url, _) = serve_ranges(body).await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("book.aaxc");
+
+        let mut stream = ResumableStream::new(url, path.clone(), Default::default()).await.unwrap();
+        let result = stream.download(|_| {}).await;
+
+        assert!(matches!(result, Err(LibationError::InvalidDownload(_))));
+        assert!(!path.exists());
+        assert!(!stream.get_state().state_file_path().exists());
+    }
 
     #[test]
     fn test_stream_state_serialization() {
@@ -523,5 +1181,55 @@ mod tests {
         let state_path = state.state_file_path();
         assert_eq!(state_path, PathBuf::from("/tmp/download.download_state.json"));
     }
-}
 
+    #[tokio::test]
+    async fn test_from_state_resumes_from_disk_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut state = StreamState::new(
+            "https://example.com/file.aaxc".to_string(),
+            dir.path().join("book.aaxc"),
+        );
+        state.content_length = 100;
+        state.license = Some(LicenseRefresh {
+            account_id: "user@example.com".to_string(),
+            asin: "B002V5D7B0".to_string(),
+            quality: DownloadQuality::High,
+            prefer_widevine: false,
+        });
+
+        // Nothing written yet: the partial file does not have to exist
+        state.save().await.unwrap();
+        let stream = ResumableStream::from_state(&state.state_file_path()).await.unwrap();
+        assert_eq!(stream.get_state().license, state.license);
+
+        // Bytes written after the last save are dropped
+        std::fs::write(&state.save_file_path, [0u8; 40]).unwrap();
+        state.write_position = 32;
+        state.save().await.unwrap();
+        let stream = ResumableStream::from_state(&state.state_file_path()).await.unwrap();
+        assert_eq!(stream.get_state().write_position, 32);
+        assert_eq!(std::fs::metadata(&state.save_file_path).unwrap().len(), 32);
+
+        state.write_position = 64;
+        state.save().await.unwrap();
+        assert!(matches!(
+            ResumableStream::from_state(&state.state_file_path()).await,
+            Err(LibationError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn test_is_expired_url_error() {
+        let status = |status_code| LibationError::UnexpectedStatusCode {
+            status_code,
+            host: "cdn.example.com".to_string(),
+        };
+        assert!(is_expired_url_error(&status(403)));
+        assert!(is_expired_url_error(&status(410)));
+        assert!(!is_expired_url_error(&status(500)));
+        // Byte counts that happen to contain "403" are not a status
+        assert!(!is_expired_url_error(&LibationError::DownloadFailed(
+            "Download incomplete: 4030/8192 bytes".to_string()
+        )));
+    }
+}
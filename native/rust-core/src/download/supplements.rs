@@ -0,0 +1,231 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Companion PDF (supplement) downloads
+//!
+//! # Reference C# Sources
+//! - **`FileLiberator/DownloadPdf.cs`** - Downloads `Book.Supplements` next to the audiobook
+//! - **`DataLayer/EfClasses/Supplement.cs`** - Supplement URL per book
+//!
+//! Many titles ship with accompanying material (maps, tables, illustrations) as a
+//! PDF. The library response exposes it as `pdf_url`, which library sync stores in
+//! the `Supplements` table.
+//!
+//! PDFs are named with the same [`PathBuilder`] template as the audio, so
+//! `Author/Title/Title.m4b` gets `Author/Title/Title.pdf`. Extra PDFs for the same
+//! book are numbered: `Title (2).pdf`, `Title (3).pdf`.
+
+use crate::api::library::LibraryItem;
+use crate::audio::metadata::AudioMetadata;
+use crate::error::{LibationError, Result};
+use crate::file::paths::PathBuilder;
+use crate::storage::{queries, Database};
+use reqwest::header::USER_AGENT;
+use std::path::PathBuf;
+
+/// Companion PDF URLs for a library item
+pub fn companion_pdf_urls(item: &LibraryItem) -> Vec<String> {
+    item.pdf_url
+        .iter()
+        .filter(|url| !url.trim().is_empty())
+        .cloned()
+        .collect()
+}
+
+/// Companion PDF URLs stored for a book by library sync
+///
+/// # Errors
+/// Returns a storage error if the database query fails
+pub async fn stored_companion_pdf_urls(db: &Database, asin: &str) -> Result<Vec<String>> {
+    let book = match queries::find_book_by_asin(db.pool(), asin).await? {
+        Some(book) => book,
+        None => return Ok(Vec::new()),
+    };
+
+    Ok(queries::find_supplements_by_book(db.pool(), book.book_id)
+        .await?
+        .into_iter()
+        .map(|s| s.url)
+        .collect())
+}
+
+/// Paths for `count` companion PDFs of a book, following the naming template
+///
+/// # Errors
+/// Returns `InvalidPath` if the rendered path is too long
+pub fn companion_pdf_paths(
+    builder: &PathBuilder,
+    metadata: &AudioMetadata,
+    count: usize,
+) -> Result<Vec<PathBuf>> {
+    let first = builder.build_path(metadata, "pdf")?;
+    let stem = first
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok((0..count)
+        .map(|i| {
+            if i == 0 {
+                first.clone()
+            } else {
+                first.with_file_name(format!("{} ({}).pdf", stem, i + 1))
+            }
+        })
+        .collect())
+}
+
+/// Download companion PDFs next to the audiobook
+///
+/// Existing files at the target paths are replaced, so re-running after a
+/// failed attempt does not leave numbered duplicates. Each PDF is written to a
+/// `.part` file first and renamed when complete.
+///
+/// # Arguments
+/// * `urls` - PDF URLs (see [`companion_pdf_urls`] / [`stored_companion_pdf_urls`])
+/// * `builder` - Path builder with the same template used for the audio file
+/// * `metadata` - Book metadata used to render the template
+/// * `user_agent` - User-Agent header for the requests
+///
+/// # Returns
+/// Paths of the downloaded PDFs, in the same order as `urls`
+///
+/// # Errors
+/// - `NetworkError` - Request failed
+/// - `UnexpectedStatusCode` - Server returned a non-success status
+/// - `FileIoError` - PDF could not be written
+pub async fn download_companion_pdfs(
+    urls: &[String],
+    builder: &PathBuilder,
+    metadata: &AudioMetadata,
+    user_agent: &str,
+) -> Result<Vec<PathBuf>> {
+    let paths = companion_pdf_paths(builder, metadata, urls.len())?;
+    let http = reqwest::Client::new();
+
+    for (url, path) in urls.iter().zip(&paths) {
+        let response = http
+            .get(url)
+            .header(USER_AGENT, user_agent)
+            .send()
+            .await
+            .map_err(|e| LibationError::network_error(format!("PDF download failed: {}", e), true))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(LibationError::UnexpectedStatusCode {
+                status_code: status.as_u16(),
+                host: response.url().host_str().unwrap_or_default().to_string(),
+            });
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| LibationError::network_error(format!("PDF download failed: {}", e), true))?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                LibationError::FileIoError(format!("create_dir: {} - {}", parent.display(), e))
+            })?;
+        }
+
+        let part_path = path.with_extension("pdf.part");
+        tokio::fs::write(&part_path, &bytes).await.map_err(|e| {
+            LibationError::FileIoError(format!("write: {} - {}", part_path.display(), e))
+        })?;
+        tokio::fs::rename(&part_path, path).await.map_err(|e| {
+            LibationError::FileIoError(format!("rename: {} - {}", path.display(), e))
+        })?;
+    }
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::paths::PathTemplate;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn metadata() -> AudioMetadata {
+        AudioMetadata {
+            title: "Maps Included".to_string(),
+            authors: vec!["Jane Doe".to_string()],
+            narrators: vec![],
+            publisher: None,
+            publication_date: None,
+            languages: vec![],
+            series: None,
+            description: None,
+            genres: vec![],
+            runtime_minutes: None,
+            asin: Some("B0PDF00001".to_string()),
+            cover_art_url: None,
+        }
+    }
+
+    #[test]
+    fn test_companion_pdf_paths_follow_template() {
+        let builder = PathBuilder::new(PathBuf::from("/library"), PathTemplate::author_book_folder());
+        let paths = companion_pdf_paths(&builder, &metadata(), 2).unwrap();
+
+        assert_eq!(paths[0], PathBuf::from("/library/Jane Doe/Maps Included/Maps Included.pdf"));
+        assert_eq!(paths[1], PathBuf::from("/library/Jane Doe/Maps Included/Maps Included (2).pdf"));
+    }
+
+    #[tokio::test]
+    async fn test_download_companion_pdfs() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let body = b"%PDF-1.4 test";
+                let header = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/pdf\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                socket.write_all(header.as_bytes()).await.unwrap();
+                socket.write_all(body).await.unwrap();
+            }
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let builder = PathBuilder::new(dir.path().to_path_buf(), PathTemplate::author_book_folder());
+        let urls = vec![
+            format!("http://{}/companion/1.pdf", addr),
+            format!("http://{}/companion/2.pdf", addr),
+        ];
+
+        let paths = download_companion_pdfs(&urls, &builder, &metadata(), "Test/1.0")
+            .await
+            .unwrap();
+
+        assert_eq!(paths.len(), 2);
+        for path in &paths {
+            assert_eq!(std::fs::read(path).unwrap(), b"%PDF-1.4 test");
+        }
+        assert!(!paths[0].with_extension("pdf.part").exists());
+    }
+}
@@ -0,0 +1,99 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! ASIN to title lookup for progress reports and logs
+//!
+//! # Reference C# Sources
+//! - **`FileLiberator/DownloadDecryptBook.cs`** - Reads `LibraryBook.Book.Title`
+//!   from the entity it was given for every status message
+//!
+//! Download code mostly knows a book by its ASIN, but progress reports and
+//! log lines should show the title. Titles are cached process-wide:
+//! [`load_titles`] reads them from the `Books` table (the download manager
+//! does this when it is created) and library sync adds each book it
+//! imports. [`DownloadProgress::for_asin`](super::progress::DownloadProgress::for_asin)
+//! fills in the title from here, falling back to the ASIN for unknown books.
+
+use crate::error::Result;
+use crate::storage::queries;
+use lazy_static::lazy_static;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+lazy_static! {
+    static ref TITLES: RwLock<HashMap<String, String>> = RwLock::new(HashMap::new());
+}
+
+/// Load every book title from the database into the cache
+///
+/// # Returns
+/// Number of titles loaded
+pub async fn load_titles(pool: &SqlitePool) -> Result<usize> {
+    let titles = queries::list_book_titles(pool).await?;
+    let count = titles.len();
+    if let Ok(mut cache) = TITLES.write() {
+        cache.extend(titles);
+    }
+    Ok(count)
+}
+
+/// Add or replace the title for `asin`
+///
+/// Blank titles are ignored so they cannot hide a good cached one.
+pub fn remember_title(asin: &str, title: &str) {
+    if title.trim().is_empty() {
+        return;
+    }
+    if let Ok(mut cache) = TITLES.write() {
+        cache.insert(asin.to_string(), title.to_string());
+    }
+}
+
+/// Cached title for `asin`, if known
+pub fn title_for(asin: &str) -> Option<String> {
+    TITLES.read().ok()?.get(asin).cloned()
+}
+
+/// Cached title for `asin`, or the ASIN itself when the book is unknown
+pub fn title_or_asin(asin: &str) -> String {
+    title_for(asin).unwrap_or_else(|| asin.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::models::NewBook;
+    use crate::storage::Database;
+
+    #[tokio::test]
+    async fn test_titles_load_from_database() {
+        let db = Database::new_in_memory().await.unwrap();
+        let book = NewBook::new("B0TITLE01".to_string(), "Project Hail Mary".to_string(), "us".to_string());
+        queries::insert_book(db.pool(), &book).await.unwrap();
+
+        assert!(load_titles(db.pool()).await.unwrap() >= 1);
+        assert_eq!(title_for("B0TITLE01").as_deref(), Some("Project Hail Mary"));
+
+        remember_title("B0TITLE01", "  ");
+        assert_eq!(title_or_asin("B0TITLE01"), "Project Hail Mary");
+        assert_eq!(title_or_asin("B0UNKNOWN"), "B0UNKNOWN");
+    }
+}
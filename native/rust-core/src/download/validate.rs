@@ -0,0 +1,247 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! Sanity checks on download responses
+//!
+//! # Reference C# Sources
+//! - **`AaxDecrypter/NetworkFileStream.cs`** - Only checks the status code, so
+//!   an error body sent with 200 is written to disk as-is
+//!
+//! CloudFront and Audible occasionally answer an expired or throttled request
+//! with an HTML/XML error page and a `200 OK`. These helpers reject such
+//! responses before anything is written, so an error page never ends up saved
+//! as the audiobook. [`check_file_integrity`] applies the same checks to files
+//! already on disk, to find bad downloads left by older versions.
+
+use crate::audio::decoder::{AudioDecoder, AudioFormat};
+use crate::error::{LibationError, Result};
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+/// Content types that are never audiobook data
+const ERROR_CONTENT_TYPES: &[&str] = &[
+    "text/html",
+    "text/xml",
+    "text/plain",
+    "application/xml",
+    "application/xhtml+xml",
+    "application/json",
+];
+
+/// Reject a response whose `Content-Type` is a document rather than audio
+///
+/// Missing or unfamiliar content types (CDNs often send
+/// `application/octet-stream`) are accepted; the body check catches the rest.
+///
+/// # Errors
+/// `InvalidDownload` if the content type is HTML, XML, JSON or plain text
+pub fn check_content_type(content_type: Option<&str>) -> Result<()> {
+    let Some(content_type) = content_type else {
+        return Ok(());
+    };
+
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    if ERROR_CONTENT_TYPES.contains(&mime.as_str()) {
+        return Err(LibationError::InvalidDownload(format!(
+            "server sent {} instead of audio",
+            mime
+        )));
+    }
+
+    Ok(())
+}
+
+/// Reject a body whose first bytes are markup or otherwise not audio
+///
+/// Only meaningful for the start of the file; resumed range requests begin
+/// mid-stream and should skip this check.
+///
+/// # Arguments
+/// * `first_bytes` - First chunk of the response body
+///
+/// # Errors
+/// `InvalidDownload` if the body starts with `<` or `{`, or has no MP4/MP3
+/// signature
+pub fn check_body_start(first_bytes: &[u8]) -> Result<()> {
+    let trimmed = first_bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .map(|i| &first_bytes[i..])
+        .unwrap_or_default();
+
+    if trimmed.starts_with(b"<") || trimmed.starts_with(b"{") {
+        let preview: String = String::from_utf8_lossy(&trimmed[..trimmed.len().min(80)])
+            .chars()
+            .filter(|c| !c.is_control())
+            .collect();
+        return Err(LibationError::InvalidDownload(format!(
+            "body starts with markup: {}",
+            preview
+        )));
+    }
+
+    // Too short to judge; the size check at the end will catch a truncated body
+    if first_bytes.len() < 12 {
+        return Ok(());
+    }
+
+    match AudioDecoder::detect_format_from_bytes(first_bytes) {
+        Ok(AudioFormat::Unknown) | Err(_) => Err(LibationError::InvalidDownload(
+            "body has no MP4 or MP3 signature".to_string(),
+        )),
+        Ok(_) => Ok(()),
+    }
+}
+
+/// Why a file on disk failed verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    /// File does not exist
+    Missing,
+    /// File exists but is empty
+    Empty,
+    /// File size differs from the expected size
+    SizeMismatch { expected: u64, actual: u64 },
+    /// File does not start like an audio file
+    NotAudio(String),
+}
+
+impl std::fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntegrityIssue::Missing => write!(f, "file is missing"),
+            IntegrityIssue::Empty => write!(f, "file is empty"),
+            IntegrityIssue::SizeMismatch { expected, actual } => {
+                write!(f, "expected {} bytes, found {}", expected, actual)
+            }
+            IntegrityIssue::NotAudio(reason) => write!(f, "not an audio file: {}", reason),
+        }
+    }
+}
+
+/// Verify a downloaded file without reading all of it
+///
+/// Checks that the file exists, is non-empty, matches `expected_size` when
+/// given, and starts with an audio signature (only the first bytes are read).
+///
+/// # Arguments
+/// * `path` - File to verify
+/// * `expected_size` - Exact size in bytes, if known
+///
+/// # Returns
+/// `None` if the file looks intact, otherwise the first problem found
+///
+/// # Errors
+/// `FileIoError` if the file exists but cannot be read
+pub async fn check_file_integrity(path: &Path, expected_size: Option<u64>) -> Result<Option<IntegrityIssue>> {
+    let metadata = match tokio::fs::metadata(path).await {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Some(IntegrityIssue::Missing)),
+        Err(e) => {
+            return Err(LibationError::FileIoError(format!("Failed to stat {}: {}", path.display(), e)))
+        }
+    };
+
+    let actual = metadata.len();
+    if actual == 0 {
+        return Ok(Some(IntegrityIssue::Empty));
+    }
+    if let Some(expected) = expected_size.filter(|&expected| expected != actual) {
+        return Ok(Some(IntegrityIssue::SizeMismatch { expected, actual }));
+    }
+
+    let mut header = Vec::with_capacity(64);
+    tokio::fs::File::open(path)
+        .await
+        .map_err(|e| LibationError::FileIoError(format!("Failed to open {}: {}", path.display(), e)))?
+        .take(64)
+        .read_to_end(&mut header)
+        .await
+        .map_err(|e| LibationError::FileIoError(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    match check_body_start(&header) {
+        Ok(()) => Ok(None),
+        Err(LibationError::InvalidDownload(reason)) => Ok(Some(IntegrityIssue::NotAudio(reason))),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_content_type() {
+        assert!(check_content_type(None).is_ok());
+        assert!(check_content_type(Some("application/octet-stream")).is_ok());
+        assert!(check_content_type(Some("audio/vnd.audible.aax")).is_ok());
+        assert!(matches!(
+            check_content_type(Some("text/html; charset=UTF-8")),
+            Err(LibationError::InvalidDownload(_))
+        ));
+        assert!(check_content_type(Some("Application/XML")).is_err());
+    }
+
+    #[test]
+    fn test_check_body_start() {
+        assert!(check_body_start(b"\x00\x00\x00\x20ftypaax \x00\x00\x00\x00").is_ok());
+        assert!(check_body_start(b"ID3\x04\x00\x00\x00\x00\x00\x00\x00\x00").is_ok());
+
+        let cloudfront = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Error><Code>AccessDenied</Code></Error>";
+        assert!(matches!(check_body_start(cloudfront), Err(LibationError::InvalidDownload(_))));
+        assert!(check_body_start(b"\r\n  <!DOCTYPE html><html><body>Error</body></html>").is_err());
+        assert!(check_body_start(b"this is not an audiobook file").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check_file_integrity() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        let good = dir.join("good.m4b");
+        std::fs::write(&good, b"\x00\x00\x00\x20ftypM4B \x00\x00\x00\x00 audio").unwrap();
+        assert_eq!(check_file_integrity(&good, None).await.unwrap(), None);
+        assert_eq!(
+            check_file_integrity(&good, Some(1000)).await.unwrap(),
+            Some(IntegrityIssue::SizeMismatch { expected: 1000, actual: 22 })
+        );
+
+        let html = dir.join("error.m4b");
+        std::fs::write(&html, b"<html><body>Access Denied</body></html>").unwrap();
+        assert!(matches!(
+            check_file_integrity(&html, None).await.unwrap(),
+            Some(IntegrityIssue::NotAudio(_))
+        ));
+
+        let empty = dir.join("empty.m4b");
+        std::fs::write(&empty, b"").unwrap();
+        assert_eq!(check_file_integrity(&empty, None).await.unwrap(), Some(IntegrityIssue::Empty));
+        assert_eq!(
+            check_file_integrity(&dir.join("missing.m4b"), None).await.unwrap(),
+            Some(IntegrityIssue::Missing)
+        );
+    }
+}
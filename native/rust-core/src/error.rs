@@ -10,8 +10,9 @@
 //! The following C# exceptions have been mapped to Rust error variants:
 //!
 //! ### API/Network Errors (from AudibleUtilities, Cdm.Api.cs)
-//! - `ApiErrorException` → `ApiError`
+//! - `ApiErrorException` → `ApiRequestFailed` with an [`AudibleErrorCode`]
 //! - `HttpRequestException` → `NetworkError`, `ApiRequestFailed`
+//! - HTTP 409 / concurrent-session errors → `SessionConflict`
 //! - `WebException` → `WebError`
 //!
 //! ### Data Validation (from AudibleUtilities)
@@ -34,7 +35,9 @@
 //!
 //! ### Database (from DataLayer)
 //! - `InvalidOperationException` (DB context) → `DatabaseError`
-//! - Entity Framework exceptions → `SqlxError` (via `#[from]`)
+//! - Book, library and account queries (`storage::queries`, `storage::accounts`)
+//!   → `Storage` with the operation and ASIN (via `StorageContext`)
+//! - Other `Database` calls and migrations → `SqlxError` (via `#[from]`)
 //!
 //! ### Configuration/State (from LibationFileManager)
 //! - `InvalidOperationException` → `InvalidState`
@@ -43,6 +46,7 @@
 //!
 //! ### Audio Processing (from FileLiberator, AaxDecrypter)
 //! - FFmpeg failures → `FfmpegError`, `FfmpegNotFound`
+//! - FFmpeg/ffprobe hanging or crashing on every attempt → `DecodeFailed`
 //! - Format detection failures → `UnsupportedAudioFormat`, `InvalidAudioFile`
 
 use thiserror::Error;
@@ -54,7 +58,11 @@ pub type Result<T> = std::result::Result<T, LibationError>;
 ///
 /// This enum provides comprehensive error handling for all operations in the application.
 /// Each variant includes descriptive error messages and relevant context.
-#[derive(Error, Debug)]
+///
+/// Exported to uniffi as a flat error: foreign code sees the variant name and
+/// its display message.
+#[derive(Error, Debug, uniffi::Error)]
+#[uniffi(flat_error)]
 pub enum LibationError {
     // ===== API Errors =====
     // Corresponds to C# ApiErrorException, authentication failures in ApiExtended.cs
@@ -75,6 +83,8 @@ pub enum LibationError {
         status_code: Option<u16>,
         /// API endpoint that failed
         endpoint: Option<String>,
+        /// Audible error code from the response body, if it had one
+        error_code: Option<AudibleErrorCode>,
     },
 
     /// API returned invalid or unexpected response format
@@ -94,6 +104,15 @@ pub enum LibationError {
         endpoint: String,
     },
 
+    /// Another session holds the resource (HTTP 409), e.g. playback or a
+    /// license request running on another device
+    #[error("Conflict with another active session: {message}")]
+    SessionConflict {
+        message: String,
+        /// Endpoint that reported the conflict
+        endpoint: Option<String>,
+    },
+
     /// Account not found in local database
     #[error("Account not found: {0}")]
     AccountNotFound(String),
@@ -195,14 +214,35 @@ pub enum LibationError {
     #[error("Invalid download URL: {0}")]
     InvalidDownloadUrl(String),
 
+    /// Server answered with something that is not audio, such as an HTML/XML
+    /// error page sent with a 200 status
+    #[error("Downloaded content is not audio: {0}")]
+    InvalidDownload(String),
+
     /// Content license missing offline URL (maps to InvalidDataException in DownloadOptions.cs)
     #[error("Content license doesn't contain an offline URL")]
     MissingOfflineUrl,
 
+    /// Streaming license without a playable URL
+    #[error("Content license doesn't contain a streaming URL")]
+    MissingStreamingUrl,
+
     /// MPEG-DASH content URL retrieval failed (maps to InvalidDataException in DownloadOptions.Factory.cs)
     #[error("Failed to get mpeg-dash content download URL")]
     MpegDashUrlFailed,
 
+    /// Title is a podcast/series parent with no audio of its own
+    #[error("{asin} is a podcast or series parent and cannot be downloaded; {suggestion}")]
+    NotDownloadableParent {
+        asin: String,
+        /// What the user should do instead
+        suggestion: String,
+    },
+
+    /// Title is a pre-order whose release date hasn't arrived yet
+    #[error("Not yet released: available on {release_date}")]
+    NotYetReleased { release_date: chrono::NaiveDate },
+
     // ===== Audio/Conversion Errors =====
     // Corresponds to audio processing in FileLiberator, ConvertToMp3.cs
 
@@ -222,8 +262,16 @@ pub enum LibationError {
     #[error("FFmpeg error: {0}")]
     FfmpegError(String),
 
-    /// FFmpeg binary not found in PATH
-    #[error("FFmpeg not found. Please install FFmpeg and ensure it's in your PATH.")]
+    /// FFmpeg or ffprobe timed out or was killed on every attempt
+    #[error("Decoding failed: {0}")]
+    DecodeFailed(String),
+
+    /// FFmpeg or ffprobe binary not found in PATH
+    #[error(
+        "FFmpeg not found. Install it (macOS: `brew install ffmpeg`, Debian/Ubuntu: \
+         `apt-get install ffmpeg`, Windows: https://ffmpeg.org/download.html) and make sure \
+         both `ffmpeg` and `ffprobe` are on PATH."
+    )]
     FfmpegNotFound,
 
     /// Audio file is corrupted or has invalid metadata
@@ -300,6 +348,17 @@ pub enum LibationError {
         entity_type: String,
     },
 
+    /// A storage operation failed, with what was being done and to which book
+    #[error("Storage error while {operation}{}: {detail}", asin_suffix(.asin))]
+    Storage {
+        /// What was being done, e.g. "inserting book"
+        operation: String,
+        /// ASIN of the book involved, if any
+        asin: Option<String>,
+        /// Underlying database error
+        detail: String,
+    },
+
     // ===== Import/Validation Errors =====
     // Corresponds to ImportValidationException, importer validation in DtoImporterService
 
@@ -459,6 +518,15 @@ impl LibationError {
             message: message.into(),
             status_code,
             endpoint,
+            error_code: None,
+        }
+    }
+
+    /// Audible error code carried by an `ApiRequestFailed` error
+    pub fn audible_error_code(&self) -> Option<&AudibleErrorCode> {
+        match self {
+            LibationError::ApiRequestFailed { error_code, .. } => error_code.as_ref(),
+            _ => None,
         }
     }
 
@@ -470,6 +538,19 @@ impl LibationError {
         }
     }
 
+    /// Create a Storage error
+    pub fn storage<S: Into<String>>(
+        operation: S,
+        asin: Option<&str>,
+        detail: impl std::fmt::Display,
+    ) -> Self {
+        LibationError::Storage {
+            operation: operation.into(),
+            asin: asin.map(str::to_string),
+            detail: detail.to_string(),
+        }
+    }
+
     /// Create a WidevineCdmError
     pub fn cdm_error<S: Into<String>>(message: S, operation: Option<String>) -> Self {
         LibationError::WidevineCdmError {
@@ -506,6 +587,7 @@ impl LibationError {
                 | LibationError::ApiRequestFailed { status_code: Some(500..=599), .. }
                 | LibationError::DownloadInterrupted
                 | LibationError::RateLimitExceeded { .. }
+                | LibationError::SessionConflict { .. }
         )
     }
 
@@ -555,8 +637,8 @@ impl LibationError {
     /// Get retry delay in seconds for retryable errors
     ///
     /// Returns `Some(seconds)` if the error includes retry timing information,
-    /// `None` otherwise. Callers should implement their own backoff strategy
-    /// for errors without explicit retry delays.
+    /// `None` otherwise. Errors without an explicit delay should be retried
+    /// with a policy from [`crate::backoff`].
     pub fn retry_after_seconds(&self) -> Option<u64> {
         match self {
             LibationError::RateLimitExceeded { retry_after_seconds, .. } => {
@@ -573,7 +655,7 @@ impl LibationError {
     pub fn user_message(&self) -> String {
         match self {
             LibationError::FfmpegNotFound => {
-                "FFmpeg is required but not found. Please install FFmpeg and ensure it's in your PATH.".to_string()
+                "FFmpeg is required but not found. Install FFmpeg and make sure both ffmpeg and ffprobe are on your PATH, then try again.".to_string()
             }
             LibationError::ActivationBytesNotFound(account) => {
                 format!("Activation bytes not found for account '{}'. Please provide activation bytes to decrypt AAX files.", account)
@@ -601,9 +683,27 @@ impl LibationError {
                     retry_after_seconds
                 )
             }
+            LibationError::SessionConflict { .. } => {
+                "Audible is busy with another session for this account, such as playback or a download on another device. Wait a minute and try again.".to_string()
+            }
+            LibationError::ApiRequestFailed {
+                error_code: Some(AudibleErrorCode::DownloadLimitExceeded),
+                ..
+            } => {
+                "This audiobook has reached its download limit. Remove it from another device and try again.".to_string()
+            }
             LibationError::MissingOfflineUrl => {
                 "This audiobook's license doesn't support offline playback.".to_string()
             }
+            LibationError::MissingStreamingUrl => {
+                "This audiobook can't be streamed right now. Try downloading it instead.".to_string()
+            }
+            LibationError::NotDownloadableParent { suggestion, .. } => {
+                format!("This is a podcast or series, not a single audiobook. Please {} instead.", suggestion)
+            }
+            LibationError::NotYetReleased { release_date } => {
+                format!("This audiobook is a pre-order. It will be available on {}.", release_date.format("%B %-d, %Y"))
+            }
             LibationError::FileSizeMismatch { expected, actual } => {
                 format!(
                     "Download verification failed: file size mismatch (expected {} MB, got {} MB). Please try downloading again.",
@@ -611,6 +711,9 @@ impl LibationError {
                     actual / 1_000_000
                 )
             }
+            LibationError::InvalidDownload(_) => {
+                "The server sent an error page instead of the audiobook. Please try downloading again.".to_string()
+            }
             LibationError::DownloadInterrupted => {
                 "Download was interrupted. Please try again.".to_string()
             }
@@ -630,11 +733,122 @@ impl LibationError {
             LibationError::AccountValidationFailed { field, book_title } => {
                 format!("Cannot process '{}': missing {} information. Please check your account settings.", book_title, field)
             }
+            LibationError::Storage { operation, asin: Some(asin), .. } => {
+                format!("Could not save library data while {} ({}). Please try again.", operation, asin)
+            }
+            LibationError::Storage { operation, asin: None, .. } => {
+                format!("Could not save library data while {}. Please try again.", operation)
+            }
             _ => self.to_string(),
         }
     }
 }
 
+/// Error codes Audible puts in API error bodies
+///
+/// Bodies look like `{"error_code": "DownloadLimitExceeded", "message": "..."}`;
+/// some endpoints use `code` or an `errors` array instead. Codes not listed
+/// here are kept as [`AudibleErrorCode::Other`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AudibleErrorCode {
+    /// A request parameter has an invalid value
+    InvalidValue,
+    /// A required parameter is missing or unknown
+    InvalidParameter,
+    /// The ASIN or resource does not exist
+    NotFound,
+    /// The title has been downloaded on too many devices
+    DownloadLimitExceeded,
+    /// The token was rejected
+    Unauthorized,
+    /// The account may not access the resource (e.g. title not owned)
+    AccessDenied,
+    /// Too many requests
+    Throttled,
+    /// Another session or request holds the resource
+    Conflict,
+    /// Any other code, verbatim
+    Other(String),
+}
+
+impl AudibleErrorCode {
+    /// Parse a code as it appears in a response
+    pub fn parse(code: &str) -> Self {
+        match code.trim() {
+            "InvalidValue" => AudibleErrorCode::InvalidValue,
+            "InvalidParameter" | "InvalidParameterValue" | "MissingParameter" => {
+                AudibleErrorCode::InvalidParameter
+            }
+            "NotFound" | "NotFoundError" | "ResourceNotFound" => AudibleErrorCode::NotFound,
+            "DownloadLimitExceeded" => AudibleErrorCode::DownloadLimitExceeded,
+            "Unauthorized" | "InvalidToken" | "InvalidAuthToken" => AudibleErrorCode::Unauthorized,
+            "AccessDenied" | "Forbidden" | "NotOwned" => AudibleErrorCode::AccessDenied,
+            "Throttled" | "ThrottlingException" | "TooManyRequests" => AudibleErrorCode::Throttled,
+            "Conflict" | "ConcurrentModification" | "ConcurrentSession" => AudibleErrorCode::Conflict,
+            other => AudibleErrorCode::Other(other.to_string()),
+        }
+    }
+
+    /// Extract the error code from an API error body
+    ///
+    /// Returns `None` if the body is not JSON or carries no code.
+    pub fn from_response_body(body: &str) -> Option<Self> {
+        let json: serde_json::Value = serde_json::from_str(body).ok()?;
+        let code = json
+            .get("error_code")
+            .or_else(|| json.get("code"))
+            .or_else(|| json.get("errors").and_then(|e| e.get(0)).and_then(|e| e.get("code")))
+            .and_then(|c| c.as_str())?;
+
+        (!code.trim().is_empty()).then(|| Self::parse(code))
+    }
+}
+
+impl std::fmt::Display for AudibleErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudibleErrorCode::InvalidValue => f.write_str("InvalidValue"),
+            AudibleErrorCode::InvalidParameter => f.write_str("InvalidParameter"),
+            AudibleErrorCode::NotFound => f.write_str("NotFound"),
+            AudibleErrorCode::DownloadLimitExceeded => f.write_str("DownloadLimitExceeded"),
+            AudibleErrorCode::Unauthorized => f.write_str("Unauthorized"),
+            AudibleErrorCode::AccessDenied => f.write_str("AccessDenied"),
+            AudibleErrorCode::Throttled => f.write_str("Throttled"),
+            AudibleErrorCode::Conflict => f.write_str("Conflict"),
+            AudibleErrorCode::Other(code) => f.write_str(code),
+        }
+    }
+}
+
+/// Format the optional ASIN of a `Storage` error for display
+fn asin_suffix(asin: &Option<String>) -> String {
+    asin.as_ref().map(|a| format!(" ({})", a)).unwrap_or_default()
+}
+
+/// Attach operation and ASIN context to database results
+///
+/// ```rust,no_run
+/// use rust_core::error::{Result, StorageContext};
+/// # async fn example(pool: &sqlx::SqlitePool, asin: &str) -> Result<()> {
+/// sqlx::query("DELETE FROM Books WHERE audible_product_id = ?")
+///     .bind(asin)
+///     .execute(pool)
+///     .await
+///     .storage_context("deleting book", Some(asin))?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait StorageContext<T> {
+    /// Convert a database error into [`LibationError::Storage`]
+    fn storage_context(self, operation: &str, asin: Option<&str>) -> Result<T>;
+}
+
+impl<T> StorageContext<T> for std::result::Result<T, sqlx::Error> {
+    fn storage_context(self, operation: &str, asin: Option<&str>) -> Result<T> {
+        self.map_err(|e| LibationError::storage(operation, asin, e))
+    }
+}
+
 // ===== IMPLEMENTATION NOTES =====
 //
 // ## Error Handling Strategy
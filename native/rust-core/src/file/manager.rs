@@ -30,20 +30,88 @@
 //! - Directory creation
 //! - Disk space checks
 //! - File cleanup (temp files, old versions)
+//! - Per-account storage roots, see [`AccountPaths`]
 
+use crate::api::license::OutputFormat;
 use crate::audio::metadata::AudioMetadata;
+use crate::backoff::FILE_BACKOFF;
 use crate::error::{LibationError, Result};
-use crate::file::paths::{avoid_collision, get_safe_filename, PathTemplate};
+use crate::file::paths::{avoid_collision, get_safe_filename, PathBuilder, PathTemplate};
 use std::path::{Path, PathBuf};
-use std::time::Duration;
 use tokio::fs;
-use tokio::time::sleep;
 
 /// Maximum retry attempts for file operations
 const MAX_RETRY_ATTEMPTS: u32 = 3;
 
-/// Delay between retry attempts
-const RETRY_DELAY: Duration = Duration::from_millis(100);
+/// Longest readable part of an account's folder name
+const MAX_ACCOUNT_DIR_PREFIX: usize = 40;
+
+/// Where one account's files live under a shared storage root
+///
+/// Libation keeps one library per install. With several accounts on one
+/// device, two accounts owning the same title would render the same file
+/// name, so each account gets its own tree:
+///
+/// ```text
+/// {storage_root}/accounts/{account}/books/      decrypted library
+/// {storage_root}/accounts/{account}/covers/     cover art
+/// {storage_root}/accounts/{account}/downloads/  encrypted downloads
+/// {storage_root}/accounts/{account}/library.db  cached library
+/// ```
+///
+/// `{account}` is the account ID made safe for a file name, followed by a
+/// short hash of the exact ID, so IDs that only differ in characters that
+/// were replaced still get separate folders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountPaths {
+    /// The account's own folder
+    pub root: PathBuf,
+    pub books: PathBuf,
+    pub covers: PathBuf,
+    pub downloads: PathBuf,
+    pub database: PathBuf,
+}
+
+impl AccountPaths {
+    /// Paths for `account_id` under `storage_root`; nothing is created
+    pub fn new(storage_root: &Path, account_id: &str) -> Self {
+        let root = storage_root.join("accounts").join(account_dir_name(account_id));
+        Self {
+            books: root.join("books"),
+            covers: root.join("covers"),
+            downloads: root.join("downloads"),
+            database: root.join("library.db"),
+            root,
+        }
+    }
+
+    /// Create the account's folders
+    ///
+    /// # Errors
+    /// - `FileIoError` - A folder could not be created
+    pub async fn create_directories(&self) -> Result<()> {
+        for dir in [&self.books, &self.covers, &self.downloads] {
+            fs::create_dir_all(dir).await.map_err(|e| {
+                LibationError::FileIoError(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Folder name for an account: readable prefix plus a hash of the full ID
+fn account_dir_name(account_id: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    let readable: String = account_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .take(MAX_ACCOUNT_DIR_PREFIX)
+        .collect();
+    let hash = hex::encode(&Sha256::digest(account_id.as_bytes())[..4]);
+    format!("{}-{}", readable.trim_matches('.'), hash)
+}
+
 
 /// File manager for safe file operations
 ///
@@ -93,7 +161,7 @@ impl FileManager {
                     )));
                 }
                 Err(_) => {
-                    sleep(RETRY_DELAY).await;
+                    FILE_BACKOFF.wait(attempts - 1).await;
                     continue;
                 }
             }
@@ -152,7 +220,7 @@ impl FileManager {
                     )));
                 }
                 Err(_) => {
-                    sleep(RETRY_DELAY).await;
+                    FILE_BACKOFF.wait(attempts - 1).await;
                     continue;
                 }
             }
@@ -204,7 +272,7 @@ impl FileManager {
                     )));
                 }
                 Err(_) => {
-                    sleep(RETRY_DELAY).await;
+                    FILE_BACKOFF.wait(attempts - 1).await;
                     continue;
                 }
             }
@@ -277,6 +345,60 @@ impl FileManager {
         Ok(actual_size == expected_size)
     }
 
+    /// Check whether a book's final output is already on disk
+    ///
+    /// Used by batch downloads to skip titles that were downloaded and
+    /// decrypted in an earlier run. The output must exist and be non-empty;
+    /// a zero-byte file is treated as a failed previous attempt.
+    pub async fn verify_downloaded(output_path: &Path) -> bool {
+        match fs::metadata(output_path).await {
+            Ok(metadata) => metadata.is_file() && metadata.len() > 0,
+            Err(_) => false,
+        }
+    }
+
+    /// Compute where a book will be saved in the library
+    ///
+    /// This is the path [`organize_audiobook`](Self::organize_audiobook) moves
+    /// the file to, before any collision suffix is added. It does not touch the
+    /// filesystem, so the UI can show it before downloading and sync can check
+    /// it to find books that are already downloaded.
+    ///
+    /// # Arguments
+    /// * `metadata` - Book metadata used to fill the template
+    /// * `template` - Naming template
+    /// * `format` - Output format, which determines the extension
+    ///
+    /// # Returns
+    /// Absolute path inside the library directory
+    ///
+    /// # Errors
+    /// - `InvalidPath` - Rendered path exceeds the platform length limit
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::api::license::OutputFormat;
+    /// # use rust_core::audio::metadata::AudioMetadata;
+    /// # use rust_core::file::{FileManager, paths::NamingPattern};
+    /// # fn example(manager: &FileManager, metadata: &AudioMetadata) -> rust_core::error::Result<()> {
+    /// let template = NamingPattern::AuthorBookFolder.to_template();
+    /// let path = manager.expected_path(metadata, &template, OutputFormat::M4b)?;
+    /// if path.exists() {
+    ///     println!("Already downloaded: {}", path.display());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn expected_path(
+        &self,
+        metadata: &AudioMetadata,
+        template: &PathTemplate,
+        format: OutputFormat,
+    ) -> Result<PathBuf> {
+        PathBuilder::new(self.library_path.clone(), template.clone())
+            .build_path(metadata, format.extension())
+    }
+
     /// Organize audiobook file: move to library with proper naming
     ///
     /// # Reference: Combined from `FileManager/FileUtility.cs` and `LibationFileManager/`
@@ -528,7 +650,7 @@ mod tests {
             narrators: vec!["Jane Smith".to_string()],
             publisher: Some("Test Publisher".to_string()),
             publication_date: Some("2023".to_string()),
-            language: Some("en".to_string()),
+            languages: vec!["en".to_string()],
             series: Some(SeriesInfo {
                 name: "Test Series".to_string(),
                 position: Some("1".to_string()),
@@ -631,6 +753,23 @@ mod tests {
         assert_eq!(content, "atomic content");
     }
 
+    #[tokio::test]
+    async fn test_verify_downloaded() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let output = temp_dir.path().join("book.m4b");
+        assert!(!FileManager::verify_downloaded(&output).await);
+
+        fs::write(&output, b"").await.unwrap();
+        assert!(!FileManager::verify_downloaded(&output).await);
+
+        fs::write(&output, b"audio content").await.unwrap();
+        assert!(FileManager::verify_downloaded(&output).await);
+
+        // Directories never count as a downloaded book
+        assert!(!FileManager::verify_downloaded(temp_dir.path()).await);
+    }
+
     #[tokio::test]
     async fn test_organize_audiobook() {
         let temp_dir = TempDir::new().unwrap();
@@ -655,4 +794,56 @@ mod tests {
         assert!(result.to_string_lossy().contains("John Doe"));
         assert!(result.to_string_lossy().contains("Test Book"));
     }
+
+    #[tokio::test]
+    async fn test_accounts_do_not_share_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let template = PathTemplate::default_audiobook();
+        let metadata = test_metadata();
+
+        let first = FileManager::new(AccountPaths::new(temp_dir.path(), "amzn1.account.FIRST").books);
+        let second = FileManager::new(AccountPaths::new(temp_dir.path(), "amzn1.account.SECOND").books);
+        let first_path = first.expected_path(&metadata, &template, OutputFormat::M4b).unwrap();
+        let second_path = second.expected_path(&metadata, &template, OutputFormat::M4b).unwrap();
+        assert_ne!(first_path, second_path);
+        assert_eq!(first_path.strip_prefix(first.library_path()), second_path.strip_prefix(second.library_path()));
+
+        // Sanitized to the same prefix, still separate
+        let plus = AccountPaths::new(temp_dir.path(), "reader+1@example.com");
+        let underscore = AccountPaths::new(temp_dir.path(), "reader_1@example.com");
+        assert_ne!(plus.root, underscore.root);
+        assert!(plus.root.file_name().unwrap().to_str().unwrap().starts_with("reader_1_example.com-"));
+
+        plus.create_directories().await.unwrap();
+        assert!(plus.covers.is_dir() && plus.downloads.is_dir());
+        assert_eq!(plus.database, plus.root.join("library.db"));
+    }
+
+    #[tokio::test]
+    async fn test_expected_path_matches_organized_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = FileManager::new(temp_dir.path().join("library"));
+        let template = PathTemplate::default_audiobook();
+        let metadata = test_metadata();
+
+        let expected = manager
+            .expected_path(&metadata, &template, OutputFormat::M4b)
+            .unwrap();
+        assert!(expected.starts_with(manager.library_path()));
+        assert_eq!(expected.extension().unwrap(), "m4b");
+        assert!(!expected.exists());
+
+        let source = temp_dir.path().join("source.m4b");
+        fs::write(&source, b"audio content").await.unwrap();
+        let organized = manager
+            .organize_audiobook(&source, &metadata, &template, "m4b")
+            .await
+            .unwrap();
+        assert_eq!(organized, expected);
+
+        let mp3 = manager
+            .expected_path(&metadata, &template, OutputFormat::Mp3)
+            .unwrap();
+        assert_eq!(mp3.with_extension("m4b"), expected);
+    }
 }
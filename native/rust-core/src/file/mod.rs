@@ -29,7 +29,8 @@
 
 pub mod manager;
 pub mod paths;
+pub mod post_write;
 
 // Re-export commonly used types
-pub use manager::FileManager;
+pub use manager::{AccountPaths, FileManager};
 pub use paths::PathBuilder;
@@ -31,10 +31,23 @@
 //! - Sanitize for filesystem compatibility
 //! - Handle path length limits
 //! - Avoid filename collisions
+//!
+//! # Missing Names
+//! Podcasts and some other items come without authors or narrators, and a few
+//! have names made only of characters that sanitizing removes. Such values
+//! render as the placeholders in [`MissingNames`] ("Unknown Author" etc.), so
+//! a template never yields an empty folder or a path starting with `/`.
+//!
+//! # Length Limits
+//! Each rendered folder and file name is cut to the byte limit in
+//! [`FilesystemLimits`], on a character boundary and marked with `…`. The
+//! default suits the platform; eCryptfs-backed storage needs the shorter
+//! [`FilesystemLimits::ECRYPTFS`].
 
 use crate::audio::metadata::AudioMetadata;
 use crate::error::{LibationError, Result};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
@@ -63,17 +76,96 @@ const MAX_COMPONENT_LENGTH: usize = 255;
 )))]
 const MAX_COMPONENT_LENGTH: usize = 255;
 
+/// File and path length limits of the filesystem books are written to
+///
+/// Lengths are bytes of UTF-8. A limit given in UTF-16 units (NTFS, exFAT,
+/// FAT32 long names) is never exceeded by a name within the same number of
+/// UTF-8 bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilesystemLimits {
+    /// Longest file or folder name
+    pub max_component_bytes: usize,
+    /// Longest full path
+    pub max_path_bytes: usize,
+}
+
+impl FilesystemLimits {
+    /// ext4, F2FS, APFS, exFAT and FAT32 long names
+    pub const POSIX: Self = Self { max_component_bytes: 255, max_path_bytes: 4096 };
+
+    /// NTFS without long path support
+    pub const WINDOWS: Self = Self { max_component_bytes: 255, max_path_bytes: 260 };
+
+    /// eCryptfs with encrypted file names (older Android devices, encrypted
+    /// Linux home folders), which leaves 143 bytes per name
+    pub const ECRYPTFS: Self = Self { max_component_bytes: 143, max_path_bytes: 4096 };
+}
+
+impl Default for FilesystemLimits {
+    /// Limits of the platform the crate was built for
+    fn default() -> Self {
+        Self {
+            max_component_bytes: MAX_COMPONENT_LENGTH,
+            max_path_bytes: MAX_PATH_LENGTH,
+        }
+    }
+}
+
+/// Names used in paths when a book has none
+///
+/// A name counts as missing when it is absent, blank, or sanitizes to
+/// nothing (e.g. `"..."`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MissingNames {
+    /// Used for `{author}` and `{authors}`
+    pub author: String,
+    /// Used for `{narrator}` and `{narrators}`
+    pub narrator: String,
+    /// Used for `{title}` and as the file name of last resort
+    pub title: String,
+}
+
+impl Default for MissingNames {
+    fn default() -> Self {
+        Self {
+            author: "Unknown Author".to_string(),
+            narrator: "Unknown Narrator".to_string(),
+            title: "Untitled".to_string(),
+        }
+    }
+}
+
+/// Whether a name is nothing but characters that sanitizing strips
+///
+/// The sanitizers turn such names into a generic "file" or "folder".
+fn is_missing(name: &str) -> bool {
+    name.chars()
+        .all(|c| c.is_whitespace() || matches!(c, '.' | ',' | '/' | '\\'))
+}
+
 /// Path template for generating filenames from book metadata
 ///
 /// # Reference: `FileManager/NamingTemplate/NamingTemplate.cs`
 #[derive(Debug, Clone)]
 pub struct PathTemplate {
     template: String,
+    missing: MissingNames,
 }
 
 impl PathTemplate {
     pub fn new(template: String) -> Self {
-        Self { template }
+        Self {
+            template,
+            missing: MissingNames::default(),
+        }
+    }
+
+    /// Use `missing` instead of the default placeholders for absent names
+    pub fn with_missing_names(mut self, missing: MissingNames) -> Self {
+        self.missing = missing;
+        self
     }
 
     /// Flat file: `{title}.m4b`
@@ -119,7 +211,7 @@ impl PathTemplate {
     ///
     /// # Reference: `FileManager/NamingTemplate/NamingTemplate.cs` Evaluate()
     pub fn render(&self, metadata: &AudioMetadata) -> Result<String> {
-        let tags = Self::extract_tags(metadata);
+        let tags = self.extract_tags(metadata);
         let mut result = self.template.clone();
 
         // Replace all tags in template
@@ -141,25 +233,34 @@ impl PathTemplate {
     /// Extract tags from metadata
     ///
     /// # Reference: `FileManager/NamingTemplate/PropertyTagCollection.cs`
-    fn extract_tags(metadata: &AudioMetadata) -> HashMap<String, String> {
+    fn extract_tags(&self, metadata: &AudioMetadata) -> HashMap<String, String> {
         let mut tags = HashMap::new();
 
         // Title
-        tags.insert("title".to_string(), metadata.title.clone());
-
-        // Author(s)
-        if !metadata.authors.is_empty() {
-            tags.insert("author".to_string(), metadata.authors[0].clone());
-            tags.insert("authors".to_string(), metadata.format_authors());
+        let title = if is_missing(&metadata.title) {
+            self.missing.title.clone()
         } else {
-            tags.insert("author".to_string(), "Unknown Author".to_string());
-            tags.insert("authors".to_string(), "Unknown Author".to_string());
-        }
-
-        // Narrator(s)
-        if !metadata.narrators.is_empty() {
-            tags.insert("narrator".to_string(), metadata.narrators[0].clone());
-            tags.insert("narrators".to_string(), metadata.format_narrators());
+            metadata.title.clone()
+        };
+        tags.insert("title".to_string(), title);
+
+        // Author(s) and narrator(s), skipping names that would render empty
+        let people = [
+            ("author", &metadata.authors, &self.missing.author),
+            ("narrator", &metadata.narrators, &self.missing.narrator),
+        ];
+        for (tag, names, fallback) in people {
+            let names: Vec<&str> = names.iter().map(|n| n.trim()).filter(|n| !is_missing(n)).collect();
+            match names.first() {
+                Some(first) => {
+                    tags.insert(tag.to_string(), first.to_string());
+                    tags.insert(format!("{}s", tag), names.join(", "));
+                }
+                None => {
+                    tags.insert(tag.to_string(), fallback.clone());
+                    tags.insert(format!("{}s", tag), fallback.clone());
+                }
+            }
         }
 
         // Series
@@ -167,8 +268,10 @@ impl PathTemplate {
         if let Some(ref series) = metadata.series {
             tags.insert("series".to_string(), series.name.clone());
 
-            let series_seq = series.position.as_ref()
-                .map(|p| p.clone())
+            // Normalised number ("Book 3" -> "3"), raw text if there is none
+            let series_seq = series.sequence()
+                .map(|s| s.to_string())
+                .or_else(|| series.position.clone())
                 .unwrap_or_default();
 
             tags.insert("series_seq".to_string(), series_seq.clone());
@@ -225,6 +328,12 @@ impl PathTemplate {
             tags.insert("asin".to_string(), asin.clone());
         }
 
+        // Language (primary, and all for multilingual titles)
+        if let Some(language) = metadata.primary_language() {
+            tags.insert("language".to_string(), language.to_string());
+            tags.insert("languages".to_string(), metadata.languages.join(", "));
+        }
+
         // Genre (first one)
         if !metadata.genres.is_empty() {
             tags.insert("genre".to_string(), metadata.genres[0].clone());
@@ -258,6 +367,7 @@ impl PathTemplate {
 pub struct PathBuilder {
     base_directory: PathBuf,
     template: PathTemplate,
+    limits: FilesystemLimits,
 }
 
 impl PathBuilder {
@@ -265,9 +375,16 @@ impl PathBuilder {
         Self {
             base_directory,
             template,
+            limits: FilesystemLimits::default(),
         }
     }
 
+    /// Cut names to `limits` instead of the platform default
+    pub fn with_limits(mut self, limits: FilesystemLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     /// Build full path from metadata
     ///
     /// # Reference: `LibationFileManager/Configuration.cs` and `FileManager/FileUtility.cs`
@@ -283,15 +400,16 @@ impl PathBuilder {
             (&[][..], parts[0])
         };
 
-        // Sanitize each directory component
+        // Sanitize each directory component, dropping any that end up empty
         let mut sanitized_dirs = Vec::new();
-        for part in dir_parts {
+        for part in dir_parts.iter().filter(|p| !is_missing(p)) {
             let sanitized = sanitize_path_component(part);
-            let truncated = truncate_component(&sanitized, MAX_COMPONENT_LENGTH);
+            let truncated = truncate_component(&sanitized, self.limits.max_component_bytes);
             sanitized_dirs.push(truncated);
         }
 
         // Sanitize filename
+        let filename = if is_missing(filename) { &self.template.missing.title } else { filename };
         let sanitized_filename = sanitize_filename(filename);
         let ext = if extension.starts_with('.') {
             extension.to_string()
@@ -300,7 +418,7 @@ impl PathBuilder {
         };
 
         // Reserve space for extension and potential collision suffix " (999)"
-        let max_filename_len = MAX_COMPONENT_LENGTH - ext.len() - 6;
+        let max_filename_len = self.limits.max_component_bytes.saturating_sub(ext.len() + 6);
         let truncated_filename = truncate_component(&sanitized_filename, max_filename_len);
 
         // Build full path
@@ -314,7 +432,7 @@ impl PathBuilder {
 
         // Ensure total path doesn't exceed limits
         let path_str = path.to_string_lossy();
-        if path_str.as_bytes().len() > MAX_PATH_LENGTH {
+        if path_str.as_bytes().len() > self.limits.max_path_bytes {
             return Err(LibationError::InvalidPath(format!(
                 "Path too long ({} bytes): {}",
                 path_str.as_bytes().len(),
@@ -552,25 +670,31 @@ fn handle_windows_reserved_names(name: &str) -> String {
 
 /// Truncate path component to fit within byte limit
 ///
+/// Cuts on a character boundary and ends the result with `…` (3 bytes).
+/// Whitespace and dots before the cut are dropped, since Windows does not
+/// allow names ending in either.
+///
 /// # Reference: `FileManager/FileUtility.cs` TruncateFilename()
 pub fn truncate_component(text: &str, max_bytes: usize) -> String {
-    let bytes = text.as_bytes();
-    if bytes.len() <= max_bytes {
+    const ELLIPSIS: &str = "…";
+
+    if text.len() <= max_bytes {
         return text.to_string();
     }
+    if max_bytes < ELLIPSIS.len() {
+        return text[..floor_char_boundary(text, max_bytes)].to_string();
+    }
+
+    let kept = &text[..floor_char_boundary(text, max_bytes - ELLIPSIS.len())];
+    format!("{}{}", kept.trim_end_matches(|c: char| c.is_whitespace() || c == '.'), ELLIPSIS)
+}
 
-    // Find valid UTF-8 boundary
-    let mut index = max_bytes;
+/// Largest character boundary in `text` at or before byte `index`
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
     while index > 0 && !text.is_char_boundary(index) {
         index -= 1;
     }
-
-    // Leave room for ellipsis if we truncated significantly
-    if index < max_bytes - 3 {
-        format!("{}...", &text[..index.saturating_sub(3)])
-    } else {
-        text[..index].to_string()
-    }
+    index
 }
 
 /// Avoid filename collision by appending (1), (2), etc.
@@ -610,19 +734,26 @@ pub fn avoid_collision(path: &Path) -> PathBuf {
 }
 
 /// Naming pattern for audiobook files
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Serialized in snake_case; the short names accepted by
+/// [`from_string`](Self::from_string) are accepted as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum NamingPattern {
     /// Flat file: `{title}.m4b`
     /// Example: "All These Worlds.m4b"
+    #[serde(alias = "flat")]
     FlatFile,
 
     /// Author/Book folder: `{author}/{title}/{title}.m4b`
     /// Example: "Dennis E. Taylor/All These Worlds/All These Worlds.m4b"
+    #[serde(alias = "author_book")]
     AuthorBookFolder,
 
     /// Author/Series+Book: `{author}/{series} {series_seq} - {title_no_series}/{series} {series_seq} - {title_no_series}.m4b`
     /// Example: "Dennis E. Taylor/Bobiverse 3 - All These Worlds/Bobiverse 3 - All These Worlds.m4b"
     /// Falls back to author/title/title if no series
+    #[serde(alias = "author_series")]
     AuthorSeriesBook,
 }
 
@@ -649,31 +780,78 @@ impl NamingPattern {
 ///
 /// Returns the relative path (no base directory) ready to append to output directory.
 /// Example: "Dennis E. Taylor/Bobiverse 3 - All These Worlds/Bobiverse 3 - All These Worlds.m4b"
+///
+/// Books without authors or narrators get the default [`MissingNames`]; use
+/// [`build_file_path_with`] to choose other placeholders. Names are cut to the
+/// platform's [`FilesystemLimits`].
 pub fn build_file_path(
     metadata: &AudioMetadata,
     pattern: NamingPattern,
     extension: &str,
 ) -> Result<String> {
-    let template = pattern.to_template();
+    build_file_path_with(metadata, pattern, extension, &MissingNames::default())
+}
+
+/// Build file path using specified naming pattern and placeholders
+///
+/// Same as [`build_file_path`], with `missing` filling in absent names. The
+/// result never starts with `/` and never contains an empty directory.
+///
+/// # Example
+/// ```rust,no_run
+/// use rust_core::file::paths::{build_file_path_with, MissingNames, NamingPattern};
+/// # fn example(metadata: &rust_core::audio::metadata::AudioMetadata) -> rust_core::error::Result<()> {
+/// let missing = MissingNames {
+///     author: "Podcasts".to_string(),
+///     ..MissingNames::default()
+/// };
+/// let path = build_file_path_with(metadata, NamingPattern::AuthorBookFolder, "m4b", &missing)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn build_file_path_with(
+    metadata: &AudioMetadata,
+    pattern: NamingPattern,
+    extension: &str,
+    missing: &MissingNames,
+) -> Result<String> {
+    build_file_path_with_limits(metadata, pattern, extension, missing, FilesystemLimits::default())
+}
+
+/// Build file path for a filesystem with the given length limits
+///
+/// Same as [`build_file_path_with`], with every folder name and the file name
+/// (extension included) cut to `limits.max_component_bytes`. The overall
+/// length is not checked, as the path is relative.
+pub fn build_file_path_with_limits(
+    metadata: &AudioMetadata,
+    pattern: NamingPattern,
+    extension: &str,
+    missing: &MissingNames,
+    limits: FilesystemLimits,
+) -> Result<String> {
+    let template = pattern.to_template().with_missing_names(missing.clone());
     let rendered = template.render(metadata)?;
 
     // Split into path components and sanitize each
     let parts: Vec<&str> = rendered.split('/').collect();
     let mut sanitized_parts = Vec::new();
 
+    let extension = extension.trim_start_matches('.');
+    let ext_len = if extension.is_empty() { 0 } else { extension.len() + 1 };
+
     for (i, part) in parts.iter().enumerate() {
         let is_last = i == parts.len() - 1;
 
         if is_last {
             // Last part is the filename
-            let sanitized = sanitize_filename(part);
-            sanitized_parts.push(sanitized);
-        } else {
+            let part = if is_missing(part) { &missing.title } else { *part };
+            let max_stem = limits.max_component_bytes.saturating_sub(ext_len);
+            sanitized_parts.push(truncate_component(&sanitize_filename(part), max_stem));
+        } else if !is_missing(part) {
             // Directory component
-            let sanitized = sanitize_path_component(part);
-            if !sanitized.is_empty() {
-                sanitized_parts.push(sanitized);
-            }
+            let folder = sanitize_path_component(part);
+            sanitized_parts.push(truncate_component(&folder, limits.max_component_bytes));
         }
     }
 
@@ -683,7 +861,7 @@ pub fn build_file_path(
     let with_ext = if extension.is_empty() || result.ends_with(extension) {
         result
     } else {
-        format!("{}.{}", result, extension.trim_start_matches('.'))
+        format!("{}.{}", result, extension)
     };
 
     Ok(with_ext)
@@ -717,7 +895,7 @@ mod tests {
             narrators: vec!["Jane Smith".to_string()],
             publisher: Some("Test Publisher".to_string()),
             publication_date: Some("2023".to_string()),
-            language: Some("en".to_string()),
+            languages: vec!["en".to_string()],
             series: Some(SeriesInfo {
                 name: "Test Series".to_string(),
                 position: Some("1".to_string()),
@@ -752,6 +930,67 @@ mod tests {
         let metadata = test_metadata();
         let result = template.render(&metadata).unwrap();
         assert_eq!(result, "John Doe/Test Series #1 - Test Book");
+
+        let mut metadata = test_metadata();
+        metadata.series.as_mut().unwrap().position = Some("Book 1-3".to_string());
+        let result = template.render(&metadata).unwrap();
+        assert_eq!(result, "John Doe/Test Series #1-3 - Test Book");
+    }
+
+    #[test]
+    fn test_render_language_template() {
+        let template = PathTemplate::new("{language}/{title} [{languages}]".to_string());
+        let mut metadata = test_metadata();
+        metadata.languages = vec!["english".to_string(), "german".to_string()];
+        let result = template.render(&metadata).unwrap();
+        assert_eq!(result, "english/Test Book [english, german]");
+    }
+
+    #[test]
+    fn test_missing_authors_use_placeholder() {
+        let mut metadata = test_metadata();
+        metadata.authors = vec![];
+        metadata.narrators = vec!["  ".to_string()];
+        metadata.series = None;
+
+        let path = build_file_path(&metadata, NamingPattern::AuthorBookFolder, "m4b").unwrap();
+        assert_eq!(path, "Unknown Author/Test Book/Test Book.m4b");
+
+        let template = PathTemplate::new("{narrator}/{title}".to_string());
+        assert_eq!(template.render(&metadata).unwrap(), "Unknown Narrator/Test Book");
+
+        // A name that sanitizes to nothing counts as missing too
+        metadata.authors = vec!["...".to_string(), "Jane Roe".to_string()];
+        let path = build_file_path(&metadata, NamingPattern::AuthorBookFolder, "m4b").unwrap();
+        assert_eq!(path, "Jane Roe/Test Book/Test Book.m4b");
+    }
+
+    #[test]
+    fn test_missing_names_are_configurable() {
+        let mut metadata = test_metadata();
+        metadata.authors = vec!["".to_string()];
+        metadata.title = "?".to_string();
+        metadata.series = None;
+        let missing = MissingNames {
+            author: "Podcasts".to_string(),
+            title: "Episode".to_string(),
+            ..MissingNames::default()
+        };
+
+        let path = build_file_path_with(&metadata, NamingPattern::AuthorBookFolder, "m4b", &missing).unwrap();
+        assert_eq!(path, "Podcasts/？/？.m4b");
+
+        metadata.title = "...".to_string();
+        let path = build_file_path_with(&metadata, NamingPattern::AuthorBookFolder, "m4b", &missing).unwrap();
+        assert!(!path.starts_with('/'));
+        assert_eq!(path, "Podcasts/Episode/Episode.m4b");
+
+        let builder = PathBuilder::new(
+            PathBuf::from("/library"),
+            PathTemplate::author_book_folder().with_missing_names(missing),
+        );
+        let path = builder.build_path(&metadata, "m4b").unwrap();
+        assert_eq!(path, PathBuf::from("/library/Podcasts/Episode/Episode.m4b"));
     }
 
     #[test]
@@ -776,6 +1015,40 @@ mod tests {
         let long_text = "a".repeat(300);
         let truncated = truncate_component(&long_text, 255);
         assert!(truncated.as_bytes().len() <= 255);
+
+        // Never splits a character, even when the limit falls inside one
+        let umlauts = "ü".repeat(200);
+        let truncated = truncate_component(&umlauts, 100);
+        assert_eq!(truncated, format!("{}…", "ü".repeat(48)));
+        assert_eq!(truncate_component("Book. Two", 8), "Book…");
+    }
+
+    #[test]
+    fn test_long_title_fits_filesystem_limits() {
+        let mut metadata = test_metadata();
+        metadata.title = format!("Über {}", "Die lange Geschichte ".repeat(15));
+        metadata.series = Some(SeriesInfo { name: "ß".repeat(300), position: Some("1".to_string()) });
+
+        for limits in [FilesystemLimits::POSIX, FilesystemLimits::WINDOWS, FilesystemLimits::ECRYPTFS] {
+            let path = build_file_path_with_limits(
+                &metadata,
+                NamingPattern::AuthorSeriesBook,
+                "m4b",
+                &MissingNames::default(),
+                limits,
+            )
+            .unwrap();
+            assert!(path.ends_with("….m4b"), "{}", path);
+            for component in path.split('/') {
+                assert!(component.len() <= limits.max_component_bytes, "{}", component);
+            }
+        }
+
+        let built = PathBuilder::new(PathBuf::from("/library"), NamingPattern::AuthorSeriesBook.to_template())
+            .with_limits(FilesystemLimits::ECRYPTFS)
+            .build_path(&metadata, "m4b")
+            .unwrap();
+        assert!(built.iter().all(|c| c.len() <= 143));
     }
 
     #[test]
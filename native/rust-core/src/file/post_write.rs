@@ -0,0 +1,195 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Host notification and permissions for finished output files
+//!
+//! # Reference C# Sources
+//! - None. Libation writes to a desktop folder the user browses directly
+//!
+//! On Android a book written to shared storage does not show up in other
+//! players until the media scanner has seen it, and the app's default file
+//! mode may keep other apps from reading it. Both are the host's business,
+//! so the core only reports each finished file:
+//!
+//! - [`set_output_permissions`] - Mode applied to every finished file (and its
+//!   folder) before the host hears about it
+//! - [`set_post_write_hook`] - A host [`PostWriteHook`] called with the path
+//!   and MIME type, e.g. to run `MediaScannerConnection.scanFile`
+//!
+//! Both are process-wide. [`notify_file_written`] is called wherever a book
+//! reaches its final path: after decrypting, after a download that needs no
+//! decrypt, and after reprocessing moves a file.
+
+use crate::error::{LibationError, Result};
+use lazy_static::lazy_static;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// Receives every finished output file
+///
+/// Implemented by the host app. Calls come from a background thread.
+#[uniffi::export(with_foreign)]
+pub trait PostWriteHook: Send + Sync {
+    /// A file was written to `path`
+    ///
+    /// # Arguments
+    /// * `path` - Absolute path of the file
+    /// * `mime_type` - MIME type derived from the extension, e.g. `audio/mp4`
+    fn file_written(&self, path: String, mime_type: String);
+}
+
+/// File modes applied to finished output files
+///
+/// `None` leaves the mode the file was created with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, uniffi::Record)]
+pub struct OutputPermissions {
+    /// Mode for the file itself, e.g. `0o644`
+    pub file_mode: Option<u32>,
+    /// Mode for the folder holding it, e.g. `0o755`
+    pub dir_mode: Option<u32>,
+}
+
+lazy_static! {
+    static ref POST_WRITE_HOOK: RwLock<Option<Arc<dyn PostWriteHook>>> = RwLock::new(None);
+    static ref OUTPUT_PERMISSIONS: RwLock<OutputPermissions> = RwLock::new(OutputPermissions::default());
+}
+
+/// Install the hook called for every finished file, or remove it with `None`
+#[uniffi::export]
+pub fn set_post_write_hook(hook: Option<Arc<dyn PostWriteHook>>) {
+    *POST_WRITE_HOOK.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = hook;
+}
+
+/// Set the modes applied to finished files
+#[uniffi::export]
+pub fn set_output_permissions(permissions: OutputPermissions) {
+    *OUTPUT_PERMISSIONS.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = permissions;
+}
+
+/// Parse an octal mode such as `"644"` or `"0o755"`
+///
+/// # Errors
+/// - `InvalidInput` - Not an octal number, or above `0o7777`
+pub fn parse_mode(mode: &str) -> Result<u32> {
+    let digits = mode.trim().trim_start_matches("0o");
+    u32::from_str_radix(digits, 8)
+        .ok()
+        .filter(|mode| *mode <= 0o7777)
+        .ok_or_else(|| LibationError::InvalidInput(format!("Invalid file mode: {}", mode)))
+}
+
+/// MIME type the host should register `path` with
+pub fn mime_type_for(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    match extension.as_str() {
+        "m4b" | "m4a" | "mp4" => "audio/mp4",
+        "mp3" => "audio/mpeg",
+        "flac" => "audio/flac",
+        "ogg" | "opus" => "audio/ogg",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "cue" | "txt" => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Apply the output permissions to `path`, then tell the host about it
+///
+/// Failures are logged, never returned: the file itself was written fine.
+pub async fn notify_file_written(path: &Path) {
+    let permissions = *OUTPUT_PERMISSIONS.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Err(e) = apply_permissions(path, permissions).await {
+        eprintln!("Could not set permissions on {}: {}", path.display(), e);
+    }
+
+    let hook = POST_WRITE_HOOK
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    if let Some(hook) = hook {
+        let path_string = path.to_string_lossy().into_owned();
+        let mime_type = mime_type_for(path).to_string();
+        // The host may block (JNI attach, scanner connection), so keep it off the runtime
+        if let Err(e) = tokio::task::spawn_blocking(move || hook.file_written(path_string, mime_type)).await {
+            eprintln!("Post-write hook failed for {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn apply_permissions(path: &Path, permissions: OutputPermissions) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Some(mode) = permissions.file_mode {
+        tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).await?;
+    }
+    if let (Some(mode), Some(dir)) = (permissions.dir_mode, path.parent()) {
+        tokio::fs::set_permissions(dir, std::fs::Permissions::from_mode(mode)).await?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn apply_permissions(_path: &Path, _permissions: OutputPermissions) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(String, String)>>);
+
+    impl PostWriteHook for Recorder {
+        fn file_written(&self, path: String, mime_type: String) {
+            self.0.lock().unwrap().push((path, mime_type));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notify_file_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let book = dir.path().join("Book.m4b");
+        tokio::fs::write(&book, b"audio").await.unwrap();
+
+        let recorder = Arc::new(Recorder::default());
+        set_post_write_hook(Some(recorder.clone()));
+        set_output_permissions(OutputPermissions { file_mode: Some(parse_mode("640").unwrap()), dir_mode: None });
+        notify_file_written(&book).await;
+        set_post_write_hook(None);
+        set_output_permissions(OutputPermissions::default());
+
+        let calls = recorder.0.lock().unwrap();
+        assert!(calls.contains(&(book.to_string_lossy().into_owned(), "audio/mp4".to_string())));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&book).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o640);
+        }
+        assert!(parse_mode("0o755").is_ok() && parse_mode("999").is_err());
+        assert_eq!(mime_type_for(Path::new("a/b.MP3")), "audio/mpeg");
+    }
+}
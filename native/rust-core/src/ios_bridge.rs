@@ -224,6 +224,7 @@ pub extern "C" fn rust_generate_oauth_url(
 /// Parse OAuth callback URL to extract authorization code
 ///
 /// # Arguments
+/// * `locale_code` - Audible locale the sign-in was started for (e.g., "us", "de")
 /// * `callback_url` - Full callback URL with authorization code
 ///
 /// # Returns
@@ -240,11 +241,17 @@ pub extern "C" fn rust_generate_oauth_url(
 /// # Safety
 /// Caller must free the returned string with `rust_free_string()`
 #[no_mangle]
-pub extern "C" fn rust_parse_oauth_callback(callback_url: *const c_char) -> *mut c_char {
+pub extern "C" fn rust_parse_oauth_callback(
+    locale_code: *const c_char,
+    callback_url: *const c_char,
+) -> *mut c_char {
     let response = catch_panic(|| {
+        let locale_code = c_str_to_string(locale_code)?;
         let callback_url = c_str_to_string(callback_url)?;
 
-        let auth_code = crate::api::auth::parse_authorization_callback(&callback_url)?;
+        let locale = crate::api::auth::Locale::from_country_code(&locale_code)
+            .ok_or_else(|| crate::LibationError::InvalidInput(format!("Invalid locale: {}", locale_code)))?;
+        let auth_code = crate::api::auth::parse_authorization_callback_for_locale(&callback_url, &locale)?;
 
         let response = serde_json::json!({
             "authorization_code": auth_code,
@@ -481,7 +488,7 @@ pub extern "C" fn rust_get_activation_bytes(
         })?;
 
         let response = serde_json::json!({
-            "activation_bytes": result,
+            "activation_bytes": result.activation_bytes,
         });
 
         Ok(success_response(response))
@@ -801,7 +808,15 @@ pub extern "C" fn rust_download_book(
 ///   "success": true,
 ///   "data": {
 ///     "output_path": "/path/to/book.m4b",
-///     "file_size": 123456789
+///     "file_size": 123456789,
+///     "audiobook": {
+///       "path": "/path/to/book.m4b",
+///       "format": "M4b",
+///       "size": 123456789,
+///       "duration": 9783.3,
+///       "chapters": [{ "title": "Chapter 1", "start_ms": 0, "end_ms": 1800250 }],
+///       "cover_path": null
+///     }
 ///   }
 /// }
 /// ```
@@ -827,16 +842,14 @@ pub extern "C" fn rust_decrypt_aax(
             let input_path = std::path::Path::new(&input_path);
             let output_path = std::path::Path::new(&output_path);
 
-            decrypter.decrypt_file(input_path, output_path).await?;
-
-            let file_size = tokio::fs::metadata(output_path)
-                .await
-                .map(|m| m.len())
-                .unwrap_or(0);
+            let audiobook = decrypter
+                .decrypt_audiobook(input_path, output_path, None, None)
+                .await?;
 
             let response = serde_json::json!({
                 "output_path": output_path.to_string_lossy(),
-                "file_size": file_size,
+                "file_size": audiobook.size,
+                "audiobook": audiobook,
             });
 
             Ok::<_, crate::LibationError>(response)
@@ -896,7 +909,7 @@ pub extern "C" fn rust_validate_activation_bytes(activation_bytes: *const c_char
 ///   "success": true,
 ///   "data": {
 ///     "locales": [
-///       {"country_code": "us", "name": "United States", "domain": "audible.com"},
+///       {"country_code": "us", "name": "United States", "domain": "audible.com", "language": "en-US", "currency": "USD"},
 ///       ...
 ///     ]
 ///   }
@@ -174,6 +174,37 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeLogFro
     output.into_raw()
 }
 
+// ============================================================================
+// DIAGNOSTICS
+// ============================================================================
+
+/// Run the native self-test
+///
+/// Takes no arguments; see [`crate::self_test::self_test`] for the checks.
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "passed": true,
+///     "version": "0.0.1",
+///     "checks": [{"name": "aes_cbc", "passed": true, "detail": "...", "duration_ms": 0}]
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeSelfTest(
+    env: JNIEnv,
+    _class: JClass,
+) -> jstring {
+    let response = catch_panic(|| success_response(crate::self_test::self_test()));
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
 // ============================================================================
 // AUTHENTICATION FUNCTIONS
 // ============================================================================
@@ -265,7 +296,8 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGenera
 /// # Arguments (JSON string)
 /// ```json
 /// {
-///   "callback_url": "https://localhost/callback?code=..."
+///   "locale_code": "us",
+///   "callback_url": "https://www.amazon.com/ap/maplanding?openid.oa2.authorization_code=..."
 /// }
 /// ```
 ///
@@ -289,6 +321,7 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeParseO
     let response = catch_panic(move || {
         #[derive(Deserialize)]
         struct Params {
+            locale_code: String,
             callback_url: String,
         }
 
@@ -297,7 +330,9 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeParseO
             let params: Params = serde_json::from_str(&params_str)
                 .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
 
-            let auth_code = crate::api::auth::parse_authorization_callback(&params.callback_url)?;
+            let locale = crate::api::auth::Locale::from_country_code(&params.locale_code)
+                .ok_or_else(|| crate::LibationError::InvalidInput(format!("Invalid locale: {}", params.locale_code)))?;
+            let auth_code = crate::api::auth::parse_authorization_callback_for_locale(&params.callback_url, &locale)?;
 
             let response = serde_json::json!({
                 "authorization_code": auth_code,
@@ -467,6 +502,66 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeRefres
         .into_raw()
 }
 
+/// Check whether the device registration is still valid
+///
+/// Use this when API calls start failing with auth errors: `registered: false`
+/// means the device was deregistered and the user must log in again, while
+/// `true` means a token refresh is enough.
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "account_json": "{...}"
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "registered": true
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeVerifyRegistration(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            account_json: String,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
+
+            let registered = RUNTIME.block_on(account.verify_registration())?;
+
+            Ok(success_response(serde_json::json!({
+                "registered": registered,
+            })))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
 /// Ensure access token is valid, refreshing if expired or expiring soon
 ///
 /// This is a just-in-time token refresh function that checks if the access token
@@ -568,10 +663,15 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeEnsure
 /// ```json
 /// {
 ///   "locale_code": "us",
-///   "access_token": "..."
+///   "access_token": "...",
+///   "account_json": "{...}", // optional, with asin: enables the fallback
+///   "asin": "B07T2F8VJM"     // optional, an AAX title in the library
 /// }
 /// ```
 ///
+/// When `account_json` and `asin` are given and the license-token request
+/// fails, the activation bytes are read from the title's download license.
+///
 /// # Returns (JSON)
 /// ```json
 /// {
@@ -594,6 +694,8 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetAct
         struct Params {
             locale_code: String,
             access_token: String,
+            account_json: Option<String>,
+            asin: Option<String>,
         }
 
         match (move || -> crate::Result<String> {
@@ -604,12 +706,20 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetAct
             let locale = crate::api::auth::Locale::from_country_code(&params.locale_code)
                 .ok_or_else(|| crate::LibationError::InvalidInput(format!("Invalid locale: {}", params.locale_code)))?;
 
-            let result = RUNTIME.block_on(async {
-                crate::api::auth::get_activation_bytes(&locale, &params.access_token).await
-            })?;
+            let activation_bytes = match (params.account_json, params.asin) {
+                (Some(account_json), Some(asin)) => {
+                    let account: crate::api::auth::Account = serde_json::from_str(&account_json)
+                        .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
+                    let client = crate::api::client::AudibleClient::new(account)?;
+                    RUNTIME.block_on(client.get_activation_bytes_with_fallback(&asin))?
+                }
+                _ => RUNTIME.block_on(async {
+                    crate::api::auth::get_activation_bytes(&locale, &params.access_token).await
+                })?.activation_bytes,
+            };
 
             let response = serde_json::json!({
-                "activation_bytes": result,
+                "activation_bytes": activation_bytes,
             });
 
             Ok(success_response(response))
@@ -632,6 +742,13 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetAct
 
 /// Synchronize library from Audible API
 ///
+/// If the account has moved to another marketplace, the sync uses the new
+/// marketplace and the response carries the migration together with the
+/// updated account, which the app should save.
+///
+/// Completed downloads are checked afterwards: damaged files are downloaded
+/// again, missing ones are only reported.
+///
 /// # Arguments (JSON string)
 /// ```json
 /// {
@@ -649,7 +766,11 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetAct
 ///     "books_added": 10,
 ///     "books_updated": 140,
 ///     "books_absent": 0,
-///     "errors": []
+///     "errors": [],
+///     "download_repairs": {"requeued": ["task-id"], "missing": [], "failed": []},
+///     // only after a marketplace migration:
+///     "marketplace_migration": {"from_marketplace_id": "AF2M0KC94RCEA", "to_marketplace_id": "A2CQZ5RBY40XE"},
+///     "account_json": "{...}"
 ///   }
 /// }
 /// ```
@@ -676,14 +797,31 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeSyncLi
             let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
                 .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
 
-            let result = RUNTIME.block_on(async {
+            let (stats, repairs, migration, account) = RUNTIME.block_on(async {
                 let db = crate::storage::Database::new(&params.db_path).await?;
+                let manager = get_or_create_manager(&params.db_path).await?;
 
                 let mut client = crate::api::client::AudibleClient::new(account.clone())?;
+                let migration = client.apply_marketplace_migration().await.unwrap_or_else(|e| {
+                    eprintln!("Warning: could not check for a marketplace migration: {}", e);
+                    None
+                });
 
-                client.sync_library(&db, &account).await
+                let (stats, repairs) = manager.sync_library(&mut client, &db, &account).await?;
+                let account = client.account().lock().await.clone();
+                Ok::<_, crate::LibationError>((stats, repairs, migration, account))
             })?;
 
+            let mut result = serde_json::to_value(&stats)
+                .map_err(|e| crate::LibationError::InternalError(format!("Failed to encode sync result: {}", e)))?;
+            result["download_repairs"] = serde_json::json!(repairs);
+            if let Some(migration) = migration {
+                let account_json = serde_json::to_string(&account)
+                    .map_err(|e| crate::LibationError::InternalError(format!("Failed to encode account: {}", e)))?;
+                result["marketplace_migration"] = serde_json::json!(migration);
+                result["account_json"] = serde_json::json!(account_json);
+            }
+
             Ok(success_response(result))
         })() {
             Ok(result) => result,
@@ -720,7 +858,8 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeSyncLi
 ///     "books_updated": 40,
 ///     "books_absent": 0,
 ///     "errors": [],
-///     "has_more": true
+///     "has_more": true,
+///     "download_repairs": {...}  // last page only, see nativeSyncLibrary
 ///   }
 /// }
 /// ```
@@ -760,7 +899,14 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeSyncLi
 
                 let mut client = crate::api::client::AudibleClient::new(account.clone())?;
 
-                client.sync_library_page(&db, &account, params.page).await
+                let stats = client.sync_library_page(&db, &account, params.page).await?;
+                let mut result = serde_json::to_value(&stats)
+                    .map_err(|e| crate::LibationError::InternalError(format!("Failed to encode sync result: {}", e)))?;
+                if !stats.has_more {
+                    let repairs = get_or_create_manager(&params.db_path).await?.repair_after_sync(&client).await;
+                    result["download_repairs"] = serde_json::json!(repairs);
+                }
+                Ok::<_, crate::LibationError>(result)
             })?;
 
             Ok(success_response(result))
@@ -829,7 +975,7 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetBoo
                         "audible_product_id": book.audible_product_id,
                         "title": book.title,
                         "subtitle": book.subtitle,
-                        "description": book.description,
+                        "description": crate::api::description::html_to_text(&book.description),
                         "duration_seconds": book.length_in_minutes * 60,
                         "language": book.language,
                         "rating": book.rating_overall,
@@ -927,7 +1073,7 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetBoo
                         "audible_product_id": book.audible_product_id,
                         "title": book.title,
                         "subtitle": book.subtitle,
-                        "description": book.description,
+                        "description": crate::api::description::html_to_text(&book.description),
                         "duration_seconds": book.length_in_minutes * 60,
                         "language": book.language,
                         "rating": book.rating_overall,
@@ -1137,7 +1283,7 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetBoo
                         "audible_product_id": book.audible_product_id,
                         "title": book.title,
                         "subtitle": book.subtitle,
-                        "description": book.description,
+                        "description": crate::api::description::html_to_text(&book.description),
                         "duration_seconds": book.length_in_minutes * 60,
                         "language": book.language,
                         "rating": book.rating_overall,
@@ -1308,45 +1454,14 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetAll
         .into_raw()
 }
 
-// ============================================================================
-// DOWNLOAD FUNCTIONS
-// ============================================================================
-
-/// Download audiobook file
-///
-/// # Arguments (JSON string)
-/// ```json
-/// {
-///   "asin": "B012345678",
-///   "access_token": "...",
-///   "locale_code": "us",
-///   "output_path": "/storage/emulated/0/Download/book.aax"
-/// }
-/// ```
+/// Get storage and usage figures for the settings screen
 ///
-/// # Returns (JSON)
-/// ```json
-/// {
-///   "success": true,
-///   "data": {
-///     "bytes_downloaded": 123456789,
-///     "output_path": "/storage/emulated/0/Download/book.aax"
-///   }
-/// }
-/// ```
-
-// ============================================================================
-// DECRYPTION FUNCTIONS
-// ============================================================================
-
-/// Decrypt AAX file to M4B using activation bytes
+/// Read from the database only; the library folder is not scanned.
 ///
 /// # Arguments (JSON string)
 /// ```json
 /// {
-///   "input_path": "/storage/emulated/0/Download/book.aax",
-///   "output_path": "/storage/emulated/0/Download/book.m4b",
-///   "activation_bytes": "1CEB00DA"
+///   "db_path": "/data/data/.../libation.db"
 /// }
 /// ```
 ///
@@ -1355,13 +1470,17 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetAll
 /// {
 ///   "success": true,
 ///   "data": {
-///     "output_path": "/storage/emulated/0/Download/book.m4b",
-///     "file_size": 123456789
+///     "books_in_library": 412,
+///     "books_downloaded": 37,
+///     "downloaded_bytes": 5123456789,
+///     "cover_cache_bytes": 2048000,
+///     "database_bytes": 9437184,
+///     "last_sync": "2024-03-01T12:00:00Z"
 ///   }
 /// }
 /// ```
 #[no_mangle]
-pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDecryptAAX(
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetUsageMetrics(
     mut env: JNIEnv,
     _class: JClass,
     params_json: JString,
@@ -1371,9 +1490,7 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDecryp
     let response = catch_panic(move || {
         #[derive(Deserialize)]
         struct Params {
-            input_path: String,
-            output_path: String,
-            activation_bytes: String,
+            db_path: String,
         }
 
         match (move || -> crate::Result<String> {
@@ -1381,30 +1498,12 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDecryp
             let params: Params = serde_json::from_str(&params_str)
                 .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
 
-            let activation_bytes = crate::crypto::activation::ActivationBytes::from_hex(&params.activation_bytes)?;
-
-            let result = RUNTIME.block_on(async {
-                let decrypter = crate::crypto::aax::AaxDecrypter::new(activation_bytes);
-
-                let input_path = std::path::Path::new(&params.input_path);
-                let output_path = std::path::Path::new(&params.output_path);
-
-                decrypter.decrypt_file(input_path, output_path).await?;
-
-                let file_size = tokio::fs::metadata(output_path)
-                    .await
-                    .map(|m| m.len())
-                    .unwrap_or(0);
-
-                let response = serde_json::json!({
-                    "output_path": params.output_path,
-                    "file_size": file_size,
-                });
-
-                Ok::<_, crate::LibationError>(response)
+            let metrics = RUNTIME.block_on(async {
+                let db = crate::storage::Database::new(&params.db_path).await?;
+                db.usage_metrics().await
             })?;
 
-            Ok(success_response(result))
+            Ok(success_response(serde_json::json!(metrics)))
         })() {
             Ok(result) => result,
             Err(e) => error_response(&e.to_string()),
@@ -1416,16 +1515,17 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDecryp
         .into_raw()
 }
 
-// ============================================================================
-// DATABASE FUNCTIONS
-// ============================================================================
-
-/// Initialize database at specified path
+/// Find which of a list of ASINs are in the user's library
+///
+/// Meant for catalog browsing ("owned" badges). Uses the synced library in
+/// the database; the API is only asked when no library has been synced yet.
 ///
 /// # Arguments (JSON string)
 /// ```json
 /// {
-///   "db_path": "/data/data/.../libation.db"
+///   "accountJson": "{...}",
+///   "db_path": "/data/data/.../libation.db",
+///   "asins": ["B001", "B002"]
 /// }
 /// ```
 ///
@@ -1434,12 +1534,12 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDecryp
 /// {
 ///   "success": true,
 ///   "data": {
-///     "initialized": true
+///     "owned": ["B001"]
 ///   }
 /// }
 /// ```
 #[no_mangle]
-pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeInitDatabase(
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetOwnedAsins(
     mut env: JNIEnv,
     _class: JClass,
     params_json: JString,
@@ -1449,7 +1549,10 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeInitDa
     let response = catch_panic(move || {
         #[derive(Deserialize)]
         struct Params {
+            #[serde(rename = "accountJson")]
+            account_json: String,
             db_path: String,
+            asins: Vec<String>,
         }
 
         match (move || -> crate::Result<String> {
@@ -1457,17 +1560,17 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeInitDa
             let params: Params = serde_json::from_str(&params_str)
                 .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
 
-            let result = RUNTIME.block_on(async {
-                let _db = crate::storage::Database::new(&params.db_path).await?;
-
-                let response = serde_json::json!({
-                    "initialized": true,
-                });
-
-                Ok::<_, crate::LibationError>(response)
+            let owned = RUNTIME.block_on(async {
+                let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
+                    .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
+                let db = crate::storage::Database::new(&params.db_path).await?;
+                let client = crate::api::client::AudibleClient::new(account)?;
+                client.owned_asins(&db, &params.asins).await
             })?;
 
-            Ok(success_response(result))
+            // Keep the caller's order
+            let owned: Vec<&String> = params.asins.iter().filter(|asin| owned.contains(*asin)).collect();
+            Ok(success_response(serde_json::json!({ "owned": owned })))
         })() {
             Ok(result) => result,
             Err(e) => error_response(&e.to_string()),
@@ -1480,15 +1583,18 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeInitDa
 }
 
 // ============================================================================
-// UTILITY FUNCTIONS
+// DOWNLOAD FUNCTIONS
 // ============================================================================
 
-/// Validate activation bytes format
+/// Download audiobook file
 ///
 /// # Arguments (JSON string)
 /// ```json
 /// {
-///   "activation_bytes": "1CEB00DA"
+///   "asin": "B012345678",
+///   "access_token": "...",
+///   "locale_code": "us",
+///   "output_path": "/storage/emulated/0/Download/book.aax"
 /// }
 /// ```
 ///
@@ -1497,12 +1603,59 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeInitDa
 /// {
 ///   "success": true,
 ///   "data": {
-///     "valid": true
+///     "bytes_downloaded": 123456789,
+///     "output_path": "/storage/emulated/0/Download/book.aax"
+///   }
+/// }
+/// ```
+
+// ============================================================================
+// DECRYPTION FUNCTIONS
+// ============================================================================
+
+/// Decrypt AAX file to M4B using activation bytes
+///
+/// With `db_path`, the manager's download settings also apply: the book is
+/// converted to their output format, the brand intro trimmed and chapters
+/// split as configured (see `DownloadSettings::conversion_options`).
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "input_path": "/storage/emulated/0/Download/book.aax",
+///   "output_path": "/storage/emulated/0/Download/book.m4b",
+///   "activation_bytes": "1CEB00DA",
+///   "cover_path": "/storage/emulated/0/Download/book.jpg",  // optional
+///   "db_path": "/data/data/.../libation.db",  // optional, shares the manager's decrypt limit and settings
+///   "metadata": { "title": "...", "authors": ["..."], ... },  // optional, AudioMetadata tags to write
+///   "delete_encrypted": false,  // optional, default false; true deletes input_path after decrypting
+///   "encrypted_backup_dir": "/storage/emulated/0/Audible/originals",  // optional, where kept files go
+///   "chapter_info": { "chapters": [...], "runtimeLengthMs": 0 },  // optional, license ChapterInfo
+///   "chapter_source": "Api"  // optional, "Api" (default) or "Embedded"
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "output_path": "/storage/emulated/0/Download/book.m4b",
+///     "file_size": 123456789,
+///     "audiobook": {
+///       "path": "/storage/emulated/0/Download/book.m4b",
+///       "format": "M4b",
+///       "size": 123456789,
+///       "duration": 9783.3,
+///       "chapters": [{ "title": "Chapter 1", "start_ms": 0, "end_ms": 1800250 }],
+///       "cover_path": "/storage/emulated/0/Download/book.jpg"
+///     },
+///     "chapter_files": []  // one path per chapter when the settings split chapters
 ///   }
 /// }
 /// ```
 #[no_mangle]
-pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeValidateActivationBytes(
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDecryptAAX(
     mut env: JNIEnv,
     _class: JClass,
     params_json: JString,
@@ -1512,7 +1665,18 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeValida
     let response = catch_panic(move || {
         #[derive(Deserialize)]
         struct Params {
+            input_path: String,
+            output_path: String,
             activation_bytes: String,
+            cover_path: Option<String>,
+            db_path: Option<String>,
+            metadata: Option<crate::audio::metadata::AudioMetadata>,
+            #[serde(default)]
+            delete_encrypted: bool,
+            encrypted_backup_dir: Option<String>,
+            chapter_info: Option<crate::api::content::ChapterInfo>,
+            #[serde(default)]
+            chapter_source: crate::audio::metadata::ChapterSource,
         }
 
         match (move || -> crate::Result<String> {
@@ -1520,13 +1684,71 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeValida
             let params: Params = serde_json::from_str(&params_str)
                 .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
 
-            let valid = crate::crypto::activation::ActivationBytes::from_hex(&params.activation_bytes).is_ok();
+            let activation_bytes = crate::crypto::activation::ActivationBytes::from_hex(&params.activation_bytes)?;
 
-            let response = serde_json::json!({
-                "valid": valid,
-            });
+            let result = RUNTIME.block_on(async {
+                let manager = match params.db_path {
+                    Some(ref db_path) => Some(get_or_create_manager(db_path).await?),
+                    None => None,
+                };
+                let conversion = match &manager {
+                    Some(manager) => manager.settings().await.conversion_options(params.chapter_info.as_ref()),
+                    None => None,
+                };
 
-            Ok(success_response(response))
+                let mut decrypter = crate::crypto::aax::AaxDecrypter::new(activation_bytes)
+                    .delete_encrypted_after(params.delete_encrypted);
+                if let Some(backup_dir) = params.encrypted_backup_dir {
+                    decrypter = decrypter.with_encrypted_backup_dir(backup_dir.into());
+                }
+                if let Some(chapter_info) = params.chapter_info {
+                    decrypter = decrypter.with_chapters(chapter_info, params.chapter_source);
+                }
+
+                let input_path = std::path::Path::new(&params.input_path);
+                let output_path = std::path::Path::new(&params.output_path);
+
+                let cover_path = params.cover_path.map(std::path::PathBuf::from);
+
+                // A converted book is decrypted to an M4B of its own first
+                let decrypted_path = match conversion {
+                    Some(_) => output_path.with_extension("decrypted.m4b"),
+                    None => output_path.to_path_buf(),
+                };
+                let job = async {
+                    let audiobook = decrypter
+                        .decrypt_audiobook(input_path, &decrypted_path, params.metadata.as_ref(), cover_path.clone())
+                        .await?;
+                    let Some(conversion) = &conversion else {
+                        return Ok((audiobook, Vec::new()));
+                    };
+
+                    let converted = crate::crypto::dispatch::convert_decrypted(&decrypted_path, output_path, conversion).await;
+                    let _ = tokio::fs::remove_file(&decrypted_path).await;
+                    let chapter_files = converted?;
+                    crate::file::post_write::notify_file_written(output_path).await;
+                    for chapter_file in &chapter_files {
+                        crate::file::post_write::notify_file_written(chapter_file).await;
+                    }
+                    let audiobook = crate::audio::decoder::AudiobookFile::from_file(output_path, cover_path.clone()).await?;
+                    Ok::<_, crate::LibationError>((audiobook, chapter_files))
+                };
+                let (audiobook, chapter_files) = match &manager {
+                    Some(manager) => manager.run_decrypt(job).await?,
+                    None => job.await?,
+                };
+
+                let response = serde_json::json!({
+                    "output_path": params.output_path,
+                    "file_size": audiobook.size,
+                    "audiobook": audiobook,
+                    "chapter_files": chapter_files,
+                });
+
+                Ok::<_, crate::LibationError>(response)
+            })?;
+
+            Ok(success_response(result))
         })() {
             Ok(result) => result,
             Err(e) => error_response(&e.to_string()),
@@ -1538,12 +1760,285 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeValida
         .into_raw()
 }
 
-/// Get list of supported locales
-///
-/// # Arguments (JSON string)
-/// ```json
-/// {}
-/// ```
+// ============================================================================
+// OUTPUT FILE HOOKS
+// ============================================================================
+
+/// Forwards finished files to a Java listener's
+/// `void onFileWritten(String path, String mimeType)`
+struct JavaPostWriteHook {
+    vm: jni::JavaVM,
+    listener: jni::objects::GlobalRef,
+}
+
+impl JavaPostWriteHook {
+    fn call_listener(&self, env: &mut JNIEnv, path: &str, mime_type: &str) -> jni::errors::Result<()> {
+        let path = env.new_string(path)?;
+        let mime_type = env.new_string(mime_type)?;
+        env.call_method(
+            &self.listener,
+            "onFileWritten",
+            "(Ljava/lang/String;Ljava/lang/String;)V",
+            &[jni::objects::JValue::Object(&path), jni::objects::JValue::Object(&mime_type)],
+        )?;
+        Ok(())
+    }
+}
+
+impl crate::file::post_write::PostWriteHook for JavaPostWriteHook {
+    fn file_written(&self, path: String, mime_type: String) {
+        let mut env = match self.vm.attach_current_thread() {
+            Ok(env) => env,
+            Err(e) => {
+                eprintln!("Post-write hook: cannot attach to the JVM: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = self.call_listener(&mut env, &path, &mime_type) {
+            eprintln!("Post-write hook failed for {}: {}", path, e);
+            // A pending Java exception would abort the next JNI call on this thread
+            if env.exception_check().unwrap_or(false) {
+                let _ = env.exception_clear();
+            }
+        }
+    }
+}
+
+/// Register the listener told about every finished book
+///
+/// The listener must have a `void onFileWritten(String path, String mimeType)`
+/// method; a typical implementation calls `MediaScannerConnection.scanFile`
+/// so the book shows up in the device's media library. It is called from a
+/// background thread. Pass `null` to remove it.
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "registered": true
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeSetPostWriteHook(
+    mut env: JNIEnv,
+    _class: JClass,
+    listener: jni::objects::JObject,
+) -> jstring {
+    let result = if listener.is_null() {
+        crate::file::post_write::set_post_write_hook(None);
+        Ok(false)
+    } else {
+        env.get_java_vm()
+            .and_then(|vm| Ok((vm, env.new_global_ref(&listener)?)))
+            .map(|(vm, listener)| {
+                crate::file::post_write::set_post_write_hook(Some(std::sync::Arc::new(JavaPostWriteHook { vm, listener })));
+                true
+            })
+    };
+
+    let response = match result {
+        Ok(registered) => success_response(serde_json::json!({ "registered": registered })),
+        Err(e) => error_response(&format!("Failed to register post-write hook: {}", e)),
+    };
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Set the file modes applied to every finished book
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "file_mode": "644",  // optional, octal
+///   "dir_mode": "755"    // optional, octal; applied to the book's folder
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "file_mode": 420,
+///     "dir_mode": 493
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeSetOutputPermissions(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            #[serde(default)]
+            file_mode: Option<String>,
+            #[serde(default)]
+            dir_mode: Option<String>,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let permissions = crate::file::post_write::OutputPermissions {
+                file_mode: params.file_mode.as_deref().map(crate::file::post_write::parse_mode).transpose()?,
+                dir_mode: params.dir_mode.as_deref().map(crate::file::post_write::parse_mode).transpose()?,
+            };
+            crate::file::post_write::set_output_permissions(permissions);
+
+            Ok(success_response(serde_json::json!({
+                "file_mode": permissions.file_mode,
+                "dir_mode": permissions.dir_mode,
+            })))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+// ============================================================================
+// DATABASE FUNCTIONS
+// ============================================================================
+
+/// Initialize database at specified path
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "db_path": "/data/data/.../libation.db"
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "initialized": true
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeInitDatabase(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            db_path: String,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let result = RUNTIME.block_on(async {
+                let _db = crate::storage::Database::new(&params.db_path).await?;
+
+                let response = serde_json::json!({
+                    "initialized": true,
+                });
+
+                Ok::<_, crate::LibationError>(response)
+            })?;
+
+            Ok(success_response(result))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+// ============================================================================
+// UTILITY FUNCTIONS
+// ============================================================================
+
+/// Validate activation bytes format
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "activation_bytes": "1CEB00DA"
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "valid": true
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeValidateActivationBytes(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            activation_bytes: String,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let valid = crate::crypto::activation::ActivationBytes::from_hex(&params.activation_bytes).is_ok();
+
+            let response = serde_json::json!({
+                "valid": valid,
+            });
+
+            Ok(success_response(response))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Get list of supported locales
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {}
+/// ```
 ///
 /// # Returns (JSON)
 /// ```json
@@ -1551,7 +2046,7 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeValida
 ///   "success": true,
 ///   "data": {
 ///     "locales": [
-///       {"country_code": "us", "name": "United States", "domain": "audible.com"},
+///       {"country_code": "us", "name": "United States", "domain": "audible.com", "language": "en-US", "currency": "USD"},
 ///       ...
 ///     ]
 ///   }
@@ -1585,7 +2080,9 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetSup
 /// {
 ///   "db_path": "/data/data/.../libation.db",
 ///   "asin": "B07T2F8VJM",
-///   "naming_pattern": "author_series_book"  // or "flat_file", "author_book_folder"
+///   "naming_pattern": "author_series_book",  // or "flat_file", "author_book_folder"
+///   "missing_names": { "author": "Podcasts" },  // optional, for books without names
+///   "filesystem_limits": { "max_component_bytes": 143 }  // optional, defaults to the platform's
 /// }
 /// ```
 ///
@@ -1612,6 +2109,10 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeBuildF
             db_path: String,
             asin: String,
             naming_pattern: String,
+            #[serde(default)]
+            missing_names: crate::file::paths::MissingNames,
+            #[serde(default)]
+            filesystem_limits: crate::file::paths::FilesystemLimits,
         }
 
         match (move || -> crate::Result<String> {
@@ -1633,7 +2134,13 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeBuildF
                     .unwrap_or(crate::file::paths::NamingPattern::AuthorSeriesBook);
 
                 // Build path
-                let file_path = crate::file::paths::build_file_path(&metadata, pattern, "m4b")?;
+                let file_path = crate::file::paths::build_file_path_with_limits(
+                    &metadata,
+                    pattern,
+                    "m4b",
+                    &params.missing_names,
+                    params.filesystem_limits,
+                )?;
 
                 Ok::<_, crate::LibationError>(serde_json::json!({
                     "file_path": file_path,
@@ -1750,33 +2257,35 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetCus
         .into_raw()
 }
 
-/// Download and decrypt an audiobook
+/// Get the user's library collections
 ///
-/// # Parameters
-/// JSON string with:
+/// # Arguments (JSON string)
 /// ```json
 /// {
-///   "accountJson": "{ ... }",  // Complete account JSON with identity
-///   "asin": "B07T2F8VJM",
-///   "outputDirectory": "/path/to/save",
-///   "quality": "High"  // "Low", "Normal", "High", "Extreme"
+///   "accountJson": "{...}",
+///   "include_items": true  // optional, also fetch each collection's books
 /// }
 /// ```
 ///
-/// # Returns
-/// JSON response:
+/// # Returns (JSON)
 /// ```json
 /// {
 ///   "success": true,
 ///   "data": {
-///     "outputPath": "/path/to/file.m4b",
-///     "fileSize": 148080000,
-///     "duration": 9783.3
+///     "collections": [{
+///       "collection_id": "amzn1.collection.xyz",
+///       "name": "Road trip",
+///       "description": null,
+///       "is_pinned": false,
+///       "creation_date": "2024-05-01T10:00:00Z",
+///       "is_builtin": false,
+///       "items": [{"asin": "B001", "added_date": "2024-05-01T10:00:00Z"}]  // with include_items
+///     }]
 ///   }
 /// }
 /// ```
 #[no_mangle]
-pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDownloadBook(
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetCollections(
     mut env: JNIEnv,
     _class: JClass,
     params_json: JString,
@@ -1788,12 +2297,8 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDownlo
         struct Params {
             #[serde(rename = "accountJson")]
             account_json: String,
-            asin: String,
-            #[serde(rename = "outputDirectory")]
-            output_directory: String,
-            quality: String,
-            #[serde(rename = "dbPath")]
-            db_path: Option<String>,
+            #[serde(default)]
+            include_items: bool,
         }
 
         match (move || -> crate::Result<String> {
@@ -1801,28 +2306,168 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDownlo
             let params: Params = serde_json::from_str(&params_str)
                 .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
 
-            let result = RUNTIME.block_on(async {
-                // TODO: Add ensure_valid_token() here once we pass db_path in params
-                // For now, WorkManager backup handles token refresh
-
-                // Parse account
+            let collections = RUNTIME.block_on(async {
                 let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
                     .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
-
-                // Parse quality
-                let quality = match params.quality.as_str() {
-                    "Low" => crate::api::content::DownloadQuality::Low,
-                    "Normal" => crate::api::content::DownloadQuality::Normal,
-                    "High" => crate::api::content::DownloadQuality::High,
-                    "Extreme" => crate::api::content::DownloadQuality::Extreme,
-                    _ => crate::api::content::DownloadQuality::High,
-                };
-
-                // Create client
                 let client = crate::api::client::AudibleClient::new(account)?;
 
-                // Get download license
-                let license = client.build_download_license(&params.asin, quality, false).await?;
+                let mut collections = Vec::new();
+                for collection in client.get_collections().await? {
+                    let mut entry = serde_json::json!(collection);
+                    entry["is_builtin"] = serde_json::json!(collection.is_builtin());
+                    if params.include_items {
+                        entry["items"] = serde_json::json!(client.get_collection_items(&collection.collection_id).await?);
+                    }
+                    collections.push(entry);
+                }
+                Ok::<_, crate::LibationError>(collections)
+            })?;
+
+            Ok(success_response(serde_json::json!({ "collections": collections })))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Get the books in one library collection
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "accountJson": "{...}",
+///   "collection_id": "amzn1.collection.xyz"
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "items": [{"asin": "B001", "added_date": "2024-05-01T10:00:00Z"}]
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetCollectionItems(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            #[serde(rename = "accountJson")]
+            account_json: String,
+            collection_id: String,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let items = RUNTIME.block_on(async {
+                let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
+                    .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
+                let client = crate::api::client::AudibleClient::new(account)?;
+
+                client.get_collection_items(&params.collection_id).await
+            })?;
+
+            Ok(success_response(serde_json::json!({ "items": items })))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Download and decrypt an audiobook
+///
+/// # Parameters
+/// JSON string with:
+/// ```json
+/// {
+///   "accountJson": "{ ... }",  // Complete account JSON with identity
+///   "asin": "B07T2F8VJM",
+///   "outputDirectory": "/path/to/save",
+///   "quality": "High"  // "Low", "Normal", "High", "Extreme"
+/// }
+/// ```
+///
+/// # Returns
+/// JSON response:
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "outputPath": "/path/to/file.m4b",
+///     "fileSize": 148080000,
+///     "duration": 9783.3
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDownloadBook(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            #[serde(rename = "accountJson")]
+            account_json: String,
+            asin: String,
+            #[serde(rename = "outputDirectory")]
+            output_directory: String,
+            quality: String,
+            #[serde(rename = "dbPath")]
+            db_path: Option<String>,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let result = RUNTIME.block_on(async {
+                // TODO: Add ensure_valid_token() here once we pass db_path in params
+                // For now, WorkManager backup handles token refresh
+
+                // Parse account
+                let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
+                    .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
+
+                // Parse quality
+                let quality = match params.quality.as_str() {
+                    "Low" => crate::api::content::DownloadQuality::Low,
+                    "Normal" => crate::api::content::DownloadQuality::Normal,
+                    "High" => crate::api::content::DownloadQuality::High,
+                    "Extreme" => crate::api::content::DownloadQuality::Extreme,
+                    _ => crate::api::content::DownloadQuality::High,
+                };
+
+                // Create client
+                let client = crate::api::client::AudibleClient::new(account)?;
+
+                // Get download license
+                let license = client.build_download_license(&params.asin, quality, false).await?;
 
                 // Extract AAXC keys
                 let (key_hex, iv_hex) = if let Some(ref keys) = license.decryption_keys {
@@ -1878,6 +2523,10 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDownlo
                     });
                 }
 
+                crate::download::validate::check_content_type(
+                    response.headers().get("content-type").and_then(|v| v.to_str().ok()),
+                )?;
+
                 use futures_util::StreamExt;
                 use tokio::io::AsyncWriteExt;
 
@@ -1885,12 +2534,21 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDownlo
                     .map_err(|e| crate::LibationError::internal(format!("Failed to create file {}: {}", encrypted_path, e)))?;
 
                 let mut stream = response.bytes_stream();
+                let mut check_body = true;
                 while let Some(chunk) = stream.next().await {
                     let chunk = chunk
                         .map_err(|e| crate::LibationError::NetworkError {
                             message: format!("Stream error: {}", e),
                             is_transient: true,
                         })?;
+                    if check_body {
+                        if let Err(e) = crate::download::validate::check_body_start(&chunk) {
+                            drop(file);
+                            let _ = tokio::fs::remove_file(&encrypted_path).await;
+                            return Err(e);
+                        }
+                        check_body = false;
+                    }
                     file.write_all(&chunk).await
                         .map_err(|e| crate::LibationError::internal(format!("Write failed: {}", e)))?;
                 }
@@ -1955,33 +2613,943 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDownlo
                     metadata: Option<BookMetadata>,
                 }
 
-                let metadata = book_metadata.map(|b| BookMetadata {
-                    title: b.title,
-                    subtitle: b.subtitle,
-                    authors: b.authors_str,
-                    narrators: b.narrators_str,
-                    publisher: b.publisher,
-                    series_name: b.series_name,
-                    series_sequence: b.series_sequence,
-                    description: Some(b.description),
-                    date_published: b.date_published,
-                    language: b.language,
-                    picture_large: b.picture_large,
-                    picture_id: b.picture_id,
-                    audible_asin: params.asin.clone(),
-                });
+                let metadata = book_metadata.map(|b| BookMetadata {
+                    title: b.title,
+                    subtitle: b.subtitle,
+                    authors: b.authors_str,
+                    narrators: b.narrators_str,
+                    publisher: b.publisher,
+                    series_name: b.series_name,
+                    series_sequence: b.series_sequence,
+                    description: Some(b.description),
+                    date_published: b.date_published,
+                    language: b.language,
+                    picture_large: b.picture_large,
+                    picture_id: b.picture_id,
+                    audible_asin: params.asin.clone(),
+                });
+
+                Ok::<_, crate::LibationError>(DownloadResultWithKeys {
+                    encrypted_path,
+                    output_path: decrypted_path,
+                    file_size: file_metadata.len(),
+                    aaxc_key: key_hex,
+                    aaxc_iv: iv_hex,
+                    metadata,
+                })
+            })?;
+
+            Ok(success_response(result))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+// ============================================================================
+// LICENSE FUNCTIONS
+// ============================================================================
+
+/// Get download license without downloading
+///
+/// This allows getting the license info (URL, keys, size) to enqueue in download manager
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "accountJson": "{ ... }",
+///   "asin": "B07T2F8VJM",
+///   "quality": "High",
+///   "qualityFallback": true,  // optional: step down to High/Normal if the tier is not offered
+///   "chapterTitlesLanguage": "de-DE",  // optional: falls back to the default titles
+///   "aacCodec": "xHE_AAC"  // optional: "AAC_LC" (default) or "xHE_AAC"
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "download_url": "https://...",
+///     "total_bytes": 72000000,
+///     "aaxc_key": "...",
+///     "aaxc_iv": "...",
+///     "request_headers": {"User-Agent": "..."},
+///     "codec": "AAC_LC",
+///     "codec_downgrade": "Requested XHeAac but the title was delivered as AacLc"  // null if the codec was delivered
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetDownloadLicense(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            #[serde(rename = "accountJson")]
+            account_json: String,
+            asin: String,
+            quality: String,
+            #[serde(rename = "qualityFallback", default)]
+            quality_fallback: bool,
+            #[serde(rename = "chapterTitlesLanguage", default)]
+            chapter_titles_language: Option<String>,
+            #[serde(rename = "aacCodec", default)]
+            aac_codec: Option<crate::api::content::Codec>,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let result = RUNTIME.block_on(async {
+                // TODO: Add ensure_valid_token() here once we pass db_path in params
+                // For now, WorkManager backup handles token refresh
+
+                let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
+                    .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
+
+                let quality = match params.quality.as_str() {
+                    "Low" => crate::api::content::DownloadQuality::Low,
+                    "Normal" => crate::api::content::DownloadQuality::Normal,
+                    "High" => crate::api::content::DownloadQuality::High,
+                    "Extreme" => crate::api::content::DownloadQuality::Extreme,
+                    _ => crate::api::content::DownloadQuality::High,
+                };
+
+                let config = crate::api::client::ClientConfig::builder()
+                    .chapter_titles_language(params.chapter_titles_language.clone())
+                    .build();
+                let client = crate::api::client::AudibleClient::with_config(account, config)?;
+                let aac_codec = params.aac_codec.unwrap_or(crate::api::content::Codec::AacLc);
+                let license = if params.quality_fallback {
+                    client.build_download_license_with_fallback(&params.asin, quality, false, aac_codec).await?
+                } else {
+                    client.build_download_license_with_codec(&params.asin, quality, false, aac_codec).await?
+                };
+
+                // Extract AAXC keys
+                let (key_hex, iv_hex) = if let Some(ref keys) = license.decryption_keys {
+                    if !keys.is_empty() && keys[0].key_part_1.len() == 16 {
+                        let key = keys[0].key_part_1.iter()
+                            .map(|b| format!("{:02x}", b))
+                            .collect::<String>();
+                        let iv = if let Some(ref iv_bytes) = keys[0].key_part_2 {
+                            iv_bytes.iter()
+                                .map(|b| format!("{:02x}", b))
+                                .collect::<String>()
+                        } else {
+                            return Err(crate::LibationError::InvalidInput("No IV in AAXC keys".to_string()));
+                        };
+                        (key, iv)
+                    } else {
+                        return Err(crate::LibationError::InvalidInput("Unsupported key format (only AAXC supported)".to_string()));
+                    }
+                } else {
+                    return Err(crate::LibationError::InvalidInput("No decryption keys in license".to_string()));
+                };
+
+                // Headers depend on the CDN the URL points at
+                let request_headers = crate::download::cdn::download_headers(&license.download_url);
+
+                // Get file size from HTTP HEAD request
+                let total_bytes = crate::download::probe_url_with_headers(&license.download_url, &request_headers)
+                    .await?
+                    .size
+                    .unwrap_or(0);
+
+                #[derive(Serialize)]
+                struct LicenseInfo {
+                    download_url: String,
+                    total_bytes: u64,
+                    aaxc_key: String,
+                    aaxc_iv: String,
+                    request_headers: std::collections::HashMap<String, String>,
+                    codec: Option<crate::api::content::Codec>,
+                    codec_downgrade: Option<String>,
+                }
+
+                Ok::<_, crate::LibationError>(LicenseInfo {
+                    codec: license.content_metadata.content_reference.as_ref().map(|r| r.codec),
+                    codec_downgrade: license.codec_downgrade.map(|d| d.to_string()),
+                    download_url: license.download_url,
+                    total_bytes,
+                    aaxc_key: key_hex,
+                    aaxc_iv: iv_hex,
+                    request_headers,
+                })
+            })?;
+
+            Ok(success_response(result))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Get a streaming license for in-app playback without downloading
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "accountJson": "{...}",
+///   "asin": "B07T2F8VJM",
+///   "quality": "High",
+///   "preferWidevine": false,  // optional; request a DASH stream
+///   "chapterTitlesLanguage": "de-DE"  // optional
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "stream_url": "https://...",
+///     "is_dash": false,
+///     "aaxc_key": "...",  // null for DASH streams
+///     "aaxc_iv": "...",
+///     "expires_at": "2025-01-01T12:50:00Z"
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetStreamingLicense(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            #[serde(rename = "accountJson")]
+            account_json: String,
+            asin: String,
+            quality: crate::api::content::DownloadQuality,
+            #[serde(rename = "preferWidevine", default)]
+            prefer_widevine: bool,
+            #[serde(rename = "chapterTitlesLanguage", default)]
+            chapter_titles_language: Option<String>,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let license = RUNTIME.block_on(async {
+                let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
+                    .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
+                let config = crate::api::client::ClientConfig::builder()
+                    .chapter_titles_language(params.chapter_titles_language.clone())
+                    .build();
+                let client = crate::api::client::AudibleClient::with_config(account, config)?;
+                client.build_streaming_license(&params.asin, params.quality, params.prefer_widevine).await
+            })?;
+
+            let key = license.decryption_keys.as_ref().and_then(|keys| keys.first());
+            let response = serde_json::json!({
+                "stream_url": license.stream_url,
+                "is_dash": license.is_dash,
+                "aaxc_key": key.map(|k| hex::encode(&k.key_part_1)),
+                "aaxc_iv": key.and_then(|k| k.key_part_2.as_ref()).map(hex::encode),
+                "expires_at": license.expires_at.to_rfc3339(),
+            });
+
+            Ok(success_response(response))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+// ============================================================================
+// DOWNLOAD MANAGER FUNCTIONS
+// ============================================================================
+
+/// Enqueue a download in the persistent download manager
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "db_path": "/data/data/.../libation.db",
+///   "asin": "B001",
+///   "title": "Book Title",
+///   "download_url": "https://...",
+///   "total_bytes": 10000000,
+///   "download_path": "/cache/B001.aax",
+///   "output_path": "/output/B001.m4b",
+///   "request_headers": {"User-Agent": "..."},
+///   "force": false,  // optional; re-download even if output_path exists
+///   "acr": "CR!...",  // optional; license content_reference.acr
+///   "content_version": "3",  // optional; license content_reference.version
+///   "quality": "High",  // optional; license licensed_quality
+///   "aac_codec": "AAC_LC"  // optional; license licensed_codec
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "task_id": "uuid-string"
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeEnqueueDownload(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            db_path: String,
+            asin: String,
+            title: String,
+            download_url: String,
+            total_bytes: u64,
+            download_path: String,
+            output_path: String,
+            request_headers: std::collections::HashMap<String, String>,
+            #[serde(default)]
+            force: bool,
+            #[serde(default)]
+            acr: Option<String>,
+            #[serde(default)]
+            content_version: Option<String>,
+            #[serde(default)]
+            quality: Option<crate::api::content::DownloadQuality>,
+            #[serde(default)]
+            aac_codec: Option<crate::api::content::Codec>,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let task_id = RUNTIME.block_on(async {
+                let manager = get_or_create_manager(&params.db_path).await?;
+
+                let task_id = manager.enqueue_download(
+                    params.asin,
+                    params.title,
+                    params.download_url,
+                    params.total_bytes,
+                    params.download_path,
+                    params.output_path,
+                    params.request_headers,
+                    params.force,
+                ).await?;
+
+                if let Some(acr) = &params.acr {
+                    let version = params.content_version.as_deref().unwrap_or_default();
+                    manager.set_content_reference(&task_id, acr, version).await?;
+                }
+                if let (Some(quality), Some(aac_codec)) = (params.quality, params.aac_codec) {
+                    manager.set_license_format(&task_id, quality, aac_codec).await?;
+                }
+                Ok::<_, crate::LibationError>(task_id)
+            })?;
+
+            let response = serde_json::json!({
+                "task_id": task_id,
+            });
+
+            Ok(success_response(response))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Set the download defaults used by `nativeEnqueueBook`
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "db_path": "/data/data/.../libation.db",
+///   "settings": {
+///     "quality": "High",            // Low | Normal | High | Extreme
+///     "quality_fallback": true,
+///     "output_format": "M4b",       // M4b | M4a | Mp3
+///     "naming_pattern": "author_series_book",
+///     "trim_intro": false,
+///     "split_chapters": false,
+///     "output_dir": "/storage/emulated/0/Audiobooks",
+///     "download_dir": "/data/data/.../cache",
+///     "storage_root": "/storage/emulated/0/LibriSync"  // optional; one tree per account under it
+///   }
+/// }
+/// ```
+/// Omitted settings keep their defaults.
+///
+/// # Returns (JSON)
+/// The settings now in effect, with defaults filled in
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeSetDownloadSettings(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            db_path: String,
+            settings: crate::download::DownloadSettings,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let settings = RUNTIME.block_on(async {
+                let manager = get_or_create_manager(&params.db_path).await?;
+                manager.set_settings(params.settings).await?;
+                Ok::<_, crate::LibationError>(manager.settings().await)
+            })?;
+
+            Ok(success_response(settings))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Enqueue a library book using the download settings
+///
+/// License, size, paths and quality all come from the manager, so only the
+/// book is needed.
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "db_path": "/data/data/.../libation.db",
+///   "accountJson": "{...}",
+///   "asin": "B001",
+///   "force": false,  // optional
+///   "chapterTitlesLanguage": "de-DE"  // optional
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "task_id": "uuid-string"
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeEnqueueBook(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            db_path: String,
+            #[serde(rename = "accountJson")]
+            account_json: String,
+            asin: String,
+            #[serde(default)]
+            force: bool,
+            #[serde(rename = "chapterTitlesLanguage", default)]
+            chapter_titles_language: Option<String>,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let task_id = RUNTIME.block_on(async {
+                let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
+                    .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
+                let config = crate::api::client::ClientConfig::builder()
+                    .chapter_titles_language(params.chapter_titles_language.clone())
+                    .build();
+                let client = crate::api::client::AudibleClient::with_config(account, config)?;
+                let manager = get_or_create_manager(&params.db_path).await?;
+
+                manager.enqueue_book(&client, &params.asin, params.force).await
+            })?;
+
+            let response = serde_json::json!({
+                "task_id": task_id,
+            });
+
+            Ok(success_response(response))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Enqueue a library book, one task per part for multi-part books
+///
+/// Like `nativeEnqueueBook`, but books Audible delivers as several files get
+/// a task for each part. Decrypt every part to its task's `output_path`, then
+/// pass `data` to `nativeFinishBookParts`.
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "db_path": "/data/data/.../libation.db",
+///   "accountJson": "{...}",
+///   "asin": "B001",
+///   "force": false  // optional
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "asin": "B001",
+///     "part_asins": ["B001P1", "B001P2"],
+///     "task_ids": ["uuid-1", "uuid-2"],
+///     "joined_path": "/storage/.../Book.m4b"  // null when parts are kept separate
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeEnqueueBookParts(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            db_path: String,
+            #[serde(rename = "accountJson")]
+            account_json: String,
+            asin: String,
+            #[serde(default)]
+            force: bool,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let download = RUNTIME.block_on(async {
+                let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
+                    .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
+                let client = crate::api::client::AudibleClient::new(account)?;
+                let manager = get_or_create_manager(&params.db_path).await?;
+
+                manager.enqueue_book_parts(&client, &params.asin, params.force).await
+            })?;
+
+            Ok(success_response(download))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Join or keep the decrypted parts of a book enqueued with `nativeEnqueueBookParts`
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "db_path": "/data/data/.../libation.db",
+///   "download": { ... }  // data returned by nativeEnqueueBookParts
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "files": ["/storage/.../Book.m4b"]
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeFinishBookParts(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            db_path: String,
+            download: crate::download::MultiPartDownload,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let files = RUNTIME.block_on(async {
+                let manager = get_or_create_manager(&params.db_path).await?;
+                manager.finish_book_parts(&params.download).await
+            })?;
+
+            let response = serde_json::json!({
+                "files": files,
+            });
+
+            Ok(success_response(response))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Check downloaded books for a newer master
+///
+/// Run after a library sync. Pass an entry's `task_id` to
+/// `nativeRedownloadMaster` to fetch the new audio.
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "db_path": "/data/data/.../libation.db",
+///   "accountJson": "{...}"
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "updates": [{
+///       "task_id": "uuid-string",
+///       "asin": "B001",
+///       "title": "Book Title",
+///       "downloaded_acr": "CR!OLD",
+///       "downloaded_version": "1",
+///       "current_acr": "CR!NEW",
+///       "current_version": "2"
+///     }]
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeCheckForUpdatedMasters(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            db_path: String,
+            #[serde(rename = "accountJson")]
+            account_json: String,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let updates = RUNTIME.block_on(async {
+                let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
+                    .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
+                let client = crate::api::client::AudibleClient::new(account)?;
+                let manager = get_or_create_manager(&params.db_path).await?;
+
+                manager.find_updated_masters(&client).await
+            })?;
+
+            Ok(success_response(serde_json::json!({ "updates": updates })))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Replace a downloaded book with its newer master
+///
+/// Downloads the book again at the quality from the download settings. The
+/// old book is kept until the new download is decrypted over it with the
+/// returned key.
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "db_path": "/data/data/.../libation.db",
+///   "accountJson": "{...}",
+///   "task_id": "uuid-string"
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "task_id": "uuid-string",
+///     "aaxc_key": "...",  // null unless the new download is AAXC
+///     "aaxc_iv": "..."
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeRedownloadMaster(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            db_path: String,
+            #[serde(rename = "accountJson")]
+            account_json: String,
+            task_id: String,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let keys = RUNTIME.block_on(async {
+                let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
+                    .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
+                let client = crate::api::client::AudibleClient::new(account)?;
+                let manager = get_or_create_manager(&params.db_path).await?;
+                let quality = manager.settings().await.quality;
+
+                manager.repair_download(&params.task_id, &client, quality).await
+            })?;
+
+            let key = keys.as_ref().and_then(|keys| keys.first()).filter(|k| k.key_part_2.is_some());
+            Ok(success_response(serde_json::json!({
+                "task_id": params.task_id,
+                "aaxc_key": key.map(|k| hex::encode(&k.key_part_1)),
+                "aaxc_iv": key.and_then(|k| k.key_part_2.as_ref()).map(hex::encode),
+            })))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Re-tag and rename downloaded books after the settings changed
+///
+/// Nothing is downloaded again. Without `asin`, every downloaded book is
+/// reprocessed and failures are skipped.
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "db_path": "/data/data/.../libation.db",
+///   "asin": "B001"  // optional
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "output_path": "/storage/.../Author/Title/Title.m4b",  // single book
+///     "reprocessed": ["B001"]
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeReprocessBooks(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            db_path: String,
+            #[serde(default)]
+            asin: Option<String>,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let response = RUNTIME.block_on(async {
+                let manager = get_or_create_manager(&params.db_path).await?;
+                let response = match params.asin {
+                    Some(asin) => {
+                        let output_path = manager.reprocess(&asin).await?;
+                        serde_json::json!({ "output_path": output_path, "reprocessed": [asin] })
+                    }
+                    None => serde_json::json!({ "reprocessed": manager.reprocess_library().await? }),
+                };
+                Ok::<_, crate::LibationError>(response)
+            })?;
+
+            Ok(success_response(response))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
+/// Enqueue several downloads, recording failures instead of aborting
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "db_path": "/data/data/.../libation.db",
+///   "requests": [
+///     {"asin": "B001", "title": "...", "download_url": "https://...", "total_bytes": 10000000,
+///      "download_path": "/cache/B001.aax", "output_path": "/output/B001.m4b", "request_headers": {}}
+///   ],
+///   "failures": [{"asin": "B002", "title": "...", "reason": "License denied"}],  // optional
+///   "force": false  // optional
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// The batch, to pass back to `nativeGetBatchSummary`:
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "entries": [{"asin": "B001", "title": "...", "task_id": "uuid", "skipped": null, "error": null}]
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeEnqueueBatch(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Failure {
+            asin: String,
+            title: String,
+            reason: String,
+        }
+
+        #[derive(Deserialize)]
+        struct Params {
+            db_path: String,
+            requests: Vec<crate::download::DownloadRequest>,
+            #[serde(default)]
+            failures: Vec<Failure>,
+            #[serde(default)]
+            force: bool,
+        }
 
-                Ok::<_, crate::LibationError>(DownloadResultWithKeys {
-                    encrypted_path,
-                    output_path: decrypted_path,
-                    file_size: file_metadata.len(),
-                    aaxc_key: key_hex,
-                    aaxc_iv: iv_hex,
-                    metadata,
-                })
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let mut batch = RUNTIME.block_on(async {
+                let manager = get_or_create_manager(&params.db_path).await?;
+                Ok::<_, crate::LibationError>(manager.enqueue_batch(params.requests, params.force).await)
             })?;
+            for failure in params.failures {
+                batch.record_failure(failure.asin, failure.title, failure.reason);
+            }
 
-            Ok(success_response(result))
+            Ok(success_response(batch))
         })() {
             Ok(result) => result,
             Err(e) => error_response(&e.to_string()),
@@ -1993,20 +3561,18 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDownlo
         .into_raw()
 }
 
-// ============================================================================
-// LICENSE FUNCTIONS
-// ============================================================================
-
-/// Get download license without downloading
+/// Estimate the space a set of books will take before downloading them
 ///
-/// This allows getting the license info (URL, keys, size) to enqueue in download manager
+/// Each book is licensed at the configured quality and its download sized
+/// with a HEAD request. Books that cannot be sized are reported with the
+/// reason and left out of `total_bytes`.
 ///
 /// # Arguments (JSON string)
 /// ```json
 /// {
-///   "accountJson": "{ ... }",
-///   "asin": "B07T2F8VJM",
-///   "quality": "High"
+///   "db_path": "/data/data/.../libation.db",
+///   "accountJson": "{...}",
+///   "asins": ["B001", "B002"]
 /// }
 /// ```
 ///
@@ -2015,16 +3581,16 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeDownlo
 /// {
 ///   "success": true,
 ///   "data": {
-///     "download_url": "https://...",
-///     "total_bytes": 72000000,
-///     "aaxc_key": "...",
-///     "aaxc_iv": "...",
-///     "request_headers": {"User-Agent": "..."}
+///     "total_bytes": 4200000000,
+///     "books": [
+///       {"asin": "B001", "bytes": 4200000000, "error": null},
+///       {"asin": "B002", "bytes": null, "error": "License denied"}
+///     ]
 ///   }
 /// }
 /// ```
 #[no_mangle]
-pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetDownloadLicense(
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeEstimateBatchSize(
     mut env: JNIEnv,
     _class: JClass,
     params_json: JString,
@@ -2034,10 +3600,10 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetDow
     let response = catch_panic(move || {
         #[derive(Deserialize)]
         struct Params {
+            db_path: String,
             #[serde(rename = "accountJson")]
             account_json: String,
-            asin: String,
-            quality: String,
+            asins: Vec<String>,
         }
 
         match (move || -> crate::Result<String> {
@@ -2045,87 +3611,16 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetDow
             let params: Params = serde_json::from_str(&params_str)
                 .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
 
-            let result = RUNTIME.block_on(async {
-                // TODO: Add ensure_valid_token() here once we pass db_path in params
-                // For now, WorkManager backup handles token refresh
-
+            let estimate = RUNTIME.block_on(async {
                 let account: crate::api::auth::Account = serde_json::from_str(&params.account_json)
                     .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
-
-                let quality = match params.quality.as_str() {
-                    "Low" => crate::api::content::DownloadQuality::Low,
-                    "Normal" => crate::api::content::DownloadQuality::Normal,
-                    "High" => crate::api::content::DownloadQuality::High,
-                    "Extreme" => crate::api::content::DownloadQuality::Extreme,
-                    _ => crate::api::content::DownloadQuality::High,
-                };
-
                 let client = crate::api::client::AudibleClient::new(account)?;
-                let license = client.build_download_license(&params.asin, quality, false).await?;
-
-                // Extract AAXC keys
-                let (key_hex, iv_hex) = if let Some(ref keys) = license.decryption_keys {
-                    if !keys.is_empty() && keys[0].key_part_1.len() == 16 {
-                        let key = keys[0].key_part_1.iter()
-                            .map(|b| format!("{:02x}", b))
-                            .collect::<String>();
-                        let iv = if let Some(ref iv_bytes) = keys[0].key_part_2 {
-                            iv_bytes.iter()
-                                .map(|b| format!("{:02x}", b))
-                                .collect::<String>()
-                        } else {
-                            return Err(crate::LibationError::InvalidInput("No IV in AAXC keys".to_string()));
-                        };
-                        (key, iv)
-                    } else {
-                        return Err(crate::LibationError::InvalidInput("Unsupported key format (only AAXC supported)".to_string()));
-                    }
-                } else {
-                    return Err(crate::LibationError::InvalidInput("No decryption keys in license".to_string()));
-                };
-
-                // Build request headers
-                let mut request_headers = std::collections::HashMap::new();
-                request_headers.insert("User-Agent".to_string(), "Audible/671 CFNetwork/1240.0.4 Darwin/20.6.0".to_string());
-
-                // Get file size from HTTP HEAD request
-                let http_client = reqwest::Client::new();
-                let head_response = http_client
-                    .head(&license.download_url)
-                    .header("User-Agent", "Audible/671 CFNetwork/1240.0.4 Darwin/20.6.0")
-                    .send()
-                    .await
-                    .map_err(|e| crate::LibationError::NetworkError {
-                        message: format!("HEAD request failed: {}", e),
-                        is_transient: true,
-                    })?;
-
-                let total_bytes = head_response
-                    .headers()
-                    .get("content-length")
-                    .and_then(|v| v.to_str().ok())
-                    .and_then(|s| s.parse::<u64>().ok())
-                    .unwrap_or(0);
-
-                #[derive(Serialize)]
-                struct LicenseInfo {
-                    download_url: String,
-                    total_bytes: u64,
-                    aaxc_key: String,
-                    aaxc_iv: String,
-                    request_headers: std::collections::HashMap<String, String>,
-                }
+                let manager = get_or_create_manager(&params.db_path).await?;
 
-                Ok::<_, crate::LibationError>(LicenseInfo {
-                    download_url: license.download_url,
-                    total_bytes,
-                    aaxc_key: key_hex,
-                    aaxc_iv: iv_hex,
-                    request_headers,
-                })
+                Ok::<_, crate::LibationError>(manager.estimate_batch_size(&client, &params.asins).await)
             })?;
 
-            Ok(success_response(result))
+            Ok(success_response(estimate))
         })() {
             Ok(result) => result,
             Err(e) => error_response(&e.to_string()),
@@ -2137,23 +3632,13 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetDow
         .into_raw()
 }
 
-// ============================================================================
-// DOWNLOAD MANAGER FUNCTIONS
-// ============================================================================
-
-/// Enqueue a download in the persistent download manager
+/// Summarize a batch returned by `nativeEnqueueBatch`
 ///
 /// # Arguments (JSON string)
 /// ```json
 /// {
 ///   "db_path": "/data/data/.../libation.db",
-///   "asin": "B001",
-///   "title": "Book Title",
-///   "download_url": "https://...",
-///   "total_bytes": 10000000,
-///   "download_path": "/cache/B001.aax",
-///   "output_path": "/output/B001.m4b",
-///   "request_headers": {"User-Agent": "..."}
+///   "batch": {"entries": [...]}
 /// }
 /// ```
 ///
@@ -2162,12 +3647,13 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetDow
 /// {
 ///   "success": true,
 ///   "data": {
-///     "task_id": "uuid-string"
+///     "succeeded": 97, "failed": 3, "skipped": 0, "pending": 0,
+///     "items": [{"asin": "B001", "title": "...", "task_id": "uuid", "outcome": "failed", "reason": "HTTP 403"}]
 ///   }
 /// }
 /// ```
 #[no_mangle]
-pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeEnqueueDownload(
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetBatchSummary(
     mut env: JNIEnv,
     _class: JClass,
     params_json: JString,
@@ -2178,13 +3664,7 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeEnqueu
         #[derive(Deserialize)]
         struct Params {
             db_path: String,
-            asin: String,
-            title: String,
-            download_url: String,
-            total_bytes: u64,
-            download_path: String,
-            output_path: String,
-            request_headers: std::collections::HashMap<String, String>,
+            batch: crate::download::DownloadBatch,
         }
 
         match (move || -> crate::Result<String> {
@@ -2192,25 +3672,15 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeEnqueu
             let params: Params = serde_json::from_str(&params_str)
                 .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
 
-            let task_id = RUNTIME.block_on(async {
+            let summary = RUNTIME.block_on(async {
                 let manager = get_or_create_manager(&params.db_path).await?;
-
-                manager.enqueue_download(
-                    params.asin,
-                    params.title,
-                    params.download_url,
-                    params.total_bytes,
-                    params.download_path,
-                    params.output_path,
-                    params.request_headers,
-                ).await
+                manager.batch_summary(&params.batch).await
             })?;
+            if summary.is_finished() {
+                eprintln!("Batch finished: {}", summary);
+            }
 
-            let response = serde_json::json!({
-                "task_id": task_id,
-            });
-
-            Ok(success_response(response))
+            Ok(success_response(summary))
         })() {
             Ok(result) => result,
             Err(e) => error_response(&e.to_string()),
@@ -2284,6 +3754,65 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetDow
         .into_raw()
 }
 
+/// Get a throughput/concurrency report for the download manager
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "db_path": "/data/data/.../libation.db"
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "active_connections": 2,
+///     "queued": 5,
+///     "aggregate_bytes_per_sec": 1843200.0,
+///     "downloads": [...],
+///     ...
+///   }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeGetDownloadDiagnostics(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            db_path: String,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            let report = RUNTIME.block_on(async {
+                let manager = get_or_create_manager(&params.db_path).await?;
+                manager.diagnostics().await
+            })?;
+            eprintln!("{}", report);
+
+            Ok(success_response(report))
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
 /// List download tasks with optional filter
 ///
 /// # Arguments (JSON string)
@@ -2399,11 +3928,15 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativePauseD
 
 /// Resume a paused download
 ///
+/// Pass `accountJson` to request a new download URL first, e.g. when the
+/// download was paused for more than a day and its URL has expired.
+///
 /// # Arguments (JSON string)
 /// ```json
 /// {
 ///   "db_path": "/data/data/.../libation.db",
-///   "task_id": "uuid-string"
+///   "task_id": "uuid-string",
+///   "accountJson": "{...}"  // optional
 /// }
 /// ```
 #[no_mangle]
@@ -2419,6 +3952,8 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeResume
         struct Params {
             db_path: String,
             task_id: String,
+            #[serde(rename = "accountJson", default)]
+            account_json: Option<String>,
         }
 
         match (move || -> crate::Result<String> {
@@ -2428,6 +3963,12 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeResume
 
             RUNTIME.block_on(async {
                 let manager = get_or_create_manager(&params.db_path).await?;
+                if let Some(account_json) = &params.account_json {
+                    let account: crate::api::auth::Account = serde_json::from_str(account_json)
+                        .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid account JSON: {}", e)))?;
+                    let client = crate::api::client::AudibleClient::new(account)?;
+                    manager.refresh_task_url(&client, &params.task_id).await?;
+                }
                 manager.resume_download(&params.task_id).await
             })?;
 
@@ -2793,6 +4334,66 @@ pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeClearB
         .into_raw()
 }
 
+/// Log out an account and remove its local data
+///
+/// Deletes the stored credentials, cached library, cover thumbnails, download
+/// tasks and partial downloads for the account. See `Database::clear_account`.
+///
+/// # Arguments (JSON string)
+/// ```json
+/// {
+///   "db_path": "/data/data/.../audible.db",
+///   "account_id": "user@example.com"
+/// }
+/// ```
+///
+/// # Returns (JSON)
+/// ```json
+/// {
+///   "success": true,
+///   "data": { "files_removed": 3, "files_failed": 0 }
+/// }
+/// ```
+#[no_mangle]
+pub extern "C" fn Java_expo_modules_rustbridge_ExpoRustBridgeModule_nativeClearAccount(
+    mut env: JNIEnv,
+    _class: JClass,
+    params_json: JString,
+) -> jstring {
+    let params_str_result = jstring_to_string(&mut env, params_json);
+
+    let response = catch_panic(move || {
+        #[derive(Deserialize)]
+        struct Params {
+            db_path: String,
+            account_id: String,
+        }
+
+        match (move || -> crate::Result<String> {
+            let params_str = params_str_result?;
+            let params: Params = serde_json::from_str(&params_str)
+                .map_err(|e| crate::LibationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+
+            RUNTIME.block_on(async {
+                let db = crate::storage::Database::new(&params.db_path).await?;
+                let cleanup = db.clear_account(&params.account_id).await?;
+
+                Ok(success_response(serde_json::json!({
+                    "files_removed": cleanup.files_removed,
+                    "files_failed": cleanup.files_failed
+                })))
+            })
+        })() {
+            Ok(result) => result,
+            Err(e) => error_response(&e.to_string()),
+        }
+    });
+
+    env.new_string(response)
+        .expect("Failed to create Java string")
+        .into_raw()
+}
+
 /// Clear all library data (for testing)
 ///
 /// # Arguments (JSON string)
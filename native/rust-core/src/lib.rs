@@ -29,12 +29,14 @@ pub mod ios_bridge;
 
 // Core modules
 pub mod error;
+pub mod backoff;
 pub mod api;
 pub mod crypto;
 pub mod download;
 pub mod audio;
 pub mod storage;
 pub mod file;
+pub mod self_test;
 
 // Re-export commonly used types for convenience
 pub use error::{LibationError, Result};
@@ -0,0 +1,183 @@
+// LibriSync - Audible Library Sync for Mobile
+// Copyright (C) 2025 Henning Berge
+//
+// This program is a Rust port of Libation (https://github.com/rmcrackan/Libation)
+// Original work Copyright (C) Libation contributors
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+//! One-tap diagnostic for the native module
+//!
+//! # Reference C# Sources
+//! - None. Libation finds a broken install only when liberating a book fails
+//!
+//! When the app misbehaves on a device it is hard to tell whether the
+//! native library loaded correctly. [`self_test`] exercises the pieces the
+//! rest of the core depends on and returns a [`SelfTestReport`] with one
+//! entry per check instead of failing on the first problem:
+//!
+//! - `aes_cbc` - AES-128-CBC against a NIST test vector, then decrypted back
+//!   (the same primitive used for license vouchers)
+//! - `dates` - RFC 3339 parsing, time zone conversion and a sane system clock
+//! - `database` - Opening an in-memory SQLite database, running the
+//!   migrations, querying and closing it
+
+use crate::storage::Database;
+use chrono::{DateTime, Datelike, Utc};
+use serde::Serialize;
+use std::time::Instant;
+
+/// Outcome of one self-test check
+#[derive(Debug, Clone, Serialize, uniffi::Record)]
+pub struct SelfTestCheck {
+    /// Short identifier such as `aes_cbc`
+    pub name: String,
+    pub passed: bool,
+    /// What was verified, or what went wrong
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+/// Result of [`self_test`]
+#[derive(Debug, Clone, Serialize, uniffi::Record)]
+pub struct SelfTestReport {
+    /// Whether every check passed
+    pub passed: bool,
+    /// Version of the native core
+    pub version: String,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+/// Run every check and report the results
+///
+/// Blocks briefly for the database check, so call it off the UI thread.
+/// Nothing is written to disk and no network access is made.
+///
+/// # Example
+/// ```rust,no_run
+/// let report = rust_core::self_test::self_test();
+/// for check in report.checks.iter().filter(|c| !c.passed) {
+///     eprintln!("{} failed: {}", check.name, check.detail);
+/// }
+/// ```
+#[uniffi::export]
+pub fn self_test() -> SelfTestReport {
+    let checks = vec![
+        run_check("aes_cbc", check_aes_cbc),
+        run_check("dates", check_dates),
+        run_check("database", check_database),
+    ];
+
+    SelfTestReport {
+        passed: checks.iter().all(|c| c.passed),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        checks,
+    }
+}
+
+fn run_check(name: &str, check: fn() -> Result<String, String>) -> SelfTestCheck {
+    let started = Instant::now();
+    // A panic in a check is a failed check, not a crash of the host app
+    let result = std::panic::catch_unwind(check)
+        .unwrap_or_else(|_| Err("check panicked".to_string()));
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let (passed, detail) = match result {
+        Ok(detail) => (true, detail),
+        Err(detail) => (false, detail),
+    };
+    SelfTestCheck { name: name.to_string(), passed, detail, duration_ms }
+}
+
+/// NIST SP 800-38A F.2.1, first block
+fn check_aes_cbc() -> Result<String, String> {
+    use aes::Aes128;
+    use cbc::cipher::block_padding::NoPadding;
+    use cbc::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+
+    let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c").map_err(|e| e.to_string())?;
+    let iv = hex::decode("000102030405060708090a0b0c0d0e0f").map_err(|e| e.to_string())?;
+    let plaintext = hex::decode("6bc1bee22e409f96e93d7e117393172a").map_err(|e| e.to_string())?;
+    let expected = "7649abac8119b246cee98e9b12e9197d";
+
+    let mut buffer = plaintext.clone();
+    let ciphertext = cbc::Encryptor::<Aes128>::new_from_slices(&key, &iv)
+        .map_err(|e| format!("cipher setup failed: {}", e))?
+        .encrypt_padded_mut::<NoPadding>(&mut buffer, plaintext.len())
+        .map_err(|e| format!("encryption failed: {}", e))?;
+    if hex::encode(ciphertext) != expected {
+        return Err(format!("ciphertext {} does not match the test vector", hex::encode(ciphertext)));
+    }
+
+    let decrypted = cbc::Decryptor::<Aes128>::new_from_slices(&key, &iv)
+        .map_err(|e| format!("cipher setup failed: {}", e))?
+        .decrypt_padded_mut::<NoPadding>(&mut buffer)
+        .map_err(|e| format!("decryption failed: {}", e))?;
+    if decrypted != plaintext.as_slice() {
+        return Err("decrypted block differs from the plaintext".to_string());
+    }
+
+    Ok("AES-128-CBC matches the NIST vector and round-trips".to_string())
+}
+
+fn check_dates() -> Result<String, String> {
+    let parsed = DateTime::parse_from_rfc3339("2024-02-29T23:30:00-05:00")
+        .map_err(|e| format!("RFC 3339 parsing failed: {}", e))?;
+    let utc = parsed.with_timezone(&Utc);
+    if utc.to_rfc3339() != "2024-03-01T04:30:00+00:00" {
+        return Err(format!("time zone conversion gave {}", utc.to_rfc3339()));
+    }
+
+    let now = Utc::now();
+    if now.year() < 2024 {
+        return Err(format!("system clock reads {}, token expiry checks will fail", now.to_rfc3339()));
+    }
+
+    Ok(format!("parsing and conversion work; clock reads {}", now.to_rfc3339()))
+}
+
+fn check_database() -> Result<String, String> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("could not start runtime: {}", e))?;
+
+    runtime.block_on(async {
+        let db = Database::new_in_memory()
+            .await
+            .map_err(|e| format!("open/migrate failed: {}", e))?;
+        let books: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM Books")
+            .fetch_one(db.pool())
+            .await
+            .map_err(|e| format!("query failed: {}", e))?;
+        db.close().await.map_err(|e| format!("close failed: {}", e))?;
+        Ok(format!("opened, migrated, queried ({} books) and closed", books))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_test_passes() {
+        let report = self_test();
+        for check in &report.checks {
+            assert!(check.passed, "{}: {}", check.name, check.detail);
+        }
+        assert!(report.passed);
+        assert_eq!(report.checks.len(), 3);
+    }
+}
@@ -11,8 +11,9 @@
 //! Functions for saving and retrieving account data from SQLite.
 //! Accounts are stored as JSON in the database for flexibility.
 
-use crate::error::{LibationError, Result};
-use sqlx::SqlitePool;
+use crate::error::{LibationError, Result, StorageContext};
+use sqlx::{Row, SqlitePool};
+use std::path::PathBuf;
 
 /// Save or update account in database
 ///
@@ -77,7 +78,8 @@ pub async fn save_account(
     .bind(token_expires_at)
     .bind(decrypt_key)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context("saving account", None)?;
 
     Ok(())
 }
@@ -108,7 +110,8 @@ pub async fn get_account(
     )
     .bind(account_id)
     .fetch_optional(pool)
-    .await?;
+    .await
+    .storage_context("loading account", None)?;
 
     if let Some((acc_id, acc_name, locale_code, identity_json, decrypt_key)) = row {
         // Parse identity JSON from database
@@ -155,7 +158,8 @@ pub async fn get_primary_account(pool: &SqlitePool) -> Result<Option<String>> {
         "#,
     )
     .fetch_optional(pool)
-    .await?;
+    .await
+    .storage_context("loading primary account", None)?;
 
     if let Some((account_id,)) = row {
         get_account(pool, &account_id).await
@@ -186,7 +190,8 @@ pub async fn update_token_expiry(
     .bind(expires_at)
     .bind(account_id)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context("updating token expiry", None)?;
 
     Ok(())
 }
@@ -209,7 +214,62 @@ pub async fn update_last_sync(
     )
     .bind(account_id)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context("recording library sync", None)?;
+
+    Ok(())
+}
+
+/// Get last library sync timestamp
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `account_id` - Account identifier
+///
+/// # Returns
+/// Timestamp as stored (ISO 8601 or SQLite `CURRENT_TIMESTAMP` format), or None
+/// if the account has never been synced
+pub async fn get_last_sync(
+    pool: &SqlitePool,
+    account_id: &str,
+) -> Result<Option<String>> {
+    let row: Option<(Option<String>,)> = sqlx::query_as(
+        "SELECT last_library_sync FROM Accounts WHERE account_id = ?",
+    )
+    .bind(account_id)
+    .fetch_optional(pool)
+    .await
+    .storage_context("loading last library sync", None)?;
+
+    Ok(row.and_then(|(last_sync,)| last_sync))
+}
+
+/// Set last library sync timestamp to a specific time
+///
+/// Unlike [`update_last_sync`], this records the caller's timestamp so incremental
+/// syncs can use the time the sync *started* and not miss books added meanwhile.
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `account_id` - Account identifier
+/// * `synced_at` - ISO 8601 timestamp
+pub async fn set_last_sync(
+    pool: &SqlitePool,
+    account_id: &str,
+    synced_at: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE Accounts
+        SET last_library_sync = ?
+        WHERE account_id = ?
+        "#,
+    )
+    .bind(synced_at)
+    .bind(account_id)
+    .execute(pool)
+    .await
+    .storage_context("recording library sync", None)?;
 
     Ok(())
 }
@@ -226,11 +286,94 @@ pub async fn delete_account(
     sqlx::query("DELETE FROM Accounts WHERE account_id = ?")
         .bind(account_id)
         .execute(pool)
-        .await?;
+        .await
+        .storage_context("deleting account", None)?;
 
     Ok(())
 }
 
+/// Delete an account and everything synced for it
+///
+/// Removes the account row (tokens, device keys, activation bytes), the
+/// account's library books with their cover thumbnails and user data, and
+/// download tasks for those books, in one transaction.
+///
+/// Files are not touched here; the returned paths are the encrypted downloads,
+/// their resume state and unfinished outputs the caller should delete.
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `account_id` - Account identifier
+///
+/// # Returns
+/// Files on disk that belonged to the account's downloads
+pub async fn delete_account_data(
+    pool: &SqlitePool,
+    account_id: &str,
+) -> Result<Vec<PathBuf>> {
+    let mut tx = pool.begin().await.storage_context("deleting account data", None)?;
+
+    let task_rows = sqlx::query(
+        r#"
+        SELECT download_path, output_path, status FROM DownloadTasks
+        WHERE asin IN (
+            SELECT b.audible_product_id FROM Books b
+            JOIN LibraryBooks lb ON lb.book_id = b.book_id
+            WHERE lb.account = ?
+        )
+        "#,
+    )
+    .bind(account_id)
+    .fetch_all(&mut *tx)
+    .await
+    .storage_context("deleting account data", None)?;
+
+    let mut files = Vec::new();
+    for row in task_rows {
+        let download_path = PathBuf::from(row.get::<String, _>("download_path"));
+        files.push(download_path.with_extension("download_state.json"));
+        files.push(download_path);
+        // Finished books in the library folder are the user's, not cache
+        if row.get::<String, _>("status") != "completed" {
+            files.push(PathBuf::from(row.get::<String, _>("output_path")));
+        }
+    }
+
+    sqlx::query(
+        r#"
+        DELETE FROM DownloadTasks
+        WHERE asin IN (
+            SELECT b.audible_product_id FROM Books b
+            JOIN LibraryBooks lb ON lb.book_id = b.book_id
+            WHERE lb.account = ?
+        )
+        "#,
+    )
+    .bind(account_id)
+    .execute(&mut *tx)
+    .await
+    .storage_context("deleting account data", None)?;
+
+    // Cascades to LibraryBooks, UserDefinedItems, CoverThumbnails and link tables
+    sqlx::query(
+        "DELETE FROM Books WHERE book_id IN (SELECT book_id FROM LibraryBooks WHERE account = ?)",
+    )
+    .bind(account_id)
+    .execute(&mut *tx)
+    .await
+    .storage_context("deleting account data", None)?;
+
+    sqlx::query("DELETE FROM Accounts WHERE account_id = ?")
+        .bind(account_id)
+        .execute(&mut *tx)
+        .await
+        .storage_context("deleting account data", None)?;
+
+    tx.commit().await.storage_context("deleting account data", None)?;
+
+    Ok(files)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -284,4 +427,31 @@ mod tests {
         let primary_json: serde_json::Value = serde_json::from_str(&primary).unwrap();
         assert_eq!(primary_json["account_id"], "first@example.com");
     }
+
+    #[tokio::test]
+    async fn test_last_sync_roundtrip() {
+        let db = Database::new_in_memory().await.unwrap();
+
+        let account_json = r#"{
+            "account_id": "sync@example.com",
+            "locale": {"country_code": "us"},
+            "identity": {}
+        }"#;
+        save_account(db.pool(), "sync@example.com", account_json)
+            .await
+            .unwrap();
+
+        assert_eq!(get_last_sync(db.pool(), "sync@example.com").await.unwrap(), None);
+
+        set_last_sync(db.pool(), "sync@example.com", "2024-04-03T10:15:00Z")
+            .await
+            .unwrap();
+        assert_eq!(
+            get_last_sync(db.pool(), "sync@example.com").await.unwrap().as_deref(),
+            Some("2024-04-03T10:15:00Z")
+        );
+
+        // Unknown accounts have no sync history
+        assert_eq!(get_last_sync(db.pool(), "missing@example.com").await.unwrap(), None);
+    }
 }
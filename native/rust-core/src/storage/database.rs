@@ -42,9 +42,10 @@
 
 use crate::error::{LibationError, Result};
 use sqlx::{
-    sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions},
-    ConnectOptions, Executor,
+    sqlite::{Sqlite, SqliteConnectOptions, SqlitePool, SqlitePoolOptions},
+    ConnectOptions, Executor, Transaction,
 };
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
@@ -176,6 +177,29 @@ impl Database {
         &self.pool
     }
 
+    /// Start a transaction for a batch of writes
+    ///
+    /// Pass `&mut tx` wherever a connection is taken. Nothing written
+    /// through it is visible to other connections until
+    /// [`Transaction::commit`]. A transaction dropped without committing is
+    /// rolled back: after an error returned with `?`, and also when the
+    /// future that holds it is cancelled, e.g. because the user stopped a
+    /// library sync.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::storage::Database;
+    /// # async fn example(db: Database) -> rust_core::error::Result<()> {
+    /// let mut tx = db.begin().await?;
+    /// sqlx::query("UPDATE LibraryBooks SET absent_from_last_scan = 0").execute(&mut *tx).await?;
+    /// tx.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn begin(&self) -> Result<Transaction<'static, Sqlite>> {
+        Ok(self.pool.begin().await?)
+    }
+
     /// Get database file path
     ///
     /// Returns `None` for in-memory databases
@@ -322,6 +346,62 @@ impl Database {
         })
     }
 
+    /// Storage and usage figures for a settings screen
+    ///
+    /// Everything comes from tables the crate already keeps, so the host does
+    /// not have to walk the library folder, and nothing is sent anywhere.
+    /// A book counts as downloaded when its latest download task completed;
+    /// its size is the size recorded for that download.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::storage::Database;
+    /// # async fn example(db: &Database) -> rust_core::Result<()> {
+    /// let metrics = db.usage_metrics().await?;
+    /// println!("{} books, {} MB", metrics.books_downloaded, metrics.downloaded_bytes / 1_000_000);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn usage_metrics(&self) -> Result<UsageMetrics> {
+        let books_in_library: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM LibraryBooks WHERE is_deleted = 0 AND absent_from_last_scan = 0",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        // SQLite takes the bare total_bytes from the row holding MAX(completed_at)
+        let (books_downloaded, downloaded_bytes): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*), COALESCE(SUM(total_bytes), 0) FROM (
+                SELECT total_bytes, MAX(COALESCE(completed_at, ''))
+                FROM DownloadTasks
+                WHERE status = 'completed'
+                GROUP BY asin
+            )
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let cover_cache_bytes: i64 =
+            sqlx::query_scalar("SELECT COALESCE(SUM(LENGTH(image)), 0) FROM CoverThumbnails")
+                .fetch_one(&self.pool)
+                .await?;
+
+        let last_sync: Option<String> = sqlx::query_scalar("SELECT MAX(last_library_sync) FROM Accounts")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(UsageMetrics {
+            books_in_library: books_in_library as u64,
+            books_downloaded: books_downloaded as u64,
+            downloaded_bytes: downloaded_bytes as u64,
+            cover_cache_bytes: cover_cache_bytes as u64,
+            database_bytes: self.get_stats().await?.total_size,
+            last_sync,
+        })
+    }
+
     /// Checkpoint WAL file to main database
     ///
     /// This writes all WAL changes back to the main database file.
@@ -388,6 +468,104 @@ impl Database {
         Ok(result == "ok")
     }
 
+    /// Remove an account and all of its local data (logout)
+    ///
+    /// Deletes the stored identity and activation bytes, the account's cached
+    /// library and cover thumbnails, its download tasks, and the encrypted
+    /// downloads, resume state and unfinished outputs on disk. Decrypted books
+    /// that finished downloading are left in the library folder.
+    ///
+    /// The database is vacuumed afterwards so the deleted tokens do not linger
+    /// in free pages or the WAL file.
+    ///
+    /// # Arguments
+    /// * `account_id` - Account identifier
+    ///
+    /// # Errors
+    /// Returns error if the database update fails. Files that cannot be
+    /// deleted are logged and counted in `files_failed`.
+    pub async fn clear_account(&self, account_id: &str) -> Result<AccountCleanup> {
+        let files = crate::storage::accounts::delete_account_data(&self.pool, account_id).await?;
+
+        let mut cleanup = AccountCleanup::default();
+        for file in files {
+            match tokio::fs::remove_file(&file).await {
+                Ok(()) => cleanup.files_removed += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    eprintln!("Failed to remove {} for {}: {}", file.display(), account_id, e);
+                    cleanup.files_failed += 1;
+                }
+            }
+        }
+
+        self.vacuum().await?;
+        if self.path.is_some() {
+            self.checkpoint().await?;
+        }
+
+        Ok(cleanup)
+    }
+
+    /// Which of `asins` are in an account's local library
+    ///
+    /// Only the synced library of `account_id` is checked, so another account
+    /// sharing this database doesn't make a title look owned. Books the
+    /// user removed, or that were missing from the last library scan, do not
+    /// count. The lookup is batched to stay under SQLite's bound-parameter
+    /// limit, so a whole catalog page can be checked at once.
+    ///
+    /// # Arguments
+    /// * `account_id` - Account whose library to check
+    /// * `asins` - ASINs to check, e.g. one page of catalog results
+    ///
+    /// # Returns
+    /// The subset of `asins` found in the library
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rust_core::storage::Database;
+    /// # async fn example(db: &Database) -> rust_core::Result<()> {
+    /// let page = vec!["B002V5D7RG".to_string(), "B017V4IM1G".to_string()];
+    /// let owned = db.owned_asins("user@example.com", &page).await?;
+    /// for asin in &page {
+    ///     println!("{} owned: {}", asin, owned.contains(asin));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn owned_asins(&self, account_id: &str, asins: &[String]) -> Result<HashSet<String>> {
+        const BATCH_SIZE: usize = 500;
+
+        let mut owned = HashSet::new();
+        for batch in asins.chunks(BATCH_SIZE) {
+            let placeholders = vec!["?"; batch.len()].join(", ");
+            let sql = format!(
+                "SELECT b.audible_product_id FROM Books b \
+                 JOIN LibraryBooks lb ON lb.book_id = b.book_id \
+                 WHERE lb.account = ? AND lb.is_deleted = 0 AND lb.absent_from_last_scan = 0 \
+                 AND b.audible_product_id IN ({})",
+                placeholders
+            );
+            let mut query = sqlx::query_scalar::<_, String>(&sql).bind(account_id);
+            for asin in batch {
+                query = query.bind(asin);
+            }
+            owned.extend(query.fetch_all(&self.pool).await?);
+        }
+
+        Ok(owned)
+    }
+
+    /// Whether `account_id`'s library has been synced into this database
+    pub async fn has_library(&self, account_id: &str) -> Result<bool> {
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM LibraryBooks WHERE account = ?)")
+            .bind(account_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(exists)
+    }
+
     /// Quick integrity check
     ///
     /// Faster version of integrity_check that only checks key structures.
@@ -400,6 +578,32 @@ impl Database {
     }
 }
 
+/// Result of [`Database::clear_account`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountCleanup {
+    /// Download files deleted from disk
+    pub files_removed: usize,
+    /// Download files that could not be deleted
+    pub files_failed: usize,
+}
+
+/// Result of [`Database::usage_metrics`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct UsageMetrics {
+    /// Books currently in the synced library
+    pub books_in_library: u64,
+    /// Books with a completed download
+    pub books_downloaded: u64,
+    /// Total size of those downloads
+    pub downloaded_bytes: u64,
+    /// Space taken by cached cover thumbnails
+    pub cover_cache_bytes: u64,
+    /// Size of the database itself
+    pub database_bytes: u64,
+    /// Most recent library sync of any account, as stored
+    pub last_sync: Option<String>,
+}
+
 /// Database statistics
 #[derive(Debug, Clone)]
 pub struct DatabaseStats {
@@ -448,6 +652,95 @@ mod tests {
         assert_eq!(result, 1);
     }
 
+    #[tokio::test]
+    async fn test_clear_account() {
+        use crate::storage::accounts::{get_account, save_account};
+        use crate::storage::models::{NewBook, NewLibraryBook};
+        use crate::storage::queries::{
+            find_cover_thumbnail_by_asin, insert_book, insert_library_book, upsert_cover_thumbnail,
+        };
+
+        let db = Database::new_in_memory().await.expect("Failed to create database");
+        let account_json = r#"{"account_id": "gone@example.com", "locale": {"country_code": "us"},
+            "identity": {"access_token": {"token": "secret"}, "refresh_token": "also-secret"}}"#;
+        save_account(db.pool(), "gone@example.com", account_json).await.unwrap();
+        save_account(db.pool(), "kept@example.com", &account_json.replace("gone", "kept")).await.unwrap();
+
+        let book_id = insert_book(db.pool(), &NewBook::new("B0GONE0001".into(), "Gone".into(), "us".into()))
+            .await
+            .unwrap();
+        insert_library_book(db.pool(), &NewLibraryBook { book_id, account: "gone@example.com".into() })
+            .await
+            .unwrap();
+        upsert_cover_thumbnail(db.pool(), book_id, "https://example.com/c.jpg", b"jpg").await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let partial = dir.path().join("B0GONE0001.aax");
+        let state = partial.with_extension("download_state.json");
+        std::fs::write(&partial, b"encrypted").unwrap();
+        std::fs::write(&state, b"{}").unwrap();
+        sqlx::query(
+            "INSERT INTO DownloadTasks (task_id, asin, title, status, download_url, download_path, output_path, request_headers)
+             VALUES ('t1', 'B0GONE0001', 'Gone', 'paused', 'https://example.com', ?, ?, '{}')",
+        )
+        .bind(partial.to_string_lossy().to_string())
+        .bind(dir.path().join("B0GONE0001.m4b").to_string_lossy().to_string())
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        let cleanup = db.clear_account("gone@example.com").await.unwrap();
+        assert_eq!(cleanup, AccountCleanup { files_removed: 2, files_failed: 0 });
+        assert!(!partial.exists() && !state.exists());
+
+        assert!(get_account(db.pool(), "gone@example.com").await.unwrap().is_none());
+        assert!(get_account(db.pool(), "kept@example.com").await.unwrap().is_some());
+        assert!(find_cover_thumbnail_by_asin(db.pool(), "B0GONE0001").await.unwrap().is_none());
+        let tasks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM DownloadTasks")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(tasks, 0);
+    }
+
+    #[tokio::test]
+    async fn test_usage_metrics() {
+        use crate::storage::accounts::{save_account, set_last_sync};
+
+        let db = Database::new_in_memory().await.expect("Failed to create database");
+        assert_eq!(db.usage_metrics().await.unwrap().books_downloaded, 0);
+
+        // B0ONE was downloaded twice; only the latest counts
+        for (task_id, asin, status, bytes, completed_at) in [
+            ("t1", "B0ONE", "completed", 100, "2024-01-01T00:00:00Z"),
+            ("t2", "B0ONE", "completed", 150, "2024-02-01T00:00:00Z"),
+            ("t3", "B0TWO", "completed", 300, "2024-01-05T00:00:00Z"),
+            ("t4", "B0THREE", "failed", 999, ""),
+        ] {
+            sqlx::query(
+                "INSERT INTO DownloadTasks (task_id, asin, title, status, total_bytes, download_url,
+                 download_path, output_path, request_headers, completed_at)
+                 VALUES (?, ?, 'Book', ?, ?, 'https://example.com', '/a', '/b', '{}', ?)",
+            )
+            .bind(task_id)
+            .bind(asin)
+            .bind(status)
+            .bind(bytes)
+            .bind(completed_at)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        }
+        let account_json = r#"{"account_id": "usage@example.com", "locale": {"country_code": "us"}}"#;
+        save_account(db.pool(), "usage@example.com", account_json).await.unwrap();
+        set_last_sync(db.pool(), "usage@example.com", "2024-03-01T12:00:00Z").await.unwrap();
+
+        let metrics = db.usage_metrics().await.unwrap();
+        assert_eq!((metrics.books_downloaded, metrics.downloaded_bytes), (2, 450));
+        assert_eq!(metrics.last_sync.as_deref(), Some("2024-03-01T12:00:00Z"));
+        assert!(metrics.database_bytes > 0);
+    }
+
     #[tokio::test]
     async fn test_database_stats() {
         let db = Database::new_in_memory().await.expect("Failed to create database");
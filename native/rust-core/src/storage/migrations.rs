@@ -31,6 +31,7 @@
 //! Since sqlx's compile-time migration system requires build-time database connection,
 //! we implement migrations as runtime SQL execution for mobile compatibility.
 
+use crate::api::auth::Locale;
 use crate::error::Result;
 use sqlx::{Executor, SqlitePool};
 
@@ -46,6 +47,11 @@ pub async fn run_migrations(pool: &SqlitePool) -> Result<()> {
     run_migration(pool, 1, "initial_schema", create_initial_schema(pool)).await?;
     run_migration(pool, 2, "download_tasks", create_download_tasks_table(pool)).await?;
     run_migration(pool, 3, "accounts", create_accounts_table(pool)).await?;
+    run_migration(pool, 4, "cover_thumbnails", create_cover_thumbnails_table(pool)).await?;
+    run_migration(pool, 5, "download_content_reference", add_download_content_reference(pool)).await?;
+    run_migration(pool, 6, "download_license_format", add_download_license_format(pool)).await?;
+    run_migration(pool, 7, "download_account", add_download_account(pool)).await?;
+    run_migration(pool, 8, "account_locale_details", backfill_locale_details(pool)).await?;
 
     Ok(())
 }
@@ -393,6 +399,42 @@ mod tests {
         assert!(count > 0, "No migrations recorded");
     }
 
+    #[tokio::test]
+    async fn test_backfill_locale_details() {
+        let db = Database::new_in_memory().await.unwrap();
+        let identity = |locale: serde_json::Value| serde_json::json!({ "locale": locale }).to_string();
+        for (account_id, identity_json) in [
+            ("old@example.com", identity(serde_json::json!({ "country_code": "de", "domain": "audible.de" }))),
+            ("odd@example.com", identity(serde_json::json!({ "country_code": "zz" }))),
+        ] {
+            sqlx::query("INSERT INTO Accounts (account_id, account_name, locale_code, identity_json) VALUES (?, ?, ?, ?)")
+                .bind(account_id)
+                .bind(account_id)
+                .bind("de")
+                .bind(identity_json)
+                .execute(db.pool())
+                .await
+                .unwrap();
+        }
+
+        backfill_locale_details(db.pool()).await.unwrap();
+
+        let pool = db.pool();
+        let locale_of = |account_id: &'static str| async move {
+            let json: String = sqlx::query_scalar("SELECT identity_json FROM Accounts WHERE account_id = ?")
+                .bind(account_id)
+                .fetch_one(pool)
+                .await
+                .unwrap();
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()["locale"].clone()
+        };
+        let locale = locale_of("old@example.com").await;
+        assert_eq!(locale["language"], "de-DE");
+        assert_eq!(locale["currency"], "EUR");
+        assert_eq!(locale["domain"], "audible.de");
+        assert!(locale_of("odd@example.com").await.get("language").is_none());
+    }
+
     #[tokio::test]
     async fn test_foreign_keys_enabled() {
         let db = Database::new_in_memory()
@@ -509,3 +551,117 @@ END;
 
     Ok(())
 }
+
+/// Create cover thumbnails table
+///
+/// Stores small cover images fetched during onboarding so the library list can
+/// render without network access. One row per book; replaced on re-fetch.
+async fn create_cover_thumbnails_table(pool: &SqlitePool) -> Result<()> {
+    pool.execute(
+        r#"
+-- ============================================================================
+-- COVER THUMBNAILS TABLE
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS CoverThumbnails (
+    book_id INTEGER PRIMARY KEY,  -- 1:1 with Books
+    url TEXT NOT NULL,            -- Source image URL
+    image BLOB NOT NULL,          -- Encoded image bytes (usually JPEG)
+    fetched_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (book_id) REFERENCES Books(book_id) ON DELETE CASCADE
+);
+        "#,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Remember which master a download was made from
+///
+/// Audible replaces the audio of a title (a new "master") without changing
+/// its ASIN; the ACR and version from the license's content reference are
+/// what change.
+async fn add_download_content_reference(pool: &SqlitePool) -> Result<()> {
+    pool.execute(
+        r#"
+ALTER TABLE DownloadTasks ADD COLUMN acr TEXT;              -- Content reference of the downloaded master
+ALTER TABLE DownloadTasks ADD COLUMN content_version TEXT;  -- Version of that master
+        "#,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Remember the quality and codec a download was licensed at
+///
+/// A refreshed download URL must be requested with the same parameters, or
+/// it may point to a different file than the bytes already on disk.
+async fn add_download_license_format(pool: &SqlitePool) -> Result<()> {
+    pool.execute(
+        r#"
+ALTER TABLE DownloadTasks ADD COLUMN quality TEXT;    -- DownloadQuality the license was requested at
+ALTER TABLE DownloadTasks ADD COLUMN aac_codec TEXT;  -- AAC codec the license was requested in
+        "#,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Remember which account a download belongs to
+///
+/// Two accounts may own the same title; their downloads are separate tasks.
+async fn add_download_account(pool: &SqlitePool) -> Result<()> {
+    pool.execute(
+        r#"
+ALTER TABLE DownloadTasks ADD COLUMN account_id TEXT;  -- NULL for tasks enqueued without an account
+CREATE INDEX IF NOT EXISTS idx_download_tasks_account_asin ON DownloadTasks(account_id, asin);
+        "#,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Fill in the language and currency of locales saved before `Locale` had them
+///
+/// Each account's identity JSON carries its locale. Locales still missing a
+/// language get both fields from the known locale with the same country code;
+/// unknown markets are left as they are.
+async fn backfill_locale_details(pool: &SqlitePool) -> Result<()> {
+    let accounts: Vec<(String, String)> = sqlx::query_as("SELECT account_id, identity_json FROM Accounts")
+        .fetch_all(pool)
+        .await?;
+
+    for (account_id, identity_json) in accounts {
+        let Ok(mut identity) = serde_json::from_str::<serde_json::Value>(&identity_json) else {
+            eprintln!("Warning: Skipping locale backfill for {}: corrupt identity JSON", account_id);
+            continue;
+        };
+        let Some(locale) = identity.get_mut("locale").and_then(|l| l.as_object_mut()) else {
+            continue;
+        };
+        if locale.get("language").and_then(|l| l.as_str()).is_some_and(|l| !l.is_empty()) {
+            continue;
+        }
+        let Some(known) = locale
+            .get("country_code")
+            .and_then(|c| c.as_str())
+            .and_then(Locale::from_country_code)
+        else {
+            continue;
+        };
+        locale.insert("language".to_string(), known.language.into());
+        locale.insert("currency".to_string(), known.currency.into());
+
+        sqlx::query("UPDATE Accounts SET identity_json = ? WHERE account_id = ?")
+            .bind(identity.to_string())
+            .bind(&account_id)
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
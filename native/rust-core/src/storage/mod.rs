@@ -66,7 +66,7 @@ pub mod models;
 pub mod queries;
 
 // Re-export commonly used types
-pub use database::{Database, DatabaseStats};
+pub use database::{AccountCleanup, Database, DatabaseStats, UsageMetrics};
 pub use models::{
     AudioFormat, Book, BookCategory, BookContributor, Category, CategoryLadder, Codec,
     ContentType, Contributor, LiberatedStatus, LibraryBook, NewBook, NewCategory,
@@ -253,6 +253,8 @@ pub struct Book {
     pub is_spatial: bool,
     #[sqlx(default)]
     pub date_published: Option<NaiveDate>,
+    /// Content languages, comma-separated for multilingual titles
+    /// (see [`Book::languages`])
     #[sqlx(default)]
     pub language: Option<String>,
 
@@ -297,6 +299,31 @@ impl Book {
             _ => self.title.clone(),
         }
     }
+
+    /// All content languages, in the order the API listed them
+    pub fn languages(&self) -> Vec<String> {
+        split_languages(self.language.as_deref().unwrap_or_default())
+    }
+
+    /// First listed content language
+    pub fn primary_language(&self) -> Option<String> {
+        self.languages().into_iter().next()
+    }
+}
+
+/// Split a stored or API language string ("english, german") into a list
+pub fn split_languages(value: &str) -> Vec<String> {
+    value
+        .split([',', ';'])
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Join languages for the `language` column; `None` when there are none
+pub fn join_languages(languages: &[String]) -> Option<String> {
+    (!languages.is_empty()).then(|| languages.join(", "))
 }
 
 /// LibraryBook - represents user ownership of a book
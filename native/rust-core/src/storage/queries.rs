@@ -34,7 +34,7 @@
 //! - Use sqlx for type-safe queries
 //! - Support transactions for multi-step operations
 
-use crate::error::{LibationError, Result};
+use crate::error::{LibationError, Result, StorageContext};
 use crate::storage::models::*;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -75,7 +75,8 @@ pub async fn insert_book(pool: &SqlitePool, book: &NewBook) -> Result<i64> {
     .bind(book.rating_performance)
     .bind(book.rating_story)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context("inserting book", Some(&book.audible_product_id))?;
 
     Ok(result.last_insert_rowid())
 }
@@ -85,7 +86,8 @@ pub async fn find_book_by_asin(pool: &SqlitePool, asin: &str) -> Result<Option<B
     let book = sqlx::query_as::<_, Book>("SELECT * FROM Books WHERE audible_product_id = ?")
         .bind(asin)
         .fetch_optional(pool)
-        .await?;
+        .await
+        .storage_context("looking up book", Some(asin))?;
 
     Ok(book)
 }
@@ -95,7 +97,8 @@ pub async fn find_book_by_id(pool: &SqlitePool, book_id: i64) -> Result<Option<B
     let book = sqlx::query_as::<_, Book>("SELECT * FROM Books WHERE book_id = ?")
         .bind(book_id)
         .fetch_optional(pool)
-        .await?;
+        .await
+        .storage_context(&format!("looking up book {}", book_id), None)?;
 
     Ok(book)
 }
@@ -128,7 +131,8 @@ pub async fn update_book(pool: &SqlitePool, book: &Book) -> Result<()> {
     .bind(book.rating_story)
     .bind(book.book_id)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context("updating book", Some(&book.audible_product_id))?;
 
     Ok(())
 }
@@ -141,7 +145,8 @@ pub async fn list_books(pool: &SqlitePool, limit: i64, offset: i64) -> Result<Ve
     .bind(limit)
     .bind(offset)
     .fetch_all(pool)
-    .await?;
+    .await
+    .storage_context("listing books", None)?;
 
     Ok(books)
 }
@@ -221,7 +226,7 @@ impl BookWithRelations {
             narrators,
             publisher: self.publisher.clone(),
             publication_date: self.date_published.clone(),
-            language: self.language.clone(),
+            languages: split_languages(self.language.as_deref().unwrap_or_default()),
             series,
             description: Some(self.description.clone()),
             genres: vec![], // Not available in BookWithRelations
@@ -317,7 +322,8 @@ pub async fn list_books_with_relations(pool: &SqlitePool, limit: i64, offset: i6
     .bind(limit)
     .bind(offset)
     .fetch_all(pool)
-    .await?;
+    .await
+    .storage_context("listing books", None)?;
 
     Ok(books)
 }
@@ -405,7 +411,8 @@ pub async fn find_book_with_relations_by_asin(pool: &SqlitePool, asin: &str) ->
     )
     .bind(asin)
     .fetch_optional(pool)
-    .await?;
+    .await
+    .storage_context("looking up book", Some(asin))?;
 
     Ok(book)
 }
@@ -414,7 +421,8 @@ pub async fn find_book_with_relations_by_asin(pool: &SqlitePool, asin: &str) ->
 pub async fn count_books(pool: &SqlitePool) -> Result<i64> {
     let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM Books")
         .fetch_one(pool)
-        .await?;
+        .await
+        .storage_context("counting books", None)?;
 
     Ok(count)
 }
@@ -608,7 +616,7 @@ pub async fn list_books_with_filters(
 
     q = q.bind(params.limit).bind(params.offset);
 
-    let books = q.fetch_all(pool).await?;
+    let books = q.fetch_all(pool).await.storage_context("listing books", None)?;
 
     Ok(books)
 }
@@ -702,7 +710,7 @@ pub async fn count_books_with_filters(
         q = q.bind(value);
     }
 
-    let count = q.fetch_one(pool).await?;
+    let count = q.fetch_one(pool).await.storage_context("counting books", None)?;
 
     Ok(count)
 }
@@ -715,7 +723,8 @@ pub async fn list_all_series(pool: &SqlitePool) -> Result<Vec<String>> {
          ORDER BY s.name"
     )
     .fetch_all(pool)
-    .await?;
+    .await
+    .storage_context("listing series", None)?;
 
     Ok(series)
 }
@@ -730,11 +739,23 @@ pub async fn list_all_categories(pool: &SqlitePool) -> Result<Vec<String>> {
          ORDER BY c.name"
     )
     .fetch_all(pool)
-    .await?;
+    .await
+    .storage_context("listing categories", None)?;
 
     Ok(categories)
 }
 
+/// Get the `(asin, title)` pair of every book
+pub async fn list_book_titles(pool: &SqlitePool) -> Result<Vec<(String, String)>> {
+    let titles: Vec<(String, String)> =
+        sqlx::query_as("SELECT audible_product_id, title FROM Books")
+            .fetch_all(pool)
+            .await
+            .storage_context("listing book titles", None)?;
+
+    Ok(titles)
+}
+
 /// Search books by title
 pub async fn search_books_by_title(pool: &SqlitePool, query: &str, limit: i64) -> Result<Vec<Book>> {
     let search_pattern = format!("%{}%", query);
@@ -745,17 +766,152 @@ pub async fn search_books_by_title(pool: &SqlitePool, query: &str, limit: i64) -
     .bind(&search_pattern)
     .bind(limit)
     .fetch_all(pool)
-    .await?;
+    .await
+    .storage_context("searching books", None)?;
 
     Ok(books)
 }
 
+/// Books in the synced library whose searchable text contains every term
+///
+/// Only books with a `LibraryBooks` row that isn't deleted are considered.
+/// Each term must appear (case-insensitively for ASCII) in the ASIN, title,
+/// subtitle, authors, narrators, first series or description; with no terms
+/// the whole library is listed.
+///
+/// # Arguments
+/// * `terms` - Search terms, all of which must match
+/// * `order_by` - SQL `ORDER BY` expression over the query's tables
+///   (`b`, `lb`, `book_authors`, `book_narrators`, `book_series`); never user input
+pub(crate) async fn search_library_books(
+    pool: &SqlitePool,
+    terms: &[String],
+    order_by: &str,
+) -> Result<Vec<BookWithRelations>> {
+    let haystack = "(b.audible_product_id || ' ' || b.title || ' ' || IFNULL(b.subtitle, '') || ' ' \
+         || IFNULL(book_authors.authors, '') || ' ' || IFNULL(book_narrators.narrators, '') || ' ' \
+         || IFNULL(book_series.series_name, '') || ' ' || b.description)";
+    let mut where_clause = String::from("WHERE lb.is_deleted = 0");
+    for _ in terms {
+        where_clause.push_str(&format!(" AND {} LIKE ? ESCAPE '\\'", haystack));
+    }
+
+    let query = format!(
+        r#"
+        WITH book_authors AS (
+            SELECT
+                bc.book_id,
+                GROUP_CONCAT(c.name, ', ') as authors
+            FROM BookContributors bc
+            JOIN Contributors c ON bc.contributor_id = c.contributor_id
+            WHERE bc.role = 1
+            GROUP BY bc.book_id
+        ),
+        book_narrators AS (
+            SELECT
+                bc.book_id,
+                GROUP_CONCAT(c.name, ', ') as narrators
+            FROM BookContributors bc
+            JOIN Contributors c ON bc.contributor_id = c.contributor_id
+            WHERE bc.role = 2
+            GROUP BY bc.book_id
+        ),
+        book_publishers AS (
+            SELECT
+                bc.book_id,
+                MIN(c.name) as publisher
+            FROM BookContributors bc
+            JOIN Contributors c ON bc.contributor_id = c.contributor_id
+            WHERE bc.role = 3
+            GROUP BY bc.book_id
+        ),
+        book_series AS (
+            SELECT
+                sb.book_id,
+                s.name as series_name,
+                sb."index" as series_sequence,
+                ROW_NUMBER() OVER (PARTITION BY sb.book_id ORDER BY sb."index", s.name) as rn
+            FROM SeriesBooks sb
+            JOIN Series s ON sb.series_id = s.series_id
+        )
+        SELECT
+            b.book_id,
+            b.audible_product_id,
+            b.title,
+            b.subtitle,
+            b.description,
+            b.length_in_minutes,
+            b.content_type,
+            b.locale,
+            b.picture_id,
+            b.picture_large,
+            b.is_abridged,
+            b.is_spatial,
+            b.date_published,
+            b.language,
+            b.rating_overall,
+            b.rating_performance,
+            b.rating_story,
+            b.pdf_url,
+            b.is_finished,
+            b.is_downloadable,
+            b.is_ayce,
+            b.origin_asin,
+            b.episode_number,
+            b.content_delivery_type,
+            b.created_at,
+            b.updated_at,
+            book_authors.authors as authors_str,
+            book_narrators.narrators as narrators_str,
+            book_publishers.publisher,
+            book_series.series_name,
+            book_series.series_sequence,
+            lb.date_added as purchase_date
+        FROM Books b
+        JOIN LibraryBooks lb ON b.book_id = lb.book_id
+        LEFT JOIN book_authors ON b.book_id = book_authors.book_id
+        LEFT JOIN book_narrators ON b.book_id = book_narrators.book_id
+        LEFT JOIN book_publishers ON b.book_id = book_publishers.book_id
+        LEFT JOIN book_series ON b.book_id = book_series.book_id AND book_series.rn = 1
+        {}
+        ORDER BY {}
+        "#,
+        where_clause,
+        order_by
+    );
+
+    let mut q = sqlx::query_as::<_, BookWithRelations>(&query);
+    for term in terms {
+        q = q.bind(format!("%{}%", escape_like(term)));
+    }
+
+    let books = q
+        .fetch_all(pool)
+        .await
+        .storage_context("searching library", None)?;
+
+    Ok(books)
+}
+
+/// Escape `%`, `_` and the escape character itself for a `LIKE ... ESCAPE '\'` pattern
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 /// Delete a book (and all related data via CASCADE)
 pub async fn delete_book(pool: &SqlitePool, book_id: i64) -> Result<()> {
     sqlx::query("DELETE FROM Books WHERE book_id = ?")
         .bind(book_id)
         .execute(pool)
-        .await?;
+        .await
+        .storage_context(&format!("deleting book {}", book_id), None)?;
 
     Ok(())
 }
@@ -775,7 +931,8 @@ pub async fn insert_library_book(pool: &SqlitePool, library_book: &NewLibraryBoo
     .bind(library_book.book_id)
     .bind(&library_book.account)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context(&format!("adding book {} to library", library_book.book_id), None)?;
 
     Ok(())
 }
@@ -785,7 +942,8 @@ pub async fn find_library_book(pool: &SqlitePool, book_id: i64) -> Result<Option
     let lib_book = sqlx::query_as::<_, LibraryBook>("SELECT * FROM LibraryBooks WHERE book_id = ?")
         .bind(book_id)
         .fetch_optional(pool)
-        .await?;
+        .await
+        .storage_context(&format!("looking up library entry for book {}", book_id), None)?;
 
     Ok(lib_book)
 }
@@ -797,7 +955,8 @@ pub async fn list_library_books_by_account(pool: &SqlitePool, account: &str) ->
     )
     .bind(account)
     .fetch_all(pool)
-    .await?;
+    .await
+    .storage_context("listing library books", None)?;
 
     Ok(books)
 }
@@ -816,7 +975,8 @@ pub async fn insert_user_defined_item(pool: &SqlitePool, item: &NewUserDefinedIt
     )
     .bind(item.book_id)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context(&format!("creating user data for book {}", item.book_id), None)?;
 
     Ok(())
 }
@@ -826,7 +986,8 @@ pub async fn find_user_defined_item(pool: &SqlitePool, book_id: i64) -> Result<O
     let item = sqlx::query_as::<_, UserDefinedItem>("SELECT * FROM UserDefinedItems WHERE book_id = ?")
         .bind(book_id)
         .fetch_optional(pool)
-        .await?;
+        .await
+        .storage_context(&format!("looking up user data for book {}", book_id), None)?;
 
     Ok(item)
 }
@@ -857,7 +1018,8 @@ pub async fn update_user_defined_item(pool: &SqlitePool, item: &UserDefinedItem)
     .bind(item.is_finished)
     .bind(item.book_id)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context(&format!("updating user data for book {}", item.book_id), None)?;
 
     Ok(())
 }
@@ -878,7 +1040,8 @@ pub async fn upsert_contributor(pool: &SqlitePool, contributor: &NewContributor)
     .bind(&contributor.audible_contributor_id)
     .bind(&contributor.audible_contributor_id)
     .fetch_optional(pool)
-    .await?;
+    .await
+    .storage_context("saving contributor", None)?;
 
     if let Some(id) = existing {
         return Ok(id);
@@ -891,7 +1054,8 @@ pub async fn upsert_contributor(pool: &SqlitePool, contributor: &NewContributor)
     .bind(&contributor.name)
     .bind(&contributor.audible_contributor_id)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context("saving contributor", None)?;
 
     Ok(result.last_insert_rowid())
 }
@@ -909,7 +1073,8 @@ pub async fn find_contributors_by_book(pool: &SqlitePool, book_id: i64, role: i3
     .bind(book_id)
     .bind(role)
     .fetch_all(pool)
-    .await?;
+    .await
+    .storage_context(&format!("listing contributors of book {}", book_id), None)?;
 
     Ok(contributors)
 }
@@ -933,7 +1098,8 @@ pub async fn add_book_contributor(
     .bind(role)
     .bind(order)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context(&format!("linking contributor to book {}", book_id), None)?;
 
     Ok(())
 }
@@ -944,7 +1110,8 @@ pub async fn remove_book_contributors_by_role(pool: &SqlitePool, book_id: i64, r
         .bind(book_id)
         .bind(role)
         .execute(pool)
-        .await?;
+        .await
+        .storage_context(&format!("unlinking contributors of book {}", book_id), None)?;
 
     Ok(())
 }
@@ -961,7 +1128,8 @@ pub async fn upsert_series(pool: &SqlitePool, series: &NewSeries) -> Result<i64>
     )
     .bind(&series.audible_series_id)
     .fetch_optional(pool)
-    .await?;
+    .await
+    .storage_context("saving series", None)?;
 
     if let Some(id) = existing {
         // Update name if provided
@@ -970,7 +1138,8 @@ pub async fn upsert_series(pool: &SqlitePool, series: &NewSeries) -> Result<i64>
                 .bind(name)
                 .bind(id)
                 .execute(pool)
-                .await?;
+                .await
+                .storage_context("saving series", None)?;
         }
         return Ok(id);
     }
@@ -982,7 +1151,8 @@ pub async fn upsert_series(pool: &SqlitePool, series: &NewSeries) -> Result<i64>
     .bind(&series.audible_series_id)
     .bind(&series.name)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context("saving series", None)?;
 
     Ok(result.last_insert_rowid())
 }
@@ -1006,7 +1176,8 @@ pub async fn add_book_to_series(
     .bind(order)
     .bind(index)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context(&format!("adding book {} to series", book_id), None)?;
 
     Ok(())
 }
@@ -1025,7 +1196,8 @@ pub async fn find_series_by_book(pool: &SqlitePool, book_id: i64) -> Result<Vec<
     )
     .bind(book_id)
     .fetch_all(pool)
-    .await?;
+    .await
+    .storage_context(&format!("listing series of book {}", book_id), None)?;
 
     let series_books = results
         .into_iter()
@@ -1061,7 +1233,8 @@ pub async fn upsert_category(pool: &SqlitePool, category: &NewCategory) -> Resul
         )
         .bind(audible_id)
         .fetch_optional(pool)
-        .await?;
+        .await
+        .storage_context("saving category", None)?;
 
         if let Some(id) = existing {
             return Ok(id);
@@ -1075,7 +1248,8 @@ pub async fn upsert_category(pool: &SqlitePool, category: &NewCategory) -> Resul
     .bind(&category.audible_category_id)
     .bind(&category.name)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context("saving category", None)?;
 
     Ok(result.last_insert_rowid())
 }
@@ -1088,7 +1262,8 @@ pub async fn upsert_category_ladder(pool: &SqlitePool, ladder: &NewCategoryLadde
     )
     .bind(&ladder.audible_ladder_id)
     .fetch_optional(pool)
-    .await?;
+    .await
+    .storage_context("saving category ladder", None)?;
 
     if let Some(id) = existing {
         return Ok(id);
@@ -1101,7 +1276,8 @@ pub async fn upsert_category_ladder(pool: &SqlitePool, ladder: &NewCategoryLadde
     .bind(&ladder.audible_ladder_id)
     .bind(&ladder.ladder)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context("saving category ladder", None)?;
 
     Ok(result.last_insert_rowid())
 }
@@ -1114,7 +1290,8 @@ pub async fn add_book_category(pool: &SqlitePool, book_id: i64, category_ladder_
     .bind(book_id)
     .bind(category_ladder_id)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context(&format!("categorizing book {}", book_id), None)?;
 
     Ok(())
 }
@@ -1131,7 +1308,8 @@ pub async fn add_supplement(pool: &SqlitePool, book_id: i64, url: &str) -> Resul
     .bind(book_id)
     .bind(url)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context(&format!("adding supplement to book {}", book_id), None)?;
 
     Ok(result.last_insert_rowid())
 }
@@ -1143,11 +1321,63 @@ pub async fn find_supplements_by_book(pool: &SqlitePool, book_id: i64) -> Result
     )
     .bind(book_id)
     .fetch_all(pool)
-    .await?;
+    .await
+    .storage_context(&format!("listing supplements of book {}", book_id), None)?;
 
     Ok(supplements)
 }
 
+// ============================================================================
+// COVER THUMBNAIL QUERIES
+// ============================================================================
+
+/// Store (or replace) a book's cover thumbnail
+pub async fn upsert_cover_thumbnail(
+    pool: &SqlitePool,
+    book_id: i64,
+    url: &str,
+    image: &[u8],
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO CoverThumbnails (book_id, url, image, fetched_at)
+        VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+        ON CONFLICT(book_id) DO UPDATE SET
+            url = excluded.url,
+            image = excluded.image,
+            fetched_at = excluded.fetched_at
+        "#,
+    )
+    .bind(book_id)
+    .bind(url)
+    .bind(image)
+    .execute(pool)
+    .await
+    .storage_context(&format!("storing cover thumbnail for book {}", book_id), None)?;
+
+    Ok(())
+}
+
+/// Find a book's cover thumbnail by ASIN
+///
+/// Returns the image bytes, or None if no thumbnail has been stored.
+pub async fn find_cover_thumbnail_by_asin(pool: &SqlitePool, asin: &str) -> Result<Option<Vec<u8>>> {
+    let image: Option<Vec<u8>> = sqlx::query_scalar(
+        r#"
+        SELECT t.image
+        FROM CoverThumbnails t
+        JOIN Books b ON b.book_id = t.book_id
+        WHERE b.audible_product_id = ?
+        "#,
+    )
+    .bind(asin)
+    .fetch_optional(pool)
+    .await
+    .storage_context("loading cover thumbnail", Some(asin))?;
+
+    Ok(image)
+}
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
@@ -1219,7 +1449,8 @@ pub async fn clear_download_state(pool: &SqlitePool) -> Result<i64> {
         "#,
     )
     .execute(pool)
-    .await?;
+    .await
+    .storage_context("clearing download state", None)?;
 
     Ok(result.rows_affected() as i64)
 }
@@ -1273,7 +1504,8 @@ pub async fn clear_book_download_state(
     )
     .bind(asin)
     .fetch_optional(pool)
-    .await?;
+    .await
+    .storage_context("clearing download state", Some(asin))?;
 
     // Delete the file if requested and file path exists
     let deleted_path = if delete_file {
@@ -1310,7 +1542,8 @@ pub async fn clear_book_download_state(
     )
     .bind(book_id)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context("clearing download state", Some(asin))?;
 
     // Delete any download tasks for this book to reset to default state
     sqlx::query(
@@ -1321,7 +1554,8 @@ pub async fn clear_book_download_state(
     )
     .bind(asin)
     .execute(pool)
-    .await?;
+    .await
+    .storage_context("clearing download state", Some(asin))?;
 
     Ok(deleted_path)
 }
@@ -1349,24 +1583,26 @@ pub async fn get_book_file_path(pool: &SqlitePool, asin: &str) -> Result<Option<
     )
     .bind(asin)
     .fetch_optional(pool)
-    .await?;
+    .await
+    .storage_context("looking up downloaded file", Some(asin))?;
 
     Ok(file_path)
 }
 
 pub async fn clear_library(pool: &SqlitePool) -> Result<()> {
     // Delete in correct order to respect foreign keys
-    sqlx::query("DELETE FROM LibraryBooks").execute(pool).await?;
-    sqlx::query("DELETE FROM SeriesBooks").execute(pool).await?;
-    sqlx::query("DELETE FROM BookContributors").execute(pool).await?;
-    sqlx::query("DELETE FROM BookCategories").execute(pool).await?;
-    sqlx::query("DELETE FROM UserDefinedItems").execute(pool).await?;
-    sqlx::query("DELETE FROM Supplements").execute(pool).await?;
-    sqlx::query("DELETE FROM Books").execute(pool).await?;
-    sqlx::query("DELETE FROM Series").execute(pool).await?;
-    sqlx::query("DELETE FROM Contributors").execute(pool).await?;
-    sqlx::query("DELETE FROM Categories").execute(pool).await?;
-    sqlx::query("DELETE FROM CategoryLadders").execute(pool).await?;
+    sqlx::query("DELETE FROM LibraryBooks").execute(pool).await.storage_context("clearing library", None)?;
+    sqlx::query("DELETE FROM SeriesBooks").execute(pool).await.storage_context("clearing library", None)?;
+    sqlx::query("DELETE FROM BookContributors").execute(pool).await.storage_context("clearing library", None)?;
+    sqlx::query("DELETE FROM BookCategories").execute(pool).await.storage_context("clearing library", None)?;
+    sqlx::query("DELETE FROM UserDefinedItems").execute(pool).await.storage_context("clearing library", None)?;
+    sqlx::query("DELETE FROM Supplements").execute(pool).await.storage_context("clearing library", None)?;
+    sqlx::query("DELETE FROM CoverThumbnails").execute(pool).await.storage_context("clearing library", None)?;
+    sqlx::query("DELETE FROM Books").execute(pool).await.storage_context("clearing library", None)?;
+    sqlx::query("DELETE FROM Series").execute(pool).await.storage_context("clearing library", None)?;
+    sqlx::query("DELETE FROM Contributors").execute(pool).await.storage_context("clearing library", None)?;
+    sqlx::query("DELETE FROM Categories").execute(pool).await.storage_context("clearing library", None)?;
+    sqlx::query("DELETE FROM CategoryLadders").execute(pool).await.storage_context("clearing library", None)?;
 
     Ok(())
 }
@@ -1399,6 +1635,52 @@ mod tests {
         assert_eq!(book.audible_product_id, "B012345678");
     }
 
+    #[tokio::test]
+    async fn test_insert_duplicate_book_reports_storage_error() {
+        let db = Database::new_in_memory().await.expect("Failed to create database");
+
+        let new_book = NewBook::new(
+            "B0DUPE0001".to_string(),
+            "Duplicate Book".to_string(),
+            "us".to_string(),
+        );
+        insert_book(db.pool(), &new_book).await.expect("Failed to insert book");
+
+        let err = insert_book(db.pool(), &new_book).await.unwrap_err();
+        match err {
+            LibationError::Storage { ref operation, ref asin, .. } => {
+                assert_eq!(operation, "inserting book");
+                assert_eq!(asin.as_deref(), Some("B0DUPE0001"));
+            }
+            other => panic!("Expected Storage error, got {:?}", other),
+        }
+        assert!(err.to_string().starts_with("Storage error while inserting book (B0DUPE0001): "));
+    }
+
+    #[tokio::test]
+    async fn test_cover_thumbnail_roundtrip() {
+        let db = Database::new_in_memory().await.expect("Failed to create database");
+
+        let new_book = NewBook::new(
+            "B0THUMB001".to_string(),
+            "Thumbnail Book".to_string(),
+            "us".to_string(),
+        );
+        let book_id = insert_book(db.pool(), &new_book).await.expect("Failed to insert book");
+
+        assert!(find_cover_thumbnail_by_asin(db.pool(), "B0THUMB001").await.unwrap().is_none());
+
+        upsert_cover_thumbnail(db.pool(), book_id, "https://example.com/a.jpg", b"first")
+            .await
+            .expect("Failed to store thumbnail");
+        upsert_cover_thumbnail(db.pool(), book_id, "https://example.com/b.jpg", b"second")
+            .await
+            .expect("Failed to replace thumbnail");
+
+        let image = find_cover_thumbnail_by_asin(db.pool(), "B0THUMB001").await.unwrap();
+        assert_eq!(image.as_deref(), Some(&b"second"[..]));
+    }
+
     #[tokio::test]
     async fn test_upsert_book() {
         let db = Database::new_in_memory().await.expect("Failed to create database");
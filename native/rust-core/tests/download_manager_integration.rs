@@ -146,6 +146,7 @@ async fn test_download_book_with_manager() -> Result<(), Box<dyn std::error::Err
         encrypted_path.to_str().unwrap().to_string(),
         output_path.to_str().unwrap().to_string(),
         request_headers,
+        false,
     ).await?;
     println!("   ✓ Download enqueued: {}", task_id);
 
@@ -287,6 +288,7 @@ async fn test_pause_resume_download() -> Result<(), Box<dyn std::error::Error>>
         encrypted_path.to_str().unwrap().to_string(),
         output_path.to_str().unwrap().to_string(),
         request_headers,
+        false,
     ).await?;
     println!("   ✓ Download started: {}", task_id);
 
@@ -388,6 +390,7 @@ async fn test_download_manager_with_public_url() -> Result<(), Box<dyn std::erro
         download_path.to_str().unwrap().to_string(),
         output_path.to_str().unwrap().to_string(),
         headers,
+        false,
     ).await?;
     println!("   ✓ Download enqueued: {}", task_id);
 
@@ -493,6 +496,7 @@ async fn test_list_downloads() -> Result<(), Box<dyn std::error::Error>> {
             format!("/tmp/book{}.aax", i),
             format!("/tmp/book{}.m4b", i),
             headers.clone(),
+            false,
         ).await?;
         task_ids.push(task_id);
     }